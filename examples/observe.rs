@@ -0,0 +1,55 @@
+// Embedding the simulation as a library: assemble an engagement with the builder, watch it with an
+// observer and drive it step by step, then load a built-in scenario and run it through.
+//
+//   cargo run --example observe
+
+use coding_interview_rust::{GuidanceLaw, Outcome, Scenario, SimError, SimEvent, SimObserver, Simulation, SimulationResult, StepOutcome};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// Prints the event log as it grows and counts the steps it was shown
+#[derive(Default)]
+struct Printer {
+    steps: usize,
+}
+
+impl SimObserver for Printer {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        self.steps += 1;
+        if self.steps.is_multiple_of(10) {
+            println!("  step {:>4}  {:>7.2} m", sim.steps(), sim.separations().last().copied().unwrap_or_default());
+        }
+    }
+
+    fn on_event(&mut self, event: &SimEvent, sim: &SimulationResult) {
+        println!("  step {:>4}  {:>7.2} m  {}", event.step(), sim.separations().last().copied().unwrap_or_default(), event.label());
+    }
+
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        println!("  {} after {} steps, Pk {:.2}", sim.outcome.as_str(), sim.steps(), sim.pk());
+    }
+}
+
+fn main() -> Result<(), SimError> {
+    let mut rng = StdRng::seed_from_u64(7);
+
+    println!("ZEM guidance against a crossing target:");
+    let mut printer = Printer::default();
+    let mut simulation = Simulation::builder()
+        .target((0.0, 40.0), 2.0)
+        .interceptor((80.0, 0.0), 3.0)
+        .guidance(GuidanceLaw::Zem)
+        .max_steps(200)
+        .build()?;
+    simulation.observe(&mut printer);
+    while let StepOutcome::Running = simulation.step(&mut rng) {}
+    let (outcome, miss_distance) = (simulation.result().outcome, simulation.result().miss_distance);
+    drop(simulation);
+    println!("  {} steps observed, closest approach {:.2} m ({})", printer.steps, miss_distance, if outcome == Outcome::Intercept { "hit" } else { "no hit" });
+
+    println!("The head-on preset, run through:");
+    let scenario = Scenario::preset("head-on").expect("head-on is a built-in preset");
+    let result = Simulation::builder().config(scenario.config).build()?.run(&mut rng);
+    println!("  {} after {} steps, miss distance {:.2} m", result.outcome.as_str(), result.steps(), result.miss_distance);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+/* C API of the pursuit simulation, see src/ffi.rs; `cargo test ffi` checks that the functions, their
+ * parameter counts, the CI_ outcomes and the fields of CiState here match the ones it exports. Link
+ * against libcoding_interview_rust.so from target/release, or libcoding_interview_rust.a with
+ * -lpthread -ldl -lm, plus -lfreetype -lfontconfig unless built with --no-default-features. */
+
+#ifndef CODING_INTERVIEW_RUST_H
+#define CODING_INTERVIEW_RUST_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* CiState.outcome */
+#define CI_RUNNING 0
+#define CI_INTERCEPT 1
+#define CI_GROUND_IMPACT 2
+#define CI_TIMEOUT 3
+#define CI_ESCAPE 4
+#define CI_STALL 5
+#define CI_MISS 6
+
+typedef struct CiSimulation CiSimulation;
+
+/* Both vehicles after the last step; meters, m/s and seconds, y the height */
+typedef struct CiState {
+    uint64_t step;
+    double time;
+    double target_x;
+    double target_y;
+    double target_vx;
+    double target_vy;
+    double interceptor_x;
+    double interceptor_y;
+    double interceptor_vx;
+    double interceptor_vy;
+    double separation;
+    double miss_distance; /* Closest approach so far */
+    int32_t outcome;      /* CI_RUNNING until the run has ended */
+} CiState;
+
+/* The message of the last call on this thread that failed, valid until the next one fails */
+const char *ci_last_error(void);
+
+/* A new simulation of a scenario document (as a scenario file; NULL for the defaults) with a
+ * random seed, or NULL if the scenario is invalid */
+CiSimulation *ci_simulation_create(const char *scenario_json, uint64_t seed);
+
+/* One step: 0 while the run goes on, 1 once it has ended, -1 for a NULL simulation */
+int32_t ci_simulation_step(CiSimulation *sim);
+
+/* The state after the last step: 0, or -1 for a NULL argument */
+int32_t ci_simulation_state(const CiSimulation *sim, CiState *state);
+
+/* Everything recorded so far as result JSON, to be released with ci_string_free */
+char *ci_simulation_result_json(const CiSimulation *sim);
+
+void ci_string_free(char *text);
+
+void ci_simulation_destroy(CiSimulation *sim);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
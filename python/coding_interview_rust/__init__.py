@@ -0,0 +1,199 @@
+"""Python bindings of the pursuit simulation, over the C API of the shared library (src/ffi.rs,
+include/coding_interview_rust.h) through ctypes, so they need nothing but the library cargo builds:
+
+    pip install .
+    python3 -c "import coding_interview_rust as ci; print(ci.simulate({'guidance': 'zem'}, seed=7)['outcome'])"
+
+`pip install` builds the library with cargo and installs it inside this package (see setup.py).
+The library is looked up in $CODING_INTERVIEW_RUST_LIB, then next to this file, then in
+target/release and target/debug of the checkout, so after `cargo build --release` the package also
+works uninstalled with python/ on the path. Results are the result JSON as `run` saves it, parsed;
+with NumPy installed their trajectories are arrays of steps + 1 rows of (x, y), otherwise lists of
+pairs.
+"""
+
+import ctypes
+import json
+import os
+import sys
+
+try:
+    import numpy as np
+except ImportError:
+    np = None
+
+__all__ = ["GUIDANCE_LAWS", "OUTCOMES", "SimulationError", "Simulation", "State", "simulate", "batch"]
+
+# The laws the "guidance" field of a config takes, as GuidanceLaw::NAMES lists them
+GUIDANCE_LAWS = ("lead_pursuit", "zem", "lqr", "mpc", "clos", "plugin", "neural")
+
+# CiState.outcome, indexed by its CI_ value
+OUTCOMES = ("running", "intercept", "ground_impact", "timeout", "escape", "stall", "miss")
+
+TRAJECTORIES = ("target_positions", "interceptor_positions", "target_velocities", "interceptor_velocities")
+
+
+class SimulationError(Exception):
+    """A scenario the library refused, or a call that failed, with ci_last_error's message"""
+
+
+class State(ctypes.Structure):
+    """Both vehicles after the last step; meters, m/s and seconds, y the height"""
+
+    _fields_ = [
+        ("step", ctypes.c_uint64),
+        ("time", ctypes.c_double),
+        ("target_x", ctypes.c_double),
+        ("target_y", ctypes.c_double),
+        ("target_vx", ctypes.c_double),
+        ("target_vy", ctypes.c_double),
+        ("interceptor_x", ctypes.c_double),
+        ("interceptor_y", ctypes.c_double),
+        ("interceptor_vx", ctypes.c_double),
+        ("interceptor_vy", ctypes.c_double),
+        ("separation", ctypes.c_double),
+        ("miss_distance", ctypes.c_double),
+        ("outcome_code", ctypes.c_int32),
+    ]
+
+    @property
+    def outcome(self):
+        return OUTCOMES[self.outcome_code]
+
+    def __repr__(self):
+        return "State(step={}, time={:.2f}, separation={:.2f}, outcome={})".format(self.step, self.time, self.separation, self.outcome)
+
+
+# File name of the shared library cargo builds on this platform
+LIBRARY = {"darwin": "libcoding_interview_rust.dylib", "win32": "coding_interview_rust.dll"}.get(sys.platform, "libcoding_interview_rust.so")
+
+
+def _library_path():
+    if os.environ.get("CODING_INTERVIEW_RUST_LIB"):
+        return os.environ["CODING_INTERVIEW_RUST_LIB"]
+    package = os.path.dirname(os.path.abspath(__file__))
+    root = os.path.dirname(os.path.dirname(package))
+    candidates = [os.path.join(package, LIBRARY)] + [os.path.join(root, "target", profile, LIBRARY) for profile in ("release", "debug")]
+    for path in candidates:
+        if os.path.exists(path):
+            return path
+    raise OSError("cannot find {}; install the package with `pip install .`, build it with `cargo build --release` or set CODING_INTERVIEW_RUST_LIB".format(LIBRARY))
+
+
+def _load():
+    lib = ctypes.CDLL(_library_path())
+    lib.ci_last_error.restype = ctypes.c_char_p
+    lib.ci_last_error.argtypes = []
+    lib.ci_simulation_create.restype = ctypes.c_void_p
+    lib.ci_simulation_create.argtypes = [ctypes.c_char_p, ctypes.c_uint64]
+    lib.ci_simulation_step.restype = ctypes.c_int32
+    lib.ci_simulation_step.argtypes = [ctypes.c_void_p]
+    lib.ci_simulation_state.restype = ctypes.c_int32
+    lib.ci_simulation_state.argtypes = [ctypes.c_void_p, ctypes.POINTER(State)]
+    # A void pointer rather than c_char_p, so that the string can still be handed back to be freed
+    lib.ci_simulation_result_json.restype = ctypes.c_void_p
+    lib.ci_simulation_result_json.argtypes = [ctypes.c_void_p]
+    lib.ci_string_free.restype = None
+    lib.ci_string_free.argtypes = [ctypes.c_void_p]
+    lib.ci_simulation_destroy.restype = None
+    lib.ci_simulation_destroy.argtypes = [ctypes.c_void_p]
+    return lib
+
+
+_lib = None
+
+
+def _library():
+    global _lib
+    if _lib is None:
+        _lib = _load()
+    return _lib
+
+
+def _error(lib):
+    return SimulationError(lib.ci_last_error().decode("utf-8", "replace"))
+
+
+class Simulation:
+    """One engagement, stepped from Python.
+
+    `scenario` is a scenario document as a dict (or its JSON text), e.g. {"config": {"guidance":
+    "zem"}}, None for the defaults; `config` is a shorthand for {"config": config}. The same
+    scenario and seed give the same run as `run --seed N`.
+    """
+
+    def __init__(self, scenario=None, seed=1, config=None):
+        if config is not None:
+            scenario = dict(scenario or {}, config=config)
+        if isinstance(scenario, dict):
+            scenario = json.dumps(scenario)
+        self._lib = _library()
+        self._sim = self._lib.ci_simulation_create(None if scenario is None else scenario.encode("utf-8"), seed)
+        if not self._sim:
+            raise _error(self._lib)
+        self.seed = seed
+
+    def _handle(self):
+        if not self._sim:
+            raise SimulationError("the simulation has been closed")
+        return self._sim
+
+    def step(self):
+        """One step; True while the run goes on, False once it has ended"""
+        code = self._lib.ci_simulation_step(self._handle())
+        if code < 0:
+            raise _error(self._lib)
+        return code == 0
+
+    def state(self):
+        state = State()
+        if self._lib.ci_simulation_state(self._handle(), ctypes.byref(state)) < 0:
+            raise _error(self._lib)
+        return state
+
+    def result(self):
+        """Everything recorded so far, as `run` saves it"""
+        text = self._lib.ci_simulation_result_json(self._handle())
+        if not text:
+            raise _error(self._lib)
+        try:
+            result = json.loads(ctypes.string_at(text).decode("utf-8"))
+        finally:
+            self._lib.ci_string_free(text)
+        if np is not None:
+            for key in TRAJECTORIES:
+                if key in result:
+                    result[key] = np.array(result[key], dtype=float).reshape(-1, 2)
+        return result
+
+    def run(self):
+        """Steps to the end and returns the result"""
+        while self.step():
+            pass
+        return self.result()
+
+    def close(self):
+        if self._sim:
+            self._lib.ci_simulation_destroy(self._sim)
+            self._sim = None
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *_):
+        self.close()
+
+    def __del__(self):
+        if getattr(self, "_sim", None):
+            self.close()
+
+
+def simulate(config=None, seed=1, scenario=None):
+    """The result of one run of `config` (a config dict, e.g. {"guidance": "zem"}) on top of `scenario`"""
+    with Simulation(scenario, seed, config) as simulation:
+        return simulation.run()
+
+
+def batch(config=None, seeds=range(1, 11), scenario=None):
+    """One result per seed, for parameter studies over a scenario's noise and envelope"""
+    return [simulate(config, seed, scenario) for seed in seeds]
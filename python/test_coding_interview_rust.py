@@ -0,0 +1,61 @@
+"""Tests of the Python bindings against the shared library; after `cargo build --release`:
+
+    python3 -m unittest discover python
+"""
+
+import unittest
+
+import coding_interview_rust as ci
+
+
+class BindingsTest(unittest.TestCase):
+    def test_a_run_steps_to_its_end(self):
+        with ci.Simulation(config={"guidance": "zem"}, seed=7) as simulation:
+            first = simulation.state()
+            self.assertEqual((first.step, first.outcome), (0, "running"))
+            steps = 0
+            while simulation.step():
+                steps += 1
+            last = simulation.state()
+            self.assertNotEqual(last.outcome, "running")
+            result = simulation.result()
+        self.assertEqual(result["outcome"], last.outcome)
+        self.assertAlmostEqual(result["miss_distance"], last.miss_distance)
+        self.assertEqual(len(result["target_positions"]), len(result["interceptor_positions"]))
+        # The start, and a point per step that went on; the intercept ends the run before anything moves
+        self.assertEqual(result["outcome"], "intercept")
+        self.assertEqual(len(result["target_positions"]), steps + 1)
+
+    def test_the_same_seed_gives_the_same_run(self):
+        first, second = ci.simulate({"guidance": "lqr"}, seed=3), ci.simulate({"guidance": "lqr"}, seed=3)
+        self.assertEqual(first["miss_distance"], second["miss_distance"])
+        self.assertEqual(len(first["target_positions"]), len(second["target_positions"]))
+
+    def test_a_batch_has_one_result_per_seed(self):
+        results = ci.batch({"guidance": "zem", "max_steps": 50}, seeds=range(4))
+        self.assertEqual(len(results), 4)
+        self.assertTrue(all(len(result["target_positions"]) <= 51 for result in results))
+
+    def test_trajectories_are_rows_of_points(self):
+        positions = ci.simulate({"max_steps": 20})["target_positions"]
+        if ci.np is not None:
+            self.assertEqual(positions.shape[1], 2)
+        else:
+            self.assertTrue(all(len(point) == 2 for point in positions))
+
+    def test_guidance_laws_are_the_ones_the_library_accepts(self):
+        with self.assertRaises(ci.SimulationError) as refused:
+            ci.Simulation(config={"guidance": "bogus"})
+        self.assertIn(", ".join(ci.GUIDANCE_LAWS), str(refused.exception))
+
+    def test_invalid_scenarios_raise(self):
+        with self.assertRaises(ci.SimulationError):
+            ci.Simulation("{not json")
+        simulation = ci.Simulation()
+        simulation.close()
+        with self.assertRaises(ci.SimulationError):
+            simulation.step()
+
+
+if __name__ == "__main__":
+    unittest.main()
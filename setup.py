@@ -0,0 +1,38 @@
+"""Builds the shared library the Python bindings (python/coding_interview_rust) load and installs it
+inside the package, so that `pip install .` gives a working module; the package metadata is in
+pyproject.toml. With $CODING_INTERVIEW_RUST_LIB set, that library is installed instead of building
+one, and $CARGO_TARGET_DIR is where cargo's output is looked for.
+"""
+
+import os
+import subprocess
+import sys
+
+from setuptools import setup
+from setuptools.command.build_py import build_py
+from setuptools.dist import Distribution
+
+ROOT = os.path.dirname(os.path.abspath(__file__))
+LIBRARY = {"darwin": "libcoding_interview_rust.dylib", "win32": "coding_interview_rust.dll"}.get(sys.platform, "libcoding_interview_rust.so")
+
+
+class BuildWithLibrary(build_py):
+    def run(self):
+        super().run()
+        library = os.environ.get("CODING_INTERVIEW_RUST_LIB")
+        if not library:
+            subprocess.run(["cargo", "build", "--release", "--lib", "--manifest-path", os.path.join(ROOT, "Cargo.toml")], check=True)
+            library = os.path.join(os.environ.get("CARGO_TARGET_DIR", os.path.join(ROOT, "target")), "release", LIBRARY)
+        package = os.path.join(self.build_lib, "coding_interview_rust")
+        self.mkpath(package)
+        self.copy_file(library, os.path.join(package, LIBRARY))
+
+
+class BinaryDistribution(Distribution):
+    """The wheel carries a native library, so it is tagged for the platform it was built on"""
+
+    def has_ext_modules(self):
+        return True
+
+
+setup(cmdclass={"build_py": BuildWithLibrary}, distclass=BinaryDistribution)
@@ -0,0 +1,170 @@
+// A/B comparison: flies the same Monte Carlo batch with two scenarios and tests whether they differ
+// by more than chance, so that a tuning change can be judged on evidence rather than on one lucky
+// batch. Run k of both batches uses seed N + k, which pairs the runs (common random numbers: the
+// same envelope draws and noise as far as the two configurations consume randomness alike), and
+// the tests are the paired ones:
+//
+//   hit rate            Wilson intervals per scenario, McNemar's exact test on the runs only one hit
+//   miss distance, Pk   bootstrap intervals of the means and of the mean paired difference, and a
+//                       sign-flip permutation test on the differences
+//   time to intercept   the same, over the runs both scenarios hit
+//
+// The table goes to the log and to ab.csv in the output directory.
+
+use crate::error::SimError;
+use crate::log;
+use crate::progress::Progress;
+use crate::scenario::Scenario;
+use crate::stats::{bootstrap_mean_interval, mcnemar_p_value, normal_quantile, paired_permutation_p_value, wilson_interval};
+use crate::{simulate_once, Outcome, SimulationResult};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+use std::path::Path;
+
+// One row of the table: the two estimates with their intervals, the difference B - A with its
+// interval, and the p-value of the test for a difference
+struct Comparison {
+    metric: &'static str,
+    a: (f64, (f64, f64)),
+    b: (f64, (f64, f64)),
+    difference: (f64, (f64, f64)),
+    p_value: f64,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// The paired comparison of a metric over the pairs in `pairs`; None without pairs
+fn paired(metric: &'static str, pairs: &[(f64, f64)], confidence: f64) -> Option<Comparison> {
+    let (a, b): (Vec<f64>, Vec<f64>) = pairs.iter().copied().unzip();
+    let differences: Vec<f64> = pairs.iter().map(|(a, b)| b - a).collect();
+    Some(Comparison {
+        metric,
+        a: (mean(&a), bootstrap_mean_interval(&a, confidence)?),
+        b: (mean(&b), bootstrap_mean_interval(&b, confidence)?),
+        difference: (mean(&differences), bootstrap_mean_interval(&differences, confidence)?),
+        p_value: paired_permutation_p_value(&differences)?,
+    })
+}
+
+fn hit_rates(a: &[SimulationResult], b: &[SimulationResult], confidence: f64) -> Comparison {
+    let hit = |sim: &SimulationResult| sim.outcome == Outcome::Intercept;
+    let n = a.len();
+    let (hits_a, hits_b) = (a.iter().filter(|s| hit(s)).count(), b.iter().filter(|s| hit(s)).count());
+    let only_a = a.iter().zip(b).filter(|(a, b)| hit(a) && !hit(b)).count();
+    let only_b = a.iter().zip(b).filter(|(a, b)| !hit(a) && hit(b)).count();
+    // The difference of two paired proportions: the discordant pairs' share, with a Wald interval
+    let difference = (only_b as f64 - only_a as f64) / n as f64;
+    let variance = ((only_a + only_b) as f64 / n as f64 - difference * difference) / n as f64;
+    let half = normal_quantile(0.5 + confidence / 2.0) * variance.max(0.0).sqrt();
+    Comparison {
+        metric: "hit rate",
+        a: (hits_a as f64 / n as f64, wilson_interval(hits_a, n, confidence)),
+        b: (hits_b as f64 / n as f64, wilson_interval(hits_b, n, confidence)),
+        difference: (difference, ((difference - half).max(-1.0), (difference + half).min(1.0))),
+        p_value: mcnemar_p_value(only_a, only_b),
+    }
+}
+
+// Runs `runs` engagements of `scenario`, run k with seed `seed + k`
+fn batch(scenario: &Scenario, runs: usize, seed: u64, progress: &mut Progress) -> Vec<SimulationResult> {
+    (0..runs as u64)
+        .map(|k| {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(k));
+            let config = scenario.envelope.sample(&scenario.config, &mut rng);
+            let sim = simulate_once(&mut rng, &config);
+            progress.inc(sim.outcome == Outcome::Intercept);
+            sim
+        })
+        .collect()
+}
+
+// Usage: ab <a.json> <b.json> [--runs N / -n N] [--seed N] [--confidence C] [--preset NAME]
+//           [--out-dir DIR / -o DIR] [--quiet / -q]
+pub fn run_ab(args: &[String]) -> Result<(), SimError> {
+    let files: Vec<&String> = args.iter().take_while(|a| !a.starts_with('-')).collect();
+    let [file_a, file_b] = files[..] else {
+        return Err("ab needs two scenario files to compare (see `help ab`)".into());
+    };
+    let mut runs: usize = 100;
+    let mut seed: u64 = 1;
+    let mut confidence = 0.95;
+    let mut out_dir = String::from(".");
+    let mut i = 2;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 1).ok_or("--runs must be an integer of at least 2")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--confidence" if i + 1 < args.len() => {
+                confidence = args[i + 1].parse().ok().filter(|c| *c > 0.0 && *c < 1.0).ok_or("--confidence must be between 0 and 1, e.g. 0.95")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    // Both files apply on top of --preset, or of the defaults
+    let base = Scenario::from_args(&args[2..])?;
+    let a = Scenario::load(Path::new(file_a), base.clone())?;
+    let b = Scenario::load(Path::new(file_b), base)?;
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    log::info!("⚖️ A/B comparison of '{}' (A) and '{}' (B), {} paired runs", file_a, file_b, runs);
+    let mut progress = Progress::new("Runs", 2 * runs, true);
+    let (sims_a, sims_b) = (batch(&a, runs, seed, &mut progress), batch(&b, runs, seed, &mut progress));
+    progress.finish();
+
+    let pairs = |metric: fn(&SimulationResult) -> Option<f64>| -> Vec<(f64, f64)> {
+        sims_a.iter().zip(&sims_b).filter_map(|(a, b)| Some((metric(a)?, metric(b)?))).collect()
+    };
+    let time_to_intercept = |sim: &SimulationResult| (sim.outcome == Outcome::Intercept).then(|| sim.steps() as f64 * sim.config.dt);
+    let rows: Vec<Comparison> = [
+        Some(hit_rates(&sims_a, &sims_b, confidence)),
+        paired("miss distance", &pairs(|sim| Some(sim.miss_distance).filter(|m| m.is_finite())), confidence),
+        paired("Pk", &pairs(|sim| Some(sim.pk())), confidence),
+        paired("time to intercept", &pairs(time_to_intercept), confidence),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let csv_path = Path::new(&out_dir).join("ab.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "metric,a,a_low,a_high,b,b_low,b_high,difference,difference_low,difference_high,p_value,significant")?;
+    let (percent, alpha) = (confidence * 100.0, 1.0 - confidence);
+    log::info!("   {:<18} {:>26} {:>26} {:>28} {:>8}", "metric", "A", "B", "B - A", "p");
+    for row in &rows {
+        let significant = row.p_value < alpha;
+        let (a, b, d) = (row.a, row.b, row.difference);
+        writeln!(
+            csv,
+            "{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6},{}",
+            row.metric, a.0, a.1 .0, a.1 .1, b.0, b.1 .0, b.1 .1, d.0, d.1 .0, d.1 .1, row.p_value, significant
+        )?;
+        let cell = |(value, (low, high)): (f64, (f64, f64))| format!("{:.3} [{:.3}, {:.3}]", value, low, high);
+        log::info!(
+            "   {:<18} {:>26} {:>26} {:>28} {:>8.4}{}",
+            row.metric,
+            cell(a),
+            cell(b),
+            cell(d),
+            row.p_value,
+            if significant { " *" } else { "" }
+        );
+    }
+    log::info!("   [..] are {}% intervals; * marks a difference significant at p < {:.3}", percent, alpha);
+    log::info!("✅ A/B comparison saved as '{}'", csv_path.display());
+    Ok(())
+}
@@ -0,0 +1,89 @@
+// The interceptor's control actuators, for stress-testing guidance against an imperfect airframe.
+// Without an actuator section the interceptor turns exactly as guidance (and the autopilot lag)
+// asks. With one, e.g.
+//
+//   "actuator": { "max_accel": 3, "accel_noise": 0.2, "fault_rate": 0.02, "fault_duration": 2 }
+//
+// the lateral acceleration it achieves is limited to `max_accel` m/s² (no limit when left out),
+// disturbed by Gaussian noise of standard deviation `accel_noise` m/s², and the actuators fail
+// intermittently: a fault starts at random `fault_rate` times per second of flight on average and
+// lasts `fault_duration` seconds on average (both exponentially distributed), during which the
+// interceptor achieves no lateral acceleration at all and flies straight on. Faults are logged as
+// actuator_fault and actuator_recovered events.
+//
+// Noise and faults draw from the run's RNG only when they are switched on, so adding a saturation
+// limit alone leaves the rest of a seeded run's random sequence unchanged.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::noise::standard_normal;
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+use rand::{Rng, RngCore};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Actuator {
+    pub max_accel: Option<f64>, // Largest lateral acceleration (m/s²) achieved
+    pub accel_noise: f64,       // Standard deviation (m/s²) of the noise on the achieved acceleration
+    pub fault_rate: f64,        // Faults per second of flight, on average
+    pub fault_duration: f64,    // Mean seconds until a fault clears
+}
+
+impl Actuator {
+    // Whether the actuators are faulted this step, given whether they were in the last one
+    pub fn faulted(&self, rng: &mut dyn RngCore, was_faulted: bool, dt: f64) -> bool {
+        match was_faulted {
+            true => !rng.gen_bool(1.0 - (-dt / self.fault_duration).exp()),
+            false => self.fault_rate > 0.0 && rng.gen_bool(1.0 - (-dt * self.fault_rate).exp()),
+        }
+    }
+
+    // The velocity the interceptor reaches from `velocity` at `speed` when the autopilot asks for
+    // `desired`: the turn between them as a lateral acceleration, limited and disturbed
+    pub fn respond(&self, rng: &mut dyn RngCore, velocity: Vec2, desired: Vec2, speed: f64, dt: f64) -> Vec2 {
+        let Some(heading) = velocity.normalized() else { return desired };
+        let mut accel = heading.cross(desired).atan2(heading.dot(desired)) * speed / dt;
+        if let Some(max_accel) = self.max_accel {
+            accel = accel.clamp(-max_accel, max_accel);
+        }
+        if self.accel_noise > 0.0 {
+            accel += self.accel_noise * standard_normal(rng);
+        }
+        heading.rotated(accel * dt / speed) * speed
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        let positive = |x: f64| x > 0.0 && x.is_finite();
+        v.check(self.max_accel.is_none_or(positive), "max_accel", "must be positive");
+        v.check(self.accel_noise >= 0.0 && self.accel_noise.is_finite(), "accel_noise", "must be zero or positive");
+        v.check(self.fault_rate >= 0.0 && self.fault_rate.is_finite(), "fault_rate", "must be zero or a positive number of faults per second");
+        v.check(positive(self.fault_duration), "fault_duration", "must be a positive number of seconds");
+    }
+}
+
+impl ToJson for Actuator {
+    fn to_json(&self) -> Json {
+        let max_accel = self.max_accel.map(|max_accel| ("max_accel", max_accel.into()));
+        Json::object(
+            max_accel
+                .into_iter()
+                .chain([
+                    ("accel_noise", self.accel_noise.into()),
+                    ("fault_rate", self.fault_rate.into()),
+                    ("fault_duration", self.fault_duration.into()),
+                ])
+                .collect(),
+        )
+    }
+}
+
+// Everything is optional: no limit, no noise and no faults, each fault lasting a second
+impl FromJson for Actuator {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Ok(Actuator {
+            max_accel: json.get("max_accel").map(|_| json.f64_field("max_accel")).transpose()?,
+            accel_noise: json.f64_field_or("accel_noise", 0.0)?,
+            fault_rate: json.f64_field_or("fault_rate", 0.0)?,
+            fault_duration: json.f64_field_or("fault_duration", 1.0)?,
+        })
+    }
+}
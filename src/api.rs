@@ -0,0 +1,340 @@
+// REST API: `api` turns the program into a simulation service for a team. It listens on
+// http://ADDR (--bind ADDR, default 127.0.0.1:8080) and flies the engagements it is sent one after
+// another in the background, keeping their results in memory until it is stopped:
+//
+//   POST /simulations              a scenario document as the body, optionally with a "seed";
+//                                  answers 201 with {"id": 1, "status": "queued", "seed": N}
+//   GET  /simulations              every simulation's id and status
+//   GET  /simulations/ID           its status (queued, running, done or failed), seed and, once
+//                                  done, outcome, steps, miss_distance and pk, or the error
+//   GET  /simulations/ID/result    the result JSON, as `run` saves it
+//   GET  /simulations/ID/plot      the rendered plot, written to the --out-dir as simulation_ID
+//
+// The scenario is taken on top of --preset or --scenario as given on the command line, and its
+// envelope (if any) is sampled with the run's seed; config fields that would name a file on
+// the server (guidance_plugin, guidance_model, evasion_model, and target_path or target_script
+// as a file name) are refused. Errors are {"error": "..."} with a 4xx status. Like serve.rs this
+// is plain HTTP/1.1 on std::net: one request per connection, each on its own thread, no TLS.
+
+use crate::checkpoint::SimRng;
+use crate::error::SimError;
+use crate::json::{Json, ToJson};
+use crate::log;
+use crate::plot::{visualize_simulation, PlotOptions};
+use crate::scenario::Scenario;
+use crate::{simulate_once, SimulationResult};
+use rand::{Rng, SeedableRng};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+// Largest request body accepted, a scenario with a long inline target path included
+const MAX_BODY: usize = 16 << 20;
+
+// Config fields that name a file when given as a string. A client must not make the service open
+// files on the server, so these are refused; target_path and target_script may still be inline.
+const FILE_FIELDS: [&str; 5] = ["target_path", "guidance_plugin", "guidance_model", "target_script", "evasion_model"];
+
+enum Status {
+    Queued,
+    Running,
+    Done(Box<SimulationResult>, Option<PathBuf>), // The result and its plot, if one was drawn
+    Failed(String),
+}
+
+struct Job {
+    seed: u64,
+    status: Status,
+}
+
+impl Job {
+    fn summary(&self, id: usize) -> Json {
+        let status = match self.status {
+            Status::Queued => "queued",
+            Status::Running => "running",
+            Status::Done(..) => "done",
+            Status::Failed(_) => "failed",
+        };
+        let mut fields = vec![("id", id.into()), ("status", status.into()), ("seed", (self.seed as usize).into())];
+        match &self.status {
+            Status::Done(sim, _) => fields.extend([
+                ("outcome", sim.outcome.as_str().into()),
+                ("steps", sim.steps().into()),
+                ("miss_distance", sim.miss_distance.into()),
+                ("pk", sim.pk().into()),
+            ]),
+            Status::Failed(e) => fields.push(("error", e.as_str().into())),
+            Status::Queued | Status::Running => {}
+        }
+        Json::object(fields)
+    }
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, json: &Json) -> Response {
+        Response { status, content_type: "application/json", body: (json.to_string_pretty() + "\n").into_bytes() }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::json(status, &Json::object(vec![("error", message.into().as_str().into())]))
+    }
+
+    fn write_to(&self, mut stream: &TcpStream) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            201 => "Created",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)
+    }
+}
+
+// What the service needs to take and run submissions
+struct Service {
+    base: Scenario,
+    plot_options: PlotOptions,
+    out_dir: PathBuf,
+    jobs: Arc<Mutex<Vec<Job>>>,
+    queue: mpsc::Sender<(usize, Scenario)>,
+}
+
+impl Service {
+    fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let job_id = |id: &str| id.parse::<usize>().ok().filter(|id| (1..=self.jobs.lock().unwrap().len()).contains(id));
+        match (method, parts.as_slice()) {
+            ("POST", ["simulations"]) => self.submit(body),
+            ("GET", ["simulations"]) => {
+                let jobs = self.jobs.lock().unwrap();
+                Response::json(200, &Json::Array(jobs.iter().enumerate().map(|(i, job)| job.summary(i + 1)).collect()))
+            }
+            ("GET", ["simulations", id, rest @ ..]) => {
+                let Some(id) = job_id(id) else { return Response::error(404, format!("no simulation '{}'", id)) };
+                let jobs = self.jobs.lock().unwrap();
+                let job = &jobs[id - 1];
+                match (rest, &job.status) {
+                    ([], _) => Response::json(200, &job.summary(id)),
+                    (["result"], Status::Done(sim, _)) => Response::json(200, &sim.to_json()),
+                    (["plot"], Status::Done(_, Some(plot))) => match std::fs::read(plot) {
+                        Ok(image) => {
+                            let svg = plot.extension().is_some_and(|e| e == "svg");
+                            Response { status: 200, content_type: if svg { "image/svg+xml" } else { "image/png" }, body: image }
+                        }
+                        Err(e) => Response::error(500, format!("cannot read '{}': {}", plot.display(), e)),
+                    },
+                    (["plot"], Status::Done(_, None)) => Response::error(404, "plots are off (--no-plot, or a build without the plot feature)"),
+                    (["result" | "plot"], Status::Failed(e)) => Response::error(409, format!("simulation {} failed: {}", id, e)),
+                    (["result" | "plot"], _) => Response::error(409, format!("simulation {} hasn't finished yet", id)),
+                    _ => Response::error(404, format!("no such resource '{}'", path)),
+                }
+            }
+            (_, ["simulations", ..]) => Response::error(405, format!("{} isn't allowed on '{}'", method, path)),
+            _ => Response::error(404, format!("no such resource '{}'", path)),
+        }
+    }
+
+    fn submit(&self, body: &[u8]) -> Response {
+        let json = match std::str::from_utf8(body).map_err(|e| e.to_string()).and_then(|text| Json::parse(text).map_err(|e| e.to_string())) {
+            Ok(json) => json,
+            Err(e) => return Response::error(400, format!("the body must be a scenario in JSON: {}", e)),
+        };
+        if let Some(field) = json.get("config").and_then(|config| FILE_FIELDS.into_iter().find(|&field| matches!(config.get(field), Some(Json::String(_))))) {
+            return Response::error(400, format!("config: '{}' names a file on the server, which the service doesn't open", field));
+        }
+        let scenario = match Scenario::from_json_with_defaults(&json, self.base.clone()) {
+            Ok(scenario) => scenario,
+            Err(e) => return Response::error(400, e),
+        };
+        // Seeds are stored as JSON numbers, which hold integers exactly up to 2^53
+        let seed = match json.get("seed") {
+            Some(seed) => match seed.as_usize().filter(|&s| s <= 1 << 53) {
+                Some(seed) => seed as u64,
+                None => return Response::error(400, "'seed' must be a non-negative integer of at most 2^53"),
+            },
+            None => rand::thread_rng().gen_range(0..=1 << 53),
+        };
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.push(Job { seed, status: Status::Queued });
+        let id = jobs.len();
+        drop(jobs);
+        if self.queue.send((id, scenario)).is_err() {
+            return Response::error(500, "the simulation worker has stopped");
+        }
+        log::info!("📥 Simulation {} queued (seed {})", id, seed);
+        Response::json(201, &self.jobs.lock().unwrap()[id - 1].summary(id))
+    }
+
+    // Flies the queued simulations in order, until the service stops
+    fn work(&self, queue: mpsc::Receiver<(usize, Scenario)>) {
+        for (id, scenario) in queue {
+            let seed = self.set_status(id, Status::Running);
+            let mut rng = SimRng::seed_from_u64(seed);
+            let config = match scenario.envelope.is_empty() {
+                true => scenario.config.clone(),
+                false => scenario.envelope.sample(&scenario.config, &mut rng),
+            };
+            let sim = simulate_once(&mut rng, &config);
+            let status = match self.draw(id, &sim, scenario) {
+                Ok(plot) => {
+                    log::info!("🎯 Simulation {}: {}, Pk {:.2}", id, sim.outcome.as_str(), sim.pk());
+                    Status::Done(Box::new(sim), plot)
+                }
+                Err(e) => {
+                    log::info!("❌ Simulation {}: {}", id, e);
+                    Status::Failed(e.to_string())
+                }
+            };
+            self.set_status(id, status);
+        }
+    }
+
+    fn draw(&self, id: usize, sim: &SimulationResult, scenario: Scenario) -> Result<Option<PathBuf>, SimError> {
+        if !self.plot_options.enabled {
+            return Ok(None);
+        }
+        let options = PlotOptions { style: scenario.style, ..self.plot_options.clone() };
+        let path = self.out_dir.join(format!("simulation_{:03}.{}", id, options.format.extension()));
+        visualize_simulation(sim, &path.to_string_lossy(), &options)?;
+        Ok(Some(path))
+    }
+
+    // Returns the job's seed
+    fn set_status(&self, id: usize, status: Status) -> u64 {
+        let job = &mut self.jobs.lock().unwrap()[id - 1];
+        job.status = status;
+        job.seed
+    }
+}
+
+// The request line's method and path, and the body
+fn read_request(stream: &TcpStream) -> Result<(String, String, Vec<u8>), Response> {
+    let bad = |e: std::io::Error| Response::error(400, format!("cannot read the request: {}", e));
+    stream.set_read_timeout(Some(Duration::from_secs(10))).map_err(bad)?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(bad)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else {
+        return Err(Response::error(400, "not an HTTP request"));
+    };
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    let method = method.to_string();
+    let mut length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(bad)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().map_err(|_| Response::error(400, "Content-Length must be a number"))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Response::error(413, format!("the body may be at most {} bytes", MAX_BODY)));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(bad)?;
+    Ok((method, path, body))
+}
+
+// Usage: api [--bind ADDR] [--out-dir DIR] [--preset NAME] [--scenario FILE] [plot options]
+pub fn run_api(args: &[String], base: Scenario) -> Result<(), SimError> {
+    let addr = args.windows(2).find(|pair| pair[0] == "--bind").map_or(DEFAULT_ADDR, |pair| pair[1].as_str());
+    let out_dir = PathBuf::from(args.windows(2).find(|pair| pair[0] == "--out-dir" || pair[0] == "-o").map_or(".", |pair| pair[1].as_str()));
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir.display())))?;
+    let plot_options = PlotOptions::from_args(args)?;
+    let listener = TcpListener::bind(addr).map_err(SimError::io(format!("cannot listen on '{}'", addr)))?;
+    let addr = listener.local_addr().map_or_else(|_| addr.to_string(), |a| a.to_string());
+
+    let (queue, queued) = mpsc::channel();
+    let service = Arc::new(Service { base, plot_options, out_dir, jobs: Arc::new(Mutex::new(Vec::new())), queue });
+    let worker = Arc::clone(&service);
+    std::thread::spawn(move || worker.work(queued));
+    log::info!("🛰️ Simulation service listening on http://{}/simulations", addr);
+
+    // A connection per thread, so that a slow client only holds up its own request
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let service = Arc::clone(&service);
+        std::thread::spawn(move || {
+            let response = match read_request(&stream) {
+                Ok((method, path, body)) => {
+                    let response = service.handle(&method, &path, &body);
+                    log::debug!("{} {} -> {}", method, path, response.status);
+                    response
+                }
+                Err(response) => response,
+            };
+            if let Err(e) = response.write_to(&stream) {
+                log::debug!("cannot answer a request: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> (Service, mpsc::Receiver<(usize, Scenario)>) {
+        let (queue, queued) = mpsc::channel();
+        let base = Scenario::preset("head-on").unwrap();
+        (Service { base, plot_options: PlotOptions::default(), out_dir: PathBuf::from("."), jobs: Arc::new(Mutex::new(Vec::new())), queue }, queued)
+    }
+
+    #[test]
+    fn file_names_are_refused_without_being_opened() {
+        let (service, queued) = service();
+        for field in FILE_FIELDS {
+            let body = format!(r#"{{"config": {{"{}": "/etc/passwd"}}}}"#, field);
+            let response = service.submit(body.as_bytes());
+            assert_eq!(response.status, 400, "{}", field);
+            let text = String::from_utf8(response.body).unwrap();
+            assert!(text.contains(field) && !text.contains("passwd"), "{}", text);
+        }
+        assert!(queued.try_recv().is_err());
+    }
+
+    #[test]
+    fn deeply_nested_bodies_are_refused() {
+        let (service, queued) = service();
+        let response = service.submit("[".repeat(200_000).as_bytes());
+        assert_eq!(response.status, 400);
+        assert!(String::from_utf8(response.body).unwrap().contains("nested too deeply"));
+        assert!(queued.try_recv().is_err());
+    }
+
+    #[test]
+    fn scenarios_without_files_are_queued() {
+        let (service, queued) = service();
+        let response = service.submit(br#"{"config": {"guidance": "zem", "target_path": null}, "seed": 7}"#);
+        assert_eq!(response.status, 201);
+        assert_eq!(queued.try_recv().unwrap().0, 1);
+    }
+}
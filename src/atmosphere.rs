@@ -0,0 +1,101 @@
+// Altitude-dependent atmosphere: the air density of the International Standard Atmosphere, which
+// sets how hard the interceptor can turn and how fast drag slows it. Without it the interceptor
+// flies at interceptor_speed and turns as hard as guidance asks (up to the actuator's max_accel).
+// A scenario enables it with
+//
+//   "atmosphere": { "altitude": 9000, "drag": 0.002, "max_accel": 40 }
+//
+//   altitude   altitude (m above sea level) of height 0; default the frame's origin, else sea level
+//   drag       deceleration per unit of speed squared at sea level (1/m): the interceptor slows by
+//              drag · σ · v² m/s², with σ the density relative to sea level (default 0, no drag)
+//   max_accel  lateral acceleration (m/s²) the airframe pulls at sea level and the launch speed;
+//              at density σ and speed v it can pull max_accel · σ · (v / interceptor_speed)²
+//              (default no limit)
+//
+// There is no thrust, so with drag the interceptor coasts: it keeps the heading guidance commands
+// but loses speed, faster low down where the air is thick, and with it the ability to turn, which
+// thin air takes away too. High- and low-altitude engagements differ accordingly, and the
+// termination's min_speed becomes a real end for a long chase. Only the interceptor is affected.
+
+use crate::frames::Frame;
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+
+// Sea-level density (kg/m³) and temperature (K), tropospheric lapse rate (K/m), the exponent
+// g / (R · lapse) - 1 of the density's power law, the tropopause and the scale height above it (m)
+const RHO0: f64 = 1.225;
+const T0: f64 = 288.15;
+const LAPSE: f64 = 0.0065;
+const EXPONENT: f64 = 4.255_876;
+const TROPOPAUSE: f64 = 11_000.0;
+const SCALE_HEIGHT: f64 = 6_341.6;
+
+// The standard atmosphere's density (kg/m³) at `altitude` meters above sea level: a power law
+// of the linearly falling temperature in the troposphere, isothermal and exponential above it
+pub fn density(altitude: f64) -> f64 {
+    let troposphere = |h: f64| RHO0 * (1.0 - LAPSE * h / T0).powf(EXPONENT);
+    match altitude <= TROPOPAUSE {
+        true => troposphere(altitude),
+        false => troposphere(TROPOPAUSE) * (-(altitude - TROPOPAUSE) / SCALE_HEIGHT).exp(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Atmosphere {
+    pub altitude: Option<f64>,  // Altitude (m) of height 0, None for the frame's origin or sea level
+    pub drag: f64,              // Deceleration per speed squared at sea level (1/m)
+    pub max_accel: Option<f64>, // Lateral acceleration (m/s²) at sea level and the launch speed
+}
+
+impl Atmosphere {
+    // The density at `height` in the simulation relative to sea level
+    pub fn relative_density(&self, height: f64, frame: Option<Frame>) -> f64 {
+        let base = self.altitude.or(frame.map(|frame| frame.origin.alt)).unwrap_or(0.0);
+        density(base + height) / RHO0
+    }
+
+    // The velocity the interceptor reaches from `from` at `height` instead of `velocity`: the turn
+    // limited to what the air lets it pull, the speed reduced by the drag over the step
+    pub fn fly(&self, from: Vec2, velocity: Vec2, height: f64, frame: Option<Frame>, launch_speed: f64, dt: f64) -> Vec2 {
+        let (sigma, speed) = (self.relative_density(height, frame), from.length());
+        if speed == 0.0 {
+            return velocity;
+        }
+        let mut turn = from.cross(velocity).atan2(from.dot(velocity));
+        if let Some(max_accel) = self.max_accel {
+            let max_turn = max_accel * sigma * (speed / launch_speed).powi(2) * dt / speed;
+            turn = turn.clamp(-max_turn, max_turn);
+        }
+        // dv/dt = -k·v² solved over the step, which stays stable however thick the air
+        let slowed = speed / (1.0 + self.drag * sigma * speed * dt);
+        from.rotated(turn) * (slowed / speed)
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.altitude.is_none_or(|h| (-500.0..=80_000.0).contains(&h)), "altitude", "must be between -500 and 80000 m");
+        v.check(self.drag >= 0.0 && self.drag.is_finite(), "drag", "must be zero or positive");
+        v.check(self.max_accel.is_none_or(|a| a > 0.0 && a.is_finite()), "max_accel", "must be a positive acceleration");
+    }
+}
+
+impl ToJson for Atmosphere {
+    fn to_json(&self) -> Json {
+        let altitude = self.altitude.map(|altitude| ("altitude", altitude.into()));
+        let max_accel = self.max_accel.map(|max_accel| ("max_accel", max_accel.into()));
+        Json::object(altitude.into_iter().chain([("drag", self.drag.into())]).chain(max_accel).collect())
+    }
+}
+
+impl FromJson for Atmosphere {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        if !matches!(json, Json::Object(_)) {
+            return Err(String::from("must be an object with altitude, drag and/or max_accel"));
+        }
+        let optional = |name: &str| match json.get(name) {
+            None | Some(Json::Null) => Ok(None),
+            Some(_) => json.f64_field(name).map(Some),
+        };
+        Ok(Atmosphere { altitude: optional("altitude")?, drag: json.f64_field_or("drag", 0.0)?, max_accel: optional("max_accel")? })
+    }
+}
@@ -0,0 +1,94 @@
+// Axis extents of the plots and the terminal view, computed from a result without drawing anything
+
+use crate::engagement;
+use crate::{GuidanceLaw, SimulationResult};
+
+// Axis extents shared by every frame of a plot so that animations don't rescale
+#[derive(Debug, Clone, Copy)]
+pub struct PlotBounds {
+    pub max_x: f64,
+    pub max_y: f64,
+    pub first_step: usize, // Steps of the per-step panels: the part of the run still recorded
+    pub max_step: usize,
+    pub max_distance: f64,
+    pub max_speed: f64,
+    pub heading_range: (f64, f64),
+    pub los_range: (f64, f64),
+    pub los_rate_range: (f64, f64),
+    pub range_rate_range: (f64, f64),
+}
+
+impl PlotBounds {
+    pub fn from_result(sim: &SimulationResult) -> Self {
+        let target_positions = &sim.target_positions;
+        let interceptor_positions = &sim.interceptor_positions;
+
+        // Calculate dynamic boundaries based on data, including the CLOS ground station
+        let station = (sim.config.guidance == GuidanceLaw::Clos).then_some((sim.config.ground_station_x, sim.config.ground_station_y));
+        let max_x = target_positions
+            .iter()
+            .chain(interceptor_positions.iter())
+            .chain(station.iter())
+            .map(|(x, _)| *x)
+            .fold(0.0, f64::max)
+            .max(10.0) * 1.1; // Add 10% padding
+
+        let max_y = target_positions
+            .iter()
+            .chain(interceptor_positions.iter())
+            .chain(station.iter())
+            .map(|(_, y)| *y)
+            .fold(0.0, f64::max)
+            .max(10.0) * 1.1; // Add 10% padding
+
+        let max_distance = sim
+            .separations()
+            .into_iter()
+            .fold(end_radius(sim).1, f64::max) * 1.05;
+
+        let velocities = || sim.target_velocities.iter().chain(&sim.interceptor_velocities);
+        let max_speed = velocities().map(|(vx, vy)| vx.hypot(*vy)).fold(1.0, f64::max) * 1.1;
+        let heading_range = padded_range(&headings_deg(&velocities().copied().collect::<Vec<_>>()), 10.0);
+        let los_range = padded_range(&sim.los_angles_deg(), 10.0);
+        let los_rate_range = padded_range(&sim.los_rates_deg(), 1.0);
+        let range_rates: Vec<f64> = engagement::history(sim).iter().map(|e| -e.closing_velocity).collect();
+        // The zero line stays in view, it separates closing from opening
+        let range_rate_range = padded_range(&[range_rates, vec![0.0]].concat(), 0.5);
+
+        PlotBounds {
+            max_x,
+            max_y,
+            first_step: sim.first_step,
+            max_step: sim.steps().max(sim.first_step + 1),
+            max_distance,
+            max_speed,
+            heading_range,
+            los_range,
+            los_rate_range,
+            range_rate_range,
+        }
+    }
+}
+
+// Min/max of the values widened by `pad` on both sides; a symmetric fallback when there is no data
+fn padded_range(values: &[f64], pad: f64) -> (f64, f64) {
+    let min = values.iter().copied().filter(|v| v.is_finite()).fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().filter(|v| v.is_finite()).fold(f64::NEG_INFINITY, f64::max);
+    if min <= max {
+        (min - pad, max + pad)
+    } else {
+        (-pad, pad)
+    }
+}
+
+// Separation at which the run ends: the fuze's detonation radius, otherwise the collision threshold
+pub fn end_radius(sim: &SimulationResult) -> (&'static str, f64) {
+    match sim.config.fuze {
+        Some(fuze) => ("Detonation radius", fuze.detonation_radius),
+        None => ("Collision threshold", sim.config.collision_threshold),
+    }
+}
+
+pub fn headings_deg(velocities: &[(f64, f64)]) -> Vec<f64> {
+    velocities.iter().map(|(vx, vy)| vy.atan2(*vx).to_degrees()).collect()
+}
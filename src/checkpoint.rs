@@ -0,0 +1,124 @@
+// Checkpoints of a run in progress, written every N steps with --checkpoint-every so a long
+// simulation can be picked up again with `resume` after it was interrupted.
+//
+// A checkpoint is the partial SimulationResult (configuration, event log, position and velocity
+// histories; the vehicles' current state is their last recorded entry) plus the RNG state and the
+// run number. Resuming continues with exactly the random sequence the original run would have
+// drawn, so the finished result is identical to an uninterrupted one.
+
+use crate::error::SimError;
+use crate::json::{FromJson, Json, ToJson};
+use crate::SimulationResult;
+use rand::{Error, RngCore, SeedableRng};
+use std::path::Path;
+
+// xoshiro256** (Blackman & Vigna). Used for the main runs instead of the thread RNG because its
+// whole state is four integers that fit in a checkpoint file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimRng {
+    state: [u64; 4],
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for SimRng {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: [u8; 32]) -> SimRng {
+        let mut state = [0; 4];
+        for (word, bytes) in state.iter_mut().zip(seed.chunks_exact(8)) {
+            *word = u64::from_le_bytes(bytes.try_into().unwrap());
+        }
+        // The all-zero state is a fixed point of the generator
+        if state == [0; 4] {
+            return SimRng::seed_from_u64(0);
+        }
+        SimRng { state }
+    }
+}
+
+// u64 words don't survive a round trip through JSON numbers (f64), so they are stored as hex strings
+impl ToJson for SimRng {
+    fn to_json(&self) -> Json {
+        Json::Array(self.state.iter().map(|word| Json::String(format!("{:016x}", word))).collect())
+    }
+}
+
+impl FromJson for SimRng {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let error = "field 'rng' must be four 64-bit hex strings";
+        let words = json.as_array().filter(|words| words.len() == 4).ok_or(error)?;
+        let mut state = [0; 4];
+        for (word, json) in state.iter_mut().zip(words) {
+            *word = json.as_str().and_then(|s| u64::from_str_radix(s, 16).ok()).ok_or(error)?;
+        }
+        Ok(SimRng { state })
+    }
+}
+
+pub struct Checkpoint {
+    pub run: usize,
+    pub rng: SimRng,
+    pub result: SimulationResult,
+}
+
+impl Checkpoint {
+    pub fn load(path: &Path) -> Result<Checkpoint, SimError> {
+        let text = std::fs::read_to_string(path).map_err(SimError::io(format!("cannot read checkpoint '{}'", path.display())))?;
+        let json = Json::parse(&text).map_err(|e| SimError::invalid_scenario(path, e))?;
+        let checkpoint = (|| -> Result<Checkpoint, String> {
+            Ok(Checkpoint {
+                run: json.field("run")?.as_usize().ok_or("field 'run' must be a non-negative integer")?,
+                rng: SimRng::from_json(json.field("rng")?)?,
+                result: SimulationResult::from_json(json.field("state")?)?,
+            })
+        })();
+        checkpoint.map_err(|e| SimError::invalid_scenario(path, e))
+    }
+}
+
+// Written to a temporary file first and then renamed, so an interruption while saving leaves the
+// previous checkpoint intact
+pub fn save(path: &Path, run: usize, rng: &SimRng, result: &SimulationResult) -> Result<(), SimError> {
+    let json = Json::object(vec![("run", run.into()), ("rng", rng.to_json()), ("state", result.to_json())]);
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json.to_string_pretty() + "\n").map_err(SimError::io(format!("cannot write '{}'", tmp.display())))?;
+    std::fs::rename(&tmp, path).map_err(SimError::io(format!("cannot write checkpoint '{}'", path.display())))
+}
+
+// --checkpoint-every N: steps between checkpoints, None when not checkpointing
+pub fn interval_from_args(args: &[String]) -> Result<Option<usize>, String> {
+    match args.windows(2).find(|pair| pair[0] == "--checkpoint-every") {
+        Some(pair) => Ok(Some(pair[1].parse().ok().filter(|n| *n > 0).ok_or("--checkpoint-every must be a positive number of steps")?)),
+        None => Ok(None),
+    }
+}
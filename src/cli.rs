@@ -0,0 +1,414 @@
+// Subcommands of the binary and the options each one takes. Every command parses its own options
+// (see the run_* functions); the tables here are for `help` and for rejecting options a command
+// doesn't take, which it would otherwise ignore without a word.
+
+pub struct Command {
+    pub name: &'static str,
+    pub usage: &'static str, // Arguments after the command name
+    pub summary: &'static str,
+    pub flags: &'static [&'static [Flag]],
+}
+
+// One option: its names (the long one first), the value it takes, empty for none, and what it does
+pub struct Flag {
+    pub names: &'static [&'static str],
+    pub value: &'static str,
+    pub help: &'static str,
+}
+
+const fn flag(names: &'static [&'static str], value: &'static str, help: &'static str) -> Flag {
+    Flag { names, value, help }
+}
+
+const LOGGING: &[Flag] = &[
+    flag(&["--verbose", "-v"], "", "log events and timings on stderr"),
+    flag(&["-vv"], "", "also log every step"),
+    flag(&["--quiet", "-q"], "", "no status lines or progress bar"),
+];
+const SCENARIO: &[Flag] = &[
+    flag(&["--scenario"], "FILE", "simulation parameters and plot styling from a scenario file"),
+    flag(&["--preset"], "NAME", "a built-in scenario, which --scenario then overrides field by field"),
+];
+const PLOT: &[Flag] = &[
+    flag(&["--format"], "png|svg", "image format of the plots (default png)"),
+    flag(&["--legend"], "POS", "legend position, e.g. upper-left, or none (default lower-right)"),
+    flag(&["--panels"], "LIST", "diagnostic panels below the chart, e.g. distance,speed,heading"),
+    flag(&["--zoom"], "M", "magnified chart of the last M meters around the end point"),
+    flag(&["--size"], "WxH", "plot size at 96 dpi (default 1400x900)"),
+    flag(&["--dpi"], "N", "resolution, scaling the image with its fonts and lines (default 96)"),
+    flag(&["--annotate"], "N", "label every Nth point with its step number"),
+    flag(&["--arrows"], "N", "velocity arrowhead on every Nth point"),
+    flag(&["--marker-every"], "N", "position markers only on every Nth step (default 1)"),
+    flag(&["--equal-aspect"], "", "the same scale on both axes"),
+    flag(&["--annotate-events"], "", "label the events on the chart with their time"),
+    flag(&["--no-plot"], "", "write no images, only CSV and JSON"),
+];
+const EXPORTS: &[Flag] = &[
+    flag(&["--view3d"], "", "also a perspective view with the step as third axis"),
+    flag(&["--view3d-gif"], "", "also that view as a GIF rotating once around"),
+    flag(&["--azimuth"], "DEG", "camera azimuth of the 3D views (default 30)"),
+    flag(&["--elevation"], "DEG", "camera elevation of the 3D views (default 15)"),
+    flag(&["--video"], "", "also an MP4 animation of each run (needs ffmpeg)"),
+    flag(&["--video-size"], "WxH", "video size, both even (default 1280x720)"),
+    flag(&["--video-fps"], "N", "video frame rate (default 30)"),
+    flag(&["--video-speed"], "STEPS", "simulation steps per second of video (default 10)"),
+    flag(&["--export-kml"], "", "also the run as KML, placed by the scenario's frame"),
+    flag(&["--export-geojson"], "", "also the run as GeoJSON, placed by the scenario's frame"),
+    flag(&["--reachability"], "[T1,T2,...]", "also where the interceptor can be T seconds after launch"),
+    flag(&["--reach-accel"], "A", "lateral acceleration limit of the reachable sets (m/s²)"),
+];
+const SEED: Flag = flag(&["--seed"], "N", "random seed, for reproducible runs (default 1)");
+const OUT_DIR: Flag = flag(&["--out-dir", "-o"], "DIR", "output directory (default .)");
+const EVENTS: Flag = flag(&["--events"], "", "print each run's event log as a timeline");
+const ACCEL_THRESHOLD: Flag = flag(&["--accel-threshold"], "M/S2", "lateral acceleration the geometry report counts as hard (default 1)");
+const UNITS: Flag = flag(&["--units"], "si|aviation", "units of the printed reports (default si)");
+const CHECKPOINT_EVERY: Flag = flag(&["--checkpoint-every"], "N", "save the run's state every N steps, for `resume`");
+const CONFIDENCE: Flag = flag(&["--confidence"], "C", "confidence level of the intervals and tests (default 0.95)");
+const ENGAGEMENT: &[Flag] = &[
+    flag(&["--seed"], "N", "random seed, for reproducible runs (default a random one)"),
+    OUT_DIR,
+    flag(&["--plot-out"], "PATH", "plot file name, {run} the run number (default collision_simulation_{run})"),
+    flag(&["--live-png"], "", "redraw live.png while the run is simulated"),
+    flag(&["--live-speed"], "STEPS", "steps per second of the live views (default 10)"),
+    flag(&["--tui"], "", "animate the run in the terminal"),
+    flag(&["--realtime"], "[SPEEDUP]", "pace the run by the wall clock, SPEEDUP times faster (default 1)"),
+    EVENTS,
+    CHECKPOINT_EVERY,
+    ACCEL_THRESHOLD,
+    UNITS,
+    flag(&["--stream"], "", "write every step to telemetry_NNN.csv while simulating"),
+    flag(&["--window"], "N", "keep only the last N samples in memory (at least 100)"),
+    flag(&["--json-stdout"], "", "print each result as one JSON line, for scripts"),
+    flag(&["--mavlink"], "ADDR", "send every step as MAVLink messages over UDP"),
+    flag(&["--mqtt"], "HOST[:PORT]", "publish every run to an MQTT broker"),
+    flag(&["--mqtt-topic"], "PREFIX", "topic prefix of the MQTT messages (default interceptor-sim)"),
+];
+const RUNS: Flag = flag(&["--runs", "-n"], "N", "number of runs (default 1)");
+const BATCH: &[Flag] = &[
+    RUNS,
+    flag(&["--randomize-interceptor", "-r"], "", "random interceptor start positions"),
+    flag(&["--overlay"], "", "also all trajectories on one chart"),
+    flag(&["--histograms"], "", "also histograms of miss distance, time and angle"),
+    flag(&["--dataset"], "FILE", "record observations and commands for imitation learning"),
+    flag(&["--antithetic"], "", "fly the runs in antithetic pairs (--runs even)"),
+    flag(&["--crn"], "", "common random numbers: run k seeded with --seed + k"),
+    flag(&["--seeds"], "LIST", "one run per seed of A..B, A..=B, S1,S2,... or a file"),
+];
+
+pub const COMMANDS: [Command; 17] = [
+    Command {
+        name: "run",
+        usage: "[options]",
+        summary: "one engagement: plot, geometry report and result JSON",
+        flags: &[
+            &[flag(&["--target-feed"], "SOURCE", "the target flies positions streamed from a file, pipe or - for stdin")],
+            ENGAGEMENT,
+            SCENARIO,
+            PLOT,
+            EXPORTS,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "batch",
+        usage: "[options]",
+        summary: "Monte Carlo batch of engagements with results.csv (the default without a command)",
+        flags: &[ENGAGEMENT, BATCH, SCENARIO, PLOT, EXPORTS, LOGGING],
+    },
+    Command {
+        name: "serve",
+        usage: "[options]",
+        summary: "batch that streams every step as JSON over a WebSocket while it runs",
+        flags: &[
+            &[flag(&["--bind"], "ADDR", "WebSocket address (default 127.0.0.1:8765)")],
+            ENGAGEMENT,
+            BATCH,
+            SCENARIO,
+            PLOT,
+            EXPORTS,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "sweep",
+        usage: "[options]",
+        summary: "hit rate over a 2D parameter grid, as sweep.csv and a heatmap",
+        flags: &[
+            &[
+                flag(&["--x"], "NAME:MIN:MAX:COUNT", "parameter and values along x (default speed:1:4:13)"),
+                flag(&["--y"], "NAME:MIN:MAX:COUNT", "parameter and values along y (default noise:0:45:10)"),
+                flag(&["--runs", "-n"], "N", "runs per cell (default 20)"),
+                SEED,
+                OUT_DIR,
+            ],
+            SCENARIO,
+            PLOT,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "lar",
+        usage: "[options]",
+        summary: "launch acceptability region: hit rate over a grid of target offsets, as lar.csv and a contour plot",
+        flags: &[
+            &[
+                flag(&["--x"], "MIN:MAX:COUNT", "downrange target offsets (default -60:60:25)"),
+                flag(&["--y"], "MIN:MAX:COUNT", "target heights (default 0:60:13)"),
+                flag(&["--headings"], "MIN:MAX:COUNT", "initial target headings (default the scenario's)"),
+                flag(&["--runs", "-n"], "N", "runs per offset and heading (default 10)"),
+                SEED,
+                flag(&["--levels"], "N", "contour bands of the plot (default 10)"),
+                OUT_DIR,
+            ],
+            SCENARIO,
+            PLOT,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "compare",
+        usage: "[options]",
+        summary: "the same engagement flown with every guidance law",
+        flags: &[&[flag(&["--laws"], "LIST", "comma-separated guidance laws (default all)"), SEED, OUT_DIR], SCENARIO, PLOT, LOGGING],
+    },
+    Command {
+        name: "ab",
+        usage: "<a.json> <b.json> [options]",
+        summary: "paired Monte Carlo batches of two scenarios, tested for a significant difference",
+        flags: &[
+            &[
+                flag(&["--runs", "-n"], "N", "runs per scenario (default 100)"),
+                flag(&["--seed"], "N", "run k of both batches is seeded with N + k (default 1)"),
+                CONFIDENCE,
+                OUT_DIR,
+                flag(&["--preset"], "NAME", "built-in scenario both files are applied on top of"),
+            ],
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "tournament",
+        usage: "[options]",
+        summary: "every pursuer policy against every evader policy, as a win-rate matrix",
+        flags: &[
+            &[
+                flag(&["--pursuers"], "LIST", "comma-separated pursuer policies (default every guidance law)"),
+                flag(&["--evaders"], "LIST", "comma-separated evader policies (default random,optimal)"),
+                flag(&["--runs", "-n"], "N", "runs per pairing (default 50)"),
+                SEED,
+                CONFIDENCE,
+                OUT_DIR,
+            ],
+            SCENARIO,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "salvo",
+        usage: "[options]",
+        summary: "salvo against shoot-look-shoot firing doctrine, and the shot spacing with the highest overall Pk",
+        flags: &[
+            &[
+                flag(&["--doctrine"], "salvo|sls", "only this doctrine (default both)"),
+                flag(&["--interval"], "S", "also the plan with shots S seconds apart"),
+                flag(&["--shots"], "N", "interceptors available for the target (default 2)"),
+                flag(&["--pk"], "P", "single-shot Pk instead of the simulated one"),
+                flag(&["--time-of-flight"], "S", "time of flight instead of the simulated one"),
+                flag(&["--time-window"], "S", "shots must arrive this soon after the first launch (default max_time, else 60)"),
+                flag(&["--correlation"], "S", "correlation time of the shots' misses, 0 for independent (default 5)"),
+                flag(&["--runs", "-n"], "N", "simulated engagements for Pk and time of flight (default 50)"),
+                SEED,
+                OUT_DIR,
+            ],
+            SCENARIO,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "raid",
+        usage: "[options]",
+        summary: "sustained raid: targets appearing in waves, engaged with a limited number of fire channels",
+        flags: &[&[flag(&["--runs", "-n"], "N", "number of raids (default 1)"), SEED, OUT_DIR], SCENARIO, LOGGING],
+    },
+    Command {
+        name: "replay",
+        usage: "<result.json> [options]",
+        summary: "redraw the plot of a saved result without simulating again",
+        flags: &[
+            &[flag(&["--out", "-o"], "FILE", "plot file (default next to the result)"), EVENTS, ACCEL_THRESHOLD, UNITS],
+            SCENARIO,
+            PLOT,
+            EXPORTS,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "resume",
+        usage: "<checkpoint.json> [options]",
+        summary: "finish a run from its checkpoint",
+        flags: &[&[CHECKPOINT_EVERY], SCENARIO, PLOT, LOGGING],
+    },
+    Command {
+        name: "tune",
+        usage: "[options]",
+        summary: "prompt that re-runs the engagement after every parameter change",
+        flags: &[&[OUT_DIR, SEED], SCENARIO, LOGGING],
+    },
+    Command {
+        name: "api",
+        usage: "[options]",
+        summary: "HTTP service that runs submitted scenarios and serves their results and plots",
+        flags: &[&[flag(&["--bind"], "ADDR", "HTTP address (default 127.0.0.1:8080)"), OUT_DIR], SCENARIO, PLOT, LOGGING],
+    },
+    Command {
+        name: "gym",
+        usage: "[options]",
+        summary: "reinforcement-learning environment speaking JSON lines on stdin/stdout",
+        flags: &[
+            &[
+                flag(&["--agent"], "interceptor|target", "the vehicle the agent flies (default interceptor)"),
+                flag(&["--observation"], "LIST", "comma-separated observation terms (default those of the neural law)"),
+                flag(&["--reward"], "TERM:WEIGHT,...", "weighted reward terms, e.g. intercept:10,effort:-0.01"),
+                flag(&["--max-accel"], "A", "limit of the agent's lateral acceleration (m/s², default 2)"),
+                SEED,
+            ],
+            SCENARIO,
+            LOGGING,
+        ],
+    },
+    Command {
+        name: "verify",
+        usage: "<golden.json>... [options]",
+        summary: "re-run seeded engagements and compare them with their stored golden results",
+        flags: &[
+            &[
+                flag(&["--tolerance"], "M", "largest position deviation accepted, in meters (default 1e-6)"),
+                flag(&["--update"], "", "write the files from the current build instead"),
+                flag(&["--seed"], "N", "with --update, the seed (default the golden's, else 1)"),
+            ],
+            SCENARIO,
+            LOGGING,
+        ],
+    },
+    Command { name: "help", usage: "[COMMAND]", summary: "this overview, or the options of one command", flags: &[] },
+];
+
+pub fn command(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|c| c.name == name)
+}
+
+impl Command {
+    fn takes(&self, name: &str) -> bool {
+        self.flags.iter().flat_map(|group| group.iter()).any(|flag| flag.names.contains(&name))
+    }
+
+    // Rejects the first option the command doesn't take. Values are left to the command's own
+    // parsing; negative numbers, and values starting with one like the range -30:30:3, are values,
+    // not options.
+    pub fn check_flags(&self, args: &[String]) -> Result<(), String> {
+        let value = |a: &str| a.parse::<f64>().is_ok() || a[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.');
+        match args.iter().find(|a| a.len() > 1 && a.starts_with('-') && !value(a) && !self.takes(a)) {
+            Some(flag) => Err(format!("'{}' doesn't take {} (see `help {}`)", self.name, flag, self.name)),
+            None => Ok(()),
+        }
+    }
+}
+
+// The command list, or with `name` the usage of that command and a line on each of its options
+// (described in full in the README)
+pub fn print_help(program: &str, name: Option<&str>) -> Result<(), String> {
+    let program = std::path::Path::new(program).file_name().map_or(program.into(), |name| name.to_string_lossy());
+    let Some(name) = name else {
+        println!("Usage: {} <COMMAND> [options]\n\nCommands:", program);
+        for command in &COMMANDS {
+            println!("  {:<10} {}", command.name, command.summary);
+        }
+        println!("\nWithout a command the options go to `batch`. `{} help COMMAND` lists the options of a command.", program);
+        return Ok(());
+    };
+    let command = self::command(name).ok_or_else(|| format!("unknown command '{}' (see `help`)", name))?;
+    println!("Usage: {} {} {}\n\n{}", program, command.name, command.usage, command.summary);
+    if !command.flags.is_empty() {
+        println!("\nOptions:");
+        let width = command.flags.iter().flat_map(|group| group.iter()).map(|flag| flag.usage().chars().count()).max().unwrap_or(0);
+        for (i, group) in command.flags.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            for flag in group.iter() {
+                println!("  {:<width$}  {}", flag.usage(), flag.help, width = width);
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Flag {
+    // e.g. "--runs N / -n"
+    fn usage(&self) -> String {
+        let mut usage = String::from(self.names[0]);
+        if !self.value.is_empty() {
+            usage = usage + " " + self.value;
+        }
+        for alias in &self.names[1..] {
+            usage = usage + " / " + alias;
+        }
+        usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, args: &[&str]) -> Result<(), String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        command(name).unwrap().check_flags(&args)
+    }
+
+    #[test]
+    fn options_of_the_command_are_taken() {
+        assert_eq!(check("run", &["--seed", "7", "--preset", "head-on", "--no-plot", "-q"]), Ok(()));
+        assert_eq!(check("batch", &["-n", "20", "--antithetic", "-o", "out"]), Ok(()));
+        assert_eq!(check("help", &[]), Ok(()));
+    }
+
+    #[test]
+    fn options_of_other_commands_are_rejected() {
+        assert_eq!(check("run", &["--runs", "5"]), Err("'run' doesn't take --runs (see `help run`)".into()));
+        assert_eq!(check("verify", &["golden/zem.json", "--video"]), Err("'verify' doesn't take --video (see `help verify`)".into()));
+        assert!(check("run", &["--sed", "7"]).is_err());
+    }
+
+    #[test]
+    fn negative_numbers_and_ranges_are_values() {
+        assert_eq!(check("run", &["--azimuth", "-30", "--elevation", "-.5", "--view3d"]), Ok(()));
+        assert_eq!(check("run", &["--target-feed", "-"]), Ok(()));
+        assert_eq!(check("lar", &["--headings", "-30:30:3", "--x", "-100:100:9"]), Ok(()));
+        assert!(check("run", &["-x"]).is_err());
+    }
+
+    #[test]
+    fn command_names_are_unique_and_found() {
+        for (i, command) in COMMANDS.iter().enumerate() {
+            assert!(COMMANDS[i + 1..].iter().all(|other| other.name != command.name), "{}", command.name);
+            assert_eq!(self::command(command.name).map(|c| c.name), Some(command.name));
+        }
+        assert!(command("fly").is_none());
+    }
+
+    #[test]
+    fn every_option_is_described_once_per_command() {
+        for command in &COMMANDS {
+            let flags: Vec<&Flag> = command.flags.iter().flat_map(|group| group.iter()).collect();
+            for (i, flag) in flags.iter().enumerate() {
+                assert!(flag.names[0].starts_with("--") || flag.names == ["-vv"], "{}", flag.names[0]);
+                assert!(!flag.help.is_empty(), "{} {}", command.name, flag.names[0]);
+                for name in flag.names {
+                    assert!(flags[i + 1..].iter().all(|other| !other.names.contains(name)), "{} {}", command.name, name);
+                }
+            }
+        }
+        assert_eq!(command("sweep").unwrap().flags[0][2].usage(), "--runs N / -n");
+    }
+}
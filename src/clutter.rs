@@ -0,0 +1,85 @@
+// Clutter and data association for tracking (see tracking.rs). With a `clutter` section the sensor
+// reports, besides the target (when it detects it), a Poisson-distributed number of false alarms
+// per look, `false_alarms` on average, scattered uniformly within `radius` meters of the target:
+//
+//   "tracking": { "filter": "kalman", "measurement_noise": 0.5, "clutter": { "false_alarms": 0.5, "radius": 4, "gate": 3 } }
+//
+// Which plot is the target is then for the tracker to decide: nearest-neighbor association picks
+// the plot closest to where the track predicts the target, among those within `gate` meters of it
+// (any distance without a gate). When that plot is a false alarm the filter follows it, which is
+// logged as an AssociationError event; with no plot inside the gate the look counts as missed.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::tracking::Measurement;
+use crate::validation::Violations;
+use rand::{Rng, RngCore};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clutter {
+    pub false_alarms: f64,  // Mean number of false plots per look
+    pub radius: f64,        // They appear within this distance of the target (m)
+    pub gate: Option<f64>,  // Plots further from the predicted position than this are never associated (m)
+}
+
+// A plot reported by the sensor, and whether it is really the target
+#[derive(Debug, Clone, Copy)]
+pub struct Plot {
+    pub measurement: Measurement,
+    pub target: bool,
+}
+
+impl Clutter {
+    // False plots for one look at the target at `around`, each with the error the sensor states
+    // for a measurement there
+    pub fn false_plots(&self, around: (f64, f64), noise: f64, rng: &mut dyn RngCore) -> Vec<Plot> {
+        // Poisson count by multiplying uniforms until they drop below e^-λ
+        let limit = (-self.false_alarms).exp();
+        let mut count = 0;
+        let mut product: f64 = rng.gen();
+        while product > limit {
+            count += 1;
+            product *= rng.gen::<f64>();
+        }
+        (0..count)
+            .map(|_| {
+                let distance = self.radius * rng.gen::<f64>().sqrt();
+                let (sin, cos) = (std::f64::consts::TAU * rng.gen::<f64>()).sin_cos();
+                let position = (around.0 + distance * cos, around.1 + distance * sin);
+                Plot { measurement: Measurement { position, noise }, target: false }
+            })
+            .collect()
+    }
+
+    // The plot nearest `predicted` within the gate, if any
+    pub fn associate(&self, plots: &[Plot], predicted: (f64, f64)) -> Option<Plot> {
+        let distance = |plot: &Plot| (plot.measurement.position.0 - predicted.0).hypot(plot.measurement.position.1 - predicted.1);
+        plots
+            .iter()
+            .filter(|plot| self.gate.is_none_or(|gate| distance(plot) <= gate))
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .copied()
+    }
+
+    // A message naming the first parameter out of range
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.false_alarms >= 0.0 && self.false_alarms <= 20.0, "false_alarms", "must be between 0 and 20 per look");
+        v.check(self.radius > 0.0 && self.radius.is_finite(), "radius", "must be positive");
+        v.check(self.gate.is_none_or(|gate| gate > 0.0 && gate.is_finite()), "gate", "must be positive");
+    }
+}
+
+impl ToJson for Clutter {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("false_alarms", self.false_alarms.into()), ("radius", self.radius.into()), ("gate", self.gate.into())])
+    }
+}
+
+impl FromJson for Clutter {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Ok(Clutter {
+            false_alarms: json.f64_field("false_alarms")?,
+            radius: json.f64_field_or("radius", 5.0)?,
+            gate: json.get("gate").filter(|v| **v != Json::Null).map(|_| json.f64_field("gate")).transpose()?,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+// Guidance law comparison: flies the same seeded engagement once per guidance law and reports how
+// each one did, as an overlay plot of all interceptor paths and a table of time to intercept, path
+// length, peak lateral acceleration and miss distance.
+
+use crate::error::SimError;
+use crate::geometry;
+use crate::log;
+use crate::plot::{visualize_comparison, PlotOptions};
+use crate::scenario::Scenario;
+use crate::{simulate_once, GuidanceLaw, Outcome, SimulationResult};
+use crate::units::{self, MetersPerSecondSquared, Seconds};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+
+// What the table reports for one law
+struct LawMetrics {
+    time_to_intercept: Option<f64>, // Seconds, None without an intercept
+    path_length: f64,               // Distance flown by the interceptor (m)
+    peak_lateral_accel: f64,        // Largest acceleration normal to the interceptor's velocity (m/s²)
+    miss_distance: f64,             // Closest approach (m)
+    pk: f64,                        // Probability of kill, see SimulationResult::pk
+}
+
+impl LawMetrics {
+    fn of(sim: &SimulationResult) -> LawMetrics {
+        let dt = sim.config.dt;
+        let path_length = geometry::path_length(&sim.interceptor_positions).0;
+        let peak_lateral_accel = geometry::lateral_accels(&sim.interceptor_velocities, Seconds(dt)).into_iter().fold(MetersPerSecondSquared(0.0), units::max).0;
+        LawMetrics {
+            time_to_intercept: (sim.outcome == Outcome::Intercept).then(|| sim.steps() as f64 * dt),
+            path_length,
+            peak_lateral_accel,
+            miss_distance: sim.miss_distance,
+            pk: sim.pk(),
+        }
+    }
+}
+
+fn parse_laws(list: &str) -> Result<Vec<GuidanceLaw>, String> {
+    list.split(',')
+        .map(|name| GuidanceLaw::parse(name.trim()).ok_or_else(|| format!("unknown guidance law '{}', expected a list of: {}", name, GuidanceLaw::NAMES)))
+        .collect()
+}
+
+// Usage: compare [--laws LIST] [--seed N] [--scenario FILE] [--preset NAME] [--out-dir DIR / -o DIR]
+//                [--quiet / -q] [plot options]
+pub fn run_compare(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut laws = GuidanceLaw::ALL.to_vec();
+    if scenario.config.guidance_plugin.is_some() {
+        laws.push(GuidanceLaw::Plugin);
+    }
+    if scenario.config.guidance_model.is_some() {
+        laws.push(GuidanceLaw::Neural);
+    }
+    let mut seed: u64 = 1;
+    let mut out_dir = String::from(".");
+    let plot_options = PlotOptions { style: scenario.style, ..PlotOptions::from_args(args)? };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--laws" if i + 1 < args.len() => {
+                laws = parse_laws(&args[i + 1])?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+    log::info!("⚖️ Comparing {} guidance laws with seed {}", laws.len(), seed);
+
+    let mut sims = Vec::new();
+    for law in &laws {
+        let _span = log::span(format!("{} run", law.as_str()));
+        let mut config = scenario.config.clone();
+        config.guidance = *law;
+        // Each law gets the same random sequence, so the differences come from the guidance alone
+        let mut rng = StdRng::seed_from_u64(seed);
+        sims.push(simulate_once(&mut rng, &config));
+    }
+
+    let csv_path = std::path::Path::new(&out_dir).join("compare.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "guidance,outcome,steps,time_to_intercept,path_length,peak_lateral_accel,miss_distance,pk")?;
+    log::info!("   {:<14} {:<14} {:>10} {:>10} {:>12} {:>9} {:>5}", "guidance", "outcome", "intercept", "path", "peak accel", "miss", "Pk");
+    for sim in &sims {
+        let metrics = LawMetrics::of(sim);
+        let time = metrics.time_to_intercept.map(|t| format!("{:.2}", t));
+        writeln!(
+            csv,
+            "{},{},{},{},{:.4},{:.4},{:.4},{:.4}",
+            sim.config.guidance.as_str(),
+            sim.outcome.as_str(),
+            sim.steps(),
+            time.clone().unwrap_or_default(),
+            metrics.path_length,
+            metrics.peak_lateral_accel,
+            metrics.miss_distance,
+            metrics.pk
+        )?;
+        log::info!(
+            "   {:<14} {:<14} {:>10} {:>8.2} m {:>7.2} m/s² {:>7.2} m {:>5.2}",
+            sim.config.guidance.as_str(),
+            sim.outcome.as_str(),
+            time.map_or_else(|| String::from("-"), |t| t + " s"),
+            metrics.path_length,
+            metrics.peak_lateral_accel,
+            metrics.miss_distance,
+            metrics.pk
+        );
+    }
+    log::info!("✅ Comparison table saved as '{}'", csv_path.display());
+
+    if plot_options.enabled {
+        let out_plot = std::path::Path::new(&out_dir).join(format!("compare.{}", plot_options.format.extension()));
+        visualize_comparison(&sims, out_plot.to_str().unwrap(), &plot_options)?;
+    }
+    Ok(())
+}
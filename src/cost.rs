@@ -0,0 +1,120 @@
+// Cost-effectiveness: what engagements use up and what a kill costs. Every run fires one
+// interceptor, which flies until the run ends and burns fuel while it manoeuvres. There is no
+// propulsion model, so fuel is counted as the delta-v of the manoeuvres: the velocity changes of
+// the interceptor summed over the flight (m/s), which a divert motor would have to supply. A
+// scenario prices them in its `costs` section:
+//
+//   "costs": { "interceptor": 50000, "fuel": 20, "flight_time": 100 }
+//
+//   interceptor  per interceptor fired (default 1, so that without prices the cost per kill is the
+//                interceptors per kill)
+//   fuel         per m/s of delta-v (default 0)
+//   flight_time  per second of flight, e.g. for the launcher and sensors (default 0)
+//
+// A batch reports the totals and the cost per engagement and per kill, counting the expected kills
+// (the sum of the runs' Pk).
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::log;
+use crate::vec2::Vec2;
+use crate::SimulationResult;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitCosts {
+    pub interceptor: f64,
+    pub fuel: f64,        // Per m/s of delta-v
+    pub flight_time: f64, // Per second
+}
+
+impl Default for UnitCosts {
+    fn default() -> Self {
+        UnitCosts { interceptor: 1.0, fuel: 0.0, flight_time: 0.0 }
+    }
+}
+
+impl FromJson for UnitCosts {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        if !matches!(json, Json::Object(_)) {
+            return Err(String::from("must be an object with interceptor, fuel and/or flight_time"));
+        }
+        let defaults = UnitCosts::default();
+        let field = |name: &str, default: f64| {
+            let value = json.f64_field_or(name, default)?;
+            if value >= 0.0 && value.is_finite() { Ok(value) } else { Err(format!("field '{}' must be a non-negative cost", name)) }
+        };
+        Ok(UnitCosts {
+            interceptor: field("interceptor", defaults.interceptor)?,
+            fuel: field("fuel", defaults.fuel)?,
+            flight_time: field("flight_time", defaults.flight_time)?,
+        })
+    }
+}
+
+// What one engagement used up
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Usage {
+    pub interceptors: usize,
+    pub fuel: f64,        // Delta-v (m/s) over the recorded flight; with --window over the part kept
+    pub flight_time: f64, // Seconds
+}
+
+impl Usage {
+    pub fn of(sim: &SimulationResult) -> Usage {
+        let fuel = sim.interceptor_velocities.windows(2).map(|pair| (Vec2::from(pair[1]) - Vec2::from(pair[0])).length()).sum();
+        Usage { interceptors: 1, fuel, flight_time: sim.steps() as f64 * sim.config.dt }
+    }
+
+    pub fn cost(&self, costs: &UnitCosts) -> f64 {
+        self.interceptors as f64 * costs.interceptor + self.fuel * costs.fuel + self.flight_time * costs.flight_time
+    }
+}
+
+impl ToJson for Usage {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("interceptors", self.interceptors.into()), ("fuel_delta_v", self.fuel.into()), ("flight_time", self.flight_time.into())])
+    }
+}
+
+// The usage of a batch's runs and the kills they achieved
+#[derive(Default)]
+pub struct CostTally {
+    runs: usize,
+    usage: Usage,
+    kills: f64, // Expected kills, the sum of Pk
+}
+
+impl CostTally {
+    pub fn add(&mut self, sim: &SimulationResult) {
+        let usage = Usage::of(sim);
+        self.runs += 1;
+        self.usage.interceptors += usage.interceptors;
+        self.usage.fuel += usage.fuel;
+        self.usage.flight_time += usage.flight_time;
+        self.kills += sim.pk();
+    }
+
+    pub fn report(&self, costs: &UnitCosts) {
+        let Usage { interceptors, fuel, flight_time } = self.usage;
+        let total = self.usage.cost(costs);
+        log::info!(
+            "💰 Used {} interceptors, {:.1} m/s of delta-v and {:.1} s of flight over {} runs: cost {:.2} in total, {:.2} per engagement",
+            interceptors,
+            fuel,
+            flight_time,
+            self.runs,
+            total,
+            total / self.runs as f64
+        );
+        match self.kills > 0.0 {
+            true => log::info!(
+                "💰 Per kill ({:.2} expected): {:.2} interceptors, {:.1} m/s of delta-v, {:.1} s of flight, cost {:.2}",
+                self.kills,
+                interceptors as f64 / self.kills,
+                fuel / self.kills,
+                flight_time / self.kills,
+                total / self.kills
+            ),
+            false => log::info!("💰 No kills, so no cost per kill"),
+        }
+    }
+}
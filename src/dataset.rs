@@ -0,0 +1,146 @@
+// Training data for imitation learning: with --dataset FILE a batch records, for every step of
+// every run, what the interceptor's guidance saw and what it commanded, so that a policy can be
+// fitted to a classical law and then flown as "guidance": "neural" (see onnx.rs). FILE, in the
+// output directory, is a NumPy .npz archive with the arrays
+//
+//   observation  float64 (N, 8)  onnx::observation at the start of the step, of the true target
+//   command      float64 (N,)    the lateral acceleration (m/s²) that turns the interceptor's
+//                                velocity as it turned over the step, in the neural law's terms
+//   run, step    int64 (N,)      which run and step the row comes from
+//
+// The command is read off the recorded velocities, so with autopilot lag or actuators it is the
+// response the interceptor achieved, and it includes the heading noise; a scenario without them
+// gives the law's own commands. Steps of a windowed run that are no longer in memory are left out.
+// Parquet would need a Parquet writer, which isn't among the dependencies; pandas converts the
+// arrays.
+
+use crate::error::SimError;
+use crate::log;
+use crate::onnx::observation;
+use crate::vec2::Vec2;
+use crate::{Interceptor, SimulationResult, Target};
+use std::path::{Path, PathBuf};
+
+pub struct Dataset {
+    path: PathBuf,
+    observations: Vec<[f64; 8]>,
+    commands: Vec<f64>,
+    runs: Vec<i64>,
+    steps: Vec<i64>,
+}
+
+impl Dataset {
+    // --dataset FILE, written to `out_dir`
+    pub fn from_args(args: &[String], out_dir: &str) -> Option<Dataset> {
+        let file = args.windows(2).find(|pair| pair[0] == "--dataset")?[1].as_str();
+        Some(Dataset { path: Path::new(out_dir).join(file), observations: Vec::new(), commands: Vec::new(), runs: Vec::new(), steps: Vec::new() })
+    }
+
+    // The steps of run `run`
+    pub fn add(&mut self, run: usize, sim: &SimulationResult) {
+        let dt = sim.config.dt;
+        let samples = sim.interceptor_positions.len();
+        for k in 0..samples.saturating_sub(1) {
+            let interceptor = Interceptor::new(sim.interceptor_positions[k], sim.interceptor_velocities[k]);
+            let target = Target::new(sim.target_positions[k], sim.target_velocities[k]);
+            let (before, after) = (Vec2::from(sim.interceptor_velocities[k]), Vec2::from(sim.interceptor_velocities[k + 1]));
+            // The neural law turns the velocity by atan(a dt / speed)
+            let turn = before.cross(after).atan2(before.dot(after));
+            self.observations.push(observation(&interceptor, &target));
+            self.commands.push(before.length() * turn.tan() / dt);
+            self.runs.push(run as i64);
+            self.steps.push((sim.first_step + k) as i64);
+        }
+    }
+
+    pub fn save(self) -> Result<(), SimError> {
+        let rows = self.commands.len();
+        if rows * 88 > u32::MAX as usize {
+            return Err(format!("{} training samples don't fit in one .npz archive; use fewer runs", rows).into());
+        }
+        let entries = [
+            ("observation.npy", npy("<f8", &[rows, 8], self.observations.iter().flatten().flat_map(|v| v.to_le_bytes()).collect())),
+            ("command.npy", npy("<f8", &[rows], self.commands.iter().flat_map(|v| v.to_le_bytes()).collect())),
+            ("run.npy", npy("<i8", &[rows], self.runs.iter().flat_map(|v| v.to_le_bytes()).collect())),
+            ("step.npy", npy("<i8", &[rows], self.steps.iter().flat_map(|v| v.to_le_bytes()).collect())),
+        ];
+        std::fs::write(&self.path, zip(&entries)).map_err(SimError::io(format!("cannot write '{}'", self.path.display())))?;
+        log::info!("🧠 {} training samples saved as '{}'", rows, self.path.display());
+        Ok(())
+    }
+}
+
+// An array in NumPy's .npy format, version 1.0: magic, header length, a Python dict literal padded
+// so that the data starts on a multiple of 64 bytes, then the data in C order
+fn npy(descr: &str, shape: &[usize], data: Vec<u8>) -> Vec<u8> {
+    let shape = match shape {
+        [n] => format!("({},)", n),
+        _ => format!("({})", shape.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+    let mut file = b"\x93NUMPY\x01\x00".to_vec();
+    file.extend((header.len() as u16).to_le_bytes());
+    file.extend(header.into_bytes());
+    file.extend(data);
+    file
+}
+
+// A zip archive of `entries`, stored without compression, as np.load reads .npz files. Without the
+// ZIP64 extensions the archive can't reach 4 GiB, which save checks.
+fn zip(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data) in entries {
+        let (offset, crc, size) = (archive.len() as u32, crc32(data), data.len() as u32);
+        // Version 2.0, no flags, stored, no timestamp
+        let fields = |out: &mut Vec<u8>| {
+            for half in [20u16, 0, 0, 0, 0] {
+                out.extend(half.to_le_bytes());
+            }
+            for word in [crc, size, size] {
+                out.extend(word.to_le_bytes());
+            }
+            out.extend((name.len() as u16).to_le_bytes());
+            out.extend(0u16.to_le_bytes()); // Extra field length
+        };
+        archive.extend(0x0403_4b50u32.to_le_bytes());
+        fields(&mut archive);
+        archive.extend(name.as_bytes());
+        archive.extend(data);
+
+        directory.extend(0x0201_4b50u32.to_le_bytes());
+        directory.extend(20u16.to_le_bytes()); // Made by version 2.0
+        fields(&mut directory);
+        for half in [0u16, 0, 0] {
+            directory.extend(half.to_le_bytes()); // Comment length, disk, internal attributes
+        }
+        directory.extend(0u32.to_le_bytes()); // External attributes
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+    let (start, length) = (archive.len() as u32, directory.len() as u32);
+    archive.extend(directory);
+    archive.extend(0x0605_4b50u32.to_le_bytes());
+    for half in [0u16, 0, entries.len() as u16, entries.len() as u16] {
+        archive.extend(half.to_le_bytes());
+    }
+    archive.extend(length.to_le_bytes());
+    archive.extend(start.to_le_bytes());
+    archive.extend(0u16.to_le_bytes()); // Comment length
+    archive
+}
+
+// CRC-32 as zip uses it (reflected, polynomial 0xEDB88320)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
@@ -0,0 +1,225 @@
+// Firing doctrine: how several interceptors are spent on one target, and the shot spacing that gives
+// the highest overall Pk. Two doctrines:
+//
+//   salvo  the shots leave `interval` seconds apart, all of them whatever the first ones achieve
+//   sls    shoot-look-shoot: the next shot leaves `interval` seconds (the look) after the previous
+//          one's intercept, and only if that missed
+//
+// At most `shots` interceptors are available. Shots are the scenario's engagement repeated: each kills with the single-shot Pk p and takes the
+// time of flight T, both from a batch of simulated engagements (or given). A shot counts only if it
+// arrives within the time window W in which the target can be engaged, so a wider spacing leaves
+// room for fewer shots. Shots fired close together meet the same target manoeuvre and the same
+// track error, so their misses are correlated: with launches Δ seconds apart a miss repeats with
+// probability q + ρp, where q = 1 - p and ρ = exp(-Δ/τ) for a correlation time τ, instead of q.
+// The overall Pk of n shots is then 1 - q(q + ρp)^(n-1); closer spacing fits more of the available
+// shots in, wider spacing makes each count for more, and the optimizer sweeps the spacing for the
+// best trade.
+
+use crate::error::SimError;
+use crate::log;
+use crate::progress::Progress;
+use crate::scenario::Scenario;
+use crate::{simulate_once, Outcome};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+
+// Spacings the optimizer tries, evenly over the window
+const SPACINGS: usize = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Doctrine {
+    Salvo,
+    ShootLookShoot,
+}
+
+impl Doctrine {
+    const ALL: [Doctrine; 2] = [Doctrine::Salvo, Doctrine::ShootLookShoot];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Doctrine::Salvo => "salvo",
+            Doctrine::ShootLookShoot => "sls",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Doctrine> {
+        Doctrine::ALL.into_iter().find(|doctrine| doctrine.as_str() == s)
+    }
+}
+
+// What a sequence of shots is fired against
+pub struct ShotModel {
+    pub pk: f64,               // Single-shot Pk
+    pub time_of_flight: f64,   // Seconds from launch to intercept
+    pub window: f64,           // Seconds from the first launch by which the shots must have arrived
+    pub correlation_time: f64, // Seconds over which the misses of two shots stay correlated, 0 for independent
+    pub max_shots: usize,      // Interceptors available
+}
+
+// The shots a doctrine fires at one spacing and what they achieve
+#[derive(Debug, Clone, Copy)]
+pub struct Plan {
+    pub interval: f64,
+    pub shots: usize,                // Shots that fit in the window (the most sls fires)
+    pub pk: f64,                     // Overall Pk
+    pub expected_interceptors: f64,  // Interceptors spent on average
+}
+
+impl ShotModel {
+    pub fn plan(&self, doctrine: Doctrine, interval: f64) -> Plan {
+        // Seconds between two launches
+        let spacing = match doctrine {
+            Doctrine::Salvo => interval,
+            Doctrine::ShootLookShoot => self.time_of_flight + interval,
+        };
+        let shots = match self.window < self.time_of_flight {
+            true => 0,
+            false if spacing <= 0.0 => self.max_shots,
+            false => self.max_shots.min(((self.window - self.time_of_flight) / spacing + 1e-9).floor() as usize + 1),
+        };
+        let correlation = if self.correlation_time > 0.0 { (-spacing / self.correlation_time).exp() } else { 0.0 };
+        let q = 1.0 - self.pk;
+        let repeat = q + correlation * self.pk;
+        // Probability that the first k shots all missed, k = 0..shots
+        let mut all_missed = 1.0;
+        let mut expected_interceptors = 0.0;
+        for k in 0..shots {
+            expected_interceptors += match doctrine {
+                Doctrine::Salvo => 1.0,
+                Doctrine::ShootLookShoot => all_missed,
+            };
+            all_missed *= if k == 0 { q } else { repeat };
+        }
+        Plan { interval, shots, pk: 1.0 - all_missed, expected_interceptors }
+    }
+
+    // The plan with the highest overall Pk over the spacings from 0 to the window, the smallest
+    // spacing among equals; None when no shot fits
+    pub fn optimize(&self, doctrine: Doctrine) -> (Vec<Plan>, Option<Plan>) {
+        let plans: Vec<Plan> = (0..=SPACINGS).map(|k| self.plan(doctrine, self.window * k as f64 / SPACINGS as f64)).collect();
+        let best = plans.iter().filter(|plan| plan.shots > 0).copied().fold(None, |best: Option<Plan>, plan| match best {
+            Some(best) if best.pk >= plan.pk - 1e-12 => Some(best),
+            _ => Some(plan),
+        });
+        (plans, best)
+    }
+}
+
+// Usage: salvo [--doctrine salvo|sls] [--interval S] [--shots N] [--pk P] [--time-of-flight S]
+//              [--time-window S] [--correlation S] [--runs N / -n N] [--seed N] [--scenario FILE]
+//              [--preset NAME] [--out-dir DIR / -o DIR] [--quiet / -q]
+pub fn run_salvo(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut doctrines = Doctrine::ALL.to_vec();
+    let mut interval: Option<f64> = None;
+    let mut max_shots: usize = 2;
+    let mut pk: Option<f64> = None;
+    let mut time_of_flight: Option<f64> = None;
+    let mut window = scenario.config.termination.max_time.unwrap_or(60.0);
+    let mut correlation_time = 5.0;
+    let mut runs: usize = 50;
+    let mut seed: u64 = 1;
+    let mut out_dir = String::from(".");
+    let seconds = |flag: &str, value: &str| value.parse().ok().filter(|s: &f64| *s >= 0.0 && s.is_finite()).ok_or(format!("{} must be a non-negative number of seconds", flag));
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--doctrine" if i + 1 < args.len() => {
+                doctrines = vec![Doctrine::parse(&args[i + 1]).ok_or("--doctrine must be salvo or sls (shoot-look-shoot)")?];
+                i += 1;
+            }
+            "--interval" if i + 1 < args.len() => {
+                interval = Some(seconds("--interval", &args[i + 1])?);
+                i += 1;
+            }
+            "--shots" if i + 1 < args.len() => {
+                max_shots = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--shots must be a positive integer")?;
+                i += 1;
+            }
+            "--pk" if i + 1 < args.len() => {
+                pk = Some(args[i + 1].parse().ok().filter(|p| (0.0..=1.0).contains(p)).ok_or("--pk must be a probability from 0 to 1")?);
+                i += 1;
+            }
+            "--time-of-flight" if i + 1 < args.len() => {
+                time_of_flight = Some(seconds("--time-of-flight", &args[i + 1])?);
+                i += 1;
+            }
+            "--time-window" if i + 1 < args.len() => {
+                window = args[i + 1].parse().ok().filter(|s: &f64| *s > 0.0 && s.is_finite()).ok_or("--time-window must be a positive number of seconds")?;
+                i += 1;
+            }
+            "--correlation" if i + 1 < args.len() => {
+                correlation_time = seconds("--correlation", &args[i + 1])?;
+                i += 1;
+            }
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--runs must be a positive integer")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    // The single shot, from the scenario's engagements as far as it isn't given
+    let (pk, time_of_flight) = match (pk, time_of_flight) {
+        (Some(pk), Some(time_of_flight)) => (pk, time_of_flight),
+        (given_pk, given_time) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut progress = Progress::new("Shots", runs, true);
+            let (mut pk_sum, mut flight_times) = (0.0, Vec::new());
+            for _ in 0..runs {
+                let config = scenario.envelope.sample(&scenario.config, &mut rng);
+                let sim = simulate_once(&mut rng, &config);
+                progress.inc(sim.outcome == Outcome::Intercept);
+                pk_sum += sim.pk();
+                if sim.outcome == Outcome::Intercept {
+                    flight_times.push(sim.steps() as f64 * sim.config.dt);
+                }
+            }
+            progress.finish();
+            let mean_flight = (!flight_times.is_empty()).then(|| flight_times.iter().sum::<f64>() / flight_times.len() as f64);
+            let time_of_flight = given_time.or(mean_flight).ok_or("no simulated shot hit, so there is no time of flight; give one with --time-of-flight")?;
+            log::info!("🎯 Single shot over {} simulated engagements: Pk {:.3}, time of flight {:.1} s", runs, pk_sum / runs as f64, time_of_flight);
+            (given_pk.unwrap_or(pk_sum / runs as f64), time_of_flight)
+        }
+    };
+    let model = ShotModel { pk, time_of_flight, window, correlation_time, max_shots };
+    log::info!(
+        "🚀 Doctrine for {} shot(s) of single-shot Pk {:.3}, time of flight {:.1} s, window {:.1} s, correlation time {:.1} s",
+        max_shots,
+        pk,
+        time_of_flight,
+        window,
+        correlation_time
+    );
+
+    let csv_path = std::path::Path::new(&out_dir).join("salvo.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "doctrine,interval,shots,pk,expected_interceptors")?;
+    let describe = |plan: &Plan| format!("{} shot(s) at an interval of {:.1} s: Pk {:.3}, {:.2} interceptors expected", plan.shots, plan.interval, plan.pk, plan.expected_interceptors);
+    for doctrine in doctrines {
+        let (plans, best) = model.optimize(doctrine);
+        for plan in &plans {
+            writeln!(csv, "{},{:.4},{},{:.4},{:.4}", doctrine.as_str(), plan.interval, plan.shots, plan.pk, plan.expected_interceptors)?;
+        }
+        if let Some(interval) = interval {
+            log::info!("   {:<6} at --interval: {}", doctrine.as_str(), describe(&model.plan(doctrine, interval)));
+        }
+        match best {
+            Some(best) => log::info!("   {:<6} best:        {}", doctrine.as_str(), describe(&best)),
+            None => log::info!("   {:<6} no shot arrives within the {:.1} s window", doctrine.as_str(), window),
+        }
+    }
+    log::info!("✅ Pk over the shot spacing saved as '{}'", csv_path.display());
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// Closing velocity and time-to-go, the two estimates most guidance laws are built on. They are
+// computed from the current state every step, feed the ZEM and LQR laws and the terminal phase,
+// and are recorded for every step of a run (exported as `closing_velocity` and `time_to_go`).
+
+use crate::{Interceptor, SimulationResult, Target};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Engagement {
+    pub range: f64,
+    pub closing_velocity: f64,      // Rate the range shrinks (m/s), negative while opening
+    pub time_to_go: Option<f64>,    // range / closing velocity, None while not closing
+}
+
+impl Engagement {
+    pub fn between(from: &Interceptor, to: &Target) -> Engagement {
+        let (r, v) = (to.position - from.position, to.velocity - from.velocity);
+        let range = r.length();
+        let closing_velocity = if range > 1e-9 { -r.dot(v) / range } else { 0.0 };
+        let time_to_go = (closing_velocity > 1e-9).then(|| range / closing_velocity);
+        Engagement { range, closing_velocity, time_to_go }
+    }
+}
+
+// The estimate at every recorded step of a run, from the positions and velocities recorded there
+pub fn history(sim: &SimulationResult) -> Vec<Engagement> {
+    sim.interceptor_positions
+        .iter()
+        .zip(&sim.interceptor_velocities)
+        .zip(sim.target_positions.iter().zip(&sim.target_velocities))
+        .map(|((&interceptor, &interceptor_velocity), (&target, &target_velocity))| {
+            Engagement::between(&Interceptor::new(interceptor, interceptor_velocity), &Target::new(target, target_velocity))
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+// Randomized initial conditions: instead of one fixed geometry, the `envelope` section of a
+// scenario file gives distributions for the target's start, speed and heading, and every run of a
+// batch (and of every sweep cell) draws its own, e.g.
+//
+//   "envelope": {
+//     "target_x": { "uniform": [0, 40] },
+//     "target_altitude": { "normal": [30, 5] },
+//     "target_speed": { "uniform": [1.5, 2.5] },
+//     "target_heading_deg": { "normal": [0, 10] }
+//   }
+//
+// Quantities without a distribution keep the configured value. Altitude and speed are clamped at 0.
+// With --antithetic a batch pairs every draw with its reflection about the middle of the
+// distribution (sample_antithetic).
+
+use crate::json::{FromJson, Json};
+use crate::noise::standard_normal;
+use crate::SimConfig;
+use rand::{Rng, RngCore};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    Uniform { min: f64, max: f64 },
+    Normal { mean: f64, std_dev: f64 },
+}
+
+impl Distribution {
+    pub fn sample(&self, rng: &mut dyn RngCore) -> f64 {
+        match *self {
+            Distribution::Uniform { min, max } if min < max => rng.gen_range(min..max),
+            Distribution::Uniform { min, .. } => min,
+            Distribution::Normal { mean, std_dev } => mean + std_dev * standard_normal(rng),
+        }
+    }
+
+    // The antithetic partner of a draw: its reflection about the center, which is as likely
+    pub fn mirror(&self, value: f64) -> f64 {
+        match *self {
+            Distribution::Uniform { min, max } => min + max - value,
+            Distribution::Normal { mean, .. } => 2.0 * mean - value,
+        }
+    }
+}
+
+impl FromJson for Distribution {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let usage = "distributions are {\"uniform\": [min, max]} or {\"normal\": [mean, std_dev]}";
+        if let Some(range) = json.get("uniform") {
+            let (min, max) = range.as_point().ok_or(usage)?;
+            if !(min.is_finite() && max.is_finite() && min <= max) {
+                return Err(String::from("uniform range must be [min, max] with min <= max"));
+            }
+            return Ok(Distribution::Uniform { min, max });
+        }
+        if let Some(params) = json.get("normal") {
+            let (mean, std_dev) = params.as_point().ok_or(usage)?;
+            if !(mean.is_finite() && std_dev >= 0.0 && std_dev.is_finite()) {
+                return Err(String::from("normal distribution must be [mean, std_dev] with std_dev >= 0"));
+            }
+            return Ok(Distribution::Normal { mean, std_dev });
+        }
+        Err(String::from(usage))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Envelope {
+    pub target_x: Option<Distribution>,
+    pub target_altitude: Option<Distribution>,
+    pub target_speed: Option<Distribution>,
+    pub target_heading_deg: Option<Distribution>, // 0° flies along +x, positive climbs
+}
+
+impl Envelope {
+    pub fn is_empty(&self) -> bool {
+        *self == Envelope::default()
+    }
+
+    // `config` with one draw for every quantity that has a distribution, in a fixed order so seeded
+    // batches are reproducible
+    pub fn sample(&self, config: &SimConfig, rng: &mut dyn RngCore) -> SimConfig {
+        self.sample_antithetic(config, rng).0
+    }
+
+    // A sample as above and its antithetic partner, with every draw mirrored (Distribution::mirror)
+    pub fn sample_antithetic(&self, config: &SimConfig, rng: &mut dyn RngCore) -> (SimConfig, SimConfig) {
+        let distributions = [self.target_x, self.target_altitude, self.target_speed, self.target_heading_deg];
+        let values = [config.target_start_x, config.target_start_y, config.target_speed, config.target_heading_deg];
+        let mut draws = values;
+        let mut mirrored = values;
+        for (i, distribution) in distributions.iter().enumerate() {
+            if let Some(distribution) = distribution {
+                draws[i] = distribution.sample(rng);
+                mirrored[i] = distribution.mirror(draws[i]);
+            }
+        }
+        let apply = |[x, altitude, speed, heading]: [f64; 4]| SimConfig {
+            target_start_x: x,
+            target_start_y: altitude.max(0.0),
+            target_speed: speed.max(0.0),
+            target_heading_deg: heading,
+            ..config.clone()
+        };
+        (apply(draws), apply(mirrored))
+    }
+}
+
+// Missing quantities keep the distribution in `base`, null removes it
+pub fn envelope_with_defaults(json: &Json, base: Envelope) -> Result<Envelope, String> {
+    let field = |key: &str, default: Option<Distribution>| match json.get(key) {
+        Some(Json::Null) => Ok(None),
+        Some(distribution) => Distribution::from_json(distribution).map(Some).map_err(|e| format!("{}: {}", key, e)),
+        None => Ok(default),
+    };
+    Ok(Envelope {
+        target_x: field("target_x", base.target_x)?,
+        target_altitude: field("target_altitude", base.target_altitude)?,
+        target_speed: field("target_speed", base.target_speed)?,
+        target_heading_deg: field("target_heading_deg", base.target_heading_deg)?,
+    })
+}
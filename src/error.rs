@@ -0,0 +1,85 @@
+// Crate-wide error type. Each variant is one failure mode the CLI reports differently and
+// callers can match on.
+
+use crate::validation::Violations;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum SimError {
+    // Invalid command line option or parameter value
+    ConfigError(String),
+    // Rendering a chart, image or video failed
+    PlottingError(String),
+    // Reading or writing a file, or talking to a child process, failed
+    IoError { context: String, source: std::io::Error },
+    // A scenario or result file that cannot be parsed or does not describe a valid engagement
+    InvalidScenario { path: String, message: String },
+    // A run that no longer reproduces its golden result (see verify.rs)
+    Regression(String),
+}
+
+impl SimError {
+    // For map_err on I/O calls: keeps the file or action the error came from in the message
+    pub fn io(context: impl Into<String>) -> impl FnOnce(std::io::Error) -> SimError {
+        let context = context.into();
+        move |source| SimError::IoError { context, source }
+    }
+
+    pub fn invalid_scenario(path: impl AsRef<std::path::Path>, message: impl fmt::Display) -> SimError {
+        SimError::InvalidScenario { path: path.as_ref().display().to_string(), message: message.to_string() }
+    }
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimError::ConfigError(message) => write!(f, "invalid configuration: {}", message),
+            SimError::PlottingError(message) => write!(f, "plotting failed: {}", message),
+            SimError::IoError { context, source } if context.is_empty() => write!(f, "I/O error: {}", source),
+            SimError::IoError { context, source } => write!(f, "{}: {}", context, source),
+            SimError::InvalidScenario { path, message } => write!(f, "invalid file '{}': {}", path, message),
+            SimError::Regression(message) => write!(f, "verification failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SimError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SimError::IoError { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SimError {
+    fn from(source: std::io::Error) -> Self {
+        SimError::IoError { context: String::new(), source }
+    }
+}
+
+// Option parsers report problems as plain messages
+impl From<String> for SimError {
+    fn from(message: String) -> Self {
+        SimError::ConfigError(message)
+    }
+}
+
+impl From<Violations> for SimError {
+    fn from(violations: Violations) -> Self {
+        SimError::ConfigError(violations.to_string())
+    }
+}
+
+impl From<&str> for SimError {
+    fn from(message: &str) -> Self {
+        SimError::ConfigError(message.into())
+    }
+}
+
+#[cfg(feature = "plot")]
+impl<E: std::error::Error + Send + Sync> From<plotters::drawing::DrawingAreaErrorKind<E>> for SimError {
+    fn from(e: plotters::drawing::DrawingAreaErrorKind<E>) -> Self {
+        SimError::PlottingError(e.to_string())
+    }
+}
@@ -0,0 +1,73 @@
+// Optimal evasion: instead of turning by random noise and the altitude hold, the target flies the
+// bang-bang manoeuvre that is optimal against a pursuer closing on a predicted intercept, turning
+// at its full lateral acceleration in the direction that grows the zero-effort miss (the miss if
+// neither vehicle turned again), so guidance laws can be judged against a worst-case adversary
+// rather than against noise. A scenario enables it with
+//
+//   "optimal_evasion": { "max_accel": 3, "range": 40 }
+//
+//   max_accel  the target's largest lateral acceleration (m/s²)
+//   range      the separation (m) within which it evades; further away it flies the noise and the
+//              altitude hold as without optimal evasion. Without it, it evades from the start.
+//
+// The direction switches when the predicted miss passes through zero, so against a pursuer that
+// keeps correcting the target reverses its turn: a weave timed by the pursuer. On a collision
+// course, where either way grows the miss, it climbs. The heading noise is still drawn every step,
+// so the random sequence stays aligned with a run without optimal evasion.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimalEvasion {
+    pub max_accel: f64,     // Largest lateral acceleration (m/s²)
+    pub range: Option<f64>, // Separation (m) within which the target evades, None for always
+}
+
+impl OptimalEvasion {
+    // The turn (radians, counter-clockwise) of a target with `velocity` at `position` this step
+    // against a threat at `threat_position` with `threat_velocity`; None while out of range
+    pub fn turn(&self, position: Vec2, velocity: Vec2, threat_position: Vec2, threat_velocity: Vec2, dt: f64) -> Option<f64> {
+        let relative = position - threat_position;
+        if self.range.is_some_and(|range| relative.length() > range) {
+            return None;
+        }
+        let speed = velocity.length();
+        if speed == 0.0 {
+            return Some(0.0);
+        }
+        let closing = velocity - threat_velocity;
+        let time_to_go = match closing.dot(closing) {
+            v2 if v2 > 0.0 => (-relative.dot(closing) / v2).max(0.0),
+            _ => 0.0,
+        };
+        let zero_effort_miss = relative + closing * time_to_go;
+        // The side of the velocity the predicted miss lies on, climbing when it lies on neither
+        let lateral = velocity.perp().dot(zero_effort_miss);
+        let sign = if lateral.abs() > 1e-9 { lateral.signum() } else if velocity.x >= 0.0 { 1.0 } else { -1.0 };
+        Some(sign * (self.max_accel * dt / speed).min(std::f64::consts::PI))
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.max_accel > 0.0 && self.max_accel.is_finite(), "max_accel", "must be a positive acceleration");
+        v.check(self.range.is_none_or(|range| range > 0.0 && range.is_finite()), "range", "must be a positive distance");
+    }
+}
+
+impl ToJson for OptimalEvasion {
+    fn to_json(&self) -> Json {
+        let range = self.range.map(|range| ("range", range.into()));
+        Json::object([("max_accel", self.max_accel.into())].into_iter().chain(range).collect())
+    }
+}
+
+impl FromJson for OptimalEvasion {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let range = match json.get("range") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("range")?),
+        };
+        Ok(OptimalEvasion { max_accel: json.f64_field("max_accel")?, range })
+    }
+}
@@ -0,0 +1,154 @@
+// Scheduled faults, for testing how guidance copes with failures at known moments rather than the
+// random ones of actuator.rs. A scenario lists them under "faults", e.g.
+//
+//   "faults": [
+//     { "type": "sensor_dropout", "start": 10, "end": 15 },
+//     { "type": "stuck_actuator", "start": 20, "end": 23 },
+//     { "type": "degraded_accel", "start": 30, "max_accel": 0.5 }
+//   ]
+//
+// Times are seconds of flight (steps × dt); a fault is active from `start` up to, not including,
+// `end`, or to the end of the run without one. During a sensor dropout the tracking sensors
+// measure nothing and the track coasts, and without tracking guidance has nothing to steer by, so
+// the interceptor holds its course. A stuck actuator keeps the lateral acceleration of the step
+// before it for as long as it lasts, whatever guidance commands. Degraded acceleration limits the
+// lateral acceleration the interceptor achieves to `max_accel` m/s². Each fault is logged as a
+// fault_started and a fault_cleared event.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+
+// Schedules are stored inline, so that a FaultSchedule is Copy
+pub const MAX_FAULTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    SensorDropout,
+    StuckActuator,
+    DegradedAccel,
+}
+
+impl FaultKind {
+    pub const NAMES: &'static str = "sensor_dropout, stuck_actuator, degraded_accel";
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FaultKind::SensorDropout => "sensor_dropout",
+            FaultKind::StuckActuator => "stuck_actuator",
+            FaultKind::DegradedAccel => "degraded_accel",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<FaultKind> {
+        match s {
+            "sensor_dropout" => Some(FaultKind::SensorDropout),
+            "stuck_actuator" => Some(FaultKind::StuckActuator),
+            "degraded_accel" => Some(FaultKind::DegradedAccel),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fault {
+    pub kind: FaultKind,
+    pub start: f64,        // Seconds of flight it starts at
+    pub end: Option<f64>,  // Seconds it clears at, None for the rest of the run
+    pub max_accel: f64,    // Lateral acceleration (m/s²) left with degraded_accel
+}
+
+impl Fault {
+    pub fn active(&self, step: usize, dt: f64) -> bool {
+        let t = step as f64 * dt;
+        t >= self.start && self.end.is_none_or(|end| t < end)
+    }
+
+    fn validate(&self, v: &mut Violations) {
+        v.check(self.start >= 0.0 && self.start.is_finite(), "start", "must be zero or a positive number of seconds");
+        v.check(self.end.is_none_or(|end| end > self.start && end.is_finite()), "end", "must be a number of seconds after start");
+        v.check(self.max_accel >= 0.0 && self.max_accel.is_finite(), "max_accel", "must be zero or positive");
+    }
+}
+
+impl ToJson for Fault {
+    fn to_json(&self) -> Json {
+        let mut fields = vec![("type", self.kind.as_str().into()), ("start", self.start.into())];
+        fields.extend(self.end.map(|end| ("end", end.into())));
+        if self.kind == FaultKind::DegradedAccel {
+            fields.push(("max_accel", self.max_accel.into()));
+        }
+        Json::object(fields)
+    }
+}
+
+impl FromJson for Fault {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let kind = json.field("type")?.as_str().and_then(FaultKind::parse).ok_or_else(|| format!("fault 'type' must be one of: {}", FaultKind::NAMES))?;
+        let end = match json.get("end") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("end")?),
+        };
+        let max_accel = match kind {
+            FaultKind::DegradedAccel => json.f64_field("max_accel")?,
+            _ => 0.0,
+        };
+        Ok(Fault { kind, start: json.f64_field("start")?, end, max_accel })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaultSchedule {
+    faults: [Option<Fault>; MAX_FAULTS], // The first ones are set
+}
+
+impl FaultSchedule {
+    pub fn new(faults: &[Fault]) -> Result<FaultSchedule, String> {
+        if faults.len() > MAX_FAULTS {
+            return Err(format!("at most {} faults", MAX_FAULTS));
+        }
+        let mut schedule = [None; MAX_FAULTS];
+        for (slot, fault) in schedule.iter_mut().zip(faults) {
+            *slot = Some(*fault);
+        }
+        Ok(FaultSchedule { faults: schedule })
+    }
+
+    pub fn faults(&self) -> impl Iterator<Item = &Fault> {
+        self.faults.iter().flatten()
+    }
+
+    // Whether a fault of `kind` is active at `step`
+    pub fn active(&self, kind: FaultKind, step: usize, dt: f64) -> bool {
+        self.faults().any(|f| f.kind == kind && f.active(step, dt))
+    }
+
+    // The lateral acceleration left at `step` by the degraded_accel faults active then
+    pub fn accel_limit(&self, step: usize, dt: f64) -> Option<f64> {
+        self.faults().filter(|f| f.kind == FaultKind::DegradedAccel && f.active(step, dt)).map(|f| f.max_accel).reduce(f64::min)
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        for (i, fault) in self.faults().enumerate() {
+            v.section(&format!("faults[{}]", i), |v| fault.validate(v));
+        }
+    }
+}
+
+impl ToJson for FaultSchedule {
+    fn to_json(&self) -> Json {
+        Json::Array(self.faults().map(Fault::to_json).collect())
+    }
+}
+
+impl FromJson for FaultSchedule {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let faults = json
+            .as_array()
+            .ok_or("must be an array of faults")?
+            .iter()
+            .enumerate()
+            .map(|(i, fault)| Fault::from_json(fault).map_err(|e| format!("[{}]: {}", i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        FaultSchedule::new(&faults)
+    }
+}
@@ -0,0 +1,77 @@
+// Target positions fed in from outside while the run goes on, so the interceptor's guidance can
+// fly against another simulator's target in real time. With `run --target-feed SOURCE` (`-` for
+// stdin, or a file or named pipe) the target goes wherever the feed says instead of evading: each
+// line is its position at the next step,
+//
+//   x,y
+//
+// in meters, y the height, as in the simulation. Blank lines and lines starting with # are skipped.
+// The run waits for every line, so it goes at the feed's pace, and the interceptor's answer comes
+// back on stdout as the telemetry line of each step (see telemetry.rs). The run ends as a timeout
+// when the feed does, unless it hit before.
+
+use crate::error::SimError;
+use crate::vec2::Vec2;
+use std::io::BufRead;
+
+pub struct TargetFeed {
+    source: String, // "stdin" or the file's path
+    reader: Box<dyn BufRead>,
+    line: usize,           // Lines read so far
+    error: Option<SimError>, // What ended the feed early; reported by finish
+}
+
+impl TargetFeed {
+    pub fn open(source: &str) -> Result<TargetFeed, SimError> {
+        let reader: Box<dyn BufRead> = match source {
+            "-" => Box::new(std::io::stdin().lock()),
+            path => Box::new(std::io::BufReader::new(
+                std::fs::File::open(path).map_err(SimError::io(format!("cannot open target feed '{}'", path)))?,
+            )),
+        };
+        Ok(TargetFeed { source: if source == "-" { "stdin".into() } else { source.into() }, reader, line: 0, error: None })
+    }
+
+    // --target-feed SOURCE
+    pub fn from_args(args: &[String]) -> Result<Option<TargetFeed>, SimError> {
+        args.windows(2).find(|pair| pair[0] == "--target-feed").map(|pair| TargetFeed::open(&pair[1])).transpose()
+    }
+
+    // The target's position at the next step, waiting for it; None once the feed has ended or
+    // sent something that isn't a position
+    pub fn next_position(&mut self) -> Option<Vec2> {
+        if self.error.is_some() {
+            return None;
+        }
+        let mut text = String::new();
+        loop {
+            text.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut text) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.error = Some(SimError::io(format!("cannot read target feed '{}'", self.source))(e));
+                    return None;
+                }
+            }
+            let line = text.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            return match line.split(',').map(|v| v.trim().parse::<f64>()).collect::<Result<Vec<_>, _>>().as_deref() {
+                Ok(&[x, y]) if x.is_finite() && y.is_finite() => Some(Vec2::new(x, y)),
+                _ => {
+                    let message = format!("line {}: expected a position x,y, found '{}'", self.line, line);
+                    self.error = Some(SimError::invalid_scenario(&self.source, message));
+                    None
+                }
+            };
+        }
+    }
+
+    // Fails if the feed ended on an error rather than at its end
+    pub fn finish(self) -> Result<(), SimError> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
@@ -0,0 +1,312 @@
+// C API for embedding the engagement in other simulators, from C, C++ or anything else with a C
+// FFI. The declarations are in include/coding_interview_rust.h; link against the static or shared
+// library cargo builds next to the binary (libcoding_interview_rust.a or .so);
+// python/coding_interview_rust/ binds the shared one for Python through ctypes. A simulation is
+// created from a scenario document, stepped by the host and read back after every step:
+//
+//   CiSimulation *sim = ci_simulation_create("{\"config\": {\"guidance\": \"zem\"}}", 42);
+//   CiState state;
+//   while (ci_simulation_step(sim) == 0) {
+//       ci_simulation_state(sim, &state);
+//       ...
+//   }
+//   ci_simulation_destroy(sim);
+//
+// Calls that fail return NULL or -1 and leave a message for ci_last_error; so does a panic inside
+// the simulation, which is caught before it can unwind into the caller (the simulation it happened
+// in should then only be destroyed). Pointers handed in must
+// be valid for the call: a simulation from ci_simulation_create that hasn't been destroyed yet, a
+// NUL-terminated UTF-8 string, a writable CiState. A simulation can move between threads but must
+// not be used from two at once. The same seed and scenario give the same run as `run --seed N`.
+
+// The functions are unsafe for the reasons above, which C callers can't be shown in Rust docs
+#![allow(clippy::missing_safety_doc)]
+
+use crate::checkpoint::SimRng;
+use crate::json::{FromJson, Json, ToJson};
+use crate::scenario::Scenario;
+use crate::simulation::Simulation;
+use crate::Outcome;
+use rand::SeedableRng;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::AssertUnwindSafe;
+
+// CiState.outcome
+pub const CI_RUNNING: i32 = 0;
+pub const CI_INTERCEPT: i32 = 1;
+pub const CI_GROUND_IMPACT: i32 = 2;
+pub const CI_TIMEOUT: i32 = 3;
+pub const CI_ESCAPE: i32 = 4;
+pub const CI_STALL: i32 = 5;
+pub const CI_MISS: i32 = 6;
+
+pub struct CiSimulation {
+    simulation: Simulation<'static>,
+    rng: SimRng,
+}
+
+// Both vehicles after the last step
+#[repr(C)]
+pub struct CiState {
+    pub step: u64,
+    pub time: f64, // Seconds
+    pub target_x: f64,
+    pub target_y: f64,
+    pub target_vx: f64,
+    pub target_vy: f64,
+    pub interceptor_x: f64,
+    pub interceptor_y: f64,
+    pub interceptor_vx: f64,
+    pub interceptor_vy: f64,
+    pub separation: f64,
+    pub miss_distance: f64, // Closest approach so far
+    pub outcome: i32,       // CI_RUNNING until the run has ended
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn fail(message: impl Into<String>) {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = message);
+}
+
+// Runs `body` for the function `name`, turning a panic into `failed` and a message for
+// ci_last_error; unwinding across the C boundary would be undefined behavior
+fn guard<T>(name: &str, failed: T, body: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|panic| {
+        let message = panic.downcast_ref::<&str>().copied().or(panic.downcast_ref::<String>().map(String::as_str)).unwrap_or("unknown cause");
+        fail(format!("{}: the simulation panicked: {}", name, message));
+        failed
+    })
+}
+
+// The message of the last call on this thread that failed, valid until the next one fails
+#[no_mangle]
+pub extern "C" fn ci_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ptr())
+}
+
+// A new simulation of the scenario document `scenario_json` (NULL for the defaults) with the
+// random seed `seed`, or NULL if the scenario is invalid
+#[no_mangle]
+pub unsafe extern "C" fn ci_simulation_create(scenario_json: *const c_char, seed: u64) -> *mut CiSimulation {
+    guard("ci_simulation_create", std::ptr::null_mut(), || {
+        let scenario = match scenario_json.is_null() {
+            true => Ok(Scenario::default()),
+            false => CStr::from_ptr(scenario_json)
+                .to_str()
+                .map_err(|e| format!("the scenario isn't UTF-8: {}", e))
+                .and_then(|text| Json::parse(text).map_err(|e| e.to_string()))
+                .and_then(|json| Scenario::from_json(&json)),
+        };
+        let scenario = match scenario {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                fail(e);
+                return std::ptr::null_mut();
+            }
+        };
+        let mut rng = SimRng::seed_from_u64(seed);
+        let config = match scenario.envelope.is_empty() {
+            true => scenario.config,
+            false => scenario.envelope.sample(&scenario.config, &mut rng),
+        };
+        match Simulation::builder().config(config).build() {
+            Ok(simulation) => Box::into_raw(Box::new(CiSimulation { simulation, rng })),
+            Err(e) => {
+                fail(e.to_string());
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+// One step; returns 0 while the run goes on, 1 once it has ended and -1 for a NULL simulation
+#[no_mangle]
+pub unsafe extern "C" fn ci_simulation_step(sim: *mut CiSimulation) -> i32 {
+    guard("ci_simulation_step", -1, || {
+        let Some(sim) = sim.as_mut() else {
+            fail("ci_simulation_step: the simulation is NULL");
+            return -1;
+        };
+        sim.simulation.step(&mut sim.rng).is_finished() as i32
+    })
+}
+
+// Writes the state after the last step to `state`; returns 0, or -1 for a NULL argument
+#[no_mangle]
+pub unsafe extern "C" fn ci_simulation_state(sim: *const CiSimulation, state: *mut CiState) -> i32 {
+    guard("ci_simulation_state", -1, || {
+        let (Some(sim), Some(state)) = (sim.as_ref(), state.as_mut()) else {
+            fail("ci_simulation_state: the simulation or the state is NULL");
+            return -1;
+        };
+        let result = sim.simulation.result();
+        let k = result.steps() - result.first_step;
+        let ((tx, ty), (ix, iy)) = (result.target_positions[k], result.interceptor_positions[k]);
+        let ((tvx, tvy), (ivx, ivy)) = (result.target_velocities[k], result.interceptor_velocities[k]);
+        *state = CiState {
+            step: result.steps() as u64,
+            time: result.steps() as f64 * result.config.dt,
+            target_x: tx,
+            target_y: ty,
+            target_vx: tvx,
+            target_vy: tvy,
+            interceptor_x: ix,
+            interceptor_y: iy,
+            interceptor_vx: ivx,
+            interceptor_vy: ivy,
+            separation: (tx - ix).hypot(ty - iy),
+            miss_distance: result.miss_distance,
+            outcome: match (sim.simulation.finished(), result.outcome) {
+                (false, _) => CI_RUNNING,
+                (true, Outcome::Intercept) => CI_INTERCEPT,
+                (true, Outcome::GroundImpact) => CI_GROUND_IMPACT,
+                (true, Outcome::Timeout) => CI_TIMEOUT,
+                (true, Outcome::Escape) => CI_ESCAPE,
+                (true, Outcome::Stall) => CI_STALL,
+                (true, Outcome::Miss) => CI_MISS,
+            },
+        };
+        0
+    })
+}
+
+// Everything recorded so far as the result JSON `run` saves, to be released with ci_string_free;
+// NULL for a NULL simulation
+#[no_mangle]
+pub unsafe extern "C" fn ci_simulation_result_json(sim: *const CiSimulation) -> *mut c_char {
+    guard("ci_simulation_result_json", std::ptr::null_mut(), || {
+        let Some(sim) = sim.as_ref() else {
+            fail("ci_simulation_result_json: the simulation is NULL");
+            return std::ptr::null_mut();
+        };
+        CString::new(sim.simulation.result().to_json().to_string()).map_or(std::ptr::null_mut(), CString::into_raw)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ci_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ci_simulation_destroy(sim: *mut CiSimulation) {
+    guard("ci_simulation_destroy", (), || {
+        if !sim.is_null() {
+            drop(Box::from_raw(sim));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = include_str!("../include/coding_interview_rust.h");
+    const SOURCE: &str = include_str!("ffi.rs");
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(ci_last_error()) }.to_string_lossy().into_owned()
+    }
+
+    // Name and number of parameters of every function in `text` declared by the line prefix
+    // `marker`, up to the opening parenthesis
+    fn functions(text: &str, marker: &str) -> Vec<(String, usize)> {
+        let mut found: Vec<(String, usize)> = text
+            .lines()
+            .filter_map(|line| {
+                let (_, rest) = line.split_once(marker)?;
+                let name = rest.trim_start_matches('*').split('(').next()?.trim().rsplit([' ', '*']).next()?.to_string();
+                let params = rest.split_once('(')?.1.split(')').next()?.trim();
+                Some((name, if params.is_empty() || params == "void" { 0 } else { params.split(',').count() }))
+            })
+            .filter(|(name, _)| name.starts_with("ci_"))
+            .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn header_declares_every_exported_function() {
+        let exported = functions(SOURCE, "extern \"C\" fn ");
+        let declared = functions(&HEADER.lines().filter(|line| !line.starts_with([' ', '/', '#'])).collect::<Vec<_>>().join("\n"), "");
+        assert_eq!(exported.len(), 7);
+        assert_eq!(exported, declared);
+    }
+
+    #[test]
+    fn header_outcomes_and_state_match() {
+        let defines: Vec<String> = HEADER.lines().filter_map(|line| line.strip_prefix("#define CI_")).map(|d| d.replacen(' ', " = ", 1)).collect();
+        let consts: Vec<String> = SOURCE.lines().filter_map(|line| line.strip_prefix("pub const CI_")).map(|c| c.replace(": i32", "").trim_end_matches(';').to_string()).collect();
+        assert_eq!(defines, consts);
+
+        let state = |text: &str, start: &str, end: &str| -> Vec<String> {
+            let body = text.split_once(start).unwrap().1.split_once(end).unwrap().0;
+            body.lines().filter_map(|line| line.split(';').next()?.split(':').next()?.split_whitespace().last().map(|name| name.trim_end_matches(',').to_string())).filter(|name| !name.starts_with("/*") && !name.is_empty()).collect()
+        };
+        let rust = state(SOURCE, "pub struct CiState {", "\n}");
+        assert_eq!(rust.len(), 13);
+        assert_eq!(rust, state(HEADER, "typedef struct CiState {", "\n} CiState;"));
+    }
+
+    #[test]
+    fn runs_an_engagement_to_the_end() {
+        unsafe {
+            let sim = ci_simulation_create(std::ptr::null(), 1);
+            assert!(!sim.is_null());
+            let mut state = std::mem::zeroed::<CiState>();
+            let mut steps = 0;
+            while ci_simulation_step(sim) == 0 {
+                steps += 1;
+                assert_eq!(ci_simulation_state(sim, &mut state), 0);
+                assert_eq!(state.outcome, CI_RUNNING);
+            }
+            assert_eq!(ci_simulation_state(sim, &mut state), 0);
+            assert_ne!(state.outcome, CI_RUNNING);
+            assert!(state.step == steps || state.step == steps + 1, "{} steps, state at {}", steps, state.step);
+            let json = ci_simulation_result_json(sim);
+            let result = Json::parse(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+            assert_eq!(result.get("miss_distance").and_then(Json::as_f64), Some(state.miss_distance));
+            ci_string_free(json);
+            ci_simulation_destroy(sim);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        let run = |seed| unsafe {
+            let sim = ci_simulation_create(c"{\"config\": {\"guidance\": \"zem\"}}".as_ptr(), seed);
+            while ci_simulation_step(sim) == 0 {}
+            let mut state = std::mem::zeroed::<CiState>();
+            ci_simulation_state(sim, &mut state);
+            ci_simulation_destroy(sim);
+            (state.step, state.miss_distance)
+        };
+        assert_eq!(run(5), run(5));
+    }
+
+    #[test]
+    fn failures_leave_a_message() {
+        unsafe {
+            assert!(ci_simulation_create(c"{\"config\": {\"dt\": -1}}".as_ptr(), 1).is_null());
+            assert!(last_error().contains("dt"), "{}", last_error());
+            assert!(ci_simulation_create(c"{not json".as_ptr(), 1).is_null());
+            assert!(last_error().contains("invalid JSON"), "{}", last_error());
+            assert_eq!(ci_simulation_step(std::ptr::null_mut()), -1);
+            assert!(last_error().contains("NULL"));
+        }
+    }
+
+    #[test]
+    fn panics_are_caught() {
+        let result = guard("ci_test", -1, || panic!("boom"));
+        assert_eq!(result, -1);
+        assert_eq!(last_error(), "ci_test: the simulation panicked: boom");
+    }
+}
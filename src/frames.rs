@@ -0,0 +1,253 @@
+// Coordinate frames for placing an engagement on the earth. The simulation's own frame is a
+// vertical plane: x runs along the ground from the frame's origin towards `bearing_deg` (clockwise
+// from north) and y is the height above the origin. With a frame in the config, e.g.
+//
+//   "frame": { "origin": { "lat": 47.3769, "lon": 8.5417, "alt": 408 }, "bearing_deg": 90, "earth": "ellipsoid" }
+//
+// positions can be given as geodetic { "lat", "lon", "alt" } (degrees, meters above the WGS-84
+// ellipsoid) or as offsets from the origin, { "enu": [east, north, up] } or
+// { "ned": [north, east, down] }, and results can be reported geodetically. A position off the
+// plane is projected onto it; the 2D simulation drops the cross-range.
+//
+// `earth` picks the model between geodetic and local coordinates: "ellipsoid" (the default) goes
+// through earth-centered coordinates on WGS-84 and is exact, "flat" scales latitude and longitude
+// by the earth's radii of curvature at the origin, which is good to a few meters within ~10 km.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use std::fmt;
+
+// WGS-84 semi-major axis (m) and flattening
+const A: f64 = 6_378_137.0;
+const F: f64 = 1.0 / 298.257_223_563;
+const E2: f64 = F * (2.0 - F); // First eccentricity squared
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    pub lat_deg: f64,
+    pub lon_deg: f64,
+    pub alt: f64, // Meters above the ellipsoid
+}
+
+// East, north, up offsets (m) from a frame's origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Enu {
+    pub east: f64,
+    pub north: f64,
+    pub up: f64,
+}
+
+// North, east, down, the same offsets as navigation systems usually give them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ned {
+    pub north: f64,
+    pub east: f64,
+    pub down: f64,
+}
+
+impl From<Ned> for Enu {
+    fn from(ned: Ned) -> Enu {
+        Enu { east: ned.east, north: ned.north, up: -ned.down }
+    }
+}
+
+impl From<Enu> for Ned {
+    fn from(enu: Enu) -> Ned {
+        Ned { north: enu.north, east: enu.east, down: -enu.up }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EarthModel {
+    Flat,
+    Ellipsoid,
+}
+
+impl EarthModel {
+    pub fn parse(s: &str) -> Option<EarthModel> {
+        match s {
+            "flat" => Some(EarthModel::Flat),
+            "ellipsoid" => Some(EarthModel::Ellipsoid),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EarthModel::Flat => "flat",
+            EarthModel::Ellipsoid => "ellipsoid",
+        }
+    }
+}
+
+// Meridian and prime vertical radii of curvature at a latitude
+fn radii(lat: f64) -> (f64, f64) {
+    let w = (1.0 - E2 * lat.sin().powi(2)).sqrt();
+    (A * (1.0 - E2) / (w * w * w), A / w)
+}
+
+fn to_ecef(geo: Geodetic) -> [f64; 3] {
+    let (lat, lon) = (geo.lat_deg.to_radians(), geo.lon_deg.to_radians());
+    let (_, n) = radii(lat);
+    [(n + geo.alt) * lat.cos() * lon.cos(), (n + geo.alt) * lat.cos() * lon.sin(), (n * (1.0 - E2) + geo.alt) * lat.sin()]
+}
+
+fn from_ecef([x, y, z]: [f64; 3]) -> Geodetic {
+    let p = x.hypot(y);
+    // Fixed-point iteration on the latitude, converged to well below a millimeter after a few rounds
+    let mut lat = z.atan2(p * (1.0 - E2));
+    let mut alt = 0.0;
+    for _ in 0..6 {
+        let (_, n) = radii(lat);
+        alt = p / lat.cos() - n;
+        lat = z.atan2(p * (1.0 - E2 * n / (n + alt)));
+    }
+    Geodetic { lat_deg: lat.to_degrees(), lon_deg: y.atan2(x).to_degrees(), alt }
+}
+
+// The simulation's plane on the earth, see the top of this file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub origin: Geodetic,
+    pub bearing_deg: f64,
+    pub earth: EarthModel,
+}
+
+impl Frame {
+    pub fn enu(&self, geo: Geodetic) -> Enu {
+        let (lat0, lon0) = (self.origin.lat_deg.to_radians(), self.origin.lon_deg.to_radians());
+        match self.earth {
+            EarthModel::Flat => {
+                let (m, n) = radii(lat0);
+                // Longitude difference wrapped to ±180° so paths across the antimeridian stay short
+                let dlon = (geo.lon_deg - self.origin.lon_deg + 540.0).rem_euclid(360.0) - 180.0;
+                Enu {
+                    east: dlon.to_radians() * (n + self.origin.alt) * lat0.cos(),
+                    north: (geo.lat_deg - self.origin.lat_deg).to_radians() * (m + self.origin.alt),
+                    up: geo.alt - self.origin.alt,
+                }
+            }
+            EarthModel::Ellipsoid => {
+                let (p, p0) = (to_ecef(geo), to_ecef(self.origin));
+                let [dx, dy, dz] = [p[0] - p0[0], p[1] - p0[1], p[2] - p0[2]];
+                let (slat, clat, slon, clon) = (lat0.sin(), lat0.cos(), lon0.sin(), lon0.cos());
+                Enu {
+                    east: -slon * dx + clon * dy,
+                    north: -slat * clon * dx - slat * slon * dy + clat * dz,
+                    up: clat * clon * dx + clat * slon * dy + slat * dz,
+                }
+            }
+        }
+    }
+
+    pub fn geodetic(&self, enu: Enu) -> Geodetic {
+        let (lat0, lon0) = (self.origin.lat_deg.to_radians(), self.origin.lon_deg.to_radians());
+        match self.earth {
+            EarthModel::Flat => {
+                let (m, n) = radii(lat0);
+                let lon_deg = self.origin.lon_deg + (enu.east / ((n + self.origin.alt) * lat0.cos())).to_degrees();
+                Geodetic {
+                    lat_deg: self.origin.lat_deg + (enu.north / (m + self.origin.alt)).to_degrees(),
+                    lon_deg: (lon_deg + 540.0).rem_euclid(360.0) - 180.0,
+                    alt: self.origin.alt + enu.up,
+                }
+            }
+            EarthModel::Ellipsoid => {
+                let p0 = to_ecef(self.origin);
+                let (slat, clat, slon, clon) = (lat0.sin(), lat0.cos(), lon0.sin(), lon0.cos());
+                let Enu { east, north, up } = enu;
+                from_ecef([
+                    p0[0] - slon * east - slat * clon * north + clat * clon * up,
+                    p0[1] + clon * east - slat * slon * north + clat * slon * up,
+                    p0[2] + clat * north + slat * up,
+                ])
+            }
+        }
+    }
+
+    // Position in the simulation plane, and how far off the plane (m, right of the bearing) `enu` is
+    pub fn local(&self, enu: Enu) -> ((f64, f64), f64) {
+        let (sin, cos) = self.bearing_deg.to_radians().sin_cos();
+        ((enu.east * sin + enu.north * cos, enu.up), enu.east * cos - enu.north * sin)
+    }
+
+    // The earth position of a point (x, y) in the simulation plane
+    pub fn geodetic_at(&self, (x, y): (f64, f64)) -> Geodetic {
+        let (sin, cos) = self.bearing_deg.to_radians().sin_cos();
+        self.geodetic(Enu { east: x * sin, north: x * cos, up: y })
+    }
+
+    // A position in the scenario file, see the top of this file
+    pub fn position_from_json(&self, json: &Json) -> Result<((f64, f64), f64), String> {
+        let triple = |key: &str| match json.get(key).and_then(Json::as_array).map(|v| v.iter().map(Json::as_f64).collect::<Option<Vec<_>>>()) {
+            Some(Some(v)) if v.len() == 3 => Ok((v[0], v[1], v[2])),
+            _ => Err(format!("'{}' must be three numbers", key)),
+        };
+        let enu = if json.get("lat").is_some() {
+            self.enu(Geodetic::from_json(json)?)
+        } else if json.get("enu").is_some() {
+            let (east, north, up) = triple("enu")?;
+            Enu { east, north, up }
+        } else if json.get("ned").is_some() {
+            let (north, east, down) = triple("ned")?;
+            Ned { north, east, down }.into()
+        } else {
+            return Err("a position must be [x, y], {\"lat\", \"lon\", \"alt\"}, {\"enu\": [e, n, u]} or {\"ned\": [n, e, d]}".into());
+        };
+        Ok(self.local(enu))
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        let Geodetic { lat_deg, lon_deg, alt } = self.origin;
+        v.check((-89.0..=89.0).contains(&lat_deg), "origin.lat", "must be between -89 and 89 degrees");
+        v.check((-180.0..=180.0).contains(&lon_deg), "origin.lon", "must be between -180 and 180 degrees");
+        v.check(alt.is_finite(), "origin.alt", "must be a finite number");
+        v.check(self.bearing_deg.is_finite(), "bearing_deg", "must be a finite number");
+    }
+}
+
+// 47.376900°N 8.541700°E 408.0 m
+impl fmt::Display for Geodetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ns = if self.lat_deg < 0.0 { 'S' } else { 'N' };
+        let ew = if self.lon_deg < 0.0 { 'W' } else { 'E' };
+        write!(f, "{:.6}°{} {:.6}°{} {:.1} m", self.lat_deg.abs(), ns, self.lon_deg.abs(), ew, self.alt)
+    }
+}
+
+impl ToJson for Geodetic {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("lat", self.lat_deg.into()), ("lon", self.lon_deg.into()), ("alt", self.alt.into())])
+    }
+}
+
+// The altitude defaults to 0, the ellipsoid's surface
+impl FromJson for Geodetic {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Ok(Geodetic { lat_deg: json.f64_field("lat")?, lon_deg: json.f64_field("lon")?, alt: json.f64_field_or("alt", 0.0)? })
+    }
+}
+
+impl ToJson for Frame {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("origin", self.origin.to_json()),
+            ("bearing_deg", self.bearing_deg.into()),
+            ("earth", self.earth.as_str().into()),
+        ])
+    }
+}
+
+impl FromJson for Frame {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let earth = match json.get("earth") {
+            Some(earth) => earth.as_str().and_then(EarthModel::parse).ok_or("frame 'earth' must be \"flat\" or \"ellipsoid\"")?,
+            None => EarthModel::Ellipsoid,
+        };
+        Ok(Frame {
+            origin: Geodetic::from_json(json.field("origin")?).map_err(|e| format!("origin: {}", e))?,
+            bearing_deg: json.f64_field_or("bearing_deg", 90.0)?,
+            earth,
+        })
+    }
+}
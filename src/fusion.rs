@@ -0,0 +1,198 @@
+// The sensors feeding a track and measurement-level fusion of their data (see tracking.rs). Each
+// sensor is a position measurement with fixed noise or a radar (radar.rs), either on board the
+// interceptor or on the ground at a fixed `position`, and its data reaches the tracker `latency`
+// steps after the look, e.g. a ground radar and the seeker:
+//
+//   "sensors": [
+//     { "radar": { "bearing_noise_deg": 0.2, "max_range": 200 }, "position": [0, 0], "latency": 2 },
+//     { "radar": { "detection_range": 20 } }
+//   ]
+//
+// All measurements go into the one filter, in the order they were taken rather than arrived: the
+// filter runs as far behind as the slowest sensor, and every step a copy of it catches up with
+// the data that has arrived since. Measurements of the same step are fused one after the other.
+
+use crate::clutter::{Clutter, Plot};
+use crate::json::{FromJson, Json, ToJson};
+use crate::noise::standard_normal;
+use crate::radar::Radar;
+use crate::tracking::{Measurement, TrackEstimate, TrackingFilter};
+use crate::validation::Violations;
+use rand::RngCore;
+
+pub const MAX_SENSORS: usize = 4;
+// Latency limit in steps; the tracker re-runs this many steps of data every step
+pub const MAX_LATENCY: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sensor {
+    Position { noise: f64 }, // Standard deviation of each measured coordinate (m)
+    Radar(Radar),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorSpec {
+    pub sensor: Sensor,
+    pub position: Option<(f64, f64)>, // Ground site, None on board the interceptor
+    pub latency: usize,               // Steps from the look until the tracker has the data
+}
+
+impl SensorSpec {
+    // One look at the target at `to`, from the interceptor at `interceptor` for an onboard sensor,
+    // and the plot associated with the track predicted at `predicted`; None if there was none
+    pub fn look(
+        &self,
+        clutter: Option<Clutter>,
+        interceptor: (f64, f64),
+        to: (f64, f64),
+        predicted: (f64, f64),
+        rng: &mut dyn RngCore,
+    ) -> Option<Plot> {
+        let from = self.position.unwrap_or(interceptor);
+        let detection = self.measure(from, to, rng).map(|measurement| Plot { measurement, target: true });
+        let Some(clutter) = clutter else {
+            return detection;
+        };
+        let noise = match self.sensor {
+            Sensor::Position { noise } => noise,
+            Sensor::Radar(radar) => radar.noise_at((to.0 - from.0).hypot(to.1 - from.1)),
+        };
+        let plots: Vec<Plot> = detection.into_iter().chain(clutter.false_plots(to, noise, rng)).collect();
+        clutter.associate(&plots, predicted)
+    }
+
+    // The sensor's measurement of the target; None if it didn't detect it
+    fn measure(&self, from: (f64, f64), to: (f64, f64), rng: &mut dyn RngCore) -> Option<Measurement> {
+        match self.sensor {
+            Sensor::Position { noise: 0.0 } => Some(Measurement { position: to, noise: 0.0 }),
+            Sensor::Position { noise } => {
+                let position = (to.0 + noise * standard_normal(rng), to.1 + noise * standard_normal(rng));
+                Some(Measurement { position, noise })
+            }
+            Sensor::Radar(radar) => radar.measure(from, to, rng),
+        }
+    }
+
+    // Exact sensors (zero noise) are only allowed when the filter doesn't need a noise
+    pub fn validate(&self, v: &mut Violations, allow_exact: bool) {
+        match self.sensor {
+            Sensor::Position { noise } if !(noise >= 0.0 && noise.is_finite()) => {
+                v.check(false, "measurement_noise", "must be zero or positive");
+            }
+            Sensor::Position { noise: 0.0 } if !allow_exact => {
+                v.check(false, "measurement_noise", "must be positive for the Kalman and IMM filters");
+            }
+            Sensor::Radar(radar) => v.section("radar", |v| radar.validate(v)),
+            _ => {}
+        }
+        v.check(self.position.is_none_or(|(x, y)| x.is_finite() && y.is_finite()), "position", "must be finite numbers");
+        v.check(self.latency <= MAX_LATENCY, "latency", "must be at most 50 steps");
+    }
+}
+
+impl ToJson for SensorSpec {
+    fn to_json(&self) -> Json {
+        let sensor = match self.sensor {
+            Sensor::Position { noise } => ("measurement_noise", noise.into()),
+            Sensor::Radar(radar) => ("radar", radar.to_json()),
+        };
+        Json::object(vec![sensor, ("position", self.position.into()), ("latency", self.latency.into())])
+    }
+}
+
+// The sensor is the radar if there is a `radar` section, otherwise positions with `measurement_noise`
+impl FromJson for SensorSpec {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let sensor = match json.get("radar") {
+            Some(radar) => Sensor::Radar(Radar::from_json(radar).map_err(|e| format!("radar: {}", e))?),
+            None => Sensor::Position { noise: json.f64_field("measurement_noise")? },
+        };
+        let position = match json.get("position").filter(|v| **v != Json::Null) {
+            Some(position) => Some(position.as_point().ok_or("sensor 'position' must be [x, y]")?),
+            None => None,
+        };
+        let latency = match json.get("latency") {
+            Some(latency) => latency.as_usize().ok_or("sensor 'latency' must be a non-negative number of steps")?,
+            None => 0,
+        };
+        Ok(SensorSpec { sensor, position, latency })
+    }
+}
+
+// The filter and the data it has been given. `looks` are the recorded looks of every step, one
+// entry per sensor.
+pub struct Tracker {
+    dt: f64,
+    latencies: Vec<usize>,
+    start: Box<dyn TrackingFilter>,   // Has taken in every step dropped from the front of the looks
+    settled: Box<dyn TrackingFilter>, // Has taken in every step before `settled_steps`, which have all arrived
+    settled_steps: usize,
+}
+
+impl Tracker {
+    // `filter` has seen nothing yet; it is brought up to date with `looks` up to step `now`
+    pub fn new(filter: Box<dyn TrackingFilter>, sensors: &[SensorSpec], dt: f64, looks: &[Vec<Option<Measurement>>], now: usize) -> Tracker {
+        let latencies = sensors.iter().map(|s| s.latency).collect();
+        let mut tracker = Tracker { dt, latencies, start: filter.clone(), settled: filter, settled_steps: 0 };
+        tracker.settle(looks, now);
+        tracker
+    }
+
+    // The estimate at step `now` from everything that has arrived by then, or None before the first
+    // measurement, and whether any new data arrived at `now`
+    pub fn estimate(&mut self, looks: &[Vec<Option<Measurement>>], now: usize) -> (Option<TrackEstimate>, bool) {
+        let mut filter = self.settled.boxed_clone();
+        let mut estimate = None;
+        for (k, step_looks) in looks.iter().enumerate().take(now + 1).skip(self.settled_steps) {
+            estimate = fuse(filter.as_mut(), step_looks, &self.latencies, self.dt, |latency| k + latency <= now);
+        }
+        let arrived = self.latencies.iter().enumerate().any(|(s, &latency)| {
+            now.checked_sub(latency).and_then(|k| looks.get(k)).is_some_and(|step_looks| step_looks[s].is_some())
+        });
+        self.settle(looks, now);
+        (estimate, arrived)
+    }
+
+    // The oldest entries of the looks, `dropped`, are about to be dropped; they must all have been
+    // settled. Returns the filter that has taken them in, which the looks kept continue from.
+    pub fn forget(&mut self, dropped: &[Vec<Option<Measurement>>]) -> Box<dyn TrackingFilter> {
+        debug_assert!(dropped.len() <= self.settled_steps);
+        for step_looks in dropped {
+            fuse(self.start.as_mut(), step_looks, &self.latencies, self.dt, |_| true);
+        }
+        self.settled_steps -= dropped.len();
+        self.start.clone()
+    }
+
+    // Steps whose data has all arrived by `now` go into the settled filter for good
+    fn settle(&mut self, looks: &[Vec<Option<Measurement>>], now: usize) {
+        let slowest = self.latencies.iter().copied().max().unwrap_or(0);
+        while self.settled_steps + slowest <= now && self.settled_steps < looks.len() {
+            fuse(self.settled.as_mut(), &looks[self.settled_steps], &self.latencies, self.dt, |_| true);
+            self.settled_steps += 1;
+        }
+    }
+}
+
+// One step of the filter with the looks of that step whose sensor latency `arrived` accepts;
+// coasts if there are none
+fn fuse(
+    filter: &mut dyn TrackingFilter,
+    looks: &[Option<Measurement>],
+    latencies: &[usize],
+    dt: f64,
+    arrived: impl Fn(usize) -> bool,
+) -> Option<TrackEstimate> {
+    let mut estimate = None;
+    let mut elapsed = dt;
+    for (look, &latency) in looks.iter().zip(latencies) {
+        if let Some(measurement) = look.filter(|_| arrived(latency)) {
+            estimate = Some(filter.update(measurement, elapsed));
+            elapsed = 0.0;
+        }
+    }
+    if elapsed > 0.0 {
+        estimate = filter.coast(dt);
+    }
+    estimate
+}
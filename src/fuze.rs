@@ -0,0 +1,67 @@
+// Proximity fuze and warhead lethality. Without a fuze the run ends as an intercept as soon as the
+// vehicles come within the collision threshold, which counts as a certain kill. With one, e.g.
+//
+//   "fuze": { "arming_delay": 3, "detonation_radius": 4, "lethal_radius": 2, "angle_sensitivity": 0.5 }
+//
+// the warhead can only go off `arming_delay` seconds after launch and then detonates the first step
+// the target is within `detonation_radius`. Whether that kills it is a probability of the miss
+// distance at detonation and the crossing angle between the two velocities:
+//
+//   Pk = exp(-½ (miss / lethal_radius)²) · (1 - angle_sensitivity · |cos(crossing angle)|)
+//
+// The fragments fly out sideways, so a head-on or tail-chase pass (crossing angle near 0° or 180°)
+// puts fewer of them on the target than one at right angles.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fuze {
+    pub arming_delay: f64,      // Seconds after launch before the fuze can fire
+    pub detonation_radius: f64, // Range (m) at which an armed fuze fires
+    pub lethal_radius: f64,     // Miss distance (m) at which Pk has dropped to about 0.6 of its best
+    pub angle_sensitivity: f64, // 0 = crossing angle doesn't matter, 1 = no kill along the target's axis
+}
+
+impl Fuze {
+    pub fn armed(&self, elapsed: f64) -> bool {
+        elapsed >= self.arming_delay
+    }
+
+    pub fn pk(&self, miss_distance: f64, crossing_angle_deg: f64) -> f64 {
+        let distance_factor = (-0.5 * (miss_distance / self.lethal_radius).powi(2)).exp();
+        let angle_factor = 1.0 - self.angle_sensitivity * crossing_angle_deg.to_radians().cos().abs();
+        distance_factor * angle_factor
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.arming_delay >= 0.0 && self.arming_delay.is_finite(), "arming_delay", "must be zero or a positive number of seconds");
+        v.check(self.detonation_radius > 0.0 && self.detonation_radius.is_finite(), "detonation_radius", "must be positive");
+        v.check(self.lethal_radius > 0.0 && self.lethal_radius.is_finite(), "lethal_radius", "must be positive");
+        v.check((0.0..=1.0).contains(&self.angle_sensitivity), "angle_sensitivity", "must be between 0 and 1");
+    }
+}
+
+impl ToJson for Fuze {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("arming_delay", self.arming_delay.into()),
+            ("detonation_radius", self.detonation_radius.into()),
+            ("lethal_radius", self.lethal_radius.into()),
+            ("angle_sensitivity", self.angle_sensitivity.into()),
+        ])
+    }
+}
+
+// Only the detonation radius is required; the lethal radius defaults to half of it
+impl FromJson for Fuze {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let detonation_radius = json.f64_field("detonation_radius")?;
+        Ok(Fuze {
+            arming_delay: json.f64_field_or("arming_delay", 0.0)?,
+            detonation_radius,
+            lethal_radius: json.f64_field_or("lethal_radius", detonation_radius / 2.0)?,
+            angle_sensitivity: json.f64_field_or("angle_sensitivity", 0.0)?,
+        })
+    }
+}
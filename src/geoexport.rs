@@ -0,0 +1,139 @@
+// Geographic export of a run for Google Earth (--export-kml) and GIS tools (--export-geojson): both
+// trajectories as 3D lines, the collision point and the event log as markers, placed on the earth
+// with the scenario's frame (see frames.rs). Altitudes are absolute, meters above the ellipsoid.
+
+use crate::error::SimError;
+use crate::frames::{Frame, Geodetic};
+use crate::json::Json;
+use crate::log;
+use crate::{SimEvent, SimulationResult};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoFormat {
+    Kml,
+    GeoJson,
+}
+
+impl GeoFormat {
+    // The formats asked for on the command line, in a fixed order
+    pub fn from_args(args: &[String]) -> Vec<GeoFormat> {
+        [("--export-kml", GeoFormat::Kml), ("--export-geojson", GeoFormat::GeoJson)]
+            .into_iter()
+            .filter(|(flag, _)| args.iter().any(|a| a == flag))
+            .map(|(_, format)| format)
+            .collect()
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GeoFormat::Kml => "kml",
+            GeoFormat::GeoJson => "geojson",
+        }
+    }
+
+    pub fn write(&self, sim: &SimulationResult, path: &Path) -> Result<(), SimError> {
+        let frame = sim.config.frame.ok_or_else(|| missing_frame(*self))?;
+        let text = match self {
+            GeoFormat::Kml => kml(sim, &frame),
+            GeoFormat::GeoJson => geojson(sim, &frame).to_string_pretty() + "\n",
+        };
+        std::fs::write(path, text).map_err(SimError::io(format!("cannot write '{}'", path.display())))
+    }
+}
+
+// Every export in `formats`, written next to the result file `result_path`
+pub fn export(formats: &[GeoFormat], sim: &SimulationResult, result_path: &Path) -> Result<(), SimError> {
+    for format in formats {
+        let path = result_path.with_extension(format.extension());
+        format.write(sim, &path)?;
+        log::info!("🌍 Trajectories saved as '{}'", path.display());
+    }
+    Ok(())
+}
+
+// For checking before anything runs that the export can be written
+pub fn missing_frame(format: GeoFormat) -> SimError {
+    let flag = match format {
+        GeoFormat::Kml => "--export-kml",
+        GeoFormat::GeoJson => "--export-geojson",
+    };
+    SimError::ConfigError(format!("{} needs a 'frame' in the scenario's config to place the engagement on the earth", flag))
+}
+
+// What is put on the map, in the simulation plane
+struct Marker {
+    name: String,
+    step: usize,
+    position: (f64, f64),
+}
+
+fn markers(sim: &SimulationResult) -> Vec<Marker> {
+    let collision = sim.collision_point.map(|position| Marker { name: String::from("Collision point"), step: sim.steps(), position });
+    // The intercept event is the collision point
+    let events = sim.events.iter().filter(|event| !matches!(event, SimEvent::Intercept { .. })).filter_map(|event| {
+        Some(Marker { name: event.label(), step: event.step(), position: sim.event_position(event)? })
+    });
+    collision.into_iter().chain(events).collect()
+}
+
+fn paths(sim: &SimulationResult) -> [(&'static str, &[(f64, f64)]); 2] {
+    [("Target", &sim.target_positions), ("Interceptor", &sim.interceptor_positions)]
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn kml(sim: &SimulationResult, frame: &Frame) -> String {
+    let coordinates = |geo: Geodetic| format!("{:.7},{:.7},{:.2}", geo.lon_deg, geo.lat_deg, geo.alt);
+    // Line colors are aabbggrr: the plot's red target and green interceptor
+    let mut kml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    kml += &format!("  <name>{} engagement, {}</name>\n", sim.config.guidance.as_str(), sim.outcome.as_str());
+    kml += "  <Style id=\"Target\"><LineStyle><color>ff0000ff</color><width>3</width></LineStyle></Style>\n";
+    kml += "  <Style id=\"Interceptor\"><LineStyle><color>ff00aa00</color><width>3</width></LineStyle></Style>\n";
+    for (name, positions) in paths(sim) {
+        let line: Vec<String> = positions.iter().map(|&p| coordinates(frame.geodetic_at(p))).collect();
+        kml += &format!(
+            "  <Placemark><name>{}</name><styleUrl>#{}</styleUrl><LineString><altitudeMode>absolute</altitudeMode><coordinates>{}</coordinates></LineString></Placemark>\n",
+            name,
+            name,
+            line.join(" ")
+        );
+    }
+    kml += "  <Folder><name>Events</name>\n";
+    for marker in markers(sim) {
+        kml += &format!(
+            "    <Placemark><name>{}</name><description>step {}, t = {:.2} s</description><Point><altitudeMode>absolute</altitudeMode><coordinates>{}</coordinates></Point></Placemark>\n",
+            escape_xml(&marker.name),
+            marker.step,
+            marker.step as f64 * sim.config.dt,
+            coordinates(frame.geodetic_at(marker.position))
+        );
+    }
+    kml + "  </Folder>\n</Document>\n</kml>\n"
+}
+
+// GeoJSON positions are [longitude, latitude, altitude]
+fn position(geo: Geodetic) -> Json {
+    Json::Array(vec![geo.lon_deg.into(), geo.lat_deg.into(), geo.alt.into()])
+}
+
+fn feature(geometry: Json, properties: Vec<(&str, Json)>) -> Json {
+    Json::object(vec![("type", "Feature".into()), ("geometry", geometry), ("properties", Json::object(properties))])
+}
+
+fn geojson(sim: &SimulationResult, frame: &Frame) -> Json {
+    let mut features = Vec::new();
+    for (name, positions) in paths(sim) {
+        let line = Json::Array(positions.iter().map(|&p| position(frame.geodetic_at(p))).collect());
+        features.push(feature(Json::object(vec![("type", "LineString".into()), ("coordinates", line)]), vec![("name", name.into())]));
+    }
+    for marker in markers(sim) {
+        let point = Json::object(vec![("type", "Point".into()), ("coordinates", position(frame.geodetic_at(marker.position)))]);
+        let properties = vec![("name", marker.name.as_str().into()), ("step", marker.step.into()), ("time", (marker.step as f64 * sim.config.dt).into())];
+        features.push(feature(point, properties));
+    }
+    Json::object(vec![("type", "FeatureCollection".into()), ("features", Json::Array(features))])
+}
+
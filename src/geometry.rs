@@ -0,0 +1,101 @@
+// Engagement geometry report, printed after every run: where and how the vehicles met and how hard
+// they had to maneuver to get there, derived from the recorded positions and velocities. The
+// quantities are typed by unit and printed in the --units chosen.
+
+use crate::engagement::Engagement;
+use crate::log;
+use crate::units::{self, Degrees, DegreesPerSecond, Meters, MetersPerSecond, MetersPerSecondSquared, Seconds, Units};
+use crate::{Interceptor, SimulationResult, Target};
+
+// Lateral acceleration above which the interceptor counts as maneuvering hard, unless
+// --accel-threshold is given (m/s²)
+pub const DEFAULT_ACCEL_THRESHOLD: MetersPerSecondSquared = MetersPerSecondSquared(1.0);
+
+#[derive(Debug, Clone, Copy)]
+pub struct GeometryReport {
+    pub aspect_angle: Degrees, // Target tail to line of sight to the interceptor over the final step: 0° tail chase, 180° head-on
+    pub closing_velocity: MetersPerSecond, // Rate the separation shrinks over the final step, negative while opening
+    pub target_path: Meters,               // Distance flown by each vehicle
+    pub interceptor_path: Meters,
+    pub target_peak_turn_rate: DegreesPerSecond, // Largest heading change per second of each vehicle
+    pub interceptor_peak_turn_rate: DegreesPerSecond,
+    pub peak_lateral_accel: MetersPerSecondSquared, // Largest interceptor acceleration normal to its velocity
+    pub time_above_threshold: Seconds,              // Time the interceptor spent above the lateral-acceleration threshold
+}
+
+impl GeometryReport {
+    pub fn of(sim: &SimulationResult, accel_threshold: MetersPerSecondSquared) -> GeometryReport {
+        let dt = Seconds(sim.config.dt);
+        // The final step's velocities with the geometry they started from: at coarse steps the
+        // interceptor usually ends up past the target, which would read as opening
+        let last = |v: &Vec<(f64, f64)>| v.last().copied().unwrap_or_default();
+        let before_last = |v: &Vec<(f64, f64)>| v.iter().rev().nth(1).or(v.last()).copied().unwrap_or_default();
+        let target = Target::new(before_last(&sim.target_positions), last(&sim.target_velocities));
+        let interceptor = Interceptor::new(before_last(&sim.interceptor_positions), last(&sim.interceptor_velocities));
+        let r = interceptor.position - target.position;
+
+        // Angle between the target's velocity and the direction to the interceptor, measured from the tail
+        let aspect_angle = if r.length() > 1e-9 && target.velocity.length() > 1e-9 {
+            Degrees(180.0 - target.velocity.angle_deg(r))
+        } else {
+            Degrees(0.0)
+        };
+        let closing_velocity = MetersPerSecond(Engagement::between(&interceptor, &target).closing_velocity);
+
+        let lateral_accels = lateral_accels(&sim.interceptor_velocities, dt);
+        GeometryReport {
+            aspect_angle,
+            closing_velocity,
+            target_path: path_length(&sim.target_positions),
+            interceptor_path: path_length(&sim.interceptor_positions),
+            target_peak_turn_rate: peak_turn_rate(&sim.target_velocities, dt),
+            interceptor_peak_turn_rate: peak_turn_rate(&sim.interceptor_velocities, dt),
+            peak_lateral_accel: lateral_accels.iter().copied().fold(MetersPerSecondSquared(0.0), units::max),
+            time_above_threshold: dt * lateral_accels.iter().filter(|a| **a > accel_threshold).count() as f64,
+        }
+    }
+
+    pub fn print(&self, accel_threshold: MetersPerSecondSquared, units: Units) {
+        log::info!("   aspect angle {:.1}°, closing velocity {:.2}", self.aspect_angle.0, units.speed(self.closing_velocity));
+        log::info!("   path flown: target {:.2}, interceptor {:.2}", units.distance(self.target_path), units.distance(self.interceptor_path));
+        log::info!("   peak turn rate: target {:.2}°/s, interceptor {:.2}°/s", self.target_peak_turn_rate.0, self.interceptor_peak_turn_rate.0);
+        log::info!(
+            "   peak lateral acceleration {:.2}, {:.2} s above {:.2}",
+            units.accel(self.peak_lateral_accel), self.time_above_threshold.0, units.accel(accel_threshold)
+        );
+    }
+}
+
+// --accel-threshold M/S2, DEFAULT_ACCEL_THRESHOLD when absent; always in m/s², whatever the --units
+pub fn accel_threshold_from_args(args: &[String]) -> Result<MetersPerSecondSquared, String> {
+    match args.windows(2).find(|w| w[0] == "--accel-threshold") {
+        Some(w) => w[1].parse().ok().filter(|a: &f64| *a >= 0.0).map(MetersPerSecondSquared).ok_or_else(|| String::from("--accel-threshold must be a non-negative number")),
+        None => Ok(DEFAULT_ACCEL_THRESHOLD),
+    }
+}
+
+pub fn path_length(positions: &[(f64, f64)]) -> Meters {
+    positions.windows(2).map(|w| Meters((w[1].0 - w[0].0).hypot(w[1].1 - w[0].1))).sum()
+}
+
+// The turn between two consecutive velocities per step: |v₀ × v₁| / |v₀| is the velocity change normal to v₀
+pub fn lateral_accels(velocities: &[(f64, f64)], dt: Seconds) -> Vec<MetersPerSecondSquared> {
+    velocities
+        .windows(2)
+        .filter_map(|w| {
+            let ((vx0, vy0), (vx1, vy1)) = (w[0], w[1]);
+            let speed = vx0.hypot(vy0);
+            (speed > 1e-9).then(|| MetersPerSecond((vx0 * vy1 - vy0 * vx1).abs() / speed) / dt)
+        })
+        .collect()
+}
+
+fn peak_turn_rate(velocities: &[(f64, f64)], dt: Seconds) -> DegreesPerSecond {
+    velocities
+        .windows(2)
+        .map(|w| {
+            let turn = (w[1].1.atan2(w[1].0) - w[0].1.atan2(w[0].0)).to_degrees().rem_euclid(360.0);
+            Degrees(turn.min(360.0 - turn)) / dt
+        })
+        .fold(DegreesPerSecond(0.0), units::max)
+}
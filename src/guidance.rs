@@ -0,0 +1,284 @@
+// Guidance laws besides the lead pursuit in lib.rs. Each one turns the current state of both
+// vehicles into a commanded heading (Steering); the interceptor flies at constant speed, so an
+// acceleration command is applied to its velocity over one step and only the new direction kept.
+
+use crate::engagement::Engagement;
+use crate::log;
+use crate::soa::Kinematics;
+use crate::vec2::Vec2;
+use crate::{GuidancePhase, Interceptor, Steering, Target};
+
+// Zero-effort miss: the miss distance that would remain if neither vehicle maneuvered from now on,
+// ZEM = r + v·tgo with relative position r, relative velocity v and tgo the engagement's time-to-go
+// estimate. Commanding a = N·ZEM⊥ / tgo² (the part normal to the line of sight, N the navigation
+// constant) drives it to zero. While the vehicles are not closing there is no time-to-go, and the
+// interceptor turns straight at the target instead (reported as no lock).
+pub fn zem_steering(from: &Interceptor, to: &Target, engagement: &Engagement, navigation_constant: f64, dt: f64) -> Steering {
+    let (r, v) = (to.position - from.position, to.velocity - from.velocity);
+    let range = engagement.range;
+    if range < 1e-9 {
+        return Steering { direction: Vec2::ZERO, lead_solution: false, phase: GuidancePhase::Direct };
+    }
+    let los = r / range;
+    let Some(tgo) = engagement.time_to_go else {
+        log::trace!("ZEM: not closing, aiming at the target's current position");
+        return Steering { direction: los, lead_solution: false, phase: GuidancePhase::Direct };
+    };
+
+    // Within the last step tgo would go to zero and the command explode
+    let tgo = tgo.max(dt);
+    let zem = r + v * tgo;
+    let zem = zem - los * zem.dot(los);
+    let accel = zem * (navigation_constant / (tgo * tgo));
+    log::trace!("ZEM: tgo {:.2}s, miss {:.2} m, commanded acceleration {:.2} m/s²", tgo, zem.length(), accel.length());
+
+    let direction = (from.velocity + accel * dt).normalized().unwrap_or(los);
+    Steering { direction, lead_solution: true, phase: GuidancePhase::Direct }
+}
+
+// Longest Riccati recursion per step; further out the gain has long converged
+const MAX_LQR_HORIZON: usize = 1000;
+
+// Cost weights of the LQR law
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LqrWeights {
+    pub miss: f64,   // On the lateral miss left at time-to-go
+    pub rate: f64,   // On the lateral relative velocity, every step
+    pub effort: f64, // On the commanded acceleration, every step
+}
+
+impl Default for LqrWeights {
+    fn default() -> Self {
+        LqrWeights { miss: 100.0, rate: 0.0, effort: 1.0 }
+    }
+}
+
+// Optimal-control guidance: the engagement is linearized normal to the current line of sight into
+// a double integrator, state x = (lateral offset y, lateral relative velocity ẏ), control u = the
+// interceptor's lateral acceleration (ÿ = -u for a non-maneuvering target). Minimizing
+//
+//   miss·y_N² + Σ (rate·ẏ_k² + effort·u_k²)
+//
+// over the N = tgo/dt remaining steps (tgo from the engagement estimate) is a finite-horizon LQR
+// problem; the first gain of the backward Riccati recursion gives the command u = -K₀x. With the
+// miss weight dominating it tends to the classic N = 3 proportional law. Not closing: straight at
+// the target, like ZEM.
+pub fn lqr_steering(from: &Interceptor, to: &Target, engagement: &Engagement, weights: LqrWeights, dt: f64) -> Steering {
+    let (r, v) = (to.position - from.position, to.velocity - from.velocity);
+    let range = engagement.range;
+    if range < 1e-9 {
+        return Steering { direction: Vec2::ZERO, lead_solution: false, phase: GuidancePhase::Direct };
+    }
+    let los = r / range;
+    let Some(tgo) = engagement.time_to_go else {
+        log::trace!("LQR: not closing, aiming at the target's current position");
+        return Steering { direction: los, lead_solution: false, phase: GuidancePhase::Direct };
+    };
+    let horizon = ((tgo / dt).ceil() as usize).clamp(1, MAX_LQR_HORIZON);
+
+    // x_{k+1} = A x_k + B u_k with A = [[1, dt], [0, 1]], B = [-dt²/2, -dt]; P symmetric
+    let (b1, b2) = (-dt * dt / 2.0, -dt);
+    let (mut p11, mut p12, mut p22) = (weights.miss, 0.0, 0.0);
+    let mut gain = (0.0, 0.0);
+    for _ in 0..horizon {
+        let (pb1, pb2) = (p11 * b1 + p12 * b2, p12 * b1 + p22 * b2);
+        let s = weights.effort + b1 * pb1 + b2 * pb2;
+        let (h1, h2) = (pb1, pb1 * dt + pb2); // BᵀPA
+        gain = (h1 / s, h2 / s);
+        let (a11, a12, a22) = (p11, p11 * dt + p12, (p11 * dt + p12) * dt + p12 * dt + p22); // AᵀPA
+        (p11, p12, p22) = (a11 - h1 * h1 / s, a12 - h1 * h2 / s, weights.rate + a22 - h2 * h2 / s);
+    }
+
+    // Normal to the line of sight; the offset along it is zero by construction
+    let normal = los.perp();
+    let lateral_rate = v.dot(normal);
+    let u = -gain.1 * lateral_rate;
+    log::trace!("LQR: tgo {:.2}s over {} steps, gain {:.3}, lateral rate {:.3} m/s, command {:.2} m/s²", tgo, horizon, -gain.1, lateral_rate, u);
+
+    let direction = (from.velocity + normal * u * dt).normalized().unwrap_or(los);
+    Steering { direction, lead_solution: true, phase: GuidancePhase::Direct }
+}
+
+// Most simulated steps MPC may spend on one guidance decision (levels² candidates × horizon)
+pub const MPC_MAX_EVALUATIONS: usize = 20_000;
+
+// Added to the cost of candidates that take the interceptor below height 0
+const GROUND_PENALTY: f64 = 1e9;
+
+// Search space of the MPC law
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MpcSettings {
+    pub horizon: usize,  // Steps looked ahead
+    pub levels: usize,   // Acceleration values tried per segment, evenly spaced in ±max_accel
+    pub max_accel: f64,  // Largest lateral acceleration considered (m/s²)
+    pub effort: f64,     // Cost per (m/s²)² of acceleration and second, relative to 1 m of miss
+}
+
+impl Default for MpcSettings {
+    fn default() -> Self {
+        MpcSettings { horizon: 15, levels: 7, max_accel: 2.0, effort: 0.01 }
+    }
+}
+
+// Model-predictive guidance: forward-simulates candidate acceleration sequences over a short
+// horizon against the target's path extrapolated at its current velocity, and applies the first
+// step of the cheapest. A candidate holds one lateral acceleration for the first half of the
+// horizon and another for the rest (levels² candidates); its cost is the closest predicted approach
+// plus the effort term, and flying into the ground rules a candidate out unless all of them do. The horizon never reaches past the run's remaining steps. Lock means a
+// candidate is predicted to come within the collision threshold.
+pub fn mpc_steering(from: &Interceptor, to: &Target, settings: MpcSettings, dt: f64, remaining_steps: usize, threshold: f64) -> Steering {
+    let horizon = settings.horizon.min(remaining_steps).max(1);
+    let levels = settings.levels.max(2);
+    let accel = |i: usize| settings.max_accel * (2.0 * i as f64 / (levels - 1) as f64 - 1.0);
+    let speed = from.velocity.length();
+    if speed < 1e-9 {
+        return Steering { direction: Vec2::ZERO, lead_solution: false, phase: GuidancePhase::Direct };
+    }
+
+    // Every pair of levels is a candidate, all rolled out together; lateral acceleration turns the
+    // velocity, the speed stays constant
+    let count = levels * levels;
+    let (firsts, seconds): (Vec<f64>, Vec<f64>) = (0..count).map(|c| (accel(c / levels), accel(c % levels))).unzip();
+    let mut candidates = Kinematics::filled(count, from.position.into(), from.velocity.into());
+    let (mut misses, mut efforts, mut grounded) = (vec![f64::INFINITY; count], vec![0.0; count], vec![false; count]);
+    let mut target = to.position;
+    let switch = horizon.div_ceil(2);
+    for k in 0..horizon {
+        let accels = if k < switch { &firsts } else { &seconds };
+        candidates.turn(accels, speed, dt);
+        candidates.advance(dt);
+        target += to.velocity * dt;
+        candidates.closest_approach(&mut misses, target.into());
+        for (effort, u) in efforts.iter_mut().zip(accels) {
+            *effort += u * u * dt;
+        }
+        for (grounded, y) in grounded.iter_mut().zip(&candidates.y) {
+            *grounded |= *y < 0.0;
+        }
+    }
+    let mut best = (f64::INFINITY, 0.0, f64::INFINITY); // (cost, first acceleration, predicted miss)
+    for (((&miss, effort), grounded), &first) in misses.iter().zip(efforts).zip(grounded).zip(&firsts) {
+        let cost = miss + settings.effort * effort + if grounded { GROUND_PENALTY } else { 0.0 };
+        if cost < best.0 {
+            best = (cost, first, miss);
+        }
+    }
+    log::trace!("MPC: {} candidates over {} steps, best first acceleration {:+.2} m/s², predicted miss {:.2} m", levels * levels, horizon, best.1, best.2);
+
+    let mut command = Kinematics::filled(1, from.position.into(), from.velocity.into());
+    command.turn(&[best.1], speed, dt);
+    Steering { direction: Vec2::new(command.vx[0], command.vy[0]) / speed, lead_solution: best.2 < threshold, phase: GuidancePhase::Direct }
+}
+
+// Command to line of sight (beam riding): the interceptor is kept on the line from a fixed ground
+// station to the target. It aims at the point of that beam `lookahead` seconds of flight beyond its
+// own projection onto it, but not past the target, which first steers it back onto the beam and then
+// along it. Lock means riding the beam, i.e. off it by less than `tolerance`.
+pub fn clos_steering(from: &Interceptor, to: &Target, station: Vec2, lookahead: f64, tolerance: f64) -> Steering {
+    let beam = to.position - station;
+    let beam_length = beam.length();
+    let unit = beam.normalized().unwrap_or(Vec2::ZERO);
+    let offset = from.position - station;
+    let along = offset.dot(unit);
+    let deviation = unit.cross(offset);
+    let aim_along = (along.max(0.0) + from.velocity.length() * lookahead).min(beam_length);
+    let aim = station + unit * aim_along;
+    log::trace!("CLOS: {:.2} m along the beam of {:.2} m, {:+.2} m off it", along, beam_length, deviation);
+
+    let direction = (aim - from.position).normalized().unwrap_or(unit);
+    Steering { direction, lead_solution: deviation.abs() < tolerance, phase: GuidancePhase::Direct }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 1.0;
+
+    fn vehicle(position: (f64, f64), velocity: (f64, f64)) -> Target {
+        Target::new(position, velocity)
+    }
+
+    // Interceptor at the origin flying +x at 2 m/s against a target at `position` moving at `velocity`
+    fn engagement(position: (f64, f64), velocity: (f64, f64)) -> (Interceptor, Target, Engagement) {
+        let (from, to) = (vehicle((0.0, 0.0), (2.0, 0.0)), vehicle(position, velocity));
+        let engagement = Engagement::between(&from, &to);
+        (from, to, engagement)
+    }
+
+    // The same, with the target climbing across the interceptor's nose
+    fn engagement_with_climb() -> (Interceptor, Target, Engagement) {
+        engagement((30.0, 0.0), (-1.0, 0.5))
+    }
+
+    fn assert_direction(steering: Steering, expected: (f64, f64)) {
+        let (x, y) = expected;
+        assert!((steering.direction.x - x).abs() < 1e-12 && (steering.direction.y - y).abs() < 1e-12, "{:?} instead of {:?}", steering.direction, expected);
+    }
+
+    #[test]
+    fn zem_holds_a_collision_course_and_turns_toward_the_miss() {
+        let (from, to, engagement) = engagement((30.0, 0.0), (-1.0, 0.0));
+        let steering = zem_steering(&from, &to, &engagement, 3.0, DT);
+        assert_direction(steering, (1.0, 0.0));
+        assert!(steering.lead_solution);
+        // A target climbing across the nose is led upward
+        let (from, to, engagement) = engagement_with_climb();
+        assert!(zem_steering(&from, &to, &engagement, 3.0, DT).direction.y > 0.0);
+    }
+
+    #[test]
+    fn zem_and_lqr_aim_straight_at_a_target_they_are_not_closing_on() {
+        // Fleeing straight up faster than the interceptor flies
+        let (from, to, engagement) = engagement((0.0, 100.0), (0.0, 5.0));
+        assert!(engagement.time_to_go.is_none());
+        for steering in [zem_steering(&from, &to, &engagement, 3.0, DT), lqr_steering(&from, &to, &engagement, LqrWeights::default(), DT)] {
+            assert_direction(steering, (0.0, 1.0));
+            assert!(!steering.lead_solution);
+        }
+    }
+
+    #[test]
+    fn lqr_holds_a_collision_course_and_turns_toward_the_miss() {
+        let (from, to, engagement) = engagement((30.0, 0.0), (-1.0, 0.0));
+        let steering = lqr_steering(&from, &to, &engagement, LqrWeights::default(), DT);
+        assert_direction(steering, (1.0, 0.0));
+        assert!(steering.lead_solution);
+        let (from, to, engagement) = engagement_with_climb();
+        assert!(lqr_steering(&from, &to, &engagement, LqrWeights::default(), DT).direction.y > 0.0);
+    }
+
+    #[test]
+    fn mpc_prefers_the_effortless_hit_and_locks_only_within_its_horizon() {
+        // Meets the target after 10 steps flying straight, well within the default 15
+        let (from, to, _) = engagement((30.0, 0.0), (-1.0, 0.0));
+        let steering = mpc_steering(&from, &to, MpcSettings::default(), DT, 100, 1.0);
+        assert_direction(steering, (1.0, 0.0));
+        assert!(steering.lead_solution);
+        // The same course ends past the horizon, or past the steps the run has left
+        let (from, to, _) = engagement((100.0, 0.0), (-1.0, 0.0));
+        assert!(!mpc_steering(&from, &to, MpcSettings::default(), DT, 100, 1.0).lead_solution);
+        let (from, to, _) = engagement((30.0, 0.0), (-1.0, 0.0));
+        assert!(!mpc_steering(&from, &to, MpcSettings::default(), DT, 5, 1.0).lead_solution);
+        // A target climbing steeply across the nose, met within a few steps, is led upward
+        let (from, to, _) = engagement((15.0, 0.0), (-1.0, 1.0));
+        let steering = mpc_steering(&from, &to, MpcSettings::default(), DT, 6, 1.0);
+        assert!(steering.direction.y > 0.0, "{:?}", steering);
+    }
+
+    #[test]
+    fn clos_rides_the_beam_and_returns_to_it() {
+        let station = Vec2::new(0.0, 0.0);
+        let diagonal = std::f64::consts::FRAC_1_SQRT_2;
+        let to = vehicle((100.0, 100.0), (-1.0, 0.0));
+        let on_beam = vehicle((10.0, 10.0), (2.0 * diagonal, 2.0 * diagonal));
+        let steering = clos_steering(&on_beam, &to, station, 2.0, 1.0);
+        assert_direction(steering, (diagonal, diagonal));
+        assert!(steering.lead_solution);
+        // Below the beam: no lock, and a turn up toward it
+        let below = vehicle((20.0, 0.0), (2.0, 0.0));
+        let steering = clos_steering(&below, &to, station, 2.0, 1.0);
+        assert!(!steering.lead_solution);
+        assert!(steering.direction.y > diagonal);
+    }
+}
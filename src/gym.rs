@@ -0,0 +1,270 @@
+// Reinforcement learning: `gym` offers the engagement as an environment in the style of OpenAI Gym,
+// so that RL frameworks can train an interceptor or an evader policy on the simulator. The agent
+// (--agent interceptor|target) flies on the lateral acceleration it is given each step, clipped to
+// ±--max-accel; the other vehicle keeps its guidance or evasion. The protocol is one JSON object
+// per line on stdin, each answered by one line on stdout:
+//
+//   {"op": "reset", "seed": 7}   a new engagement (the seed is optional; without one the next
+//                                seed of --seed N is used) -> {"observation": [...], "step": 0, "time": 0}
+//   {"op": "step", "action": a}  one step on acceleration a (m/s², positive turning the agent
+//                                counter-clockwise) -> {"observation", "reward", "done", "step",
+//                                "time"}, plus "outcome" and "miss_distance" once done
+//   {"op": "spec"}               {"observation": [names], "reward": {term: weight}, "max_accel"}
+//
+// Mistakes are answered with {"error": "..."} and the session goes on. The observation is a list of
+// quantities named with --observation LIST, the reward a weighted sum of the terms given with
+// --reward TERM:WEIGHT,... (see QUANTITIES and TERMS); the interceptor's default observation is the
+// one the neural guidance law (onnx.rs) feeds its model, so a policy trained here can fly as
+// "guidance": "neural". A scenario's envelope is sampled on every reset.
+
+use crate::checkpoint::SimRng;
+use crate::engagement::Engagement;
+use crate::error::SimError;
+use crate::json::Json;
+use crate::log;
+use crate::scenario::Scenario;
+use crate::simulation::Simulation;
+use crate::{Interceptor, Outcome, Target, Vehicle};
+use rand::SeedableRng;
+use std::io::{BufRead, Write};
+
+// What an observation can hold; r is the target's position and velocity relative to the interceptor
+const QUANTITIES: [&str; 17] =
+    ["rx", "ry", "rvx", "rvy", "ix", "iy", "ivx", "ivy", "tx", "ty", "tvx", "tvy", "range", "closing", "los", "los_rate", "time"];
+const INTERCEPTOR_OBSERVATION: &str = "rx,ry,rvx,rvy,ivx,ivy,range,closing";
+const TARGET_OBSERVATION: &str = "rx,ry,rvx,rvy,tvx,tvy,range,closing";
+
+// Reward terms, each per step: progress is the range closed (m), effort the squared action times
+// dt, time dt; intercept, ground_impact and timeout are 1 on the step the run ends that way, and
+// miss the miss distance (m) when it ends
+const TERMS: [&str; 7] = ["progress", "effort", "time", "intercept", "ground_impact", "timeout", "miss"];
+const INTERCEPTOR_REWARD: &str = "progress:0.1,effort:-0.01,intercept:10,ground_impact:-10,miss:-0.1";
+const TARGET_REWARD: &str = "progress:-0.1,effort:-0.01,intercept:-10,ground_impact:-10,miss:0.1";
+
+const DEFAULT_MAX_ACCEL: f64 = 2.0;
+
+pub struct Environment {
+    scenario: Scenario,
+    agent: Vehicle,
+    observation: Vec<&'static str>,
+    reward: Vec<(&'static str, f64)>,
+    max_accel: f64,
+    rng: SimRng,
+    simulation: Option<Simulation<'static>>, // None before the first reset
+}
+
+fn parse_observation(list: &str) -> Result<Vec<&'static str>, String> {
+    list.split(',')
+        .map(|name| QUANTITIES.iter().find(|&&q| q == name.trim()).copied().ok_or_else(|| format!("unknown observation '{}' (one of {})", name, QUANTITIES.join(", "))))
+        .collect()
+}
+
+fn parse_reward(list: &str) -> Result<Vec<(&'static str, f64)>, String> {
+    list.split(',')
+        .map(|term| {
+            let bad = || format!("reward term '{}' must be TERM:WEIGHT with TERM one of {}", term, TERMS.join(", "));
+            let (name, weight) = term.split_once(':').ok_or_else(bad)?;
+            let name = TERMS.iter().find(|&&t| t == name.trim()).ok_or_else(bad)?;
+            weight.trim().parse::<f64>().ok().filter(|w| w.is_finite()).map(|weight| (*name, weight)).ok_or_else(bad)
+        })
+        .collect()
+}
+
+impl Environment {
+    // An environment for `agent` with its default observation and reward
+    pub fn new(scenario: Scenario, agent: Vehicle, seed: u64) -> Environment {
+        let (observation, reward) = match agent {
+            Vehicle::Interceptor => (INTERCEPTOR_OBSERVATION, INTERCEPTOR_REWARD),
+            Vehicle::Target => (TARGET_OBSERVATION, TARGET_REWARD),
+        };
+        Environment {
+            scenario,
+            agent,
+            observation: parse_observation(observation).unwrap(),
+            reward: parse_reward(reward).unwrap(),
+            max_accel: DEFAULT_MAX_ACCEL,
+            rng: SimRng::seed_from_u64(seed),
+            simulation: None,
+        }
+    }
+
+    // --agent interceptor|target, --observation LIST, --reward TERM:WEIGHT,..., --max-accel A, --seed N
+    pub fn from_args(args: &[String], scenario: Scenario) -> Result<Environment, SimError> {
+        let value = |flag: &str| args.windows(2).find(|pair| pair[0] == flag).map(|pair| pair[1].as_str());
+        let agent = match value("--agent") {
+            None | Some("interceptor") => Vehicle::Interceptor,
+            Some("target") => Vehicle::Target,
+            Some(other) => return Err(format!("--agent must be interceptor or target, not '{}'", other).into()),
+        };
+        let seed = value("--seed").map_or(Ok(1), str::parse).map_err(|_| "--seed must be a non-negative integer")?;
+        let mut env = Environment::new(scenario, agent, seed);
+        if let Some(list) = value("--observation") {
+            env.observation = parse_observation(list)?;
+        }
+        if let Some(list) = value("--reward") {
+            env.reward = parse_reward(list)?;
+        }
+        if let Some(a) = value("--max-accel") {
+            env.max_accel = a.parse().ok().filter(|&a: &f64| a > 0.0 && a.is_finite()).ok_or("--max-accel must be a positive number")?;
+        }
+        Ok(env)
+    }
+
+    // Starts a new engagement, reseeded with `seed` if given, and returns the first observation
+    pub fn reset(&mut self, seed: Option<u64>) -> Result<Vec<f64>, SimError> {
+        if let Some(seed) = seed {
+            self.rng = SimRng::seed_from_u64(seed);
+        }
+        let config = match self.scenario.envelope.is_empty() {
+            true => self.scenario.config.clone(),
+            false => self.scenario.envelope.sample(&self.scenario.config, &mut self.rng),
+        };
+        let simulation = Simulation::builder().config(config).build()?;
+        let observation = observe(&self.observation, &simulation);
+        self.simulation = Some(simulation);
+        Ok(observation)
+    }
+
+    // One step on lateral acceleration `action`: the observation after it, the reward and whether
+    // the engagement has ended
+    pub fn step(&mut self, action: f64) -> Result<(Vec<f64>, f64, bool), String> {
+        let simulation = self.simulation.as_mut().filter(|s| !s.finished()).ok_or("the engagement has ended (or not started); reset it")?;
+        let action = action.clamp(-self.max_accel, self.max_accel);
+        let (target, interceptor) = vehicles(simulation);
+        let before = Engagement::between(&interceptor, &target).range;
+        let done = simulation.command(self.agent, action).step(&mut self.rng).is_finished();
+        let result = simulation.result();
+        let (target, interceptor) = vehicles(simulation);
+        let progress = match result.outcome {
+            Outcome::Intercept => before,
+            _ => before - Engagement::between(&interceptor, &target).range,
+        };
+        let dt = result.config.dt;
+        let reward = self
+            .reward
+            .iter()
+            .map(|&(term, weight)| {
+                weight
+                    * match term {
+                        "progress" => progress,
+                        "effort" => action * action * dt,
+                        "time" => dt,
+                        "intercept" => (done && result.outcome == Outcome::Intercept) as u8 as f64,
+                        "ground_impact" => (done && result.outcome == Outcome::GroundImpact) as u8 as f64,
+                        "timeout" => (done && result.outcome == Outcome::Timeout) as u8 as f64,
+                        _ => if done { result.miss_distance } else { 0.0 },
+                    }
+            })
+            .sum();
+        Ok((observe(&self.observation, simulation), reward, done))
+    }
+
+    fn spec(&self) -> Json {
+        Json::object(vec![
+            ("agent", self.agent.as_str().into()),
+            ("observation", Json::Array(self.observation.iter().map(|&q| q.into()).collect())),
+            ("reward", Json::object(self.reward.iter().map(|&(term, weight)| (term, weight.into())).collect())),
+            ("max_accel", self.max_accel.into()),
+        ])
+    }
+
+    // The answer to one request line
+    fn handle(&mut self, line: &str) -> Result<Json, String> {
+        let request = Json::parse(line).map_err(|e| format!("not JSON: {}", e))?;
+        match request.field("op")?.as_str() {
+            Some("reset") => {
+                let seed = match request.get("seed") {
+                    Some(seed) => Some(seed.as_usize().ok_or("'seed' must be a non-negative integer")? as u64),
+                    None => None,
+                };
+                let observation = self.reset(seed).map_err(|e| e.to_string())?;
+                Ok(Json::object(vec![("observation", floats(&observation)), ("step", 0.into()), ("time", 0.0.into())]))
+            }
+            Some("step") => {
+                let action = request.f64_field("action")?;
+                if !action.is_finite() {
+                    return Err("'action' must be a finite number".into());
+                }
+                let (observation, reward, done) = self.step(action)?;
+                let result = self.simulation.as_ref().unwrap().result();
+                let mut fields = vec![
+                    ("observation", floats(&observation)),
+                    ("reward", reward.into()),
+                    ("done", done.into()),
+                    ("step", result.steps().into()),
+                    ("time", (result.steps() as f64 * result.config.dt).into()),
+                ];
+                if done {
+                    fields.extend([("outcome", result.outcome.as_str().into()), ("miss_distance", result.miss_distance.into())]);
+                }
+                Ok(Json::object(fields))
+            }
+            Some("spec") => Ok(self.spec()),
+            _ => Err("'op' must be reset, step or spec".into()),
+        }
+    }
+}
+
+// The target and the interceptor as last recorded
+fn vehicles(simulation: &Simulation) -> (Target, Interceptor) {
+    let result = simulation.result();
+    let k = result.steps() - result.first_step;
+    (
+        Target::new(result.target_positions[k], result.target_velocities[k]),
+        Interceptor::new(result.interceptor_positions[k], result.interceptor_velocities[k]),
+    )
+}
+
+// The quantities listed in `observation`, as the simulation stands
+fn observe(observation: &[&str], simulation: &Simulation) -> Vec<f64> {
+    let (target, interceptor) = vehicles(simulation);
+    let (r, v) = (target.position - interceptor.position, target.velocity - interceptor.velocity);
+    let engagement = Engagement::between(&interceptor, &target);
+    let time = simulation.result().steps() as f64 * simulation.result().config.dt;
+    observation
+        .iter()
+        .map(|&quantity| match quantity {
+            "rx" => r.x,
+            "ry" => r.y,
+            "rvx" => v.x,
+            "rvy" => v.y,
+            "ix" => interceptor.position.x,
+            "iy" => interceptor.position.y,
+            "ivx" => interceptor.velocity.x,
+            "ivy" => interceptor.velocity.y,
+            "tx" => target.position.x,
+            "ty" => target.position.y,
+            "tvx" => target.velocity.x,
+            "tvy" => target.velocity.y,
+            "range" => engagement.range,
+            "closing" => engagement.closing_velocity,
+            "los" => r.y.atan2(r.x),
+            "los_rate" => match engagement.range > 1e-9 {
+                true => r.cross(v) / (engagement.range * engagement.range),
+                false => 0.0,
+            },
+            _ => time,
+        })
+        .collect()
+}
+
+fn floats(values: &[f64]) -> Json {
+    Json::Array(values.iter().map(|&v| v.into()).collect())
+}
+
+// Usage: gym [--agent interceptor|target] [--observation LIST] [--reward TERM:WEIGHT,...] [--max-accel A] [--seed N] [--preset NAME] [--scenario FILE]
+pub fn run_gym(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut env = Environment::from_args(args, scenario)?;
+    log::debug!("environment for the {} on stdin/stdout: {}", env.agent.as_str(), env.spec());
+    let mut stdout = std::io::stdout().lock();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let answer = env.handle(&line).unwrap_or_else(|e| Json::object(vec![("error", e.as_str().into())]));
+        writeln!(stdout, "{}", answer)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,237 @@
+// Interacting Multiple Model tracker for maneuvering targets. Three Kalman filters on the state
+// [x, y, vx, vy] run side by side, one per motion model:
+//
+//   constant velocity, and coordinated turns at +turn_rate_deg and -turn_rate_deg
+//
+// Every step the target is assumed to switch models with probability `switch_probability`. The
+// filters start from estimates mixed according to how likely each switch is, are updated with the
+// measurement, and the model probabilities are re-weighted by how well each predicted it. The
+// estimate handed to guidance is the probability-weighted mix, so it follows a weaving target
+// through its turns where a single constant-velocity filter lags behind.
+
+use crate::json::{Json, ToJson};
+use crate::log;
+use crate::tracking::{numbers, Measurement, TrackEstimate, TrackingFilter, INITIAL_VELOCITY_VARIANCE};
+
+type Vector = [f64; 4];
+type Matrix = [[f64; 4]; 4];
+
+#[derive(Clone, Copy)]
+struct Model {
+    turn_rate: f64, // rad/s, 0 for constant velocity
+    state: Vector,
+    covariance: Matrix,
+}
+
+impl Model {
+    // State transition over `dt` seconds
+    fn transition(&self, dt: f64) -> Matrix {
+        let w = self.turn_rate;
+        let (sin, cos) = (w * dt).sin_cos();
+        // sin(ωT)/ω and (1 - cos(ωT))/ω, which go to T and 0 for a straight line
+        let (a, b) = if w.abs() < 1e-9 { (dt, 0.0) } else { (sin / w, (1.0 - cos) / w) };
+        [[1.0, 0.0, a, -b], [0.0, 1.0, b, a], [0.0, 0.0, cos, -sin], [0.0, 0.0, sin, cos]]
+    }
+
+    fn predict(&mut self, dt: f64, process_noise: f64) {
+        let f = self.transition(dt);
+        self.state = mul_vector(&f, &self.state);
+        let mut p = mul(&mul(&f, &self.covariance), &transpose(&f));
+        // White acceleration noise on each axis
+        let (q4, q3, q2) = (process_noise * dt.powi(4) / 4.0, process_noise * dt.powi(3) / 2.0, process_noise * dt * dt);
+        for axis in 0..2 {
+            p[axis][axis] += q4;
+            p[axis][axis + 2] += q3;
+            p[axis + 2][axis] += q3;
+            p[axis + 2][axis + 2] += q2;
+        }
+        self.covariance = p;
+    }
+
+    // Updates the prediction with the measured position; returns the likelihood of the measurement
+    fn update(&mut self, measurement: Measurement) -> f64 {
+        let ((zx, zy), p) = (measurement.position, self.covariance);
+        let measurement_variance = measurement.noise * measurement.noise;
+        // Innovation and its 2×2 covariance S
+        let (rx, ry) = (zx - self.state[0], zy - self.state[1]);
+        let (s11, s12, s22) = (p[0][0] + measurement_variance, p[0][1], p[1][1] + measurement_variance);
+        let det = s11 * s22 - s12 * s12;
+        let (i11, i12, i22) = (s22 / det, -s12 / det, s11 / det);
+
+        // Gain K = P Hᵀ S⁻¹, with H picking the position
+        let gain: [(f64, f64); 4] = std::array::from_fn(|r| (p[r][0] * i11 + p[r][1] * i12, p[r][0] * i12 + p[r][1] * i22));
+        for (r, (k1, k2)) in gain.iter().enumerate() {
+            self.state[r] += k1 * rx + k2 * ry;
+        }
+        // P - K H P
+        self.covariance = std::array::from_fn(|r| std::array::from_fn(|c| p[r][c] - gain[r].0 * p[0][c] - gain[r].1 * p[1][c]));
+
+        let mahalanobis = rx * (i11 * rx + i12 * ry) + ry * (i12 * rx + i22 * ry);
+        (-0.5 * mahalanobis).exp() / (2.0 * std::f64::consts::PI * det.sqrt())
+    }
+}
+
+#[derive(Clone)]
+pub struct Imm {
+    process_noise: f64,
+    switch_probability: f64,
+    models: [Model; 3],
+    probabilities: [f64; 3],
+    initial_velocity: (f64, f64),
+    started: bool,
+}
+
+impl Imm {
+    pub fn new(process_noise: f64, turn_rate_deg: f64, switch_probability: f64, initial_velocity: (f64, f64)) -> Imm {
+        let model = |turn_rate: f64| Model { turn_rate, state: [0.0; 4], covariance: [[0.0; 4]; 4] };
+        let w = turn_rate_deg.to_radians();
+        Imm {
+            process_noise,
+            switch_probability,
+            models: [model(0.0), model(w), model(-w)],
+            probabilities: [1.0 / 3.0; 3],
+            initial_velocity,
+            started: false,
+        }
+    }
+
+    // Probability that the target flies model `to` this step given it flew `from` the last
+    fn transition_probability(&self, from: usize, to: usize) -> f64 {
+        if from == to { 1.0 - self.switch_probability } else { self.switch_probability / 2.0 }
+    }
+
+    fn estimate(&self) -> TrackEstimate {
+        let mut mixed = [0.0; 4];
+        for (model, mu) in self.models.iter().zip(self.probabilities) {
+            for (m, s) in mixed.iter_mut().zip(model.state) {
+                *m += mu * s;
+            }
+        }
+        TrackEstimate { position: (mixed[0], mixed[1]), velocity: (mixed[2], mixed[3]) }
+    }
+}
+
+impl Imm {
+    // Mixing: each filter starts from the estimates of all models, weighted by how likely the target
+    // switched from them into this one; then every model predicts. Returns the predicted model
+    // probabilities. No time passes for a second measurement of the same moment, so no switching.
+    fn predict(&mut self, dt: f64) -> [f64; 3] {
+        if dt == 0.0 {
+            return self.probabilities;
+        }
+        let predicted: [f64; 3] = std::array::from_fn(|j| (0..3).map(|i| self.transition_probability(i, j) * self.probabilities[i]).sum());
+        let mixing: [[f64; 3]; 3] =
+            std::array::from_fn(|j| std::array::from_fn(|i| self.transition_probability(i, j) * self.probabilities[i] / predicted[j]));
+        let previous = self.models;
+        for (model, weights) in self.models.iter_mut().zip(mixing) {
+            let mut state = [0.0; 4];
+            for (w, other) in weights.iter().zip(&previous) {
+                for (s, o) in state.iter_mut().zip(other.state) {
+                    *s += w * o;
+                }
+            }
+            let mut covariance = [[0.0; 4]; 4];
+            for (w, other) in weights.iter().zip(&previous) {
+                let d: Vector = std::array::from_fn(|k| other.state[k] - state[k]);
+                for r in 0..4 {
+                    for c in 0..4 {
+                        covariance[r][c] += w * (other.covariance[r][c] + d[r] * d[c]);
+                    }
+                }
+            }
+            model.state = state;
+            model.covariance = covariance;
+            model.predict(dt, self.process_noise);
+        }
+        predicted
+    }
+}
+
+impl TrackingFilter for Imm {
+    fn update(&mut self, measurement: Measurement, dt: f64) -> TrackEstimate {
+        if !self.started {
+            // The first measurement starts every model there, with the handover velocity
+            let ((x, y), (vx, vy), r) = (measurement.position, self.initial_velocity, measurement.noise * measurement.noise);
+            for model in self.models.iter_mut() {
+                model.state = [x, y, vx, vy];
+                model.covariance = diagonal([r, r, INITIAL_VELOCITY_VARIANCE, INITIAL_VELOCITY_VARIANCE]);
+            }
+            self.started = true;
+            return self.estimate();
+        }
+
+        // Model-matched filtering, then the probabilities re-weighted by the likelihoods
+        let predicted = self.predict(dt);
+        let likelihoods: [f64; 3] = std::array::from_fn(|j| self.models[j].update(measurement));
+        let total: f64 = likelihoods.iter().zip(predicted).map(|(l, c)| l * c).sum();
+        self.probabilities = if total > 0.0 && total.is_finite() {
+            std::array::from_fn(|j| likelihoods[j] * predicted[j] / total)
+        } else {
+            // A measurement far off all models; keep the predicted probabilities
+            predicted
+        };
+        log::trace!(
+            "imm: model probabilities straight {:.2}, left {:.2}, right {:.2}",
+            self.probabilities[0], self.probabilities[1], self.probabilities[2]
+        );
+        self.estimate()
+    }
+
+    fn coast(&mut self, dt: f64) -> Option<TrackEstimate> {
+        if !self.started {
+            return None;
+        }
+        self.probabilities = self.predict(dt);
+        Some(self.estimate())
+    }
+
+    fn boxed_clone(&self) -> Box<dyn TrackingFilter> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, state: &Json) -> Result<(), String> {
+        let models = state.field("models")?.as_array().filter(|models| models.len() == 3).ok_or("field 'models' must have three entries")?;
+        for (model, saved) in self.models.iter_mut().zip(models) {
+            model.state = numbers(saved, "state")?;
+            let covariance: [f64; 16] = numbers(saved, "covariance")?;
+            model.covariance = std::array::from_fn(|r| std::array::from_fn(|c| covariance[4 * r + c]));
+        }
+        self.probabilities = numbers(state, "probabilities")?;
+        self.started = match state.field("started")? {
+            Json::Bool(started) => *started,
+            _ => return Err("field 'started' must be true or false".into()),
+        };
+        Ok(())
+    }
+}
+
+// Each model's state and covariance (row by row) and the model probabilities
+impl ToJson for Imm {
+    fn to_json(&self) -> Json {
+        let numbers = |values: &[f64]| Json::Array(values.iter().map(|&v| v.into()).collect());
+        let models = self.models.iter().map(|model| {
+            Json::object(vec![("state", numbers(&model.state)), ("covariance", numbers(model.covariance.as_flattened()))])
+        });
+        Json::object(vec![
+            ("models", Json::Array(models.collect())),
+            ("probabilities", numbers(&self.probabilities)),
+            ("started", self.started.into()),
+        ])
+    }
+}
+
+fn diagonal(values: Vector) -> Matrix {
+    std::array::from_fn(|r| std::array::from_fn(|c| if r == c { values[r] } else { 0.0 }))
+}
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    std::array::from_fn(|r| std::array::from_fn(|c| (0..4).map(|k| a[r][k] * b[k][c]).sum()))
+}
+
+fn mul_vector(a: &Matrix, v: &Vector) -> Vector {
+    std::array::from_fn(|r| (0..4).map(|k| a[r][k] * v[k]).sum())
+}
+
+fn transpose(a: &Matrix) -> Matrix {
+    std::array::from_fn(|r| std::array::from_fn(|c| a[c][r]))
+}
@@ -0,0 +1,175 @@
+// Launch acceptability region (LAR): the target start positions, relative to the interceptor, from
+// which the interceptor intercepts. The lar command flies a batch of seeded engagements for every
+// point of a grid of target offsets, downrange (x) and in height (y) from the interceptor's start,
+// optionally for several initial target headings, and renders the hit rate over the grid as a
+// filled contour plot whose 50% line bounds the region.
+//
+// Every run draws from the scenario's envelope as a sweep does, but the grid sets the target's
+// start (and with --headings its heading) over what the envelope drew. Every point sees the same
+// random sequence, so differences between points come from the geometry alone.
+
+use crate::error::SimError;
+use crate::log;
+use crate::plot::{visualize_contour, PlotOptions};
+use crate::progress::Progress;
+use crate::scenario::Scenario;
+use crate::sweep::Heatmap;
+use crate::{simulate_once, Outcome, SimConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+
+// "MIN:MAX:COUNT" values of `flag`, `count` evenly spaced from MIN to MAX
+fn grid_values(flag: &str, s: &str, min_count: usize) -> Result<Vec<f64>, String> {
+    let usage = || format!("{} must be MIN:MAX:COUNT with COUNT of at least {}, e.g. -60:60:25", flag, min_count);
+    let parts: Vec<&str> = s.split(':').collect();
+    let [min, max, count] = parts[..] else {
+        return Err(usage());
+    };
+    let (min, max): (f64, f64) = (min.parse().map_err(|_| usage())?, max.parse().map_err(|_| usage())?);
+    let count: usize = count.parse().ok().filter(|c| *c >= min_count).ok_or_else(usage)?;
+    if count == 1 {
+        return Ok(vec![min]);
+    }
+    Ok((0..count).map(|k| min + (max - min) * k as f64 / (count - 1) as f64).collect())
+}
+
+// Usage: lar [--x MIN:MAX:COUNT] [--y MIN:MAX:COUNT] [--headings MIN:MAX:COUNT] [--runs N / -n N]
+//            [--seed N] [--levels N] [--scenario FILE] [--out-dir DIR / -o DIR] [--quiet / -q]
+//            [plot options]
+pub fn run_lar(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut xs = grid_values("--x", "-60:60:25", 2)?;
+    let mut ys = grid_values("--y", "0:60:13", 2)?;
+    let mut headings = vec![scenario.config.target_heading_deg];
+    let mut runs: usize = 10;
+    let mut seed: u64 = 1;
+    let mut levels: usize = 10;
+    let mut out_dir = String::from(".");
+    let plot_options = PlotOptions { style: scenario.style, ..PlotOptions::from_args(args)? };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--x" if i + 1 < args.len() => {
+                xs = grid_values("--x", &args[i + 1], 2)?;
+                i += 1;
+            }
+            "--y" if i + 1 < args.len() => {
+                ys = grid_values("--y", &args[i + 1], 2)?;
+                i += 1;
+            }
+            "--headings" if i + 1 < args.len() => {
+                headings = grid_values("--headings", &args[i + 1], 1)?;
+                i += 1;
+            }
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--runs must be a positive integer")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--levels" if i + 1 < args.len() => {
+                levels = args[i + 1].parse().ok().filter(|n| (2..=20).contains(n)).ok_or("--levels must be an integer from 2 to 20")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    let origin = (scenario.config.interceptor_start_x, scenario.config.interceptor_start_y);
+    log::info!(
+        "🎯 Launch acceptability region over {} × {} target offsets from ({}, {}), {} heading(s), {} runs each",
+        xs.len(),
+        ys.len(),
+        origin.0,
+        origin.1,
+        headings.len(),
+        runs
+    );
+
+    let csv_path = std::path::Path::new(&out_dir).join("lar.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "x_offset,y_offset,target_heading_deg,runs,hits,hit_rate,mean_miss_distance,mean_pk")?;
+
+    let mut hit_rates = vec![vec![0.0; xs.len()]; ys.len()];
+    let mut progress = Progress::new("LAR", xs.len() * ys.len() * headings.len() * runs, true);
+    for (row, &y) in ys.iter().enumerate() {
+        let _span = log::span(format!("lar row {}", row + 1));
+        for (col, &x) in xs.iter().enumerate() {
+            let mut hits = 0;
+            for &heading in &headings {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let (mut heading_hits, mut miss_sum, mut pk_sum) = (0, 0.0, 0.0);
+                for _ in 0..runs {
+                    let run_config = SimConfig {
+                        target_start_x: origin.0 + x,
+                        target_start_y: origin.1 + y,
+                        target_heading_deg: heading,
+                        ..scenario.envelope.sample(&scenario.config, &mut rng)
+                    };
+                    let sim = simulate_once(&mut rng, &run_config);
+                    heading_hits += (sim.outcome == Outcome::Intercept) as usize;
+                    progress.inc(sim.outcome == Outcome::Intercept);
+                    miss_sum += sim.miss_distance;
+                    pk_sum += sim.pk();
+                }
+                log::debug!("offset ({}, {}), heading {}°: {}/{} hits", x, y, heading, heading_hits, runs);
+                writeln!(
+                    csv,
+                    "{},{},{},{},{},{:.4},{:.4},{:.4}",
+                    x,
+                    y,
+                    heading,
+                    runs,
+                    heading_hits,
+                    heading_hits as f64 / runs as f64,
+                    miss_sum / runs as f64,
+                    pk_sum / runs as f64
+                )?;
+                hits += heading_hits;
+            }
+            hit_rates[row][col] = hits as f64 / (runs * headings.len()) as f64;
+        }
+        log::info!("   height offset {:.1} m: row {}/{} done", y, row + 1, ys.len());
+    }
+    progress.finish();
+
+    // Extent of the region: the offsets at which at least half of the runs hit
+    let inside: Vec<(f64, f64)> =
+        ys.iter().zip(&hit_rates).flat_map(|(&y, row)| xs.iter().zip(row).filter(|(_, rate)| **rate >= 0.5).map(move |(&x, _)| (x, y))).collect();
+    match inside.is_empty() {
+        true => log::info!("   No offset of the grid reaches a 50% hit rate"),
+        false => log::info!(
+            "   {} of {} offsets reach a 50% hit rate, out to {:.1} m from the interceptor",
+            inside.len(),
+            xs.len() * ys.len(),
+            inside.iter().map(|(x, y)| x.hypot(*y)).fold(0.0, f64::max)
+        ),
+    }
+    log::info!("✅ Launch acceptability region saved as '{}'", csv_path.display());
+
+    if plot_options.enabled {
+        let caption = match headings[..] {
+            [heading] => format!("Hit rate over {} runs, target heading {}°", runs, heading),
+            _ => format!("Hit rate over {} runs × {} target headings", runs, headings.len()),
+        };
+        let heatmap = Heatmap {
+            caption,
+            x_desc: "Target offset downrange (m)",
+            y_desc: "Target offset in height (m)",
+            xs,
+            ys,
+            values: hit_rates,
+        };
+        let out_plot = std::path::Path::new(&out_dir).join(format!("lar.{}", plot_options.format.extension()));
+        visualize_contour(&heatmap, levels, Some(((0.0, 0.0), "Interceptor")), out_plot.to_str().unwrap(), &plot_options)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,1856 @@
+// Without the plot feature, the helpers that only the drawing code uses go unused
+#![cfg_attr(not(feature = "plot"), allow(dead_code))]
+
+mod ab;
+mod actuator;
+mod api;
+mod atmosphere;
+mod bounds;
+mod checkpoint;
+mod cli;
+mod clutter;
+mod compare;
+mod cost;
+mod dataset;
+mod doctrine;
+mod engagement;
+mod envelope;
+mod error;
+mod evasion;
+mod faults;
+mod feed;
+mod ffi;
+mod fusion;
+mod frames;
+mod fuze;
+mod geoexport;
+mod geometry;
+mod guidance;
+mod gym;
+mod imm;
+mod json;
+mod lar;
+#[cfg(feature = "plot")]
+mod live;
+mod log;
+mod mavlink;
+mod mqtt;
+mod noise;
+#[cfg(not(feature = "plot"))]
+mod noplot;
+mod onnx;
+#[cfg(feature = "plot")]
+mod plot;
+#[cfg(feature = "plot")]
+mod plot3d;
+mod playback;
+mod plugin;
+mod progress;
+mod raid;
+mod radar;
+mod reachability;
+mod realtime;
+mod scenario;
+mod script;
+mod seeds;
+mod schedule;
+mod serve;
+#[cfg(feature = "simd")]
+mod simd;
+mod simulation;
+mod soa;
+mod stats;
+mod sweep;
+mod systems;
+mod telemetry;
+mod termination;
+mod terrain;
+mod tournament;
+mod tracking;
+mod trajectory;
+mod tui;
+mod tune;
+mod units;
+mod validation;
+mod vec2;
+mod verify;
+#[cfg(feature = "plot")]
+mod video;
+mod wasm;
+mod world;
+mod zones;
+
+// The library API: assemble an engagement with Simulation::builder() (or from a Scenario), drive it
+// step by step or run it through, and watch it with a SimObserver; see examples/observe.rs
+pub use error::SimError;
+pub use scenario::Scenario;
+pub use simulation::{SimObserver, Simulation, SimulationBuilder, StepOutcome};
+
+// Without the plot feature noplot.rs stands in for the modules that draw
+#[cfg(not(feature = "plot"))]
+use noplot::{self as live, self as plot, self as plot3d, self as video};
+use actuator::Actuator;
+use checkpoint::{Checkpoint, SimRng};
+use cost::{CostTally, Usage};
+use dataset::Dataset;
+use evasion::OptimalEvasion;
+use faults::{FaultKind, FaultSchedule};
+use feed::TargetFeed;
+use frames::Frame;
+use fuze::Fuze;
+use geoexport::GeoFormat;
+use geometry::GeometryReport;
+use json::{FromJson, Json, ToJson};
+use live::LivePng;
+use mavlink::MavlinkOutput;
+use mqtt::MqttPublisher;
+use guidance::{LqrWeights, MpcSettings};
+use noise::NoiseKind;
+use schedule::GainSchedule;
+use playback::Playback;
+use tui::TuiView;
+use units::{Meters, MetersPerSecond, Units};
+use plot::{visualize_histograms, visualize_overlay, visualize_simulation, PlotOptions};
+use plot3d::{export_3d, View3dOptions};
+use progress::Progress;
+use reachability::ReachOptions;
+use realtime::RealtimePacer;
+use scenario::ScenarioWatcher;
+use serve::TelemetryServer;
+use telemetry::TelemetryStream;
+use termination::TerminationCriteria;
+use atmosphere::Atmosphere;
+use terrain::Terrain;
+use tracking::{Measurement, Tracking, TrackingFilter};
+use trajectory::TargetPath;
+use script::TargetScript;
+use seeds::SeedLog;
+use plugin::GuidancePlugin;
+use onnx::NeuralGuidance;
+use zones::KeepOut;
+use rand::{Rng, SeedableRng};
+use vec2::Vec2;
+use video::{export_video, VideoOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Target {
+    position: Vec2,
+    velocity: Vec2,
+}
+
+impl Target {
+    fn new(position: impl Into<Vec2>, velocity: impl Into<Vec2>) -> Self {
+        Target { position: position.into(), velocity: velocity.into() }
+    }
+
+    fn update(&mut self, dt: f64) {
+        // Update position based on velocity
+        self.position += self.velocity * dt;
+    }
+
+    fn distance_to(&self, other: &Target) -> f64 {
+        // Calculate distance to another projectile
+        self.position.distance(other.position)
+    }
+}
+
+pub type Interceptor = Target;
+
+// Commanded heading plus which guidance mode produced it
+#[derive(Debug, Clone, Copy)]
+pub struct Steering {
+    direction: Vec2,     // Unit vector, zero when already at the aim point
+    lead_solution: bool,   // A predicted intercept point exists (otherwise aiming at the target itself)
+    phase: GuidancePhase,
+}
+
+// Calculate steering direction towards target (unit vector).
+// Uses a simple lead-pursuit intercept calculation to aim where the target
+// will be, and if the resulting approach angle relative to the target's
+// velocity is <= 5°, it nudges the heading to ensure a >5° approach
+// (unless `constrain_angle` is off, as in the terminal phase).
+fn calculate_steering_direction(from: &Interceptor, to: &Target, constrain_angle: bool) -> Steering {
+    // --- Derivation / algorithm notes ---
+    // We want to compute a heading (unit vector) so the interceptor will meet
+    // the target. Let r = to - from be the relative position, v the target
+    // velocity, and s the interceptor speed (magnitude of from.v).
+    // The intercept condition is: ||r + v * t|| = s * t for some t > 0.
+    // Squaring both sides gives a quadratic in t:
+    //   (v·v - s^2) t^2 + 2 (r·v) t + (r·r) = 0
+    // Solve this quadratic for positive roots and choose the smallest
+    // positive root (earliest intercept). If no positive root exists
+    // (discriminant < 0 or no positive roots), fall back to aiming at the
+    // target's current position.
+    // Once an intercept time t is chosen, the aim point is to + v * t and the
+    // desired heading is (aim - from) normalized. Finally, we compute the
+    // angle between the heading and the target velocity; if it is <= 5° we
+    // rotate the heading by a small buffer (~5.5°) away from parallel to
+    // enforce an approach angle greater than 5° (sign chosen by 2D cross).
+
+    // Relative position and target velocity
+    let r = to.position - from.position;
+    let v = to.velocity;
+
+    // Interceptor speed taken from its current velocity magnitude
+    let interceptor_speed = from.velocity.length();
+
+    // Solve quadratic: (v·v - s^2) t^2 + 2(r·v) t + r·r = 0
+    let a = v.dot(v) - interceptor_speed * interceptor_speed;
+    let b = 2.0 * r.dot(v);
+    let c = r.dot(r);
+
+    let mut t_opt: Option<f64> = None;
+
+    if a.abs() < 1e-9 {
+        // Degenerate to linear: b t + c = 0 => t = -c / b
+        if b.abs() > 1e-9 {
+            let t = -c / b;
+            if t > 0.0 {
+                t_opt = Some(t);
+            }
+        }
+    } else {
+        let disc = b * b - 4.0 * a * c;
+        if disc >= 0.0 {
+            let sqrt = disc.sqrt();
+            let t1 = (-b + sqrt) / (2.0 * a);
+            let t2 = (-b - sqrt) / (2.0 * a);
+            let mut candidates = vec![];
+            if t1 > 0.0 { candidates.push(t1); }
+            if t2 > 0.0 { candidates.push(t2); }
+            if !candidates.is_empty() {
+                candidates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                t_opt = Some(candidates[0]);
+            }
+        }
+    }
+
+    // Determine aim point: predicted intercept if possible, otherwise current target
+    let aim = if let Some(t) = t_opt {
+        let aim = to.position + v * t;
+        log::trace!("lead solution: intercept in {:.2}s at ({:.2}, {:.2})", t, aim.x, aim.y);
+        aim
+    } else {
+        log::trace!("no lead solution, aiming at the target's current position");
+        to.position
+    };
+
+    // Desired direction to aim point
+    let lead_solution = t_opt.is_some();
+    let Some(mut direction) = (aim - from.position).normalized() else {
+        return Steering { direction: Vec2::ZERO, lead_solution, phase: GuidancePhase::Direct };
+    };
+
+    // Ensure approach angle relative to target velocity is > 5°
+    let angle = direction.angle_deg(v);
+    let mut phase = GuidancePhase::Direct;
+    if angle <= 5.0 && constrain_angle {
+        phase = GuidancePhase::AngleConstrained;
+        // Determine rotation direction via cross product sign
+        let sign = if direction.cross(v) >= 0.0 { 1.0 } else { -1.0 };
+        let min_deg = 5.5_f64; // small buffer above 5°
+        log::trace!("approach angle {:.2}° too shallow, turning heading by {:+.1}°", angle, sign * min_deg);
+        if let Some(rotated) = direction.rotated(sign * min_deg.to_radians()).normalized() {
+            direction = rotated;
+        }
+    }
+
+    Steering { direction, lead_solution, phase }
+}
+
+
+// Parameters of a single engagement
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimConfig {
+    target_start_x: f64,
+    target_start_y: f64,
+    target_speed: f64,
+    target_heading_deg: f64,  // Initial heading, 0° = level in +x, positive climbs
+    target_path: Option<Arc<TargetPath>>, // Recorded path the target flies instead of evading, see trajectory.rs
+    target_script: Option<Arc<TargetScript>>, // Script that turns the target instead of noise and altitude hold, see script.rs
+    optimal_evasion: Option<OptimalEvasion>, // Worst-case manoeuvre the target flies instead of noise and altitude hold, see evasion.rs
+    evasion_model: Option<Arc<NeuralGuidance>>, // Network that turns the target instead of noise and altitude hold, see onnx.rs
+    frame: Option<Frame>,     // Where the simulation plane lies on the earth, see frames.rs
+    interceptor_start_x: f64,
+    interceptor_start_y: f64,
+    interceptor_speed: f64,   // Speed of interceptor projectile
+    guidance: GuidanceLaw,
+    guidance_plugin: Option<Arc<GuidancePlugin>>, // Module the plugin law runs, see plugin.rs
+    guidance_model: Option<Arc<NeuralGuidance>>,  // Network the neural law runs, see onnx.rs
+    navigation_constant: f64, // N of the ZEM law
+    lqr_weights: LqrWeights,
+    mpc: MpcSettings,
+    ground_station_x: f64, // Origin of the CLOS beam
+    ground_station_y: f64,
+    clos_lookahead: f64,   // Seconds of flight along the beam the CLOS law aims ahead
+    terminal_time_to_go: f64, // Time-to-go (s) below which guidance enters its terminal phase, 0 = never
+    guidance_rate_hz: Option<f64>, // Guidance updates per second, holding the command in between; None = every step
+    autopilot_tau: f64,       // Time constant (s) of the lag from commanded to achieved acceleration, 0 = none
+    actuator: Option<Actuator>, // Saturation, noise and faults of the achieved acceleration, see actuator.rs
+    faults: Option<FaultSchedule>, // Failures at scheduled times, see faults.rs
+    atmosphere: Option<Atmosphere>, // Air density limiting the interceptor's turns and slowing it by drag, see atmosphere.rs
+    termination: TerminationCriteria, // Criteria ending a run besides intercepts, ground impacts and max_steps, see termination.rs
+    keep_out: Option<Arc<KeepOut>>, // Zones guidance steers the interceptor around, see zones.rs
+    terrain: Option<Arc<Terrain>>, // Ground height profile replacing the flat ground at 0, see terrain.rs
+    collision_threshold: f64, // Stop at < threshold distance
+    fuze: Option<Fuze>,       // Replaces the collision threshold as what ends the run, see fuze.rs
+    tracking: Option<Tracking>, // Guidance sees a filtered noisy track instead of the true target, see tracking.rs
+    target_initial_height: f64, // Initial/target height for correction
+    correction_weight: f64,   // Weight of correction (0.0 = pure random, 1.0 = pure correction)
+    p_gain: f64,              // P-Regler Verstärkung (Proportional gain)
+    p_gain_schedule: Option<GainSchedule>, // Replaces p_gain with a gain interpolated by range or closing speed
+    noise_range_deg: f64,     // Target evasion jitter per step: ±range for uniform noise, standard deviation otherwise
+    noise_model: NoiseKind,
+    interceptor_noise_deg: f64, // Random deviation from the commanded heading, same meaning as noise_range_deg
+    interceptor_noise_model: NoiseKind,
+    max_steps: usize,
+    dt: f64,                  // Seconds per step
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            target_start_x: 0.0,
+            target_start_y: 30.0,
+            target_speed: 2.0,
+            target_heading_deg: 0.0,
+            target_path: None,
+            target_script: None,
+            optimal_evasion: None,
+            evasion_model: None,
+            frame: None,
+            interceptor_start_x: 0.0,
+            interceptor_start_y: 0.0,
+            interceptor_speed: 2.5,
+            guidance: GuidanceLaw::LeadPursuit,
+            guidance_plugin: None,
+            guidance_model: None,
+            navigation_constant: 3.0,
+            lqr_weights: LqrWeights::default(),
+            mpc: MpcSettings::default(),
+            ground_station_x: 0.0,
+            ground_station_y: 0.0,
+            clos_lookahead: 2.0,
+            terminal_time_to_go: 0.0,
+            guidance_rate_hz: None,
+            autopilot_tau: 0.0,
+            actuator: None,
+            faults: None,
+            atmosphere: None,
+            termination: TerminationCriteria::default(),
+            keep_out: None,
+            terrain: None,
+            collision_threshold: 1.0,
+            fuze: None,
+            tracking: None,
+            target_initial_height: 30.0,
+            correction_weight: 0.0,
+            p_gain: 0.2,
+            p_gain_schedule: None,
+            noise_range_deg: 5.0,
+            noise_model: NoiseKind::Uniform,
+            interceptor_noise_deg: 0.0,
+            interceptor_noise_model: NoiseKind::Uniform,
+            max_steps: 1000,
+            dt: 1.0,
+        }
+    }
+}
+
+// How the interceptor computes its heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuidanceLaw {
+    LeadPursuit, // Aim at the predicted intercept point, approach angle kept above 5°
+    Zem,         // Accelerate against the zero-effort miss, see guidance::zem_steering
+    Lqr,         // Optimal control of the linearized engagement, see guidance::lqr_steering
+    Mpc,         // Best of forward-simulated acceleration sequences, see guidance::mpc_steering
+    Clos,        // Ride the beam from the ground station to the target, see guidance::clos_steering
+    Plugin,      // The command of a WebAssembly module, see plugin.rs
+    Neural,      // A neural network's command, see onnx.rs
+}
+
+impl GuidanceLaw {
+    const NAMES: &'static str = "lead_pursuit, zem, lqr, mpc, clos, plugin, neural";
+    const ALL: [GuidanceLaw; 5] = [GuidanceLaw::LeadPursuit, GuidanceLaw::Zem, GuidanceLaw::Lqr, GuidanceLaw::Mpc, GuidanceLaw::Clos];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuidanceLaw::LeadPursuit => "lead_pursuit",
+            GuidanceLaw::Zem => "zem",
+            GuidanceLaw::Lqr => "lqr",
+            GuidanceLaw::Mpc => "mpc",
+            GuidanceLaw::Clos => "clos",
+            GuidanceLaw::Plugin => "plugin",
+            GuidanceLaw::Neural => "neural",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<GuidanceLaw> {
+        match s {
+            "lead_pursuit" => Some(GuidanceLaw::LeadPursuit),
+            "zem" => Some(GuidanceLaw::Zem),
+            "lqr" => Some(GuidanceLaw::Lqr),
+            "mpc" => Some(GuidanceLaw::Mpc),
+            "clos" => Some(GuidanceLaw::Clos),
+            "plugin" => Some(GuidanceLaw::Plugin),
+            "neural" => Some(GuidanceLaw::Neural),
+            _ => None,
+        }
+    }
+}
+
+// Notable moments of an engagement, in the order they happened
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimEvent {
+    Launch { step: usize },
+    LockAcquired { step: usize }, // A lead-pursuit solution (predicted intercept point) exists again
+    LockLost { step: usize },     // No intercept solution; steering falls back to the target's position
+    PhaseChange { step: usize, phase: GuidancePhase },
+    ParametersChanged { step: usize }, // The configuration was changed mid-run, effective from this step
+    MeasurementMissed { step: usize }, // The tracking sensor didn't detect the target; the track coasted
+    AssociationError { step: usize },  // The track was updated with a false alarm instead of the target
+    ActuatorFault { step: usize },     // The interceptor's actuators failed; it flies straight until they recover
+    ActuatorRecovered { step: usize },
+    FaultStarted { step: usize, fault: FaultKind }, // A scheduled fault became active, see faults.rs
+    FaultCleared { step: usize, fault: FaultKind },
+    Intercept { step: usize, x: f64, y: f64 },
+    GroundImpact { step: usize, vehicle: Vehicle, x: f64 },
+    TargetMasked { step: usize },  // The terrain hides the target from the interceptor, see terrain.rs
+    TargetVisible { step: usize },
+    KeepOutEntered { step: usize, zone: usize }, // The interceptor flew into keep-out zone `zone` (from 1), see zones.rs
+    KeepOutLeft { step: usize, zone: usize },
+    Pass { step: usize, miss: f64 },    // The interceptor overshot, closest to the target at `miss` (m) on that pass
+    Escape { step: usize, range: f64 }, // The target got beyond the escape range
+    Stall { step: usize, speed: f64 },  // The interceptor got slower than the minimum speed
+    Timeout { step: usize },
+}
+
+impl SimEvent {
+    pub fn step(&self) -> usize {
+        match *self {
+            SimEvent::Launch { step }
+            | SimEvent::LockAcquired { step }
+            | SimEvent::LockLost { step }
+            | SimEvent::PhaseChange { step, .. }
+            | SimEvent::ParametersChanged { step }
+            | SimEvent::MeasurementMissed { step }
+            | SimEvent::AssociationError { step }
+            | SimEvent::ActuatorFault { step }
+            | SimEvent::ActuatorRecovered { step }
+            | SimEvent::FaultStarted { step, .. }
+            | SimEvent::FaultCleared { step, .. }
+            | SimEvent::TargetMasked { step }
+            | SimEvent::TargetVisible { step }
+            | SimEvent::KeepOutEntered { step, .. }
+            | SimEvent::KeepOutLeft { step, .. }
+            | SimEvent::Intercept { step, .. }
+            | SimEvent::GroundImpact { step, .. }
+            | SimEvent::Pass { step, .. }
+            | SimEvent::Escape { step, .. }
+            | SimEvent::Stall { step, .. }
+            | SimEvent::Timeout { step } => step,
+        }
+    }
+
+    // Short human-readable description, used for the printed event log and plot labels
+    pub fn label(&self) -> String {
+        match *self {
+            SimEvent::Launch { .. } => String::from("launch"),
+            SimEvent::LockAcquired { .. } => String::from("lock acquired"),
+            SimEvent::LockLost { .. } => String::from("lock lost"),
+            SimEvent::PhaseChange { phase, .. } => format!("phase: {}", phase.as_str().replace('_', " ")),
+            SimEvent::ParametersChanged { .. } => String::from("parameters changed"),
+            SimEvent::MeasurementMissed { .. } => String::from("measurement missed"),
+            SimEvent::AssociationError { .. } => String::from("false plot associated"),
+            SimEvent::ActuatorFault { .. } => String::from("actuator fault"),
+            SimEvent::ActuatorRecovered { .. } => String::from("actuator recovered"),
+            SimEvent::FaultStarted { fault, .. } => format!("fault: {}", fault.as_str().replace('_', " ")),
+            SimEvent::FaultCleared { fault, .. } => format!("cleared: {}", fault.as_str().replace('_', " ")),
+            SimEvent::TargetMasked { .. } => String::from("target masked by terrain"),
+            SimEvent::TargetVisible { .. } => String::from("target visible again"),
+            SimEvent::KeepOutEntered { zone, .. } => format!("entered keep-out zone {}", zone),
+            SimEvent::KeepOutLeft { zone, .. } => format!("left keep-out zone {}", zone),
+            SimEvent::Intercept { x, y, .. } => format!("intercept at ({:.2}, {:.2})", x, y),
+            SimEvent::GroundImpact { vehicle, x, .. } => format!("{} ground impact at x = {:.2}", vehicle.as_str(), x),
+            SimEvent::Pass { miss, .. } => format!("pass missed by {:.2}", miss),
+            SimEvent::Escape { range, .. } => format!("target escaped at range {:.2}", range),
+            SimEvent::Stall { speed, .. } => format!("interceptor stalled at {:.2} m/s", speed),
+            SimEvent::Timeout { .. } => String::from("timeout"),
+        }
+    }
+}
+
+// How the interceptor's heading is being chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuidancePhase {
+    Direct,           // Straight at the aim point
+    AngleConstrained, // Turned away from the aim point to keep the approach angle above 5°
+    Terminal,         // Time-to-go below terminal_time_to_go: the approach angle is no longer enforced
+}
+
+impl GuidancePhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuidancePhase::Direct => "direct",
+            GuidancePhase::AngleConstrained => "angle_constrained",
+            GuidancePhase::Terminal => "terminal",
+        }
+    }
+
+    fn parse(s: &str) -> Option<GuidancePhase> {
+        match s {
+            "direct" => Some(GuidancePhase::Direct),
+            "angle_constrained" => Some(GuidancePhase::AngleConstrained),
+            "terminal" => Some(GuidancePhase::Terminal),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vehicle {
+    Target,
+    Interceptor,
+}
+
+impl Vehicle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Vehicle::Target => "target",
+            Vehicle::Interceptor => "interceptor",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Vehicle> {
+        match s {
+            "target" => Some(Vehicle::Target),
+            "interceptor" => Some(Vehicle::Interceptor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Intercept,
+    GroundImpact, // One of the vehicles flew into the ground
+    Escape,       // The target got beyond the escape range, see termination.rs
+    Stall,        // The interceptor got slower than the minimum speed
+    Miss,         // The interceptor missed max_passes passes
+    Timeout,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Intercept => "intercept",
+            Outcome::GroundImpact => "ground_impact",
+            Outcome::Escape => "escape",
+            Outcome::Stall => "stall",
+            Outcome::Miss => "miss",
+            Outcome::Timeout => "timeout",
+        }
+    }
+
+    // Process exit code of `run` and `batch` with --json-stdout; errors exit with 1
+    fn exit_code(&self) -> u8 {
+        match self {
+            Outcome::Intercept => 0,
+            Outcome::GroundImpact => 2,
+            Outcome::Timeout => 3,
+            Outcome::Escape => 4,
+            Outcome::Stall => 5,
+            Outcome::Miss => 6,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Outcome> {
+        match s {
+            "intercept" => Some(Outcome::Intercept),
+            "ground_impact" => Some(Outcome::GroundImpact),
+            "escape" => Some(Outcome::Escape),
+            "stall" => Some(Outcome::Stall),
+            "miss" => Some(Outcome::Miss),
+            "timeout" => Some(Outcome::Timeout),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SimulationResult {
+    pub config: SimConfig,
+    pub events: Vec<SimEvent>,
+    // Steps dropped from the front of the per-step records below by a telemetry window, so their
+    // first entry belongs to this step; 0 when the whole run is kept
+    pub first_step: usize,
+    pub target_positions: Vec<(f64, f64)>,
+    pub interceptor_positions: Vec<(f64, f64)>,
+    // Velocity (vx, vy) that brought each vehicle to the position with the same index
+    pub target_velocities: Vec<(f64, f64)>,
+    pub interceptor_velocities: Vec<(f64, f64)>,
+    pub heading_noise_deg: Vec<(f64, f64)>, // (target, interceptor) random heading deviation applied at each step
+    // With tracking, the noisy target positions measured at each step, one per sensor, and the
+    // estimate guidance used there (one entry per step, none without)
+    pub track_measurements: Vec<Vec<Option<Measurement>>>, // None where a sensor missed
+    pub track_positions: Vec<(f64, f64)>,
+    pub track_velocities: Vec<(f64, f64)>,
+    // The track filter after the measurements a window dropped, which those still recorded
+    // continue from; None while none were dropped
+    pub track_filter: Option<Box<dyn TrackingFilter>>,
+    // With guidance_rate_hz, the velocity guidance commanded at each step (held between updates)
+    pub guidance_commands: Vec<(f64, f64)>,
+    pub outcome: Outcome,
+    pub collision_point: Option<(f64, f64)>,
+    pub collision_angle: Option<f64>,
+    pub miss_distance: f64, // Closest approach over the whole run
+}
+
+impl SimulationResult {
+    // Number of simulated steps (recorded positions minus the start point)
+    pub fn steps(&self) -> usize {
+        (self.first_step + self.target_positions.len()).saturating_sub(1)
+    }
+
+    // The criterion that ended the run (see termination.rs), None while it goes on
+    fn termination(&self) -> Option<&'static str> {
+        let limit = self.config.termination.step_limit(self.config.max_steps, self.config.dt);
+        match (self.outcome, self.events.last()) {
+            (Outcome::Intercept, _) if self.config.fuze.is_some() => Some("fuze"),
+            (Outcome::Intercept, _) => Some("collision_threshold"),
+            (Outcome::GroundImpact, _) => Some("ground_impact"),
+            (Outcome::Escape, _) => Some("escape_range"),
+            (Outcome::Stall, _) => Some("min_speed"),
+            (Outcome::Miss, _) => Some("max_passes"),
+            (Outcome::Timeout, Some(SimEvent::Timeout { step })) if *step < limit => Some("target_feed"),
+            (Outcome::Timeout, Some(SimEvent::Timeout { .. })) if limit < self.config.max_steps => Some("max_time"),
+            (Outcome::Timeout, Some(SimEvent::Timeout { .. })) => Some("max_steps"),
+            (Outcome::Timeout, _) => None,
+        }
+    }
+
+    // The closest approach of every pass the interceptor missed, in order
+    pub fn passes(&self) -> Vec<f64> {
+        self.events.iter().filter_map(|e| if let SimEvent::Pass { miss, .. } = *e { Some(miss) } else { None }).collect()
+    }
+
+    // Drops the oldest per-step records so that at most `samples` positions remain
+    fn keep_last(&mut self, samples: usize) {
+        let dropped = self.target_positions.len().saturating_sub(samples);
+        for history in [
+            &mut self.target_positions,
+            &mut self.interceptor_positions,
+            &mut self.target_velocities,
+            &mut self.interceptor_velocities,
+            &mut self.heading_noise_deg,
+            &mut self.track_positions,
+            &mut self.track_velocities,
+            &mut self.guidance_commands,
+        ] {
+            history.drain(..dropped.min(history.len()));
+        }
+        self.track_measurements.drain(..dropped.min(self.track_measurements.len()));
+        self.first_step += dropped;
+    }
+
+    // Separation between interceptor and target at every recorded step
+    pub fn separations(&self) -> Vec<f64> {
+        self.target_positions
+            .iter()
+            .zip(&self.interceptor_positions)
+            .map(|(t, i)| ((t.0 - i.0).powi(2) + (t.1 - i.1).powi(2)).sqrt())
+            .collect()
+    }
+
+    // Line-of-sight angle from interceptor to target at every recorded step (degrees, 0° = +x)
+    fn los_angles_deg(&self) -> Vec<f64> {
+        self.target_positions
+            .iter()
+            .zip(&self.interceptor_positions)
+            .map(|(t, i)| (t.1 - i.1).atan2(t.0 - i.0).to_degrees())
+            .collect()
+    }
+
+    // Change of the line-of-sight angle per step (degrees/step), starting at the first step.
+    // Differences are wrapped into (-180°, 180°] so crossing ±180° doesn't show up as a jump.
+    fn los_rates_deg(&self) -> Vec<f64> {
+        self.los_angles_deg()
+            .windows(2)
+            .map(|w| {
+                let d = (w[1] - w[0]).rem_euclid(360.0);
+                if d > 180.0 { d - 360.0 } else { d }
+            })
+            .collect()
+    }
+
+    // Probability that the engagement killed the target: 0 without an intercept, 1 for a hit within
+    // the collision threshold, and with a fuze its Pk at the detonation (the last recorded step)
+    pub fn pk(&self) -> f64 {
+        match (self.outcome, self.config.fuze) {
+            (Outcome::Intercept, Some(fuze)) => {
+                let miss = self.separations().last().copied().unwrap_or_default();
+                fuze.pk(miss, self.collision_angle.unwrap_or_default())
+            }
+            (Outcome::Intercept, None) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    // Where an event is shown: where it happened, or where the interceptor was at the time. None
+    // for events from before a telemetry window, which have no position left to show them at.
+    fn event_position(&self, event: &SimEvent) -> Option<(f64, f64)> {
+        match *event {
+            SimEvent::Intercept { x, y, .. } => Some((x, y)),
+            SimEvent::GroundImpact { x, .. } => Some((x, self.config.terrain.as_ref().map_or(0.0, |terrain| terrain.height(x)))),
+            _ if event.step() < self.first_step || self.interceptor_positions.is_empty() => None,
+            _ => Some(self.interceptor_positions[(event.step() - self.first_step).min(self.interceptor_positions.len() - 1)]),
+        }
+    }
+
+    // Where on the earth the intercept happened, when the config places the engagement with a frame
+    fn collision_point_geodetic(&self) -> Option<frames::Geodetic> {
+        Some(self.config.frame?.geodetic_at(self.collision_point?))
+    }
+
+    // Copy of the first `samples` recorded positions, e.g. for animation frames
+    fn truncated(&self, samples: usize) -> SimulationResult {
+        let last_step = samples.saturating_sub(1);
+        SimulationResult {
+            config: self.config.clone(),
+            events: self.events.iter().copied().filter(|e| e.step() <= self.first_step + last_step).collect(),
+            target_positions: self.target_positions[..samples.min(self.target_positions.len())].to_vec(),
+            interceptor_positions: self.interceptor_positions[..samples.min(self.interceptor_positions.len())].to_vec(),
+            target_velocities: self.target_velocities[..samples.min(self.target_velocities.len())].to_vec(),
+            interceptor_velocities: self.interceptor_velocities[..samples.min(self.interceptor_velocities.len())].to_vec(),
+            heading_noise_deg: self.heading_noise_deg[..samples.min(self.heading_noise_deg.len())].to_vec(),
+            track_measurements: self.track_measurements[..last_step.min(self.track_measurements.len())].to_vec(),
+            track_positions: self.track_positions[..last_step.min(self.track_positions.len())].to_vec(),
+            track_velocities: self.track_velocities[..last_step.min(self.track_velocities.len())].to_vec(),
+            guidance_commands: self.guidance_commands[..last_step.min(self.guidance_commands.len())].to_vec(),
+            track_filter: self.track_filter.clone(),
+            ..*self
+        }
+    }
+}
+
+impl ToJson for SimConfig {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("target_start", (self.target_start_x, self.target_start_y).into()),
+            ("target_speed", self.target_speed.into()),
+            ("target_heading_deg", self.target_heading_deg.into()),
+            ("interceptor_start", (self.interceptor_start_x, self.interceptor_start_y).into()),
+            ("interceptor_speed", self.interceptor_speed.into()),
+            ("guidance", self.guidance.as_str().into()),
+            ("navigation_constant", self.navigation_constant.into()),
+            ("lqr_miss_weight", self.lqr_weights.miss.into()),
+            ("lqr_rate_weight", self.lqr_weights.rate.into()),
+            ("lqr_effort_weight", self.lqr_weights.effort.into()),
+            ("mpc_horizon", self.mpc.horizon.into()),
+            ("mpc_levels", self.mpc.levels.into()),
+            ("mpc_max_accel", self.mpc.max_accel.into()),
+            ("mpc_effort_weight", self.mpc.effort.into()),
+            ("ground_station", (self.ground_station_x, self.ground_station_y).into()),
+            ("clos_lookahead", self.clos_lookahead.into()),
+            ("terminal_time_to_go", self.terminal_time_to_go.into()),
+            ("autopilot_tau", self.autopilot_tau.into()),
+            ("collision_threshold", self.collision_threshold.into()),
+            ("target_initial_height", self.target_initial_height.into()),
+            ("correction_weight", self.correction_weight.into()),
+            ("p_gain", self.p_gain.into()),
+            ("noise_range_deg", self.noise_range_deg.into()),
+            ("max_steps", self.max_steps.into()),
+            ("dt", self.dt.into()),
+        ]
+        .into_iter()
+        .chain(self.noise_model.json_fields("noise_model", "noise_tau"))
+        .chain([("interceptor_noise_deg", self.interceptor_noise_deg.into())])
+        .chain(self.interceptor_noise_model.json_fields("interceptor_noise_model", "interceptor_noise_tau"))
+        .chain(self.guidance_rate_hz.map(|rate| ("guidance_rate_hz", rate.into())))
+        .chain(self.target_path.as_ref().map(|path| ("target_path", path.to_json())))
+        .chain(self.guidance_plugin.as_ref().map(|plugin| ("guidance_plugin", plugin.file().to_string_lossy().as_ref().into())))
+        .chain(self.guidance_model.as_ref().map(|model| ("guidance_model", model.file().to_string_lossy().as_ref().into())))
+        .chain(self.target_script.as_ref().map(|script| ("target_script", script.to_json())))
+        .chain(self.optimal_evasion.map(|evasion| ("optimal_evasion", evasion.to_json())))
+        .chain(self.evasion_model.as_ref().map(|model| ("evasion_model", model.file().to_string_lossy().as_ref().into())))
+        .chain(self.frame.map(|frame| ("frame", frame.to_json())))
+        .chain(self.p_gain_schedule.map(|schedule| ("p_gain_schedule", schedule.to_json())))
+        .chain(self.actuator.map(|actuator| ("actuator", actuator.to_json())))
+        .chain(self.faults.map(|faults| ("faults", faults.to_json())))
+        .chain(self.atmosphere.map(|atmosphere| ("atmosphere", atmosphere.to_json())))
+        .chain((!self.termination.is_empty()).then(|| ("termination", self.termination.to_json())))
+        .chain(self.keep_out.as_ref().map(|keep_out| ("keep_out", keep_out.to_json())))
+        .chain(self.terrain.as_ref().map(|terrain| ("terrain", terrain.to_json())))
+        .chain(self.fuze.map(|fuze| ("fuze", fuze.to_json())))
+        .chain(self.tracking.map(|tracking| ("tracking", tracking.to_json())))
+        .collect())
+    }
+}
+
+impl ToJson for SimEvent {
+    fn to_json(&self) -> Json {
+        let event = |kind: &str, step: usize| vec![("type", kind.into()), ("step", step.into())];
+        match *self {
+            SimEvent::Launch { step } => Json::object(event("launch", step)),
+            SimEvent::LockAcquired { step } => Json::object(event("lock_acquired", step)),
+            SimEvent::LockLost { step } => Json::object(event("lock_lost", step)),
+            SimEvent::PhaseChange { step, phase } => {
+                Json::object([event("phase_change", step), vec![("phase", phase.as_str().into())]].concat())
+            }
+            SimEvent::ParametersChanged { step } => Json::object(event("parameters_changed", step)),
+            SimEvent::MeasurementMissed { step } => Json::object(event("measurement_missed", step)),
+            SimEvent::AssociationError { step } => Json::object(event("association_error", step)),
+            SimEvent::ActuatorFault { step } => Json::object(event("actuator_fault", step)),
+            SimEvent::ActuatorRecovered { step } => Json::object(event("actuator_recovered", step)),
+            SimEvent::FaultStarted { step, fault } => Json::object([event("fault_started", step), vec![("fault", fault.as_str().into())]].concat()),
+            SimEvent::FaultCleared { step, fault } => Json::object([event("fault_cleared", step), vec![("fault", fault.as_str().into())]].concat()),
+            SimEvent::TargetMasked { step } => Json::object(event("target_masked", step)),
+            SimEvent::TargetVisible { step } => Json::object(event("target_visible", step)),
+            SimEvent::KeepOutEntered { step, zone } => Json::object([event("keep_out_entered", step), vec![("zone", zone.into())]].concat()),
+            SimEvent::KeepOutLeft { step, zone } => Json::object([event("keep_out_left", step), vec![("zone", zone.into())]].concat()),
+            SimEvent::Intercept { step, x, y } => Json::object([event("intercept", step), vec![("position", (x, y).into())]].concat()),
+            SimEvent::GroundImpact { step, vehicle, x } => Json::object(
+                [event("ground_impact", step), vec![("vehicle", vehicle.as_str().into()), ("x", x.into())]].concat(),
+            ),
+            SimEvent::Pass { step, miss } => Json::object([event("pass", step), vec![("miss", miss.into())]].concat()),
+            SimEvent::Escape { step, range } => Json::object([event("escape", step), vec![("range", range.into())]].concat()),
+            SimEvent::Stall { step, speed } => Json::object([event("stall", step), vec![("speed", speed.into())]].concat()),
+            SimEvent::Timeout { step } => Json::object(event("timeout", step)),
+        }
+    }
+}
+
+impl ToJson for SimulationResult {
+    fn to_json(&self) -> Json {
+        let engagement = engagement::history(self);
+        let points = |positions: &[(f64, f64)]| Json::Array(positions.iter().map(|&p| p.into()).collect());
+        let mut fields = vec![
+            ("config", self.config.to_json()),
+            ("outcome", self.outcome.as_str().into()),
+            ("termination", self.termination().map_or(Json::Null, Json::from)),
+            ("collision_point", self.collision_point.into()),
+            ("collision_angle_deg", self.collision_angle.into()),
+            ("miss_distance", self.miss_distance.into()),
+            ("pk", self.pk().into()),
+            ("events", Json::Array(self.events.iter().map(ToJson::to_json).collect())),
+            ("first_step", self.first_step.into()),
+            ("target_positions", points(&self.target_positions)),
+            ("interceptor_positions", points(&self.interceptor_positions)),
+            ("target_velocities", points(&self.target_velocities)),
+            ("interceptor_velocities", points(&self.interceptor_velocities)),
+            ("heading_noise_deg", points(&self.heading_noise_deg)),
+            (
+                "track_measurements",
+                Json::Array(
+                    self.track_measurements.iter().map(|looks| Json::Array(looks.iter().map(|m| m.map(|m| m.to_json()).into()).collect())).collect(),
+                ),
+            ),
+            ("track_positions", points(&self.track_positions)),
+            ("track_velocities", points(&self.track_velocities)),
+            // Derived from the states above, for consumers of the telemetry; not read back
+            ("closing_velocity", Json::Array(engagement.iter().map(|e| e.closing_velocity.into()).collect())),
+            ("time_to_go", Json::Array(engagement.iter().map(|e| e.time_to_go.into()).collect())),
+            ("usage", Usage::of(self).to_json()),
+        ];
+        if !self.guidance_commands.is_empty() {
+            fields.push(("guidance_commands", points(&self.guidance_commands)));
+        }
+        if let Some(filter) = &self.track_filter {
+            fields.push(("track_filter", filter.to_json()));
+        }
+        if let Some(geo) = self.collision_point_geodetic() {
+            fields.push(("collision_point_geodetic", geo.to_json()));
+        }
+        Json::object(fields)
+    }
+}
+
+impl FromJson for SimConfig {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let (interceptor_start_x, interceptor_start_y) = json
+            .field("interceptor_start")?
+            .as_point()
+            .ok_or("field 'interceptor_start' must be [x, y]")?;
+        // Target start, speed, guidance, dt, the noise models and the gain schedule became configurable
+        // later; older files use the defaults
+        let defaults = SimConfig::default();
+        let (target_start_x, target_start_y) = match json.get("target_start") {
+            Some(start) => start.as_point().ok_or("field 'target_start' must be [x, y]")?,
+            None => (defaults.target_start_x, defaults.target_start_y),
+        };
+        let guidance = match json.get("guidance") {
+            Some(law) => law.as_str().and_then(GuidanceLaw::parse).ok_or_else(|| format!("field 'guidance' must be one of: {}", GuidanceLaw::NAMES))?,
+            None => defaults.guidance,
+        };
+        let (ground_station_x, ground_station_y) = match json.get("ground_station") {
+            Some(station) => station.as_point().ok_or("field 'ground_station' must be [x, y]")?,
+            None => (defaults.ground_station_x, defaults.ground_station_y),
+        };
+        let guidance_plugin = match json.get("guidance_plugin").filter(|v| **v != Json::Null) {
+            Some(file) => Some(GuidancePlugin::load(Path::new(file.as_str().ok_or("field 'guidance_plugin' must be a file name")?)).map_err(|e| format!("guidance_plugin: {}", e))?),
+            None => defaults.guidance_plugin,
+        };
+        let guidance_model = match json.get("guidance_model").filter(|v| **v != Json::Null) {
+            Some(file) => Some(NeuralGuidance::load(Path::new(file.as_str().ok_or("field 'guidance_model' must be a file name")?)).map_err(|e| format!("guidance_model: {}", e))?),
+            None => defaults.guidance_model,
+        };
+        Ok(SimConfig {
+            target_start_x,
+            target_start_y,
+            target_speed: json.f64_field_or("target_speed", defaults.target_speed)?,
+            target_heading_deg: json.f64_field_or("target_heading_deg", defaults.target_heading_deg)?,
+            target_path: match json.get("target_path").filter(|v| **v != Json::Null) {
+                Some(path) => Some(Arc::new(TargetPath::from_json(path).map_err(|e| format!("target_path: {}", e))?)),
+                None => defaults.target_path,
+            },
+            target_script: match json.get("target_script").filter(|v| **v != Json::Null) {
+                Some(script) => Some(Arc::new(TargetScript::from_json(script).map_err(|e| format!("target_script: {}", e))?)),
+                None => defaults.target_script,
+            },
+            frame: match json.get("frame").filter(|v| **v != Json::Null) {
+                Some(frame) => Some(Frame::from_json(frame).map_err(|e| format!("frame: {}", e))?),
+                None => defaults.frame,
+            },
+            interceptor_start_x,
+            interceptor_start_y,
+            interceptor_speed: json.f64_field("interceptor_speed")?,
+            guidance,
+            guidance_plugin,
+            guidance_model,
+            navigation_constant: json.f64_field_or("navigation_constant", defaults.navigation_constant)?,
+            lqr_weights: LqrWeights {
+                miss: json.f64_field_or("lqr_miss_weight", defaults.lqr_weights.miss)?,
+                rate: json.f64_field_or("lqr_rate_weight", defaults.lqr_weights.rate)?,
+                effort: json.f64_field_or("lqr_effort_weight", defaults.lqr_weights.effort)?,
+            },
+            mpc: mpc_settings_from_json(json, defaults.mpc)?,
+            ground_station_x,
+            ground_station_y,
+            clos_lookahead: json.f64_field_or("clos_lookahead", defaults.clos_lookahead)?,
+            terminal_time_to_go: json.f64_field_or("terminal_time_to_go", defaults.terminal_time_to_go)?,
+            guidance_rate_hz: json.get("guidance_rate_hz").filter(|v| **v != Json::Null).map(|_| json.f64_field("guidance_rate_hz")).transpose()?,
+            autopilot_tau: json.f64_field_or("autopilot_tau", defaults.autopilot_tau)?,
+            collision_threshold: json.f64_field("collision_threshold")?,
+            target_initial_height: json.f64_field("target_initial_height")?,
+            correction_weight: json.f64_field("correction_weight")?,
+            p_gain: json.f64_field("p_gain")?,
+            p_gain_schedule: match json.get("p_gain_schedule").filter(|v| **v != Json::Null) {
+                Some(schedule) => Some(GainSchedule::from_json(schedule).map_err(|e| format!("p_gain_schedule: {}", e))?),
+                None => defaults.p_gain_schedule,
+            },
+            evasion_model: match json.get("evasion_model").filter(|v| **v != Json::Null) {
+                Some(file) => Some(NeuralGuidance::load(Path::new(file.as_str().ok_or("field 'evasion_model' must be a file name")?)).map_err(|e| format!("evasion_model: {}", e))?),
+                None => defaults.evasion_model,
+            },
+            optimal_evasion: match json.get("optimal_evasion").filter(|v| **v != Json::Null) {
+                Some(evasion) => Some(OptimalEvasion::from_json(evasion).map_err(|e| format!("optimal_evasion: {}", e))?),
+                None => defaults.optimal_evasion,
+            },
+            actuator: match json.get("actuator").filter(|v| **v != Json::Null) {
+                Some(actuator) => Some(Actuator::from_json(actuator).map_err(|e| format!("actuator: {}", e))?),
+                None => defaults.actuator,
+            },
+            faults: match json.get("faults").filter(|v| **v != Json::Null) {
+                Some(faults) => Some(FaultSchedule::from_json(faults).map_err(|e| format!("faults: {}", e))?),
+                None => defaults.faults,
+            },
+            atmosphere: match json.get("atmosphere").filter(|v| **v != Json::Null) {
+                Some(atmosphere) => Some(Atmosphere::from_json(atmosphere).map_err(|e| format!("atmosphere: {}", e))?),
+                None => defaults.atmosphere,
+            },
+            termination: match json.get("termination").filter(|v| **v != Json::Null) {
+                Some(termination) => TerminationCriteria::from_json(termination).map_err(|e| format!("termination: {}", e))?,
+                None => defaults.termination,
+            },
+            keep_out: match json.get("keep_out").filter(|v| **v != Json::Null) {
+                Some(keep_out) => Some(Arc::new(KeepOut::from_json(keep_out).map_err(|e| format!("keep_out: {}", e))?)),
+                None => defaults.keep_out,
+            },
+            terrain: match json.get("terrain").filter(|v| **v != Json::Null) {
+                Some(terrain) => Some(Arc::new(Terrain::from_json(terrain).map_err(|e| format!("terrain: {}", e))?)),
+                None => defaults.terrain,
+            },
+            fuze: match json.get("fuze").filter(|v| **v != Json::Null) {
+                Some(fuze) => Some(Fuze::from_json(fuze).map_err(|e| format!("fuze: {}", e))?),
+                None => defaults.fuze,
+            },
+            tracking: match json.get("tracking").filter(|v| **v != Json::Null) {
+                Some(tracking) => Some(Tracking::from_json(tracking).map_err(|e| format!("tracking: {}", e))?),
+                None => defaults.tracking,
+            },
+            noise_range_deg: json.f64_field_or("noise_range_deg", defaults.noise_range_deg)?,
+            noise_model: NoiseKind::from_json_fields(json, "noise_model", "noise_tau", defaults.noise_model)?,
+            interceptor_noise_deg: json.f64_field_or("interceptor_noise_deg", defaults.interceptor_noise_deg)?,
+            interceptor_noise_model: NoiseKind::from_json_fields(
+                json,
+                "interceptor_noise_model",
+                "interceptor_noise_tau",
+                defaults.interceptor_noise_model,
+            )?,
+            max_steps: json.field("max_steps")?.as_usize().ok_or("field 'max_steps' must be a non-negative integer")?,
+            dt: json.f64_field_or("dt", defaults.dt)?,
+        })
+    }
+}
+
+impl FromJson for SimEvent {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let step = json.field("step")?.as_usize().ok_or("event 'step' must be a non-negative integer")?;
+        match json.field("type")?.as_str() {
+            Some("launch") => Ok(SimEvent::Launch { step }),
+            Some("intercept") => {
+                let (x, y) = json.field("position")?.as_point().ok_or("intercept 'position' must be [x, y]")?;
+                Ok(SimEvent::Intercept { step, x, y })
+            }
+            Some("lock_acquired") => Ok(SimEvent::LockAcquired { step }),
+            Some("lock_lost") => Ok(SimEvent::LockLost { step }),
+            Some("phase_change") => {
+                let phase = json.field("phase")?.as_str().and_then(GuidancePhase::parse);
+                Ok(SimEvent::PhaseChange { step, phase: phase.ok_or("phase_change 'phase' must be \"direct\", \"angle_constrained\" or \"terminal\"")? })
+            }
+            Some("parameters_changed") => Ok(SimEvent::ParametersChanged { step }),
+            Some("measurement_missed") => Ok(SimEvent::MeasurementMissed { step }),
+            Some("association_error") => Ok(SimEvent::AssociationError { step }),
+            Some("actuator_fault") => Ok(SimEvent::ActuatorFault { step }),
+            Some("actuator_recovered") => Ok(SimEvent::ActuatorRecovered { step }),
+            Some(kind @ ("fault_started" | "fault_cleared")) => {
+                let fault = json.field("fault")?.as_str().and_then(FaultKind::parse);
+                let fault = fault.ok_or_else(|| format!("{} 'fault' must be one of: {}", kind, FaultKind::NAMES))?;
+                Ok(if kind == "fault_started" { SimEvent::FaultStarted { step, fault } } else { SimEvent::FaultCleared { step, fault } })
+            }
+            Some("target_masked") => Ok(SimEvent::TargetMasked { step }),
+            Some("target_visible") => Ok(SimEvent::TargetVisible { step }),
+            Some(kind @ ("keep_out_entered" | "keep_out_left")) => {
+                let zone = json.field("zone")?.as_usize().filter(|zone| *zone > 0).ok_or_else(|| format!("{} 'zone' must be a positive integer", kind))?;
+                Ok(if kind == "keep_out_entered" { SimEvent::KeepOutEntered { step, zone } } else { SimEvent::KeepOutLeft { step, zone } })
+            }
+            Some("ground_impact") => {
+                let vehicle = json.field("vehicle")?.as_str().and_then(Vehicle::parse);
+                Ok(SimEvent::GroundImpact {
+                    step,
+                    vehicle: vehicle.ok_or("ground_impact 'vehicle' must be \"target\" or \"interceptor\"")?,
+                    x: json.f64_field("x")?,
+                })
+            }
+            Some("pass") => Ok(SimEvent::Pass { step, miss: json.f64_field("miss")? }),
+            Some("escape") => Ok(SimEvent::Escape { step, range: json.f64_field("range")? }),
+            Some("stall") => Ok(SimEvent::Stall { step, speed: json.f64_field("speed")? }),
+            Some("timeout") => Ok(SimEvent::Timeout { step }),
+            other => Err(format!("unknown event type {:?}", other)),
+        }
+    }
+}
+
+impl FromJson for SimulationResult {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let points = |key: &str| -> Result<Vec<(f64, f64)>, String> {
+            json.field(key)?
+                .as_array()
+                .ok_or_else(|| format!("field '{}' must be an array", key))?
+                .iter()
+                .map(|p| p.as_point().ok_or_else(|| format!("field '{}' must contain [x, y] points", key)))
+                .collect()
+        };
+        let optional = |key: &str| json.get(key).filter(|v| **v != Json::Null);
+        // Velocity, noise and track histories were added later; older files simply have none
+        let optional_points = |key: &str| if json.get(key).is_some() { points(key) } else { Ok(Vec::new()) };
+        let config = SimConfig::from_json(json.field("config")?)?;
+        let track_filter = match (optional("track_filter"), config.tracking) {
+            (Some(state), Some(tracking)) => {
+                let mut filter = tracking.new_filter(&config);
+                filter.restore(state).map_err(|e| format!("track_filter: {}", e))?;
+                Some(filter)
+            }
+            (Some(_), None) => return Err("field 'track_filter' needs a tracking configuration".into()),
+            (None, _) => None,
+        };
+        Ok(SimulationResult {
+            config,
+            events: json
+                .field("events")?
+                .as_array()
+                .ok_or("field 'events' must be an array")?
+                .iter()
+                .map(SimEvent::from_json)
+                .collect::<Result<_, _>>()?,
+            first_step: match json.get("first_step") {
+                Some(first_step) => first_step.as_usize().ok_or("field 'first_step' must be a step number")?,
+                None => 0,
+            },
+            target_positions: points("target_positions")?,
+            interceptor_positions: points("interceptor_positions")?,
+            target_velocities: optional_points("target_velocities")?,
+            interceptor_velocities: optional_points("interceptor_velocities")?,
+            heading_noise_deg: optional_points("heading_noise_deg")?,
+            track_measurements: match json.get("track_measurements") {
+                Some(measurements) => measurements
+                    .as_array()
+                    .ok_or("field 'track_measurements' must be an array")?
+                    .iter()
+                    .map(|looks| {
+                        let measurement = |m: &Json| if *m == Json::Null { Ok(None) } else { Measurement::from_json(m).map(Some) };
+                        match looks.as_array() {
+                            Some(looks) => looks.iter().map(measurement).collect(),
+                            // Results from before multiple sensors: the one sensor's measurement
+                            None => measurement(looks).map(|m| vec![m]),
+                        }
+                    })
+                    .collect::<Result<_, _>>()?,
+                None => Vec::new(),
+            },
+            track_positions: optional_points("track_positions")?,
+            track_velocities: optional_points("track_velocities")?,
+            track_filter,
+            guidance_commands: optional_points("guidance_commands")?,
+            outcome: json
+                .field("outcome")?
+                .as_str()
+                .and_then(Outcome::parse)
+                .ok_or("field 'outcome' must be \"intercept\", \"ground_impact\" or \"timeout\"")?,
+            collision_point: optional("collision_point").and_then(Json::as_point),
+            collision_angle: optional("collision_angle_deg").and_then(Json::as_f64),
+            // Infinite miss distances are exported as null
+            miss_distance: optional("miss_distance").and_then(Json::as_f64).unwrap_or(f64::INFINITY),
+        })
+    }
+}
+
+// The mpc_* fields of a config object; missing ones keep their value in `base`
+fn mpc_settings_from_json(json: &Json, base: MpcSettings) -> Result<MpcSettings, String> {
+    let usize_or = |key: &str, default: usize| match json.get(key) {
+        Some(v) => v.as_usize().ok_or_else(|| format!("field '{}' must be a non-negative integer", key)),
+        None => Ok(default),
+    };
+    Ok(MpcSettings {
+        horizon: usize_or("mpc_horizon", base.horizon)?,
+        levels: usize_or("mpc_levels", base.levels)?,
+        max_accel: json.f64_field_or("mpc_max_accel", base.max_accel)?,
+        effort: json.f64_field_or("mpc_effort_weight", base.effort)?,
+    })
+}
+
+fn load_result(path: &std::path::Path) -> Result<SimulationResult, SimError> {
+    let text = std::fs::read_to_string(path).map_err(SimError::io(format!("cannot read '{}'", path.display())))?;
+    let json = Json::parse(&text).map_err(|e| SimError::invalid_scenario(path, e))?;
+    SimulationResult::from_json(&json).map_err(|e| SimError::invalid_scenario(path, e))
+}
+
+// Regenerate the plot of a previously exported result without re-running the physics.
+// Only the style section of a --scenario file applies; the physics come from the result.
+// Usage: replay <result.json> [--out FILE / -o FILE] [--scenario FILE] [--format png|svg] [--legend POS] [--video ...]
+//        [--export-kml] [--export-geojson] [--reachability [T1,T2,...]] [--reach-accel A] [--units si|aviation]
+fn run_replay(args: &[String]) -> Result<(), SimError> {
+    let mut input: Option<std::path::PathBuf> = None;
+    let mut out_path: Option<std::path::PathBuf> = None;
+    let plot_options = PlotOptions { style: Scenario::from_args(args)?.style, ..PlotOptions::from_args(args)? };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--out" | "-o" if i + 1 < args.len() => {
+                out_path = Some(args[i + 1].clone().into());
+                i += 1;
+            }
+            // Its times are optional
+            "--reachability" => i += args.get(i + 1).is_some_and(|a| a.split(',').all(|t| t.trim().parse::<f64>().is_ok())) as usize,
+            flag if parsed_elsewhere(flag) => i += flag_values(flag),
+            other => input = Some(other.into()),
+        }
+        i += 1;
+    }
+    let input = input.ok_or("usage: replay <result.json> [--out FILE] [--format png|svg] [--video]")?;
+    let out_path = out_path.unwrap_or_else(|| input.with_extension(plot_options.format.extension()));
+    let geo_exports = GeoFormat::from_args(args);
+    if !plot_options.enabled && geo_exports.is_empty() {
+        return Err("replay only redraws the plots of a result, which --no-plot or a build without the plot feature turns off".into());
+    }
+
+    let units = Units::from_args(args)?;
+    let sim = load_result(&input)?;
+    log::info!("🔁 Replaying '{}' ({}, {} steps)", input.display(), sim.outcome.as_str(), sim.steps());
+    if args.iter().any(|a| a == "--events") {
+        print_events(&sim, units);
+    }
+    let accel_threshold = geometry::accel_threshold_from_args(args)?;
+    GeometryReport::of(&sim, accel_threshold).print(accel_threshold, units);
+    if let Some(summary) = tracking::summary(&sim) {
+        log::info!("🛰️ {}", summary);
+    }
+    geoexport::export(&geo_exports, &sim, &input)?;
+    if !plot_options.enabled {
+        return Ok(());
+    }
+    visualize_simulation(&sim, out_path.to_str().unwrap(), &plot_options)?;
+    if let Some(reach) = ReachOptions::from_args(args)? {
+        reachability::export(&sim, &reach, 1, &out_path, &plot_options)?;
+    }
+    if let Some(view) = View3dOptions::from_args(args)? {
+        export_3d(&sim, &out_path, &view, &plot_options)?;
+    }
+    if let Some(video) = VideoOptions::from_args(args)? {
+        let out_mp4 = out_path.with_extension("mp4");
+        export_video(&sim, out_mp4.to_str().unwrap(), &video, &plot_options)?;
+    }
+    Ok(())
+}
+
+// Plot, video, scenario and logging flags are picked up by their own from_args; subcommands that
+// also take positional arguments skip them (and their values) with these two
+fn parsed_elsewhere(flag: &str) -> bool {
+    flag_values(flag) > 0
+        || matches!(flag, "--video" | "--equal-aspect" | "--view3d" | "--view3d-gif" | "-v" | "-vv" | "--verbose" | "--quiet" | "-q"
+            | "--events" | "--annotate-events" | "--no-plot" | "--export-kml" | "--export-geojson")
+}
+
+fn flag_values(flag: &str) -> usize {
+    match flag {
+        "--preset" | "--scenario" | "--format" | "--legend" | "--panels" | "--zoom" | "--size" | "--dpi" | "--annotate" | "--arrows"
+        | "--marker-every" | "--video-size" | "--video-fps" | "--video-speed" | "--azimuth" | "--elevation"
+        | "--checkpoint-every" | "--accel-threshold" | "--units" | "--reach-accel" => 1,
+        _ => 0,
+    }
+}
+
+// The event log as a timeline, one line per event with its time and the separation then (none for
+// steps no longer recorded) in the --units chosen
+fn print_events(sim: &SimulationResult, units: Units) {
+    let separations = sim.separations();
+    for event in &sim.events {
+        let separation = event.step().checked_sub(sim.first_step).and_then(|k| separations.get(k));
+        let separation = separation.map_or(String::from("          "), |&d| format!("{:<10}", format!("{:>7.2}", units.distance(Meters(d)))));
+        log::info!("   {:>8.2} s  step {:>4}  {}  {}", event.step() as f64 * sim.config.dt, event.step(), separation, event.label());
+    }
+}
+
+// Continue an interrupted run from its checkpoint and finish it like a regular run: the plot and
+// result_NNN.json are written next to the checkpoint, which keeps being updated with
+// --checkpoint-every N and is removed once the result is saved.
+fn run_resume(args: &[String]) -> Result<(), SimError> {
+    let plot_options = PlotOptions { style: Scenario::from_args(args)?.style, ..PlotOptions::from_args(args)? };
+    let every = checkpoint::interval_from_args(args)?;
+    let mut input: Option<std::path::PathBuf> = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            flag if parsed_elsewhere(flag) => i += flag_values(flag),
+            other => input = Some(other.into()),
+        }
+        i += 1;
+    }
+    let input = input.ok_or("usage: resume <checkpoint.json> [--checkpoint-every N] [--format png|svg]")?;
+    let out_dir = input.parent().unwrap_or(std::path::Path::new(".")).to_string_lossy().into_owned();
+    let out_dir = if out_dir.is_empty() { String::from(".") } else { out_dir };
+
+    let Checkpoint { run, mut rng, result: sim } = Checkpoint::load(&input)?;
+    log::info!("⏯️ Resuming run {} from '{}' at step {}/{}", run, input.display(), sim.steps(), sim.config.max_steps);
+    let mut simulation = Simulation::resume(sim);
+    run_to_end(&mut rng, &mut simulation, run, every.map(|n| (input.as_path(), n)), None, None)?;
+    let sim = simulation.into_result();
+
+    if plot_options.enabled {
+        let out_plot = plot_path(&out_dir, "collision_simulation_{run}", run, 1, plot_options.format.extension());
+        visualize_simulation(&sim, out_plot.to_str().unwrap(), &plot_options)?;
+    }
+    let out_json = std::path::Path::new(&out_dir).join(format!("result_{:03}.json", run));
+    std::fs::write(&out_json, sim.to_json().to_string_pretty() + "\n")
+        .map_err(SimError::io(format!("cannot write '{}'", out_json.display())))?;
+    log::info!("✅ Result saved as '{}'", out_json.display());
+    std::fs::remove_file(&input).map_err(SimError::io(format!("cannot remove checkpoint '{}'", input.display())))?;
+    Ok(())
+}
+
+// Runs an engagement to its end. With `checkpoint` = (path, every) the state is saved to `path`
+// every `every` steps while the run is still going; with `playback` the run is stepped one step at
+// a time so it can be paced, paused and single-stepped, and with `watcher` edits to the scenario
+// file are applied between steps.
+fn run_to_end(
+    rng: &mut SimRng,
+    sim: &mut Simulation,
+    run_idx: usize,
+    checkpoint: Option<(&std::path::Path, usize)>,
+    mut playback: Option<&mut Playback>,
+    mut watcher: Option<&mut ScenarioWatcher>,
+) -> Result<(), SimError> {
+    let start = sim.result().steps();
+    loop {
+        // Interactive runs go one step at a time, otherwise as far as the next checkpoint
+        let outcome = match (&playback, &watcher) {
+            (None, None) => sim.advance(rng, checkpoint.map_or(usize::MAX, |(_, every)| every)),
+            _ => sim.step(rng),
+        };
+        if outcome.is_finished() {
+            break;
+        }
+        if let Some(playback) = playback.as_deref_mut() {
+            playback.wait(sim.result().steps());
+        }
+        if let Some(watcher) = watcher.as_deref_mut() {
+            match watcher.reload() {
+                Ok(true) if watcher.apply(&sim.result().config) != sim.result().config => {
+                    log::info!("🔄 '{}' changed, new parameters from step {}", watcher.path().display(), sim.result().steps());
+                    sim.set_config(watcher.apply(&sim.result().config));
+                }
+                Ok(_) => {}
+                Err(e) => log::info!("⚠️ {} (keeping the previous parameters)", e),
+            }
+        }
+        if let Some((path, _)) = checkpoint.filter(|(_, every)| (sim.result().steps() - start).is_multiple_of(*every)) {
+            checkpoint::save(path, run_idx, rng, sim.result())?;
+            log::debug!("checkpoint at step {} saved as '{}'", sim.result().steps(), path.display());
+        }
+    }
+    if let Some(playback) = playback {
+        playback.end_run();
+    }
+    Ok(())
+}
+
+// Plot file of one run: `template` relative to `out_dir` with {run} replaced by the zero-padded run
+// number. The format's extension is added when missing, the run number when several runs would
+// otherwise overwrite the same file.
+fn plot_path(out_dir: &str, template: &str, run_idx: usize, runs: usize, extension: &str) -> std::path::PathBuf {
+    let run = format!("{:03}", run_idx);
+    let mut path = std::path::Path::new(out_dir).join(template.replace("{run}", &run));
+    if runs > 1 && !template.contains("{run}") {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        path.set_file_name(match path.extension() {
+            Some(ext) => format!("{}_{}.{}", stem, run, ext.to_string_lossy()),
+            None => format!("{}_{}", stem, run),
+        });
+    }
+    if path.extension().is_none() {
+        path.set_extension(extension);
+    }
+    path
+}
+
+fn simulate_once(rng: &mut impl Rng, config: &SimConfig) -> SimulationResult {
+    Simulation::new(config).run(rng)
+}
+
+// The command-line program (see main.rs)
+pub fn cli_main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, SimError> {
+    let args: Vec<String> = std::env::args().collect();
+    log::init_from_args(&args);
+    // Without a command the options go to `batch`, as before there were subcommands
+    let (name, rest) = match args.get(1).map(String::as_str) {
+        Some("--help" | "-h") => ("help", &args[2..]),
+        Some(name) if !name.starts_with('-') => (name, &args[2..]),
+        _ => ("batch", &args[1..]),
+    };
+    let command = cli::command(name).ok_or_else(|| format!("unknown command '{}' (see `help`)", name))?;
+    command.check_flags(rest)?;
+    match command.name {
+        "run" | "batch" => return run_batch(rest, None),
+        "serve" => return run_batch(rest, Some(TelemetryServer::from_args(rest)?)),
+        "sweep" => sweep::run_sweep(rest, Scenario::from_args(rest)?),
+        "compare" => compare::run_compare(rest, Scenario::from_args(rest)?),
+        "ab" => ab::run_ab(rest),
+        "tournament" => tournament::run_tournament(rest, Scenario::from_args(rest)?),
+        "salvo" => doctrine::run_salvo(rest, Scenario::from_args(rest)?),
+        "raid" => raid::run_raid(rest, Scenario::from_args(rest)?),
+        "lar" => lar::run_lar(rest, Scenario::from_args(rest)?),
+        "replay" => run_replay(rest),
+        "resume" => run_resume(rest),
+        "tune" => tune::run_tune(rest, Scenario::from_args(rest)?),
+        "api" => api::run_api(rest, Scenario::from_args(rest)?),
+        "gym" => gym::run_gym(rest, Scenario::from_args(rest)?),
+        "verify" => verify::run_verify(rest),
+        _ => Ok(cli::print_help(&args[0], rest.first().map(String::as_str))?),
+    }?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Runs engagements and writes their plots, results and results.csv. `run` is the same without the
+// batch options, so it flies one engagement, and `serve` streams the runs to `server` as well.
+// Options: --preset NAME, --scenario FILE, --seed N, --randomize-interceptor / -r, --runs N / -n, --out-dir DIR / -o, --format png|svg, --legend POS, --panels LIST, --zoom M, --equal-aspect,
+// --size WxH, --dpi N, --plot-out PATH, --annotate N, --arrows N, --marker-every N,
+// --overlay, --histograms, --view3d, --view3d-gif, --azimuth DEG, --elevation DEG, -v / -vv, --quiet / -q,
+// --checkpoint-every N, --events, --annotate-events, --realtime [SPEEDUP], --accel-threshold M/S2, --units si|aviation,
+// --stream, --window N, --no-plot, --json-stdout, --target-feed SOURCE, --bind ADDR, --mavlink ADDR,
+// --mqtt HOST[:PORT], --mqtt-topic PREFIX, --dataset FILE, --reachability [T1,T2,...], --reach-accel A
+//
+// With --json-stdout every run's result is printed to stdout as one line of JSON, and the exit code
+// is that of the first run that missed (see Outcome::exit_code), 0 when all of them hit.
+fn run_batch(args: &[String], mut server: Option<TelemetryServer>) -> Result<ExitCode, SimError> {
+    // Basic parameters come from the scenario file, see SimConfig::default() for the fallbacks
+    let scenario = Scenario::from_args(args)?;
+    let base_config = scenario.config.clone();
+    let randomize_interceptor = args.iter().any(|a| a == "--randomize-interceptor" || a == "-r");
+    let video = VideoOptions::from_args(args)?;
+    let view3d = View3dOptions::from_args(args)?;
+    let reach = ReachOptions::from_args(args)?;
+    let live = args.iter().any(|a| a == "--live-png");
+    let tui = args.iter().any(|a| a == "--tui");
+    let overlay = args.iter().any(|a| a == "--overlay");
+    let histograms = args.iter().any(|a| a == "--histograms");
+    let events = args.iter().any(|a| a == "--events");
+    let antithetic = args.iter().any(|a| a == "--antithetic");
+    let common_random_numbers = args.iter().any(|a| a == "--crn");
+    let checkpoint_every = checkpoint::interval_from_args(args)?;
+    let accel_threshold = geometry::accel_threshold_from_args(args)?;
+    let units = Units::from_args(args)?;
+    let stream = telemetry::stream_from_args(args);
+    let window = telemetry::window_from_args(args)?;
+    let mut mavlink = MavlinkOutput::from_args(args)?;
+    let mut mqtt = MqttPublisher::from_args(args)?;
+    let json_stdout = args.iter().any(|a| a == "--json-stdout");
+    if json_stdout && (live || tui) {
+        return Err("--json-stdout leaves stdout to the results, which --live-png and --tui would share".into());
+    }
+    let mut exit_code = 0;
+    let geo_exports = GeoFormat::from_args(args);
+    if let (Some(&format), None) = (geo_exports.first(), base_config.frame) {
+        return Err(geoexport::missing_frame(format));
+    }
+    let mut live_speed = 10.0;
+    let mut seed: Option<u64> = None;
+    let mut runs: usize = 1;
+    let mut out_dir = String::from(".");
+    let mut plot_template = String::from("collision_simulation_{run}");
+    let plot_options = PlotOptions { style: scenario.style, ..PlotOptions::from_args(args)? };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--runs" | "-n" if i + 1 < args.len() => {
+                if let Ok(v) = args[i + 1].parse::<usize>() { runs = v; }
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            "--plot-out" if i + 1 < args.len() => {
+                plot_template = args[i + 1].clone();
+                i += 1;
+            }
+            "--live-speed" if i + 1 < args.len() => {
+                if let Some(v) = args[i + 1].parse::<f64>().ok().filter(|v| *v > 0.0) { live_speed = v; }
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = Some(args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?);
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    // A seed list sets the runs, one per seed
+    let seed_list = seeds::from_args(args)?;
+    if let Some(seed_list) = &seed_list {
+        if args.iter().any(|a| matches!(a.as_str(), "--runs" | "-n" | "--seed" | "--crn" | "--antithetic")) {
+            return Err("--seeds flies one run per seed, without --runs, --seed, --crn or --antithetic".into());
+        }
+        runs = seed_list.len();
+    }
+    if antithetic && !runs.is_multiple_of(2) {
+        return Err("--antithetic flies the runs in pairs, so it needs an even number of --runs".into());
+    }
+
+    // A target fed from outside flies one engagement, answered on stdout
+    let mut feed = TargetFeed::from_args(args)?;
+    if feed.is_some() && (json_stdout || live || tui || checkpoint_every.is_some()) {
+        return Err("--target-feed flies a single run answered on stdout, without --json-stdout, --live-png, --tui or --checkpoint-every".into());
+    }
+
+    // Real-time pacing replaces the --live-speed step rate; served runs are paced by default
+    let mut realtime = RealtimePacer::from_args(args)?.or_else(|| server.is_some().then(|| RealtimePacer::new(1.0)));
+    if let Some(pacer) = &realtime {
+        live_speed = f64::INFINITY;
+        log::info!("⏱️ Real-time pacing at {}× wall-clock speed", pacer.speedup());
+    }
+
+    // A fixed seed makes the whole batch reproducible, including randomized start positions
+    let mut rng = match seed {
+        Some(seed) => SimRng::seed_from_u64(seed),
+        None => SimRng::from_entropy(),
+    };
+    // With common random numbers run k (antithetic pair k) draws from a stream of its own, seeded
+    // with seed + k, instead of continuing the batch's stream: it then sees the same random numbers
+    // whatever the earlier runs consumed, so batches of two configurations with the same --seed
+    // differ by the configurations rather than by how their random sequences drifted apart
+    let common_seed = common_random_numbers.then(|| seed.unwrap_or_else(|| rng.gen()));
+    if let (Some(common_seed), None) = (common_seed, seed) {
+        log::info!("🎲 Common random numbers from seed {}", common_seed);
+    }
+
+    // Ensure output directory exists
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    // Results CSV
+    let results_path = std::path::Path::new(&out_dir).join("results.csv");
+    let mut results_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&results_path)
+        .map_err(SimError::io(format!("cannot open '{}'", results_path.display())))?;
+
+    // Write CSV header if file was just created and empty
+    if results_file.metadata()?.len() == 0 {
+        writeln!(results_file, "run,interceptor_start_x,interceptor_start_y,collided,collision_x,collision_y,angle_deg")?;
+    }
+
+    let mut dataset = Dataset::from_args(args, &out_dir);
+    let mut seed_log = seed_list.is_some().then(|| SeedLog::create(&out_dir)).transpose()?;
+    let mut live_png = live.then(|| LivePng::new(&std::path::Path::new(&out_dir).join("live.png"), plot_options.clone()));
+    let mut tui_view = tui.then(TuiView::new);
+    let mut playback = (live || tui).then(|| Playback::new(live_speed));
+    // Runs that are watched as they happen pick up edits to the scenario file
+    let mut watcher = if live || tui || realtime.is_some() { ScenarioWatcher::from_args(args)? } else { None };
+    // Results kept for the batch-wide plots
+    let mut batch = Vec::new();
+    let mut intercept_times = Vec::new();
+    let mut costs = CostTally::default();
+    let mut progress = Progress::new("Runs", runs, !tui);
+
+    // The second run of an antithetic pair: its mirrored start and the RNG state its partner started with
+    let mut partner = None;
+    let mut pk_values = Vec::new();
+    let mut outcomes = Vec::new();
+
+    for run_idx in 1..=runs {
+        let mirrored = partner.is_some();
+        // The seed of a run with its own random sequence, which `batch --seed` flies again
+        let run_seed = match (&seed_list, common_seed) {
+            (Some(seed_list), _) => Some(seed_list[run_idx - 1]),
+            (None, Some(common_seed)) => Some(common_seed.wrapping_add(if antithetic { (run_idx - 1) / 2 } else { run_idx - 1 } as u64)),
+            (None, None) => None,
+        };
+        if let (Some(run_seed), false) = (run_seed, mirrored) {
+            log::info!("🌱 Run {}: seed {}", run_idx, run_seed);
+        }
+        let ((interceptor_start_x, interceptor_start_y), run_config, mut run_rng) = match partner.take() {
+            Some(partner) => partner,
+            None => {
+                let mut run_rng = match run_seed {
+                    Some(run_seed) => SimRng::seed_from_u64(run_seed),
+                    None => rng,
+                };
+                // Decide interceptor start; its antithetic partner starts mirrored in the same box
+                let (start, mirrored_start) = if randomize_interceptor {
+                    let x = run_rng.gen_range(0.0..50.0);
+                    let y = run_rng.gen_range(0.0..20.0);
+                    ((x, y), (50.0 - x, 20.0 - y))
+                } else {
+                    let start = (base_config.interceptor_start_x, base_config.interceptor_start_y);
+                    (start, start)
+                };
+                // Draw the target's initial conditions when the scenario gives distributions for them
+                let (config, mirrored_config) = scenario.envelope.sample_antithetic(&base_config, &mut run_rng);
+                // Both runs of a pair go on with the same random sequence, so they differ by the mirrored draws alone
+                if antithetic {
+                    partner = Some((mirrored_start, mirrored_config, run_rng));
+                }
+                (start, config, run_rng)
+            }
+        };
+        if mirrored {
+            log::info!("🪞 Run {}: antithetic partner of run {}", run_idx, run_idx - 1);
+        }
+        if randomize_interceptor {
+            log::info!("🔀 Run {}: Randomized interceptor start: ({:.2}, {:.2})", run_idx, interceptor_start_x, interceptor_start_y);
+        }
+        if !scenario.envelope.is_empty() {
+            log::info!(
+                "🎲 Run {}: target from ({:.2}, {:.2}) at {:.2} m/s, heading {:.1}°",
+                run_idx, run_config.target_start_x, run_config.target_start_y, run_config.target_speed, run_config.target_heading_deg
+            );
+        }
+
+        // Run simulation
+        let span = log::span(format!("run {} simulation", run_idx));
+        let checkpoint_path = std::path::Path::new(&out_dir).join(format!("checkpoint_{:03}.json", run_idx));
+        let mut telemetry = match stream {
+            true => Some(TelemetryStream::create(&std::path::Path::new(&out_dir).join(format!("telemetry_{:03}.csv", run_idx)))?),
+            false => None,
+        };
+        let mut simulation = Simulation::builder()
+            .config(watcher.as_ref().map_or_else(|| run_config.clone(), |w| w.apply(&run_config)))
+            .interceptor((interceptor_start_x, interceptor_start_y), base_config.interceptor_speed)
+            .build()?;
+        if let Some(view) = live_png.as_mut() { simulation.observe(view); }
+        if let Some(view) = tui_view.as_mut() { simulation.observe(view); }
+        if let Some(pacer) = realtime.as_mut() { simulation.observe(pacer); }
+        if let Some(stream) = telemetry.as_mut() { simulation.observe(stream); }
+        if let Some(output) = mavlink.as_mut() { simulation.observe(output); }
+        if let Some(publisher) = mqtt.as_mut() {
+            publisher.start_run(run_idx, &simulation.result().config);
+            simulation.observe(publisher);
+        }
+        let mut answers = feed.is_some().then(TelemetryStream::stdout).transpose()?;
+        if let Some(answers) = answers.as_mut() { simulation.observe(answers); }
+        if let Some(feed) = feed.as_mut() { simulation.feed_target(feed); }
+        if let Some(window) = window { simulation.window(window); }
+        if let Some(server) = server.as_mut() {
+            server.wait_for_client();
+            server.start_run(run_idx, &simulation.result().config);
+            simulation.observe(server);
+        }
+        run_to_end(&mut run_rng, &mut simulation, run_idx, checkpoint_every.map(|n| (checkpoint_path.as_path(), n)), playback.as_mut(), watcher.as_mut())?;
+        let sim = simulation.into_result();
+        // The batch's stream goes on from the first run of a pair
+        if run_seed.is_none() && !mirrored {
+            rng = run_rng;
+        }
+        if let Some(feed) = feed.take() {
+            feed.finish()?;
+        }
+        if let Some(answers) = answers {
+            answers.finish()?;
+        }
+        if let Some(stream) = telemetry {
+            stream.finish()?;
+            log::info!("💾 Run {}: telemetry streamed to 'telemetry_{:03}.csv'", run_idx, run_idx);
+        }
+        drop(span);
+        if events {
+            log::info!("📋 Run {}: {} after {} steps", run_idx, sim.outcome.as_str(), sim.steps());
+            print_events(&sim, units);
+        }
+        log::info!("🎯 Run {}: {}, Pk {:.2}", run_idx, sim.outcome.as_str(), sim.pk());
+        let passes = sim.passes();
+        if !passes.is_empty() {
+            let misses: Vec<String> = passes.iter().map(|&miss| format!("{:.2}", units.distance(Meters(miss)))).collect();
+            log::info!("🔁 Run {}: {} missed pass(es) before re-engaging or giving up, closest approaches {}", run_idx, passes.len(), misses.join(", "));
+        }
+        if let (Some(atmosphere), Some(&first), Some(&last)) = (sim.config.atmosphere, sim.interceptor_velocities.first(), sim.interceptor_velocities.last()) {
+            let densities = sim.interceptor_positions.iter().map(|&(_, y)| atmosphere.relative_density(y, sim.config.frame));
+            let (thinnest, thickest) = densities.fold((f64::INFINITY, 0.0_f64), |(lo, hi), sigma| (lo.min(sigma), hi.max(sigma)));
+            let speed = |(vx, vy): (f64, f64)| units.speed(MetersPerSecond(vx.hypot(vy)));
+            log::info!("💨 Run {}: air density {:.3}–{:.3} of sea level, interceptor speed {:.2} → {:.2}", run_idx, thinnest, thickest, speed(first), speed(last));
+        }
+        let violations: Vec<String> = sim.events.iter().filter_map(|e| if let SimEvent::KeepOutEntered { zone, .. } = *e { Some(zone.to_string()) } else { None }).collect();
+        if !violations.is_empty() {
+            log::info!("⛔ Run {}: entered a keep-out zone {} time(s), zones {} in order", run_idx, violations.len(), violations.join(", "));
+        }
+        if let Some(geo) = sim.collision_point_geodetic() {
+            log::info!("🌍 Run {}: intercept at {}", run_idx, geo);
+        }
+        pk_values.push(sim.pk());
+        outcomes.push(sim.outcome == Outcome::Intercept);
+        if sim.outcome == Outcome::Intercept {
+            intercept_times.push(sim.steps() as f64 * sim.config.dt);
+        }
+        log::info!("📐 Run {}: engagement geometry", run_idx);
+        GeometryReport::of(&sim, accel_threshold).print(accel_threshold, units);
+        if let Some(summary) = tracking::summary(&sim) {
+            log::info!("🛰️ Run {}: {}", run_idx, summary);
+        }
+
+        // Save visualization per run
+        if plot_options.enabled {
+            let span = log::span(format!("run {} plotting", run_idx));
+            let out_plot = plot_path(&out_dir, &plot_template, run_idx, runs, plot_options.format.extension());
+            visualize_simulation(&sim, out_plot.to_str().unwrap(), &plot_options)?;
+            if let Some(reach) = &reach {
+                reachability::export(&sim, reach, run_idx, &out_plot, &plot_options)?;
+            }
+            if let Some(view) = &view3d {
+                export_3d(&sim, &out_plot, view, &plot_options)?;
+            }
+            if let Some(video) = &video {
+                let out_mp4 = out_plot.with_extension("mp4");
+                export_video(&sim, out_mp4.to_str().unwrap(), video, &plot_options)?;
+            }
+            drop(span);
+        }
+
+        // Save structured result per run
+        let out_json = std::path::Path::new(&out_dir)
+            .join(format!("result_{:03}.json", run_idx));
+        std::fs::write(&out_json, sim.to_json().to_string_pretty() + "\n")
+            .map_err(SimError::io(format!("cannot write '{}'", out_json.display())))?;
+        log::info!("✅ Result saved as '{}'", out_json.display());
+        geoexport::export(&geo_exports, &sim, &out_json)?;
+        if json_stdout {
+            writeln!(std::io::stdout(), "{}", sim.to_json()).map_err(SimError::io("cannot write the result to stdout"))?;
+            if exit_code == 0 {
+                exit_code = sim.outcome.exit_code();
+            }
+        }
+        if checkpoint_path.exists() {
+            std::fs::remove_file(&checkpoint_path)
+                .map_err(SimError::io(format!("cannot remove checkpoint '{}'", checkpoint_path.display())))?;
+        }
+
+        // Append result to CSV
+        if let Some((cx, cy)) = sim.collision_point {
+            let angle_str = sim.collision_angle.map(|a| format!("{:.2}", a)).unwrap_or_else(|| "".into());
+            writeln!(results_file, "{},{:.3},{:.3},true,{:.3},{:.3},{}", run_idx, interceptor_start_x, interceptor_start_y, cx, cy, angle_str)?;
+        } else {
+            writeln!(results_file, "{},{:.3},{:.3},false,,,", run_idx, interceptor_start_x, interceptor_start_y)?;
+        }
+
+        if let Some(dataset) = dataset.as_mut() {
+            dataset.add(run_idx, &sim);
+        }
+        if let (Some(seed_log), Some(run_seed)) = (seed_log.as_mut(), run_seed) {
+            seed_log.add(run_idx, run_seed, &sim)?;
+        }
+        progress.inc(sim.outcome == Outcome::Intercept);
+        costs.add(&sim);
+        if (overlay || histograms) && plot_options.enabled {
+            batch.push(sim);
+        }
+    }
+    progress.finish();
+
+    if overlay && plot_options.enabled {
+        let out_overlay = std::path::Path::new(&out_dir).join(format!("overlay.{}", plot_options.format.extension()));
+        visualize_overlay(&batch, out_overlay.to_str().unwrap(), &plot_options)?;
+    }
+    if histograms && plot_options.enabled {
+        let out_histograms = std::path::Path::new(&out_dir).join(format!("histograms.{}", plot_options.format.extension()));
+        visualize_histograms(&batch, out_histograms.to_str().unwrap(), &plot_options)?;
+    }
+
+    if runs > 1 {
+        let hits = intercept_times.len();
+        let mean_pk = pk_values.iter().sum::<f64>() / runs as f64;
+        if antithetic {
+            // The runs of a pair aren't independent, the pairs are: the intervals are over the pair means
+            let pairs = |values: &[f64]| values.chunks(2).map(|pair| (pair[0] + pair[1]) / 2.0).collect::<Vec<_>>();
+            let (low, high) = stats::bootstrap_mean_interval(&pairs(&pk_values), 0.95).unwrap_or((mean_pk, mean_pk));
+            log::info!("🎯 Mean Pk over {} runs: {:.3} (95% CI {:.3}–{:.3}, bootstrap over {} antithetic pairs)", runs, mean_pk, low, high, runs / 2);
+            let hit_values: Vec<f64> = outcomes.iter().map(|hit| if *hit { 1.0 } else { 0.0 }).collect();
+            let (low, high) = stats::bootstrap_mean_interval(&pairs(&hit_values), 0.95).unwrap_or((0.0, 1.0));
+            log::info!("🎯 Hit rate {}/{} = {:.1}% (95% CI {:.1}–{:.1}%, bootstrap over {} antithetic pairs)", hits, runs, 100.0 * hits as f64 / runs as f64, 100.0 * low, 100.0 * high, runs / 2);
+        } else {
+            log::info!("🎯 Mean Pk over {} runs: {:.3}", runs, mean_pk);
+            let (low, high) = stats::wilson_interval(hits, runs, 0.95);
+            log::info!("🎯 Hit rate {}/{} = {:.1}% (95% CI {:.1}–{:.1}%, Wilson)", hits, runs, 100.0 * hits as f64 / runs as f64, 100.0 * low, 100.0 * high);
+        }
+        if let Some((low, high)) = stats::bootstrap_mean_interval(&intercept_times, 0.95) {
+            let mean = intercept_times.iter().sum::<f64>() / hits as f64;
+            log::info!("⏱️ Mean time to intercept {:.2} s (95% CI {:.2}–{:.2} s, bootstrap over {} intercepts)", mean, low, high, hits);
+        }
+        costs.report(&scenario.costs);
+    }
+    if let Some(seed_log) = seed_log {
+        seed_log.finish();
+    }
+    if let Some(server) = server {
+        server.close();
+    }
+    if let Some(publisher) = mqtt {
+        publisher.finish()?;
+    }
+    if let Some(dataset) = dataset {
+        dataset.save()?;
+    }
+    log::info!("✅ All runs complete. Results in: {}", out_dir);
+    Ok(ExitCode::from(exit_code))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::SimRng;
+    use rand::SeedableRng;
+
+    fn run(seed: u64, config: &SimConfig) -> SimulationResult {
+        simulate_once(&mut SimRng::seed_from_u64(seed), config)
+    }
+
+    #[test]
+    fn lead_pursuit_aims_at_the_intercept_point() {
+        // The intercept is at (30 + t, 40) with 2 t the distance from the origin, at t ≈ 40.55 s
+        let from = Interceptor::new((0.0, 0.0), (2.0, 0.0));
+        let steering = calculate_steering_direction(&from, &Target::new((30.0, 40.0), (1.0, 0.0)), true);
+        let t = (60.0 + 33600f64.sqrt()) / 6.0;
+        let aim = Vec2::new(30.0 + t, 40.0) / (2.0 * t);
+        assert!((steering.direction - aim).length() < 1e-12, "{:?}", steering.direction);
+        assert!(steering.lead_solution);
+        assert_eq!(steering.phase, GuidancePhase::Direct);
+    }
+
+    #[test]
+    fn lead_pursuit_without_a_solution_aims_at_the_target() {
+        // Fleeing faster than the interceptor flies
+        let from = Interceptor::new((0.0, 0.0), (2.0, 0.0));
+        let steering = calculate_steering_direction(&from, &Target::new((10.0, 10.0), (0.0, 5.0)), true);
+        assert!(!steering.lead_solution);
+        assert!((steering.direction - Vec2::new(1.0, 1.0).normalized().unwrap()).length() < 1e-12);
+    }
+
+    #[test]
+    fn shallow_approaches_are_widened_unless_the_angle_is_free() {
+        // A tail chase along the target's own track
+        let from = Interceptor::new((0.0, 10.0), (3.0, 0.0));
+        let to = Target::new((20.0, 10.0), (1.0, 0.0));
+        let constrained = calculate_steering_direction(&from, &to, true);
+        assert_eq!(constrained.phase, GuidancePhase::AngleConstrained);
+        assert!(constrained.direction.angle_deg(to.velocity) > 5.0);
+        let free = calculate_steering_direction(&from, &to, false);
+        assert_eq!(free.phase, GuidancePhase::Direct);
+        assert!((free.direction - Vec2::new(1.0, 0.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_run() {
+        let config = SimConfig::default();
+        assert!(config.noise_range_deg > 0.0);
+        let (first, second) = (run(7, &config), run(7, &config));
+        assert_eq!(first.to_json().to_string(), second.to_json().to_string());
+        // The noise comes from the seed, so another one flies another path
+        assert_ne!(first.target_positions, run(8, &config).target_positions);
+    }
+
+    #[test]
+    fn stepping_gives_the_same_run_as_running_through() {
+        let config = SimConfig { guidance: GuidanceLaw::Zem, ..SimConfig::default() };
+        let mut rng = SimRng::seed_from_u64(3);
+        let mut simulation = Simulation::new(&config);
+        let mut steps = 0;
+        while let StepOutcome::Running = simulation.step(&mut rng) {
+            steps += 1;
+        }
+        let stepped = simulation.result().to_json().to_string();
+        assert_eq!(stepped, run(3, &config).to_json().to_string());
+        assert!(steps > 0 && steps <= config.max_steps);
+    }
+
+    #[test]
+    fn a_chunked_windowed_tracking_run_matches_the_unchunked_run() {
+        // An IMM track through a long chase, so that the window drops measurements many times over
+        let tracking = Tracking::from_json(&Json::parse(r#"{"filter": "imm", "measurement_noise": 0.5}"#).unwrap()).unwrap();
+        let config = SimConfig { tracking: Some(tracking), interceptor_speed: 1.9, ..SimConfig::default() };
+        let whole = run(5, &config);
+        let mut rng = SimRng::seed_from_u64(5);
+        let mut simulation = Simulation::new(&config);
+        simulation.window(100);
+        // Every third chunk the run goes through a checkpoint, as `resume` reads it back
+        let mut chunks = 0;
+        while !simulation.advance(&mut rng, 7).is_finished() {
+            chunks += 1;
+            if chunks % 3 == 0 {
+                let saved = Json::parse(&simulation.result().to_json().to_string()).unwrap();
+                simulation = Simulation::resume(SimulationResult::from_json(&saved).unwrap());
+                simulation.window(100);
+            }
+        }
+        let windowed = simulation.result();
+        assert!(windowed.first_step > 0 && windowed.track_filter.is_some());
+        assert_eq!((windowed.outcome, windowed.steps()), (whole.outcome, whole.steps()));
+        let dropped = windowed.first_step;
+        assert_eq!(windowed.target_positions, whole.target_positions[dropped..]);
+        assert_eq!(windowed.interceptor_positions, whole.interceptor_positions[dropped..]);
+        assert_eq!(windowed.track_positions, whole.track_positions[dropped..]);
+    }
+}
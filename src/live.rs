@@ -0,0 +1,65 @@
+// --live-png: re-renders the engagement into a PNG while the simulation runs; pacing and pause /
+// single-step are handled by playback.rs.
+//
+// This is not a window: no windowing toolkit is available to this crate, so the image is
+// atomically replaced on every frame and left to a viewer that reloads on change (e.g.
+// `feh --reload 0.1 live.png` or `eog live.png`). A windowed viewer is still to be written.
+
+use crate::bounds::PlotBounds;
+use crate::error::SimError;
+use crate::plot::{draw_engagement, PlotOptions};
+use crate::simulation::SimObserver;
+use crate::SimulationResult;
+use plotters::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+// Re-rendering is comparatively slow, so frames are capped independently of the step rate
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct LivePng {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    plot: PlotOptions,
+    last_frame: Option<Instant>,
+}
+
+impl LivePng {
+    pub fn new(path: &Path, plot: PlotOptions) -> Self {
+        println!("📺 Live PNG: '{}' (p = pause, s = single step, + / - = speed, q = skip to end; confirm with Enter)", path.display());
+        LivePng { path: path.to_path_buf(), tmp_path: path.with_extension("tmp.png"), plot, last_frame: None }
+    }
+
+    fn render(&self, sim: &SimulationResult) -> Result<(), SimError> {
+        let bounds = PlotBounds::from_result(sim);
+        {
+            let root = BitMapBackend::new(&self.tmp_path, self.plot.image_size()).into_drawing_area();
+            draw_engagement(&root, sim, bounds, &self.plot)?;
+            root.present()?;
+        }
+        // Replace atomically so viewers never load a half-written frame
+        std::fs::rename(&self.tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl SimObserver for LivePng {
+    // Called once per simulation step with the result recorded so far
+    fn on_step(&mut self, sim: &SimulationResult) {
+        let due = self.last_frame.is_none_or(|t| t.elapsed() >= MIN_FRAME_INTERVAL);
+        if due {
+            if let Err(e) = self.render(sim) {
+                eprintln!("⚠️ Live PNG render failed: {}", e);
+            }
+            self.last_frame = Some(Instant::now());
+        }
+    }
+
+    // Draw the final state regardless of the frame cap; the view can then be reused for the next run
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        if let Err(e) = self.render(sim) {
+            eprintln!("⚠️ Live PNG render failed: {}", e);
+        }
+        self.last_frame = None;
+    }
+}
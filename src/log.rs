@@ -0,0 +1,105 @@
+// Console output levels. The emoji status lines go to stdout through info!, which --quiet / -q
+// silences for scripted use (as do --json-stdout and --target-feed, whose output stdout is for);
+// diagnostics go to stderr, gated by the verbosity flags:
+//
+//   -v   engagement events (launch, intercept, timeout) and how long each stage took
+//   -vv  additionally every step: distance, commanded angles and controller terms
+//
+// Both print around an active progress bar instead of through it.
+
+use crate::progress;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+// -v counts once, -vv twice, and repeated flags add up (-v -v is the same as -vv)
+pub fn init_from_args(args: &[String]) {
+    let quiet = args.iter().any(|a| a == "--quiet" || a == "-q" || a == "--json-stdout" || a == "--target-feed");
+    QUIET.store(quiet || args.get(1).is_some_and(|command| command == "gym"), Ordering::Relaxed);
+    let verbosity: usize = args
+        .iter()
+        .map(|a| match a.as_str() {
+            "-v" | "--verbose" => 1,
+            "-vv" => 2,
+            _ => 0,
+        })
+        .sum();
+    VERBOSITY.store(verbosity.min(Level::Trace as usize) as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    VERBOSITY.load(Ordering::Relaxed) >= level as u8
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn write(level: Level, message: std::fmt::Arguments) {
+    progress::suspend(|| eprintln!("[{}] {}", level.as_str(), message));
+}
+
+pub fn print(message: std::fmt::Arguments) {
+    if !quiet() {
+        progress::suspend(|| println!("{}", message));
+    }
+}
+
+// Logs at debug level how long it lived, e.g. `let _span = log::span(format!("run {}", n));`
+pub struct Span {
+    name: String,
+    start: Instant,
+}
+
+pub fn span(name: impl Into<String>) -> Span {
+    Span { name: name.into(), start: Instant::now() }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if enabled(Level::Debug) {
+            write(Level::Debug, format_args!("{} took {:.1?}", self.name, self.start.elapsed()));
+        }
+    }
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::log::print(format_args!($($arg)*))
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::write($crate::log::Level::Debug, format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            $crate::log::write($crate::log::Level::Trace, format_args!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use {debug, info, trace};
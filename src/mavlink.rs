@@ -0,0 +1,189 @@
+// MAVLink telemetry: with --mavlink ADDR every step's state goes out as MAVLink 1 messages over
+// UDP to ADDR (e.g. 127.0.0.1:14550, where QGroundControl listens), so ground-station tools show
+// the simulated vehicles. The interceptor is system 1 and the target system 2, each sending
+//
+//   HEARTBEAT           at the start and once per simulated second, as a fixed-wing vehicle
+//   LOCAL_POSITION_NED  position and velocity, north-east-down from the simulation's origin
+//   ATTITUDE            pitch and yaw along the velocity (no roll), and their rates
+//
+// The plane's x axis points along the frame's bearing (east without a frame, as its default) and
+// y is the height, so north = x cos(bearing), east = x sin(bearing) and down = -y. Time stamps are
+// simulated milliseconds since launch; pair with --realtime for a live view. Nothing is received,
+// and a packet that can't be sent is dropped.
+
+use crate::error::SimError;
+use crate::simulation::SimObserver;
+use crate::SimulationResult;
+use std::f64::consts::{PI, TAU};
+use std::net::UdpSocket;
+
+const MSG_HEARTBEAT: (u8, u8) = (0, 50); // Message id and CRC_EXTRA seed of the message's fields
+const MSG_ATTITUDE: (u8, u8) = (30, 39);
+const MSG_LOCAL_POSITION_NED: (u8, u8) = (32, 185);
+
+const MAV_TYPE_FIXED_WING: u8 = 1;
+const MAV_AUTOPILOT_GENERIC: u8 = 0;
+const MAV_STATE_ACTIVE: u8 = 4;
+
+const INTERCEPTOR_SYSTEM: u8 = 1;
+const TARGET_SYSTEM: u8 = 2;
+
+// Heading the plane's x axis points along without a frame
+const DEFAULT_BEARING_DEG: f64 = 90.0;
+
+pub struct MavlinkOutput {
+    socket: UdpSocket,
+    sequence: u8,
+    sent: usize, // Steps sent so far
+}
+
+impl MavlinkOutput {
+    pub fn connect(addr: &str) -> Result<MavlinkOutput, SimError> {
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(SimError::io("cannot open a UDP socket for MAVLink"))?;
+        socket.connect(addr).map_err(SimError::io(format!("cannot send MAVLink to '{}'", addr)))?;
+        Ok(MavlinkOutput { socket, sequence: 0, sent: 0 })
+    }
+
+    // --mavlink ADDR
+    pub fn from_args(args: &[String]) -> Result<Option<MavlinkOutput>, SimError> {
+        args.windows(2).find(|pair| pair[0] == "--mavlink").map(|pair| MavlinkOutput::connect(&pair[1])).transpose()
+    }
+
+    fn send(&mut self, system: u8, message: (u8, u8), payload: &[u8]) {
+        let _ = self.socket.send(&packet(self.sequence, system, message, payload));
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    fn heartbeat(&mut self, system: u8) {
+        let mut payload = 0u32.to_le_bytes().to_vec(); // custom_mode
+        payload.extend_from_slice(&[MAV_TYPE_FIXED_WING, MAV_AUTOPILOT_GENERIC, 0, MAV_STATE_ACTIVE, 3]);
+        self.send(system, MSG_HEARTBEAT, &payload);
+    }
+
+    // LOCAL_POSITION_NED and ATTITUDE of a vehicle at `k` in its records, the rates over the step
+    // before
+    fn vehicle(&mut self, system: u8, sim: &SimulationResult, positions: &[(f64, f64)], velocities: &[(f64, f64)], k: usize, step: usize) {
+        let bearing = sim.config.frame.map_or(DEFAULT_BEARING_DEG, |frame| frame.bearing_deg).to_radians();
+        let (sin, cos) = bearing.sin_cos();
+        let time_ms = ((step as f64 * sim.config.dt * 1000.0).round() as u32).to_le_bytes();
+        let ned = |(x, y): (f64, f64)| [x * cos, x * sin, -y];
+        let position = ned(positions[k]);
+        let velocity = ned(velocities[k]);
+        self.send(system, MSG_LOCAL_POSITION_NED, &fields(time_ms, position.into_iter().chain(velocity)));
+
+        let attitude = |(vx, vy): (f64, f64)| (vy.atan2(vx.abs()), wrap_angle(if vx < 0.0 { bearing + PI } else { bearing }));
+        let (pitch, yaw) = attitude(velocities[k]);
+        let (pitch_rate, yaw_rate) = match k.checked_sub(1).map(|previous| attitude(velocities[previous])) {
+            Some((last_pitch, last_yaw)) => ((pitch - last_pitch) / sim.config.dt, wrap_angle(yaw - last_yaw) / sim.config.dt),
+            None => (0.0, 0.0),
+        };
+        self.send(system, MSG_ATTITUDE, &fields(time_ms, [0.0, pitch, yaw, 0.0, pitch_rate, yaw_rate].into_iter()));
+    }
+
+    // The steps recorded since the last call that are still in memory
+    fn send_new(&mut self, sim: &SimulationResult) {
+        for step in self.sent.max(sim.first_step)..=sim.steps() {
+            let k = step - sim.first_step;
+            // Whenever the simulated clock passes a whole second, and at the start
+            let second = |step: usize| (step as f64 * sim.config.dt).floor();
+            if step == 0 || second(step) > second(step - 1) {
+                self.heartbeat(INTERCEPTOR_SYSTEM);
+                self.heartbeat(TARGET_SYSTEM);
+            }
+            self.vehicle(INTERCEPTOR_SYSTEM, sim, &sim.interceptor_positions, &sim.interceptor_velocities, k, step);
+            self.vehicle(TARGET_SYSTEM, sim, &sim.target_positions, &sim.target_velocities, k, step);
+        }
+        self.sent = sim.steps() + 1;
+    }
+}
+
+impl SimObserver for MavlinkOutput {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        self.send_new(sim);
+    }
+
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        self.send_new(sim);
+        self.sent = 0;
+    }
+}
+
+// To ±π, so headings and turns through north stay small
+fn wrap_angle(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+// A payload of a u32 time stamp followed by f32 fields, as both state messages are laid out
+fn fields(time_ms: [u8; 4], values: impl Iterator<Item = f64>) -> Vec<u8> {
+    let mut payload = time_ms.to_vec();
+    for value in values {
+        payload.extend_from_slice(&(value as f32).to_le_bytes());
+    }
+    payload
+}
+
+// A MAVLink 1 packet from `system`'s component 1
+fn packet(sequence: u8, system: u8, (id, crc_extra): (u8, u8), payload: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0xFE, payload.len() as u8, sequence, system, 1, id];
+    packet.extend_from_slice(payload);
+    let crc = crc_x25(&packet[1..], crc_extra);
+    packet.extend_from_slice(&crc.to_le_bytes());
+    packet
+}
+
+// CRC-16/MCRF4XX over the packet after its start byte, finished with the message's CRC_EXTRA
+fn crc_x25(data: &[u8], crc_extra: u8) -> u16 {
+    data.iter().chain([crc_extra].iter()).fold(0xFFFF, |crc: u16, &byte| {
+        let mut tmp = byte ^ (crc & 0xFF) as u8;
+        tmp ^= tmp << 4;
+        (crc >> 8) ^ ((tmp as u16) << 8) ^ ((tmp as u16) << 3) ^ ((tmp as u16) >> 4)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // CRC_EXTRA as the MAVLink generator derives it: the CRC of the message name and its fields'
+    // types and names in wire order, folded to one byte
+    fn crc_extra(name: &str, fields: &[(&str, &str)]) -> u8 {
+        let mut text = format!("{} ", name);
+        for (kind, field) in fields {
+            text += &format!("{} {} ", kind, field);
+        }
+        let (last, rest) = text.as_bytes().split_last().unwrap();
+        let crc = crc_x25(rest, *last);
+        (crc & 0xFF) as u8 ^ (crc >> 8) as u8
+    }
+
+    #[test]
+    fn the_checksum_is_crc16_mcrf4xx() {
+        // The catalogue's check value: the CRC of "123456789"
+        assert_eq!(crc_x25(b"12345678", b'9'), 0x6F91);
+    }
+
+    #[test]
+    fn crc_extra_seeds_match_the_message_definitions() {
+        let heartbeat = [
+            ("uint32_t", "custom_mode"),
+            ("uint8_t", "type"),
+            ("uint8_t", "autopilot"),
+            ("uint8_t", "base_mode"),
+            ("uint8_t", "system_status"),
+            ("uint8_t", "mavlink_version"),
+        ];
+        assert_eq!(crc_extra("HEARTBEAT", &heartbeat), MSG_HEARTBEAT.1);
+        let floats = |names: [&'static str; 6]| [("uint32_t", "time_boot_ms")].into_iter().chain(names.map(|name| ("float", name))).collect::<Vec<_>>();
+        assert_eq!(crc_extra("ATTITUDE", &floats(["roll", "pitch", "yaw", "rollspeed", "pitchspeed", "yawspeed"])), MSG_ATTITUDE.1);
+        assert_eq!(crc_extra("LOCAL_POSITION_NED", &floats(["x", "y", "z", "vx", "vy", "vz"])), MSG_LOCAL_POSITION_NED.1);
+    }
+
+    #[test]
+    fn a_heartbeat_packet_is_framed_byte_for_byte() {
+        let payload = [0, 0, 0, 0, MAV_TYPE_FIXED_WING, MAV_AUTOPILOT_GENERIC, 0, MAV_STATE_ACTIVE, 3];
+        assert_eq!(
+            packet(0, INTERCEPTOR_SYSTEM, MSG_HEARTBEAT, &payload),
+            [0xFE, 0x09, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x04, 0x03, 0x61, 0x05]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+// MQTT publishing: with --mqtt HOST[:PORT] (port 1883 by default) runs publish their state to an
+// MQTT broker as they are simulated, for dashboards that already collect telemetry that way
+// (Node-RED, or Grafana through a bridge). Messages are JSON, under the topic prefix given with
+// --mqtt-topic PREFIX (default "interceptor-sim"):
+//
+//   PREFIX/run     {"run": 1, "config": {...}}                when a run starts
+//   PREFIX/state   {"run": 1, "step": 12, "time": 12.0, "target": {...}, "interceptor": {...},
+//                   "separation": 3.2}                        after every step
+//   PREFIX/event   {"run": 1, "event": {"type": "lock_acquired", "step": 3}}  as events are logged
+//   PREFIX/end     {"run": 1, "outcome": "intercept", "steps": 42, "miss_distance": 0.4, "pk": 0.98}
+//
+// The fields are those of serve's messages (see serve.rs). Publishing is MQTT 3.1.1 at QoS 0 over
+// plain TCP, without credentials; a broker that goes away mid-run fails the command at the end.
+
+use crate::error::SimError;
+use crate::json::{Json, ToJson};
+use crate::log;
+use crate::serve::{end_fields, step_fields};
+use crate::simulation::SimObserver;
+use crate::{SimConfig, SimEvent, SimulationResult};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 1883;
+const DEFAULT_TOPIC: &str = "interceptor-sim";
+
+// Packet types, in the high nibble of the first byte
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const DISCONNECT: u8 = 0xE0;
+
+pub struct MqttPublisher {
+    broker: String,
+    stream: TcpStream,
+    topic: String, // Prefix of every topic
+    run: usize,
+    sent: usize,                   // Steps published of the current run
+    error: Option<std::io::Error>, // The first publish that failed; reported by finish
+}
+
+impl MqttPublisher {
+    pub fn connect(broker: &str, topic: &str) -> Result<MqttPublisher, SimError> {
+        let addr = match broker.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+            true => broker.to_string(),
+            false => format!("{}:{}", broker, DEFAULT_PORT),
+        };
+        let failed = || SimError::io(format!("cannot connect to MQTT broker '{}'", addr));
+        let mut stream = TcpStream::connect(&addr).map_err(failed())?;
+        stream.set_read_timeout(Some(Duration::from_secs(5))).map_err(failed())?;
+        let client_id = format!("interceptor-sim-{}", std::process::id());
+        stream.write_all(&connect_packet(&client_id)).map_err(failed())?;
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack).map_err(failed())?;
+        if connack[0] != CONNACK || connack[3] != 0 {
+            return Err(SimError::ConfigError(format!("MQTT broker '{}' refused the connection (code {})", addr, connack[3])));
+        }
+        log::info!("📤 Publishing telemetry to mqtt://{}/{}", addr, topic);
+        Ok(MqttPublisher { broker: addr, stream, topic: topic.trim_end_matches('/').to_string(), run: 0, sent: 0, error: None })
+    }
+
+    // --mqtt HOST[:PORT] and --mqtt-topic PREFIX
+    pub fn from_args(args: &[String]) -> Result<Option<MqttPublisher>, SimError> {
+        let topic = args.windows(2).find(|pair| pair[0] == "--mqtt-topic").map_or(DEFAULT_TOPIC, |pair| pair[1].as_str());
+        args.windows(2).find(|pair| pair[0] == "--mqtt").map(|pair| MqttPublisher::connect(&pair[1], topic)).transpose()
+    }
+
+    // Announces run `run` flown with `config`; call before it is observed
+    pub fn start_run(&mut self, run: usize, config: &SimConfig) {
+        self.run = run;
+        self.sent = 0;
+        self.publish("run", vec![("config", config.to_json())]);
+    }
+
+    // Disconnects; fails if anything couldn't be published
+    pub fn finish(mut self) -> Result<(), SimError> {
+        let result = match self.error.take() {
+            Some(e) => Err(e),
+            None => self.stream.write_all(&packet(DISCONNECT, &[])),
+        };
+        result.map_err(SimError::io(format!("cannot publish to MQTT broker '{}'", self.broker)))
+    }
+
+    fn publish(&mut self, subtopic: &str, fields: Vec<(&str, Json)>) {
+        if self.error.is_some() {
+            return;
+        }
+        let message = Json::object([("run", self.run.into())].into_iter().chain(fields).collect());
+        let packet = publish_packet(&format!("{}/{}", self.topic, subtopic), message.to_string().as_bytes());
+        self.error = self.stream.write_all(&packet).err();
+    }
+
+    // The steps recorded since the last call that are still in memory
+    fn publish_new(&mut self, sim: &SimulationResult) {
+        for step in self.sent.max(sim.first_step)..=sim.steps() {
+            self.publish("state", step_fields(sim, step));
+        }
+        self.sent = sim.steps() + 1;
+    }
+}
+
+impl SimObserver for MqttPublisher {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        self.publish_new(sim);
+    }
+
+    fn on_event(&mut self, event: &SimEvent, _sim: &SimulationResult) {
+        self.publish("event", vec![("event", event.to_json())]);
+    }
+
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        self.publish_new(sim);
+        self.publish("end", end_fields(sim));
+    }
+}
+
+// Protocol name and level 4 (3.1.1), a clean session, no keep-alive, and the client id
+fn connect_packet(client_id: &str) -> Vec<u8> {
+    let mut body = string(b"MQTT");
+    body.extend_from_slice(&[4, 0x02, 0, 0]);
+    body.extend(string(client_id.as_bytes()));
+    packet(CONNECT, &body)
+}
+
+// A QoS 0 message, which has no packet id
+fn publish_packet(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = string(topic.as_bytes());
+    body.extend_from_slice(payload);
+    packet(PUBLISH, &body)
+}
+
+// A UTF-8 string or binary field: two bytes of length, then the bytes
+fn string(bytes: &[u8]) -> Vec<u8> {
+    let mut field = (bytes.len() as u16).to_be_bytes().to_vec();
+    field.extend_from_slice(bytes);
+    field
+}
+
+// A control packet: type, the remaining length as a variable-length integer, then `body`
+fn packet(kind: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![kind];
+    let mut length = body.len();
+    loop {
+        let byte = (length % 128) as u8;
+        length /= 128;
+        packet.push(if length > 0 { byte | 0x80 } else { byte });
+        if length == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+    packet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_lengths_are_encoded_as_in_the_specification() {
+        // The boundaries of one to four length bytes, from the table in section 2.2.3
+        for (length, encoded) in [
+            (0, &[0x00][..]),
+            (127, &[0x7F]),
+            (128, &[0x80, 0x01]),
+            (16_383, &[0xFF, 0x7F]),
+            (16_384, &[0x80, 0x80, 0x01]),
+            (2_097_151, &[0xFF, 0xFF, 0x7F]),
+            (2_097_152, &[0x80, 0x80, 0x80, 0x01]),
+        ] {
+            let packet = packet(PUBLISH, &vec![0; length]);
+            assert_eq!(&packet[1..1 + encoded.len()], encoded, "{}", length);
+            assert_eq!(packet.len(), 1 + encoded.len() + length);
+        }
+    }
+
+    #[test]
+    fn connect_is_framed_byte_for_byte() {
+        let expected = [
+            0x10, 0x0F, // CONNECT, 15 bytes follow
+            0x00, 0x04, b'M', b'Q', b'T', b'T', // protocol name
+            0x04, 0x02, 0x00, 0x00, // level 4, clean session, no keep-alive
+            0x00, 0x03, b's', b'i', b'm', // client id
+        ];
+        assert_eq!(connect_packet("sim"), expected);
+    }
+
+    #[test]
+    fn publish_is_framed_byte_for_byte() {
+        let expected = [0x30, 0x07, 0x00, 0x03, b'a', b'/', b'b', b'{', b'}'];
+        assert_eq!(publish_packet("a/b", b"{}"), expected);
+    }
+}
@@ -0,0 +1,138 @@
+// Random heading deviations. Every step the target's evasion jitter, and the interceptor's heading
+// noise if enabled, is drawn from a NoiseModel chosen per vehicle in the configuration:
+//
+//   uniform             uniform in ±amplitude, independent from step to step (the original jitter)
+//   gaussian            normal with standard deviation = amplitude, independent from step to step
+//   ornstein_uhlenbeck  normal with standard deviation = amplitude, correlated over `tau` seconds,
+//                       so the vehicle weaves through longer turns instead of jittering
+//
+// Draws come from the run's RNG, so --seed makes them reproducible. The deviation applied at every
+// step is recorded in the result, which is what correlated models continue from after a resume.
+
+use crate::json::Json;
+use rand::{Rng, RngCore};
+
+// Correlation time of the Ornstein-Uhlenbeck model when none is given
+pub const DEFAULT_TAU: f64 = 5.0;
+
+pub trait NoiseModel {
+    // Deviation for the next step in degrees, given the previous step's deviation and the step length
+    fn sample(&self, rng: &mut dyn RngCore, previous_deg: f64, dt: f64) -> f64;
+}
+
+pub struct Uniform {
+    pub range_deg: f64,
+}
+
+pub struct Gaussian {
+    pub sigma_deg: f64,
+}
+
+pub struct OrnsteinUhlenbeck {
+    pub sigma_deg: f64,
+    pub tau: f64, // Correlation time in seconds
+}
+
+// A zero amplitude draws nothing, so switching a vehicle's noise off leaves the other's sequence unchanged
+impl NoiseModel for Uniform {
+    fn sample(&self, rng: &mut dyn RngCore, _previous_deg: f64, _dt: f64) -> f64 {
+        if self.range_deg > 0.0 {
+            rng.gen_range(-self.range_deg..self.range_deg)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl NoiseModel for Gaussian {
+    fn sample(&self, rng: &mut dyn RngCore, _previous_deg: f64, _dt: f64) -> f64 {
+        if self.sigma_deg > 0.0 {
+            self.sigma_deg * standard_normal(rng)
+        } else {
+            0.0
+        }
+    }
+}
+
+// Exact discretization, so the statistics don't depend on dt: the previous value decays with
+// exp(-dt/tau) and fresh noise keeps the stationary standard deviation at sigma
+impl NoiseModel for OrnsteinUhlenbeck {
+    fn sample(&self, rng: &mut dyn RngCore, previous_deg: f64, dt: f64) -> f64 {
+        if self.sigma_deg > 0.0 {
+            let decay = (-dt / self.tau).exp();
+            previous_deg * decay + self.sigma_deg * (1.0 - decay * decay).sqrt() * standard_normal(rng)
+        } else {
+            0.0
+        }
+    }
+}
+
+// Box-Muller transform
+pub fn standard_normal(rng: &mut dyn RngCore) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>(); // (0, 1], keeps ln finite
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+// Which model a vehicle uses; the amplitude is configured separately
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseKind {
+    Uniform,
+    Gaussian,
+    OrnsteinUhlenbeck { tau: f64 },
+}
+
+impl NoiseKind {
+    pub const NAMES: &'static str = "uniform, gaussian, ornstein_uhlenbeck";
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoiseKind::Uniform => "uniform",
+            NoiseKind::Gaussian => "gaussian",
+            NoiseKind::OrnsteinUhlenbeck { .. } => "ornstein_uhlenbeck",
+        }
+    }
+
+    pub fn parse(name: &str, tau: f64) -> Option<NoiseKind> {
+        match name {
+            "uniform" => Some(NoiseKind::Uniform),
+            "gaussian" => Some(NoiseKind::Gaussian),
+            "ornstein_uhlenbeck" => Some(NoiseKind::OrnsteinUhlenbeck { tau }),
+            _ => None,
+        }
+    }
+
+    pub fn tau(&self) -> Option<f64> {
+        match *self {
+            NoiseKind::OrnsteinUhlenbeck { tau } => Some(tau),
+            _ => None,
+        }
+    }
+
+    pub fn model(&self, amplitude_deg: f64) -> Box<dyn NoiseModel> {
+        match *self {
+            NoiseKind::Uniform => Box::new(Uniform { range_deg: amplitude_deg }),
+            NoiseKind::Gaussian => Box::new(Gaussian { sigma_deg: amplitude_deg }),
+            NoiseKind::OrnsteinUhlenbeck { tau } => Box::new(OrnsteinUhlenbeck { sigma_deg: amplitude_deg, tau }),
+        }
+    }
+
+    // `model_key` names the model, `tau_key` the correlation time; missing fields keep `default`
+    pub fn from_json_fields(json: &Json, model_key: &str, tau_key: &str, default: NoiseKind) -> Result<NoiseKind, String> {
+        let tau = json.f64_field_or(tau_key, default.tau().unwrap_or(DEFAULT_TAU))?;
+        let name = match json.get(model_key) {
+            Some(name) => name.as_str().ok_or_else(|| format!("field '{}' must be one of: {}", model_key, NoiseKind::NAMES))?,
+            None => default.as_str(),
+        };
+        NoiseKind::parse(name, tau).ok_or_else(|| format!("field '{}' must be one of: {}", model_key, NoiseKind::NAMES))
+    }
+
+    // The fields read by from_json_fields
+    pub fn json_fields(&self, model_key: &'static str, tau_key: &'static str) -> Vec<(&'static str, Json)> {
+        let mut fields = vec![(model_key, self.as_str().into())];
+        if let Some(tau) = self.tau() {
+            fields.push((tau_key, tau.into()));
+        }
+        fields
+    }
+}
@@ -0,0 +1,116 @@
+// Stands in for the drawing modules (plot, plot3d, video and live) in builds without the `plot`
+// feature, which leaves plotters out, so the rest of the program needs no conditional code. Plot
+// options always come out disabled, and callers check that before drawing, so nothing here is ever
+// asked to draw; flags that only make sense with images are rejected when the options are parsed.
+
+use crate::error::SimError;
+use crate::json::{FromJson, Json};
+use crate::reachability::ReachableSet;
+use crate::simulation::SimObserver;
+use crate::sweep::Heatmap;
+use crate::SimulationResult;
+use std::path::Path;
+
+// Flags whose whole point is an image or a video
+const DRAWING_FLAGS: [&str; 7] = ["--live-png", "--video", "--view3d", "--view3d-gif", "--overlay", "--histograms", "--reachability"];
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutputFormat;
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        "png"
+    }
+}
+
+// A scenario's style section is accepted and ignored
+#[derive(Debug, Clone, Default)]
+pub struct PlotStyle;
+
+impl FromJson for PlotStyle {
+    fn from_json(_json: &Json) -> Result<Self, String> {
+        Ok(PlotStyle)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlotOptions {
+    pub format: OutputFormat,
+    pub style: PlotStyle,
+    pub enabled: bool,
+}
+
+impl PlotOptions {
+    pub fn from_args(args: &[String]) -> Result<PlotOptions, String> {
+        match args.iter().find(|a| DRAWING_FLAGS.contains(&a.as_str())) {
+            Some(flag) => Err(format!("{} draws images, which this build leaves out (see the plot feature)", flag)),
+            None => Ok(PlotOptions::default()),
+        }
+    }
+}
+
+fn not_built() -> SimError {
+    SimError::PlottingError("this build leaves out image output (see the plot feature)".into())
+}
+
+pub fn visualize_simulation(_sim: &SimulationResult, _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_overlay(_sims: &[SimulationResult], _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_comparison(_sims: &[SimulationResult], _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_histograms(_sims: &[SimulationResult], _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_heatmap(_heatmap: &Heatmap, _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_reachability(_sim: &SimulationResult, _sets: &[ReachableSet], _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub fn visualize_contour(_heatmap: &Heatmap, _levels: usize, _marker: Option<((f64, f64), &str)>, _out_path: &str, _options: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub struct View3dOptions;
+
+impl View3dOptions {
+    pub fn from_args(_args: &[String]) -> Result<Option<View3dOptions>, String> {
+        Ok(None)
+    }
+}
+
+pub fn export_3d(_sim: &SimulationResult, _base_path: &Path, _view: &View3dOptions, _plot: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub struct VideoOptions;
+
+impl VideoOptions {
+    pub fn from_args(_args: &[String]) -> Result<Option<VideoOptions>, String> {
+        Ok(None)
+    }
+}
+
+pub fn export_video(_sim: &SimulationResult, _out_path: &str, _options: &VideoOptions, _plot: &PlotOptions) -> Result<(), SimError> {
+    Err(not_built())
+}
+
+pub struct LivePng;
+
+impl LivePng {
+    pub fn new(_path: &Path, _plot: PlotOptions) -> Self {
+        LivePng
+    }
+}
+
+impl SimObserver for LivePng {}
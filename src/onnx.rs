@@ -0,0 +1,622 @@
+// Learned guidance: a neural network in an ONNX file maps the engagement to the interceptor's
+// acceleration command, so policies trained elsewhere are flown and scored by the same physics and
+// reports as the classical laws. With "guidance": "neural" and "guidance_model": "policy.onnx" the
+// model gets each step the observation
+//
+//   [rx, ry, rvx, rvy, ivx, ivy, range, closing]
+//
+// the target's position and velocity relative to the interceptor (as guidance sees the target),
+// the interceptor's own velocity, the range and the closing speed, as a tensor of shape [1, 8] (or
+// [8] if its input has rank 1). Its one output is either a single value, the lateral acceleration
+// (m/s²) normal to the interceptor's velocity, positive counter-clockwise, or two, an acceleration
+// (ax, ay) of which the part normal to the velocity is applied, as the interceptor keeps its speed.
+//
+// Models are read with a small protobuf decoder and evaluated here, in f64, which covers the
+// operators multilayer perceptrons export to: Gemm, MatMul, Add, Sub, Mul, Div (with broadcasting),
+// Relu, LeakyRelu, Elu, Tanh, Sigmoid, Softplus, Clip, Neg, Abs, Exp, Identity, Flatten, Reshape
+// and Constant, on FLOAT or DOUBLE tensors. A model with anything else is rejected when it is
+// loaded, which also evaluates it once to check its shapes. A command that isn't a finite number
+// leaves the interceptor flying straight, counts as no lock and is reported once.
+//
+// A loaded model is shared through an Arc by every config (and tournament entry) that flies it.
+
+use crate::engagement::Engagement;
+use crate::log;
+use crate::vec2::Vec2;
+use crate::{GuidancePhase, Interceptor, Steering, Target};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// What the policy sees of the engagement, in the order listed above
+pub fn observation(from: &Interceptor, to: &Target) -> [f64; 8] {
+    let (r, v) = (to.position - from.position, to.velocity - from.velocity);
+    let engagement = Engagement::between(from, to);
+    [r.x, r.y, v.x, v.y, from.velocity.x, from.velocity.y, engagement.range, engagement.closing_velocity]
+}
+
+// A protobuf field: its number and value by wire type
+enum Field<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut value = 0u64;
+    for shift in (0..70).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or("truncated protobuf")?;
+        *pos += 1;
+        value |= ((byte & 0x7F) as u64) << shift.min(63);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("malformed protobuf varint".into())
+}
+
+fn fields(bytes: &[u8]) -> Result<Vec<(u64, Field<'_>)>, String> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let key = varint(bytes, &mut pos)?;
+        let len = match key & 7 {
+            0 => 0,
+            1 => 8,
+            2 => varint(bytes, &mut pos)? as usize,
+            5 => 4,
+            wire => return Err(format!("unsupported protobuf wire type {}", wire)),
+        };
+        let value = bytes.get(pos..pos.saturating_add(len)).ok_or("truncated protobuf")?;
+        let field = match key & 7 {
+            0 => Field::Varint(varint(bytes, &mut pos)?),
+            1 => Field::Fixed64(u64::from_le_bytes(value.try_into().unwrap())),
+            2 => Field::Bytes(value),
+            _ => Field::Fixed32(u32::from_le_bytes(value.try_into().unwrap())),
+        };
+        pos += len;
+        fields.push((key >> 3, field));
+    }
+    Ok(fields)
+}
+
+fn string(field: &Field) -> Result<String, String> {
+    match field {
+        Field::Bytes(b) => String::from_utf8(b.to_vec()).map_err(|_| "a name is not UTF-8".into()),
+        _ => Err("expected a string".into()),
+    }
+}
+
+// Repeated integers, packed or not
+fn ints(field: &Field, into: &mut Vec<i64>) -> Result<(), String> {
+    match field {
+        Field::Varint(v) => into.push(*v as i64),
+        Field::Bytes(b) => {
+            let mut pos = 0;
+            while pos < b.len() {
+                into.push(varint(b, &mut pos)? as i64);
+            }
+        }
+        _ => return Err("expected integers".into()),
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Tensor {
+    shape: Vec<usize>,
+    data: Vec<f64>,
+}
+
+impl Tensor {
+    fn decode(bytes: &[u8]) -> Result<(String, Tensor), String> {
+        let (mut name, mut dims, mut kind, mut data, mut raw) = (String::new(), Vec::new(), 1, Vec::new(), None);
+        for (number, field) in fields(bytes)? {
+            match (number, &field) {
+                (1, _) => ints(&field, &mut dims)?,
+                (2, Field::Varint(v)) => kind = *v,
+                (4, Field::Fixed32(v)) => data.push(f32::from_bits(*v) as f64),
+                (4, Field::Bytes(b)) => data.extend(b.chunks_exact(4).map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64)),
+                (7, _) => {
+                    let mut values = Vec::new();
+                    ints(&field, &mut values)?;
+                    data.extend(values.into_iter().map(|v| v as f64));
+                }
+                (8, _) => name = string(&field)?,
+                (9, Field::Bytes(b)) => raw = Some(*b),
+                (10, Field::Fixed64(v)) => data.push(f64::from_bits(*v)),
+                (10, Field::Bytes(b)) => data.extend(b.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap()))),
+                _ => {}
+            }
+        }
+        if let Some(raw) = raw {
+            data = match kind {
+                1 => raw.chunks_exact(4).map(|c| f32::from_le_bytes(c.try_into().unwrap()) as f64).collect(),
+                11 => raw.chunks_exact(8).map(|c| f64::from_le_bytes(c.try_into().unwrap())).collect(),
+                7 => raw.chunks_exact(8).map(|c| i64::from_le_bytes(c.try_into().unwrap()) as f64).collect(),
+                _ => return Err(format!("tensor '{}' has unsupported data type {}", name, kind)),
+            };
+        }
+        let shape: Vec<usize> = dims.iter().map(|&d| d.max(0) as usize).collect();
+        if shape.iter().product::<usize>() != data.len() {
+            return Err(format!("tensor '{}' has {} values for shape {:?}", name, data.len(), shape));
+        }
+        Ok((name, Tensor { shape, data }))
+    }
+
+    fn scalar(value: f64) -> Tensor {
+        Tensor { shape: Vec::new(), data: vec![value] }
+    }
+
+    fn map(&self, f: impl Fn(f64) -> f64) -> Tensor {
+        Tensor { shape: self.shape.clone(), data: self.data.iter().map(|&x| f(x)).collect() }
+    }
+
+    // Elementwise with numpy broadcasting
+    fn zip(&self, other: &Tensor, f: impl Fn(f64, f64) -> f64) -> Result<Tensor, String> {
+        let rank = self.shape.len().max(other.shape.len());
+        let padded = |shape: &[usize]| std::iter::repeat_n(1, rank - shape.len()).chain(shape.iter().copied()).collect::<Vec<_>>();
+        let (a, b) = (padded(&self.shape), padded(&other.shape));
+        let shape = a.iter().zip(&b).map(|(&x, &y)| if x == y || y == 1 { Ok(x) } else if x == 1 { Ok(y) } else { Err(format!("shapes {:?} and {:?} don't broadcast", self.shape, other.shape)) }).collect::<Result<Vec<_>, _>>()?;
+        let len = shape.iter().product::<usize>();
+        let index = |dims: &[usize], mut flat: usize| {
+            // The element of a tensor of `dims` at position `flat` of the broadcast shape
+            let (mut at, mut stride) = (0, 1);
+            for axis in (0..rank).rev() {
+                let coordinate = flat % shape[axis];
+                flat /= shape[axis];
+                if dims[axis] != 1 {
+                    at += coordinate * stride;
+                }
+                stride *= dims[axis];
+            }
+            at
+        };
+        let data = (0..len).map(|i| f(self.data[index(&a, i)], other.data[index(&b, i)])).collect();
+        Ok(Tensor { shape, data })
+    }
+
+    // As a matrix of rows × columns
+    fn matrix(&self, transpose: bool) -> Result<(usize, usize, Vec<f64>), String> {
+        let (rows, columns) = match self.shape[..] {
+            [n] => (1, n),
+            [rows, columns] => (rows, columns),
+            _ => return Err(format!("expected a matrix, found shape {:?}", self.shape)),
+        };
+        if !transpose {
+            return Ok((rows, columns, self.data.clone()));
+        }
+        Ok((columns, rows, (0..rows * columns).map(|i| self.data[(i % rows) * columns + i / rows]).collect()))
+    }
+}
+
+fn matmul((n, k, a): (usize, usize, Vec<f64>), (k2, m, b): (usize, usize, Vec<f64>)) -> Result<Tensor, String> {
+    if k != k2 {
+        return Err(format!("matrix product of {}×{} and {}×{}", n, k, k2, m));
+    }
+    let data = (0..n * m).map(|i| (0..k).map(|j| a[(i / m) * k + j] * b[j * m + i % m]).sum()).collect();
+    Ok(Tensor { shape: vec![n, m], data })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Attribute {
+    Float(f64),
+    Int(i64),
+    Tensor(Tensor),
+    Other,
+}
+
+#[derive(Debug)]
+struct Node {
+    op: String,
+    inputs: Vec<String>, // "" for an optional input left out
+    outputs: Vec<String>,
+    attributes: HashMap<String, Attribute>,
+}
+
+impl Node {
+    fn decode(bytes: &[u8]) -> Result<Node, String> {
+        let mut node = Node { op: String::new(), inputs: Vec::new(), outputs: Vec::new(), attributes: HashMap::new() };
+        for (number, field) in fields(bytes)? {
+            match number {
+                1 => node.inputs.push(string(&field)?),
+                2 => node.outputs.push(string(&field)?),
+                4 => node.op = string(&field)?,
+                5 => {
+                    let Field::Bytes(attribute) = field else { return Err("malformed attribute".into()) };
+                    let (mut name, mut value) = (String::new(), Attribute::Other);
+                    for (number, field) in fields(attribute)? {
+                        match (number, field) {
+                            (1, field) => name = string(&field)?,
+                            (2, Field::Fixed32(f)) => value = Attribute::Float(f32::from_bits(f) as f64),
+                            (3, Field::Varint(i)) => value = Attribute::Int(i as i64),
+                            (5, Field::Bytes(t)) => value = Attribute::Tensor(Tensor::decode(t)?.1),
+                            _ => {}
+                        }
+                    }
+                    node.attributes.insert(name, value);
+                }
+                7 => {
+                    let domain = string(&field)?;
+                    if !domain.is_empty() && domain != "ai.onnx" {
+                        return Err(format!("operators of domain '{}' are not supported", domain));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(node)
+    }
+
+    fn float(&self, name: &str, default: f64) -> f64 {
+        match self.attributes.get(name) {
+            Some(Attribute::Float(f)) => *f,
+            _ => default,
+        }
+    }
+
+    fn int(&self, name: &str, default: i64) -> i64 {
+        match self.attributes.get(name) {
+            Some(Attribute::Int(i)) => *i,
+            _ => default,
+        }
+    }
+
+    fn run(&self, inputs: &[Option<&Tensor>]) -> Result<Tensor, String> {
+        let input = |i: usize| inputs.get(i).copied().flatten().ok_or_else(|| format!("{} lacks input {}", self.op, i + 1));
+        Ok(match self.op.as_str() {
+            "Gemm" => {
+                let product = matmul(input(0)?.matrix(self.int("transA", 0) != 0)?, input(1)?.matrix(self.int("transB", 0) != 0)?)?;
+                let alpha = self.float("alpha", 1.0);
+                match inputs.get(2).copied().flatten() {
+                    Some(c) => {
+                        let beta = self.float("beta", 1.0);
+                        product.zip(c, |p, c| alpha * p + beta * c)?
+                    }
+                    None => product.map(|p| alpha * p),
+                }
+            }
+            "MatMul" => {
+                let (a, b) = (input(0)?, input(1)?);
+                let product = matmul(a.matrix(false)?, b.matrix(false)?)?;
+                // A vector operand's axis is dropped again
+                match (a.shape.len(), b.shape.len()) {
+                    (1, _) => Tensor { shape: product.shape[1..].to_vec(), data: product.data },
+                    _ => product,
+                }
+            }
+            "Add" => input(0)?.zip(input(1)?, |a, b| a + b)?,
+            "Sub" => input(0)?.zip(input(1)?, |a, b| a - b)?,
+            "Mul" => input(0)?.zip(input(1)?, |a, b| a * b)?,
+            "Div" => input(0)?.zip(input(1)?, |a, b| a / b)?,
+            "Relu" => input(0)?.map(|x| x.max(0.0)),
+            "LeakyRelu" => {
+                let alpha = self.float("alpha", 0.01);
+                input(0)?.map(|x| if x < 0.0 { alpha * x } else { x })
+            }
+            "Elu" => {
+                let alpha = self.float("alpha", 1.0);
+                input(0)?.map(|x| if x < 0.0 { alpha * x.exp_m1() } else { x })
+            }
+            "Tanh" => input(0)?.map(f64::tanh),
+            "Sigmoid" => input(0)?.map(|x| 1.0 / (1.0 + (-x).exp())),
+            "Softplus" => input(0)?.map(|x| x.exp().ln_1p()),
+            "Neg" => input(0)?.map(|x| -x),
+            "Abs" => input(0)?.map(f64::abs),
+            "Exp" => input(0)?.map(f64::exp),
+            "Identity" => input(0)?.clone(),
+            "Clip" => {
+                // Bounds are inputs since opset 11, attributes before
+                let bound = |i: usize, name: &str, default: f64| inputs.get(i).copied().flatten().map_or(self.float(name, default), |t| t.data[0]);
+                let (min, max) = (bound(1, "min", f64::NEG_INFINITY), bound(2, "max", f64::INFINITY));
+                input(0)?.map(|x| x.max(min).min(max))
+            }
+            "Flatten" => {
+                let x = input(0)?;
+                let axis = self.int("axis", 1);
+                let axis = if axis < 0 { axis + x.shape.len() as i64 } else { axis }.clamp(0, x.shape.len() as i64) as usize;
+                let rows = x.shape[..axis].iter().product::<usize>();
+                Tensor { shape: vec![rows, x.data.len() / rows.max(1)], data: x.data.clone() }
+            }
+            "Reshape" => {
+                let (x, target) = (input(0)?, input(1)?);
+                let mut shape: Vec<i64> = target.data.iter().enumerate().map(|(i, &d)| if d == 0.0 { x.shape.get(i).copied().unwrap_or(1) as i64 } else { d as i64 }).collect();
+                let known: i64 = shape.iter().filter(|&&d| d > 0).product();
+                for d in shape.iter_mut().filter(|d| **d < 0) {
+                    *d = x.data.len() as i64 / known.max(1);
+                }
+                let shape: Vec<usize> = shape.into_iter().map(|d| d as usize).collect();
+                if shape.iter().product::<usize>() != x.data.len() {
+                    return Err(format!("cannot reshape {:?} to {:?}", x.shape, shape));
+                }
+                Tensor { shape, data: x.data.clone() }
+            }
+            "Constant" => match self.attributes.get("value") {
+                Some(Attribute::Tensor(t)) => t.clone(),
+                _ => match self.attributes.get("value_float") {
+                    Some(Attribute::Float(f)) => Tensor::scalar(*f),
+                    _ => return Err("Constant without a tensor value".into()),
+                },
+            },
+            op => return Err(format!("operator {} is not supported", op)),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct NeuralGuidance {
+    file: PathBuf,
+    nodes: Vec<Node>, // In the order they are evaluated
+    initializers: HashMap<String, Tensor>,
+    input: String,
+    input_rank: usize,
+    output: String,
+    failed: AtomicBool, // Whether a failing evaluation was reported already
+}
+
+// Models are told apart by the file they were loaded from
+impl PartialEq for NeuralGuidance {
+    fn eq(&self, other: &Self) -> bool {
+        self.file == other.file
+    }
+}
+
+impl NeuralGuidance {
+    pub fn decode(bytes: &[u8], file: &Path) -> Result<NeuralGuidance, String> {
+        let graph = fields(bytes)?.into_iter().find_map(|(number, field)| match (number, field) {
+            (7, Field::Bytes(graph)) => Some(graph),
+            _ => None,
+        });
+        let graph = graph.ok_or("not an ONNX model (it has no graph)")?;
+        let (mut nodes, mut initializers, mut inputs, mut outputs) = (Vec::new(), HashMap::new(), Vec::new(), Vec::new());
+        for (number, field) in fields(graph)? {
+            let Field::Bytes(bytes) = field else { continue };
+            match number {
+                1 => nodes.push(Node::decode(bytes)?),
+                5 => {
+                    let (name, tensor) = Tensor::decode(bytes)?;
+                    initializers.insert(name, tensor);
+                }
+                11 | 12 => {
+                    // A value's name and the rank of its tensor type
+                    let (mut name, mut rank) = (String::new(), 0);
+                    for (number, field) in fields(bytes)? {
+                        match (number, field) {
+                            (1, field) => name = string(&field)?,
+                            (2, Field::Bytes(ty)) => rank = tensor_rank(ty)?,
+                            _ => {}
+                        }
+                    }
+                    if number == 11 { inputs.push((name, rank)) } else { outputs.push(name) }
+                }
+                _ => {}
+            }
+        }
+        // Older exporters list the initializers among the inputs as well
+        inputs.retain(|(name, _)| !initializers.contains_key(name));
+        let [(input, input_rank)] = &inputs[..] else {
+            return Err(format!("the model must have one input, found {}", inputs.len()));
+        };
+        let output = outputs.first().ok_or("the model has no output")?.clone();
+        let model = NeuralGuidance { file: file.to_path_buf(), nodes, initializers, input: input.clone(), input_rank: *input_rank, output, failed: AtomicBool::new(false) };
+        model.command(&[0.0; 8]).map(|_| model)
+    }
+
+    // The ONNX file at `path`
+    pub fn load(path: &Path) -> Result<Arc<NeuralGuidance>, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        let model = NeuralGuidance::decode(&bytes, path).map_err(|e| format!("'{}': {}", path.display(), e))?;
+        log::debug!("guidance model '{}' loaded: {} nodes", path.display(), model.nodes.len());
+        Ok(Arc::new(model))
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    // The model's output for an observation: one value or two
+    fn evaluate(&self, observation: &[f64; 8]) -> Result<Vec<f64>, String> {
+        let shape = if self.input_rank == 1 { vec![8] } else { vec![1, 8] };
+        let mut values: HashMap<&str, Tensor> = HashMap::new();
+        values.insert(&self.input, Tensor { shape, data: observation.to_vec() });
+        for node in &self.nodes {
+            let inputs: Vec<Option<&Tensor>> = node.inputs.iter().map(|name| values.get(name.as_str()).or_else(|| self.initializers.get(name))).collect();
+            if let Some(missing) = node.inputs.iter().zip(&inputs).find(|(name, value)| !name.is_empty() && value.is_none()) {
+                return Err(format!("{} reads '{}', which nothing computes", node.op, missing.0));
+            }
+            let output = node.run(&inputs).map_err(|e| format!("{}: {}", node.op, e))?;
+            let name = node.outputs.first().ok_or_else(|| format!("{} has no output", node.op))?;
+            values.insert(name, output);
+        }
+        let output = values.remove(self.output.as_str()).ok_or("nothing computes the model's output")?;
+        match output.data.len() {
+            1 | 2 => Ok(output.data),
+            n => Err(format!("the output must hold 1 or 2 values, found {}", n)),
+        }
+    }
+
+    // Lateral acceleration (m/s², positive counter-clockwise) normal to `heading`
+    fn command(&self, observation: &[f64; 8]) -> Result<f64, String> {
+        let heading = Vec2::new(observation[4], observation[5]).normalized().unwrap_or(Vec2::new(1.0, 0.0));
+        let accel = match self.evaluate(observation)?[..] {
+            [lateral] => lateral,
+            [ax, ay] => Vec2::new(ax, ay).dot(heading.perp()),
+            _ => unreachable!(),
+        };
+        match accel.is_finite() {
+            true => Ok(accel),
+            false => Err("the command is not a finite number".into()),
+        }
+    }
+
+    pub fn steering(&self, from: &Interceptor, to: &Target, dt: f64) -> Steering {
+        let heading = from.velocity.normalized().unwrap_or(Vec2::new(1.0, 0.0));
+        match self.command(&observation(from, to)) {
+            Ok(accel) => {
+                log::trace!("neural: commanded acceleration {:.2} m/s²", accel);
+                let direction = (from.velocity + heading.perp() * (accel * dt)).normalized().unwrap_or(heading);
+                Steering { direction, lead_solution: true, phase: GuidancePhase::Direct }
+            }
+            Err(e) => {
+                if !self.failed.swap(true, Ordering::Relaxed) {
+                    log::info!("⚠️ guidance model '{}' failed: {} (flying straight; later failures aren't reported)", self.file.display(), e);
+                }
+                Steering { direction: heading, lead_solution: false, phase: GuidancePhase::Direct }
+            }
+        }
+    }
+}
+
+// Rank of a TypeProto's tensor type; 2 when the model doesn't say
+fn tensor_rank(ty: &[u8]) -> Result<usize, String> {
+    for (number, field) in fields(ty)? {
+        let (1, Field::Bytes(tensor)) = (number, field) else { continue };
+        for (number, field) in fields(tensor)? {
+            match (number, field) {
+                (1, Field::Varint(kind)) if kind != 1 && kind != 11 => return Err(format!("the model's values must be FLOAT or DOUBLE, found type {}", kind)),
+                (2, Field::Bytes(shape)) => return Ok(fields(shape)?.iter().filter(|(number, _)| *number == 1).count()),
+                _ => {}
+            }
+        }
+    }
+    Ok(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Protobuf encoding, enough to assemble models field by field
+
+    fn varint_bytes(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn bytes_field(number: u64, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint_bytes(number << 3 | 2, &mut out);
+        varint_bytes(bytes.len() as u64, &mut out);
+        out.extend(bytes);
+        out
+    }
+
+    fn varint_field(number: u64, value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint_bytes(number << 3, &mut out);
+        varint_bytes(value, &mut out);
+        out
+    }
+
+    // A DOUBLE tensor, its values packed
+    fn tensor(name: &str, dims: &[u64], values: &[f64]) -> Vec<u8> {
+        let dims: Vec<u8> = dims.iter().flat_map(|&d| varint_field(1, d)).collect();
+        let values: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        [dims, varint_field(2, 11), bytes_field(8, name.as_bytes()), bytes_field(10, &values)].concat()
+    }
+
+    fn node(op: &str, inputs: &[&str], output: &str) -> Vec<u8> {
+        let inputs: Vec<u8> = inputs.iter().flat_map(|name| bytes_field(1, name.as_bytes())).collect();
+        [inputs, bytes_field(2, output.as_bytes()), bytes_field(4, op.as_bytes())].concat()
+    }
+
+    // A value of a FLOAT tensor type of rank `rank`
+    fn value(name: &str, rank: usize) -> Vec<u8> {
+        let shape: Vec<u8> = (0..rank).flat_map(|_| bytes_field(1, &[])).collect();
+        let tensor_type = [varint_field(1, 1), bytes_field(2, &shape)].concat();
+        [bytes_field(1, name.as_bytes()), bytes_field(2, &bytes_field(1, &tensor_type))].concat()
+    }
+
+    fn model(nodes: &[Vec<u8>], initializers: &[Vec<u8>], inputs: &[Vec<u8>], output: &str) -> Vec<u8> {
+        let mut graph: Vec<u8> = nodes.iter().flat_map(|n| bytes_field(1, n)).collect();
+        graph.extend(initializers.iter().flat_map(|t| bytes_field(5, t)));
+        graph.extend(inputs.iter().flat_map(|v| bytes_field(11, v)));
+        graph.extend(bytes_field(12, &value(output, 2)));
+        [varint_field(1, 7), bytes_field(7, &graph)].concat()
+    }
+
+    // tanh(obs · weights + 0.1), a one-layer perceptron; WEIGHTS reads rx and ry only
+    fn perceptron(weights: &[f64], dims: &[u64]) -> Vec<u8> {
+        model(
+            &[node("Gemm", &["obs", "W", "B"], "hidden"), node("Tanh", &["hidden"], "command")],
+            &[tensor("W", dims, weights), tensor("B", &[1], &[0.1])],
+            &[value("obs", 2)],
+            "command",
+        )
+    }
+
+    const WEIGHTS: [f64; 8] = [0.5, -0.25, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+    fn decode(bytes: &[u8]) -> Result<NeuralGuidance, String> {
+        NeuralGuidance::decode(bytes, Path::new("test.onnx"))
+    }
+
+    #[test]
+    fn a_tiny_model_is_evaluated() {
+        let model = decode(&perceptron(&WEIGHTS, &[8, 1])).unwrap();
+        let observation = [2.0, 4.0, 1.0, 1.0, 3.0, 0.0, 10.0, 1.0];
+        assert_eq!(model.evaluate(&observation), Ok(vec![(0.5 * 2.0 - 0.25 * 4.0 + 0.1f64).tanh()]));
+        assert_eq!(model.command(&observation), Ok(0.1f64.tanh()));
+        // A rank-1 input gets the observation as a vector
+        let vector = model_with_input_rank(1);
+        assert_eq!(vector.input_rank, 1);
+        assert_eq!(vector.evaluate(&observation), Ok(vec![2.0 * 2.0]));
+    }
+
+    fn model_with_input_rank(rank: usize) -> NeuralGuidance {
+        let bytes = model(&[node("MatMul", &["obs", "W"], "command")], &[tensor("W", &[8, 1], &[2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])], &[value("obs", rank)], "command");
+        decode(&bytes).unwrap()
+    }
+
+    #[test]
+    fn a_two_value_output_is_taken_normal_to_the_velocity() {
+        // The output is the observation's relative position; flying along +x, its y is the command
+        let bytes = model(&[node("MatMul", &["obs", "W"], "command")], &[tensor("W", &[8, 2], &[1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0])], &[value("obs", 2)], "command");
+        let model = decode(&bytes).unwrap();
+        assert_eq!(model.command(&[5.0, -3.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0]), Ok(-3.0));
+    }
+
+    #[test]
+    fn truncated_or_overlong_varints_are_errors() {
+        let good = perceptron(&WEIGHTS, &[8, 1]);
+        assert!(decode(&good).is_ok());
+        // A varint whose last byte still says more follow
+        assert_eq!(varint(&[0x96, 0x81], &mut 0), Err("truncated protobuf".into()));
+        assert_eq!(varint(&[0xFF; 11], &mut 0), Err("malformed protobuf varint".into()));
+        assert_eq!(varint(&[0x96, 0x01], &mut 0), Ok(150));
+        let mut cut = good.clone();
+        cut.push(0x80);
+        assert_eq!(decode(&cut).err(), Some("truncated protobuf".into()));
+        // Cut off anywhere, the model is an error and not a panic
+        for len in 0..good.len() {
+            assert!(decode(&good[..len]).is_err(), "{} bytes", len);
+        }
+    }
+
+    #[test]
+    fn tensors_of_the_wrong_shape_are_rejected() {
+        // Fewer values than the dimensions say
+        let error = decode(&perceptron(&WEIGHTS[..7], &[8, 1])).err().unwrap();
+        assert_eq!(error, "tensor 'W' has 7 values for shape [8, 1]");
+        // Consistent in itself, but not with the observation: caught by the trial evaluation
+        let error = decode(&perceptron(&WEIGHTS[..7], &[7, 1])).err().unwrap();
+        assert!(error.starts_with("Gemm: matrix product of 1×8 and 7×1"), "{}", error);
+        // An output of three values
+        let error = decode(&perceptron(&[0.0; 24], &[8, 3])).err().unwrap();
+        assert_eq!(error, "the output must hold 1 or 2 values, found 3");
+    }
+
+    #[test]
+    fn unsupported_models_are_rejected() {
+        let unknown = model(&[node("Softmax", &["obs"], "command")], &[], &[value("obs", 2)], "command");
+        assert_eq!(decode(&unknown).err(), Some("Softmax: operator Softmax is not supported".into()));
+        let two_inputs = model(&[node("Add", &["obs", "other"], "command")], &[], &[value("obs", 2), value("other", 2)], "command");
+        assert_eq!(decode(&two_inputs).err(), Some("the model must have one input, found 2".into()));
+        assert_eq!(decode(&varint_field(1, 7)).err(), Some("not an ONNX model (it has no graph)".into()));
+    }
+}
@@ -0,0 +1,113 @@
+// Interactive playback control for --live-png and --tui: pacing to a step rate plus pause,
+// single-step and resume from the terminal. The run loop drives the simulation one step at a time with
+// Simulation::step() and calls `wait` in between, which is where pausing blocks.
+//
+// Commands are typed into the terminal and confirmed with Enter:
+//   p  pause / resume (a bare Enter works too)   s  single step (pauses when running)
+//   +  double speed      -  halve speed      q  skip to end
+
+use crate::log;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+enum Command {
+    TogglePause,
+    Step,
+    Faster,
+    Slower,
+    Skip,
+}
+
+pub struct Playback {
+    steps_per_second: f64,
+    paused: bool,
+    skipping: bool,
+    commands: Receiver<Command>,
+    last_step: Instant,
+}
+
+impl Playback {
+    pub fn new(steps_per_second: f64) -> Self {
+        let (tx, rx) = mpsc::channel();
+        // Reader thread is detached; it ends with the process
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lines().map_while(Result::ok) {
+                let command = match line.trim() {
+                    "p" | "P" | "" => Command::TogglePause,
+                    "s" | "S" => Command::Step,
+                    "+" => Command::Faster,
+                    "-" => Command::Slower,
+                    "q" | "Q" => Command::Skip,
+                    _ => continue,
+                };
+                if tx.send(command).is_err() {
+                    break;
+                }
+            }
+        });
+        Playback { steps_per_second, paused: false, skipping: false, commands: rx, last_step: Instant::now() }
+    }
+
+    // Called between two steps: applies pending commands, blocks while paused (until resumed or a
+    // single step is requested) and otherwise paces the run to the current step rate
+    pub fn wait(&mut self, step: usize) {
+        loop {
+            let command = if self.paused && !self.skipping {
+                match self.commands.recv() {
+                    Ok(c) => c,
+                    // No terminal to resume from
+                    Err(_) => {
+                        self.paused = false;
+                        break;
+                    }
+                }
+            } else {
+                match self.commands.try_recv() {
+                    Ok(c) => c,
+                    Err(TryRecvError::Empty | TryRecvError::Disconnected) => break,
+                }
+            };
+            match command {
+                Command::TogglePause => {
+                    self.paused = !self.paused;
+                    log::info!("{}", if self.paused { format!("⏸️ Paused at step {}", step) } else { String::from("▶️ Resumed") });
+                }
+                Command::Step if self.paused => {
+                    log::info!("⏭️ Step {}", step + 1);
+                    self.last_step = Instant::now();
+                    return;
+                }
+                Command::Step => {
+                    self.paused = true;
+                    log::info!("⏸️ Paused at step {}", step);
+                }
+                Command::Faster => {
+                    self.steps_per_second *= 2.0;
+                    log::info!("⏩ {} steps/s", self.steps_per_second);
+                }
+                Command::Slower => {
+                    self.steps_per_second /= 2.0;
+                    log::info!("⏪ {} steps/s", self.steps_per_second);
+                }
+                Command::Skip => {
+                    self.skipping = true;
+                    self.paused = false;
+                }
+            }
+            self.last_step = Instant::now();
+        }
+
+        if !self.skipping {
+            let step_duration = Duration::from_secs_f64(1.0 / self.steps_per_second);
+            if let Some(remaining) = step_duration.checked_sub(self.last_step.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+        self.last_step = Instant::now();
+    }
+
+    // Skipping applies to the current run only
+    pub fn end_run(&mut self) {
+        self.skipping = false;
+    }
+}
@@ -0,0 +1,1781 @@
+use crate::error::SimError;
+use crate::json::{FromJson, Json};
+use crate::bounds::{end_radius, headings_deg, PlotBounds};
+use crate::engagement;
+use crate::log;
+use crate::reachability::ReachableSet;
+use crate::stats::{summarize, Histogram};
+use crate::sweep::Heatmap;
+use crate::{GuidanceLaw, SimEvent, SimulationResult};
+use plotters::coord::Shift;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use plotters::prelude::*;
+
+// Height of each additional diagnostic panel in saved images, at the nominal 96 dpi
+const PANEL_HEIGHT: u32 = 300;
+const NOMINAL_DPI: u32 = 96;
+
+// Diagnostic panels that can be stacked below the trajectory chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Distance, // Interceptor–target separation vs step
+    Speed,    // Speed magnitude of both vehicles vs step
+    Heading,  // Flight path angle of both vehicles vs step
+    Los,      // Line-of-sight angle interceptor → target vs step
+    LosRate,  // Line-of-sight rate vs step, the key signal for proportional navigation
+    Energy,   // Kinetic energy per unit mass of both vehicles vs step
+    Phase,    // Range rate vs range, the engagement's phase plane
+    Timeline, // The event log over time, one lane per kind of event
+}
+
+impl Panel {
+    pub const NAMES: &'static str = "distance, speed, heading, los, los-rate, energy, phase, timeline";
+
+    pub fn parse(s: &str) -> Option<Panel> {
+        match s {
+            "distance" => Some(Panel::Distance),
+            "speed" => Some(Panel::Speed),
+            "heading" => Some(Panel::Heading),
+            "los" => Some(Panel::Los),
+            "los-rate" => Some(Panel::LosRate),
+            "energy" => Some(Panel::Energy),
+            "phase" => Some(Panel::Phase),
+            "timeline" => Some(Panel::Timeline),
+            _ => None,
+        }
+    }
+}
+
+// Output image format / plotters backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<OutputFormat> {
+        match s.to_ascii_lowercase().as_str() {
+            "png" => Some(OutputFormat::Png),
+            "svg" => Some(OutputFormat::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Svg => "svg",
+        }
+    }
+}
+
+// Colors, line widths, marker sizes and fonts of all charts
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotStyle {
+    pub background: RGBColor,
+    pub foreground: RGBColor, // Text, axes and grid lines
+    pub target: RGBColor,
+    pub interceptor: RGBColor,
+    pub highlight: RGBColor,  // End point marker, collision threshold ring, LOS series
+    pub separation: RGBColor, // Distance panel series
+    pub line_width: u32,
+    pub marker_size: u32,
+    pub end_marker_size: u32,
+    pub font: String, // Font family, e.g. "sans-serif" or "serif"
+    pub scale: f64,   // Output pixels per nominal pixel, derived from PlotOptions::dpi
+}
+
+impl Default for PlotStyle {
+    fn default() -> Self {
+        PlotStyle::light()
+    }
+}
+
+impl PlotStyle {
+    pub const THEMES: &'static str = "light, dark, colorblind";
+
+    // The original look: primary colors on white
+    pub fn light() -> Self {
+        PlotStyle {
+            background: WHITE,
+            foreground: BLACK,
+            target: RED,
+            interceptor: GREEN,
+            highlight: BLUE,
+            separation: MAGENTA,
+            line_width: 2,
+            marker_size: 3,
+            end_marker_size: 25,
+            font: "sans-serif".into(),
+            scale: 1.0,
+        }
+    }
+
+    // Muted colors on a dark grey background for screens and slides
+    pub fn dark() -> Self {
+        PlotStyle {
+            background: RGBColor(0x1e, 0x1e, 0x1e),
+            foreground: RGBColor(0xdc, 0xdc, 0xdc),
+            target: RGBColor(0xff, 0x6b, 0x6b),
+            interceptor: RGBColor(0x4c, 0xd9, 0x8c),
+            highlight: RGBColor(0x6c, 0xb4, 0xff),
+            separation: RGBColor(0xe0, 0x7b, 0xe0),
+            ..PlotStyle::light()
+        }
+    }
+
+    // Okabe–Ito palette, distinguishable with the common forms of color blindness
+    pub fn colorblind() -> Self {
+        PlotStyle {
+            target: RGBColor(0xd5, 0x5e, 0x00),
+            interceptor: RGBColor(0x00, 0x72, 0xb2),
+            highlight: RGBColor(0xe6, 0x9f, 0x00),
+            separation: RGBColor(0xcc, 0x79, 0xa7),
+            ..PlotStyle::light()
+        }
+    }
+
+    pub fn theme(name: &str) -> Option<PlotStyle> {
+        match name {
+            "light" => Some(PlotStyle::light()),
+            "dark" => Some(PlotStyle::dark()),
+            "colorblind" => Some(PlotStyle::colorblind()),
+            _ => None,
+        }
+    }
+
+    // All sizes in the style and the chart layout are nominal pixels, scaled here for high-DPI output
+    pub fn px(&self, nominal: u32) -> u32 {
+        (nominal as f64 * self.scale).round() as u32
+    }
+
+    pub fn text(&self, size: u32) -> TextStyle<'_> {
+        (self.font.as_str(), self.px(size)).into_font().color(&self.foreground)
+    }
+
+    pub fn line(&self, color: RGBColor) -> ShapeStyle {
+        ShapeStyle::from(&color).stroke_width(self.px(self.line_width))
+    }
+}
+
+// "#rrggbb" hex notation
+fn parse_color(s: &str) -> Option<RGBColor> {
+    let hex = s.strip_prefix('#').filter(|h| h.len() == 6 && h.is_ascii())?;
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(RGBColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+// { "theme": "dark", "target": "#ff8800", "line_width": 3, ... }: the theme (default light)
+// provides every value that is not given explicitly
+impl FromJson for PlotStyle {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let mut style = match json.get("theme") {
+            None => PlotStyle::default(),
+            Some(theme) => theme
+                .as_str()
+                .and_then(PlotStyle::theme)
+                .ok_or_else(|| format!("field 'theme' must be one of: {}", PlotStyle::THEMES))?,
+        };
+        for (key, color) in [
+            ("background", &mut style.background),
+            ("foreground", &mut style.foreground),
+            ("target", &mut style.target),
+            ("interceptor", &mut style.interceptor),
+            ("highlight", &mut style.highlight),
+            ("separation", &mut style.separation),
+        ] {
+            if let Some(value) = json.get(key) {
+                *color = value.as_str().and_then(parse_color).ok_or(format!("field '{}' must be a color like \"#1f77b4\"", key))?;
+            }
+        }
+        for (key, size) in [
+            ("line_width", &mut style.line_width),
+            ("marker_size", &mut style.marker_size),
+            ("end_marker_size", &mut style.end_marker_size),
+        ] {
+            if let Some(value) = json.get(key) {
+                *size = value.as_usize().ok_or(format!("field '{}' must be a non-negative integer", key))? as u32;
+            }
+        }
+        if let Some(font) = json.get("font") {
+            style.font = font.as_str().ok_or("field 'font' must be a string")?.into();
+        }
+        Ok(style)
+    }
+}
+
+// Presentation settings shared by all plot outputs
+#[derive(Debug, Clone)]
+pub struct PlotOptions {
+    pub format: OutputFormat,
+    pub legend: Option<SeriesLabelPosition>, // None hides the legend
+    pub panels: Vec<Panel>,
+    pub zoom: Option<f64>, // Half-width in meters of the terminal-phase chart around the end point
+    pub equal_aspect: bool, // Same meters per pixel on both axes of the trajectory charts
+    pub style: PlotStyle,
+    pub size: (u32, u32), // Trajectory chart size in nominal pixels; panels add to the height
+    pub dpi: u32,         // 96 renders at nominal size, 192 doubles every pixel measure
+    pub annotate_every: Option<usize>, // Label every Nth trajectory point with its step number
+    pub arrows_every: Option<usize>,   // Velocity direction arrowhead on every Nth trajectory point
+    pub marker_every: usize,           // Draw a position marker on every Nth step only
+    pub annotate_events: bool,         // Mark and label the entries of the event log
+    pub enabled: bool,                 // False with --no-plot: nothing is drawn, results are still saved
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        // Lower right is usually empty: the target flies high and the interceptor starts low left
+        PlotOptions {
+            format: OutputFormat::default(),
+            legend: Some(SeriesLabelPosition::LowerRight),
+            panels: Vec::new(),
+            zoom: None,
+            equal_aspect: false,
+            style: PlotStyle::default(),
+            size: (1400, 900),
+            dpi: NOMINAL_DPI,
+            annotate_every: None,
+            arrows_every: None,
+            marker_every: 1,
+            annotate_events: false,
+            enabled: true,
+        }
+    }
+}
+
+impl PlotOptions {
+    // Picks up --format png|svg, --legend POSITION|none, --panels a,b,.., --zoom METERS, --equal-aspect,
+    // --size WxH, --dpi N, --annotate N, --arrows N, --marker-every N, --annotate-events and --no-plot
+    // from the command line
+    pub fn from_args(args: &[String]) -> Result<PlotOptions, String> {
+        let mut options = PlotOptions {
+            equal_aspect: args.iter().any(|a| a == "--equal-aspect"),
+            annotate_events: args.iter().any(|a| a == "--annotate-events"),
+            enabled: !args.iter().any(|a| a == "--no-plot"),
+            ..PlotOptions::default()
+        };
+        for pair in args.windows(2) {
+            match pair[0].as_str() {
+                "--format" => {
+                    options.format = OutputFormat::parse(&pair[1]).ok_or("--format must be png or svg")?;
+                }
+                "--legend" => {
+                    options.legend = match pair[1].as_str() {
+                        "none" => None,
+                        other => Some(parse_legend_position(other).ok_or(
+                            "--legend must be none or [upper-|lower-]left|middle|right, e.g. lower-right",
+                        )?),
+                    };
+                }
+                "--panels" => {
+                    options.panels = pair[1]
+                        .split(',')
+                        .map(|p| Panel::parse(p.trim()).ok_or_else(|| format!("unknown panel '{}' (available: {})", p, Panel::NAMES)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "--zoom" => {
+                    let radius = pair[1].parse::<f64>().ok().filter(|r| *r > 0.0);
+                    options.zoom = Some(radius.ok_or("--zoom must be a positive distance in meters")?);
+                }
+                "--size" => {
+                    options.size = parse_size(&pair[1]).ok_or("--size must be WIDTHxHEIGHT in pixels, e.g. 1920x1080")?;
+                }
+                "--dpi" => {
+                    options.dpi = pair[1].parse().ok().filter(|d| *d > 0).ok_or("--dpi must be a positive integer")?;
+                }
+                "--annotate" => {
+                    let every = pair[1].parse().ok().filter(|n| *n > 0);
+                    options.annotate_every = Some(every.ok_or("--annotate must be a positive number of steps")?);
+                }
+                "--arrows" => {
+                    let every = pair[1].parse().ok().filter(|n| *n > 0);
+                    options.arrows_every = Some(every.ok_or("--arrows must be a positive number of steps")?);
+                }
+                "--marker-every" => {
+                    let every = pair[1].parse().ok().filter(|n| *n > 0);
+                    options.marker_every = every.ok_or("--marker-every must be a positive number of steps")?;
+                }
+                _ => {}
+            }
+        }
+        Ok(options)
+    }
+
+    // Size of the whole image in output pixels, including the panels
+    pub fn image_size(&self) -> (u32, u32) {
+        let style = self.scaled_style();
+        let (width, height) = self.size;
+        (style.px(width), style.px(height + PANEL_HEIGHT * self.panels.len() as u32))
+    }
+
+    pub fn scaled_style(&self) -> PlotStyle {
+        PlotStyle { scale: self.dpi as f64 / NOMINAL_DPI as f64, ..self.style.clone() }
+    }
+}
+
+// Parse a "WIDTHxHEIGHT" resolution string
+pub fn parse_size(s: &str) -> Option<(u32, u32)> {
+    let (w, h) = s.split_once('x')?;
+    let (w, h) = (w.parse().ok()?, h.parse().ok()?);
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+fn parse_legend_position(s: &str) -> Option<SeriesLabelPosition> {
+    use SeriesLabelPosition::*;
+    Some(match s {
+        "upper-left" => UpperLeft,
+        "upper-middle" => UpperMiddle,
+        "upper-right" => UpperRight,
+        "middle-left" => MiddleLeft,
+        "middle" | "middle-middle" => MiddleMiddle,
+        "middle-right" => MiddleRight,
+        "lower-left" => LowerLeft,
+        "lower-middle" => LowerMiddle,
+        "lower-right" => LowerRight,
+        _ => return None,
+    })
+}
+
+pub fn visualize_simulation(
+    sim: &SimulationResult,
+    out_path: &str,
+    options: &PlotOptions,
+) -> Result<(), SimError> {
+    // Extra panels extend the image downwards instead of squeezing the trajectory chart
+    let size = options.image_size();
+    if let Some(parent) = std::path::Path::new(out_path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(SimError::io(format!("cannot create directory '{}'", parent.display())))?;
+    }
+    let bounds = PlotBounds::from_result(sim);
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_engagement(&root, sim, bounds, options)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_engagement(&root, sim, bounds, options)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Graph saved as '{}'", out_path);
+
+    Ok(())
+}
+
+// All runs of a batch on one chart: faint trajectories, one marker per collision point
+pub fn visualize_overlay(
+    sims: &[SimulationResult],
+    out_path: &str,
+    options: &PlotOptions,
+) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(options.size.1));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_overlay(&root, sims, options, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_overlay(&root, sims, options, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Overlay saved as '{}'", out_path);
+    Ok(())
+}
+
+fn draw_overlay<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sims: &[SimulationResult],
+    options: &PlotOptions,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let (max_x, max_y) = sims
+        .iter()
+        .map(PlotBounds::from_result)
+        .fold((0.0, 0.0), |(x, y): (f64, f64), b| (x.max(b.max_x), y.max(b.max_y)));
+    let intercepts: Vec<(f64, f64)> = sims.iter().filter_map(|sim| sim.collision_point).collect();
+
+    let caption = format!("Monte Carlo overlay ({} runs, {} intercepts)", sims.len(), intercepts.len());
+    let mut ranges = ((0.0, max_x), (0.0, max_y));
+    if options.equal_aspect {
+        let pixels = plot_area_pixels(root, &caption, &style.text(30), style.px(15), style.px(40), style.px(50))?;
+        ranges = equalize_aspect(ranges, pixels, true);
+    }
+    let ((x_min, x_max), (y_min, y_max)) = ranges;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(40))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc("Height (m)")
+        .x_desc("Distance (m)")
+        .draw()?;
+
+    // More runs means fainter lines, so dense regions show where most trajectories go
+    let alpha = (5.0 / sims.len() as f64).clamp(0.08, 0.8);
+    for (k, sim) in sims.iter().enumerate() {
+        for (positions, color, label) in [
+            (&sim.target_positions, style.target, "Target trajectories"),
+            (&sim.interceptor_positions, style.interceptor, "Interceptor trajectories"),
+        ] {
+            let series = chart.draw_series(LineSeries::new(
+                positions.iter().copied(),
+                ShapeStyle::from(&color.mix(alpha)).stroke_width(style.px(style.line_width)),
+            ))?;
+            // One legend entry per vehicle, not per run
+            if k == 0 {
+                let line = style.line(color);
+                series.label(label).legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+            }
+        }
+    }
+
+    let cross = style.px(style.marker_size + 3);
+    let highlight = ShapeStyle::from(&style.highlight).stroke_width(style.px(2));
+    chart
+        .draw_series(intercepts.iter().map(|&p| Cross::new(p, cross, highlight)))?
+        .label("Collision points")
+        .legend(move |(x, y)| Cross::new((x + 10, y), 5, highlight));
+
+    if let Some(position) = &options.legend {
+        chart
+            .configure_series_labels()
+            .position(position.clone())
+            .background_style(style.background.mix(0.8))
+            .border_style(style.foreground)
+            .label_font(style.text(18))
+            .draw()?;
+    }
+    Ok(())
+}
+
+// The interceptor's reachable sets (see reachability.rs) over both paths, each set in its own
+// color with the target's predicted position at its time
+pub fn visualize_reachability(sim: &SimulationResult, sets: &[ReachableSet], out_path: &str, options: &PlotOptions) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(options.size.1));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_reachability(&root, sim, sets, options, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_reachability(&root, sim, sets, options, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Reachability plot saved as '{}'", out_path);
+    Ok(())
+}
+
+fn draw_reachability<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    sets: &[ReachableSet],
+    options: &PlotOptions,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let points = sim.interceptor_positions.iter().chain(&sim.target_positions).chain(sets.iter().flat_map(|set| set.boundary.iter().chain([&set.predicted_target])));
+    let (mut x_range, mut y_range) = ((f64::INFINITY, f64::NEG_INFINITY), (f64::INFINITY, f64::NEG_INFINITY));
+    for &(x, y) in points {
+        x_range = (x_range.0.min(x), x_range.1.max(x));
+        y_range = (y_range.0.min(y), y_range.1.max(y));
+    }
+    let pad = 0.05 * (x_range.1 - x_range.0).max(y_range.1 - y_range.0).max(1.0);
+    let caption = format!("Interceptor reachable sets ({})", sim.outcome.as_str());
+    // A turning circle only looks like one with equal scales
+    let pixels = plot_area_pixels(root, &caption, &style.text(30), style.px(15), style.px(40), style.px(50))?;
+    let ((x_min, x_max), (y_min, y_max)) = equalize_aspect(((x_range.0 - pad, x_range.1 + pad), (y_range.0 - pad, y_range.1 + pad)), pixels, false);
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(40))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc("Height (m)")
+        .x_desc("Distance (m)")
+        .draw()?;
+
+    // The latest and largest set first, so the earlier ones stay visible on top of it
+    for (k, set) in sets.iter().enumerate().rev() {
+        let color = ViridisRGB.get_color(k as f32 / sets.len().max(2) as f32 * 0.9);
+        let outline = ShapeStyle::from(&color).stroke_width(style.px(2));
+        chart.draw_series(std::iter::once(Polygon::new(set.boundary.clone(), color.mix(0.25).filled())))?;
+        let mut closed = set.boundary.clone();
+        closed.extend(set.boundary.first());
+        chart
+            .draw_series(std::iter::once(PathElement::new(closed, outline)))?
+            .label(format!("Reach after {:.1} s", set.time))
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.mix(0.5).filled()));
+        let marker = style.px(style.marker_size + 3);
+        chart.draw_series(std::iter::once(Cross::new(set.predicted_target, marker, outline)))?;
+        let font = style.text(14).pos(Pos::new(HPos::Left, VPos::Bottom));
+        chart.draw_series(std::iter::once(Text::new(format!(" {:.1} s", set.time), set.predicted_target, font)))?;
+    }
+
+    for (positions, color, label) in [(&sim.target_positions, style.target, "Target"), (&sim.interceptor_positions, style.interceptor, "Interceptor")] {
+        let line = style.line(color);
+        chart
+            .draw_series(LineSeries::new(positions.iter().copied(), line))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+    }
+    let dashed = style.foreground.mix(0.6).stroke_width(style.px(1));
+    if let (Some(&start), Some(last)) = (sim.target_positions.first(), sets.last().map(|set| set.predicted_target)) {
+        chart
+            .draw_series(std::iter::once(PathElement::new(vec![start, last], dashed)))?
+            .label("Target prediction")
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], dashed));
+    }
+
+    if let Some(position) = &options.legend {
+        chart
+            .configure_series_labels()
+            .position(position.clone())
+            .background_style(style.background.mix(0.8))
+            .border_style(style.foreground)
+            .label_font(style.text(18))
+            .draw()?;
+    }
+    Ok(())
+}
+
+// One engagement flown by several guidance laws: the target's path once, every law's interceptor in
+// its own color with a cross where it ended
+pub fn visualize_comparison(
+    sims: &[SimulationResult],
+    out_path: &str,
+    options: &PlotOptions,
+) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(options.size.1));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_comparison(&root, sims, options, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_comparison(&root, sims, options, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Comparison saved as '{}'", out_path);
+    Ok(())
+}
+
+// Interceptor colors of a comparison: the style's own colors first, then Okabe–Ito tones
+fn comparison_color(style: &PlotStyle, k: usize) -> RGBColor {
+    let colors = [
+        style.interceptor,
+        style.highlight,
+        style.separation,
+        RGBColor(0xe6, 0x9f, 0x00),
+        RGBColor(0x00, 0x9e, 0x73),
+        RGBColor(0x56, 0xb4, 0xe9),
+    ];
+    colors[k % colors.len()]
+}
+
+fn draw_comparison<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sims: &[SimulationResult],
+    options: &PlotOptions,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let (max_x, max_y) = sims
+        .iter()
+        .map(PlotBounds::from_result)
+        .fold((0.0, 0.0), |(x, y): (f64, f64), b| (x.max(b.max_x), y.max(b.max_y)));
+
+    let caption = format!("Guidance law comparison ({} laws)", sims.len());
+    let mut ranges = ((0.0, max_x), (0.0, max_y));
+    if options.equal_aspect {
+        let pixels = plot_area_pixels(root, &caption, &style.text(30), style.px(15), style.px(40), style.px(50))?;
+        ranges = equalize_aspect(ranges, pixels, true);
+    }
+    let ((x_min, x_max), (y_min, y_max)) = ranges;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(40))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc("Height (m)")
+        .x_desc("Distance (m)")
+        .draw()?;
+
+    // Every law sees the same seeded target, so the longest run's path covers all the others
+    if let Some(longest) = sims.iter().max_by_key(|sim| sim.steps()) {
+        let line = style.line(style.target);
+        chart
+            .draw_series(LineSeries::new(longest.target_positions.iter().copied(), line))?
+            .label("Target")
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+    }
+
+    let cross = style.px(style.marker_size + 3);
+    for (k, sim) in sims.iter().enumerate() {
+        let line = style.line(comparison_color(style, k));
+        chart
+            .draw_series(LineSeries::new(sim.interceptor_positions.iter().copied(), line))?
+            .label(format!("{} ({})", sim.config.guidance.as_str(), sim.outcome.as_str()))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+        if let Some(&end) = sim.interceptor_positions.last() {
+            chart.draw_series(std::iter::once(Cross::new(end, cross, line)))?;
+        }
+    }
+
+    if let Some(position) = &options.legend {
+        chart
+            .configure_series_labels()
+            .position(position.clone())
+            .background_style(style.background.mix(0.8))
+            .border_style(style.foreground)
+            .label_font(style.text(18))
+            .draw()?;
+    }
+    Ok(())
+}
+
+// Distributions over a batch: miss distance of every run, time to intercept and collision angle of the hits
+pub fn visualize_histograms(
+    sims: &[SimulationResult],
+    out_path: &str,
+    options: &PlotOptions,
+) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(3 * PANEL_HEIGHT));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_histograms(&root, sims, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_histograms(&root, sims, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Histograms saved as '{}'", out_path);
+    Ok(())
+}
+
+fn draw_histograms<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sims: &[SimulationResult],
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let hits: Vec<&SimulationResult> = sims.iter().filter(|sim| sim.collision_point.is_some()).collect();
+    let misses: Vec<f64> = sims.iter().map(|sim| sim.miss_distance).collect();
+    let times: Vec<f64> = hits.iter().map(|sim| sim.steps() as f64).collect();
+    let angles: Vec<f64> = hits.iter().filter_map(|sim| sim.collision_angle).collect();
+
+    let areas = root.split_evenly((3, 1));
+    draw_histogram(&areas[0], "Miss distance, all runs", "Closest approach (m)", &misses, style.separation, style)?;
+    draw_histogram(&areas[1], "Time to intercept", "Steps", &times, style.interceptor, style)?;
+    draw_histogram(&areas[2], "Collision angle", "Angle between velocities (°)", &angles, style.target, style)?;
+    Ok(())
+}
+
+fn draw_histogram<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    title: &str,
+    x_desc: &str,
+    values: &[f64],
+    color: RGBColor,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let Some(summary) = summarize(values) else {
+        area.titled(&format!("{}: no data", title), style.text(20))?;
+        return Ok(());
+    };
+    let caption = format!(
+        "{} (n = {}, mean {:.2}, σ {:.2}, median {:.2}, range {:.2}–{:.2})",
+        title, summary.count, summary.mean, summary.std_dev, summary.median, summary.min, summary.max
+    );
+    let histogram = Histogram::new(values, Histogram::auto_bins(summary.count));
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(caption, style.text(20))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(35))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(histogram.min..histogram.max(), 0.0..max_count as f64 * 1.1)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .x_desc(x_desc)
+        .y_desc("Runs")
+        .draw()?;
+
+    chart.draw_series(histogram.counts.iter().enumerate().map(|(k, &count)| {
+        let (lo, hi) = histogram.bin_range(k);
+        let mut bar = Rectangle::new([(lo, 0.0), (hi, count as f64)], color.mix(0.8).filled());
+        bar.set_margin(0, 0, style.px(1), style.px(1));
+        bar
+    }))?;
+    Ok(())
+}
+
+pub fn visualize_heatmap(heatmap: &Heatmap, out_path: &str, options: &PlotOptions) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(options.size.1));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_heatmap(&root, heatmap, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_heatmap(&root, heatmap, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Heatmap saved as '{}'", out_path);
+    Ok(())
+}
+
+// Half the spacing of neighbouring grid values, so cells tile the axis without gaps
+fn cell_edges(values: &[f64]) -> Vec<(f64, f64)> {
+    let half = |k: usize| match values.len() {
+        0 | 1 => 0.5,
+        _ => (values[k.min(values.len() - 2) + 1] - values[k.min(values.len() - 2)]).abs() / 2.0,
+    };
+    values.iter().enumerate().map(|(k, v)| (v - half(k), v + half(k))).collect()
+}
+
+fn draw_heatmap<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    heatmap: &Heatmap,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let (width, _) = root.dim_in_pixel();
+    let (main, color_bar) = root.split_horizontally(width - style.px(110));
+    let (x_cells, y_cells) = (cell_edges(&heatmap.xs), cell_edges(&heatmap.ys));
+    let x_range = x_cells.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c.0), hi.max(c.1)));
+    let y_range = y_cells.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), c| (lo.min(c.0), hi.max(c.1)));
+
+    let mut chart = ChartBuilder::on(&main)
+        .caption(&heatmap.caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(45))
+        .y_label_area_size(style.px(60))
+        .build_cartesian_2d(x_range.0..x_range.1, y_range.0..y_range.1)?;
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .x_desc(heatmap.x_desc)
+        .y_desc(heatmap.y_desc)
+        .draw()?;
+
+    // Cell labels only while they still fit
+    let labelled = heatmap.xs.len() <= 20 && heatmap.ys.len() <= 20;
+    for (row, &(y0, y1)) in y_cells.iter().enumerate() {
+        for (col, &(x0, x1)) in x_cells.iter().enumerate() {
+            let value = heatmap.values[row][col];
+            let fill = ViridisRGB.get_color(value.clamp(0.0, 1.0) as f32);
+            chart.draw_series(std::iter::once(Rectangle::new([(x0, y0), (x1, y1)], fill.filled())))?;
+            if labelled {
+                // Dark text on the bright end of the color map, light text on the dark end
+                let text_color = if value > 0.6 { BLACK } else { WHITE };
+                let label = (style.font.as_str(), style.px(13)).into_font().color(&text_color).pos(Pos::new(HPos::Center, VPos::Center));
+                chart.draw_series(std::iter::once(Text::new(format!("{:.0}%", value * 100.0), ((x0 + x1) / 2.0, (y0 + y1) / 2.0), label)))?;
+            }
+        }
+    }
+
+    // Vertical color scale from 0 to 100 %
+    let mut bar = ChartBuilder::on(&color_bar)
+        .margin_top(style.px(60))
+        .margin_bottom(style.px(60))
+        .margin_right(style.px(15))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(0.0..1.0, 0.0..100.0)?;
+    bar.configure_mesh()
+        .disable_mesh()
+        .disable_x_axis()
+        .axis_style(style.foreground)
+        .y_label_style(style.text(13))
+        .y_label_formatter(&|v| format!("{:.0}%", v))
+        .draw()?;
+    bar.draw_series((0..100).map(|k| {
+        let fill = ViridisRGB.get_color(k as f32 / 99.0);
+        Rectangle::new([(0.0, k as f64), (1.0, (k + 1) as f64)], fill.filled())
+    }))?;
+    Ok(())
+}
+
+// A grid of values in [0, 1] as a filled contour plot: `levels` bands of equal width, their
+// boundaries as contour lines with the 50% line drawn bold, and an optional labelled marker
+pub fn visualize_contour(heatmap: &Heatmap, levels: usize, marker: Option<((f64, f64), &str)>, out_path: &str, options: &PlotOptions) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    let size = (style.px(options.size.0), style.px(options.size.1));
+    match options.format {
+        OutputFormat::Png => {
+            let root = BitMapBackend::new(out_path, size).into_drawing_area();
+            draw_contour(&root, heatmap, levels, marker, style)?;
+            root.present()?;
+        }
+        OutputFormat::Svg => {
+            let root = SVGBackend::new(out_path, size).into_drawing_area();
+            draw_contour(&root, heatmap, levels, marker, style)?;
+            root.present()?;
+        }
+    }
+    log::info!("✅ Contour plot saved as '{}'", out_path);
+    Ok(())
+}
+
+// Bilinear interpolation of the grid at (x, y), which lies within it
+fn grid_value(heatmap: &Heatmap, x: f64, y: f64) -> f64 {
+    let locate = |values: &[f64], v: f64| {
+        let k = values.windows(2).position(|pair| v <= pair[1]).unwrap_or(values.len() - 2);
+        (k, ((v - values[k]) / (values[k + 1] - values[k])).clamp(0.0, 1.0))
+    };
+    let ((col, fx), (row, fy)) = (locate(&heatmap.xs, x), locate(&heatmap.ys, y));
+    let v = &heatmap.values;
+    let bottom = v[row][col] * (1.0 - fx) + v[row][col + 1] * fx;
+    let top = v[row + 1][col] * (1.0 - fx) + v[row + 1][col + 1] * fx;
+    bottom * (1.0 - fy) + top * fy
+}
+
+// Segments of the contour line at `level`, by marching squares over the grid cells
+fn contour_segments(heatmap: &Heatmap, level: f64) -> Vec<[(f64, f64); 2]> {
+    let (xs, ys, v) = (&heatmap.xs, &heatmap.ys, &heatmap.values);
+    let mut segments = Vec::new();
+    for row in 0..ys.len() - 1 {
+        for col in 0..xs.len() - 1 {
+            // Corners counterclockwise from the bottom left, and the edges from each to the next
+            let corners = [(col, row), (col + 1, row), (col + 1, row + 1), (col, row + 1)];
+            let value = |k: usize| v[corners[k].1][corners[k].0];
+            let point = |k: usize| (xs[corners[k].0], ys[corners[k].1]);
+            let crossing = |k: usize| {
+                let (a, b) = (value(k), value((k + 1) % 4));
+                ((a >= level) != (b >= level)).then(|| {
+                    let t = (level - a) / (b - a);
+                    let (pa, pb) = (point(k), point((k + 1) % 4));
+                    (pa.0 + (pb.0 - pa.0) * t, pa.1 + (pb.1 - pa.1) * t)
+                })
+            };
+            let edges: Vec<Option<(f64, f64)>> = (0..4).map(crossing).collect();
+            let crossed: Vec<(f64, f64)> = edges.iter().flatten().copied().collect();
+            match crossed[..] {
+                [a, b] => segments.push([a, b]),
+                // A saddle: the centre decides which corners the line cuts off
+                [_, _, _, _] => {
+                    let centre = (0..4).map(value).sum::<f64>() / 4.0;
+                    let e = |k: usize| edges[k].unwrap();
+                    match (centre >= level) == (value(0) >= level) {
+                        true => segments.extend([[e(0), e(1)], [e(2), e(3)]]),
+                        false => segments.extend([[e(3), e(0)], [e(1), e(2)]]),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    segments
+}
+
+fn draw_contour<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    heatmap: &Heatmap,
+    levels: usize,
+    marker: Option<((f64, f64), &str)>,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let (width, _) = root.dim_in_pixel();
+    let (main, color_bar) = root.split_horizontally(width - style.px(110));
+    let (xs, ys) = (&heatmap.xs, &heatmap.ys);
+    let (x_range, y_range) = ((xs[0], xs[xs.len() - 1]), (ys[0], ys[ys.len() - 1]));
+    let band_color = |band: usize| ViridisRGB.get_color((band as f64 + 0.5) as f32 / levels as f32);
+
+    let mut chart = ChartBuilder::on(&main)
+        .caption(&heatmap.caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(45))
+        .y_label_area_size(style.px(60))
+        .build_cartesian_2d(x_range.0..x_range.1, y_range.0..y_range.1)?;
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .x_desc(heatmap.x_desc)
+        .y_desc(heatmap.y_desc)
+        .draw()?;
+
+    // The bands on a fine raster of the interpolated grid, neighbouring samples of a row in the
+    // same band merged into one rectangle
+    let resolution = 200;
+    let (dx, dy) = ((x_range.1 - x_range.0) / resolution as f64, (y_range.1 - y_range.0) / resolution as f64);
+    let band = |x: f64, y: f64| ((grid_value(heatmap, x, y) * levels as f64) as usize).min(levels - 1);
+    for j in 0..resolution {
+        let y0 = y_range.0 + j as f64 * dy;
+        let mut start = 0;
+        for k in 1..=resolution {
+            let current = band(x_range.0 + (start as f64 + 0.5) * dx, y0 + dy / 2.0);
+            if k < resolution && band(x_range.0 + (k as f64 + 0.5) * dx, y0 + dy / 2.0) == current {
+                continue;
+            }
+            let (x0, x1) = (x_range.0 + start as f64 * dx, x_range.0 + k as f64 * dx);
+            chart.draw_series(std::iter::once(Rectangle::new([(x0, y0), (x1, y0 + dy)], band_color(current).filled())))?;
+            start = k;
+        }
+    }
+
+    for level in 1..levels {
+        let level = level as f64 / levels as f64;
+        let bold = (level - 0.5).abs() < 1e-9;
+        let line = match bold {
+            true => style.foreground.stroke_width(style.px(style.line_width + 1)),
+            false => style.foreground.mix(0.5).stroke_width(style.px(1)),
+        };
+        chart.draw_series(contour_segments(heatmap, level).into_iter().map(|segment| PathElement::new(segment.to_vec(), line)))?;
+    }
+
+    if let Some((position, label)) = marker {
+        let font = style.text(16).pos(Pos::new(HPos::Left, VPos::Bottom));
+        chart.draw_series(std::iter::once(Circle::new(position, style.px(style.marker_size + 2), style.highlight.filled())))?;
+        chart.draw_series(std::iter::once(Text::new(format!(" {}", label), position, font)))?;
+    }
+
+    // Vertical color scale from 0 to 100 % in the plot's bands
+    let mut bar = ChartBuilder::on(&color_bar)
+        .margin_top(style.px(60))
+        .margin_bottom(style.px(60))
+        .margin_right(style.px(15))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(0.0..1.0, 0.0..100.0)?;
+    bar.configure_mesh()
+        .disable_mesh()
+        .disable_x_axis()
+        .axis_style(style.foreground)
+        .y_label_style(style.text(13))
+        .y_label_formatter(&|v| format!("{:.0}%", v))
+        .draw()?;
+    let step = 100.0 / levels as f64;
+    bar.draw_series((0..levels).map(|k| Rectangle::new([(0.0, k as f64 * step), (1.0, (k + 1) as f64 * step)], band_color(k).filled())))?;
+    Ok(())
+}
+
+// Draw the trajectory chart and any configured panels onto an arbitrary drawing area
+pub fn draw_engagement<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    bounds: PlotBounds,
+    options: &PlotOptions,
+) -> Result<(), SimError> {
+    let style = &options.scaled_style();
+    root.fill(&style.background)?;
+    let (top, bottom) = if options.panels.is_empty() {
+        (root.clone(), None)
+    } else {
+        // Panels together take at most 60% of the height so the trajectory chart stays readable
+        let (_, height) = root.dim_in_pixel();
+        let panel_height = (height as f64 * 0.6 / options.panels.len() as f64).min(style.px(PANEL_HEIGHT) as f64) as u32;
+        let (top, bottom) = root.split_vertically(height - panel_height * options.panels.len() as u32);
+        (top, Some(bottom))
+    };
+
+    match options.zoom {
+        Some(radius) => {
+            let (width, _) = top.dim_in_pixel();
+            let (main, zoom) = top.split_horizontally(width * 65 / 100);
+            draw_trajectories(&main, sim, bounds, options, style)?;
+            draw_terminal_zoom(&zoom, sim, radius, options, style)?;
+        }
+        None => draw_trajectories(&top, sim, bounds, options, style)?,
+    }
+
+    let Some(bottom) = bottom else {
+        return Ok(());
+    };
+    for (area, panel) in bottom.split_evenly((options.panels.len(), 1)).iter().zip(&options.panels) {
+        match panel {
+            Panel::Distance => draw_time_series_panel(area, distance_panel(sim, bounds, style), bounds, style)?,
+            Panel::Speed => draw_time_series_panel(area, speed_panel(sim, bounds, style), bounds, style)?,
+            Panel::Heading => draw_time_series_panel(area, heading_panel(sim, bounds, style), bounds, style)?,
+            Panel::Los => draw_time_series_panel(area, los_panel(sim, bounds, style), bounds, style)?,
+            Panel::LosRate => draw_time_series_panel(area, los_rate_panel(sim, bounds, style), bounds, style)?,
+            Panel::Energy => draw_time_series_panel(area, energy_panel(sim, bounds, style), bounds, style)?,
+            Panel::Phase => draw_phase_panel(area, sim, bounds, style)?,
+            Panel::Timeline => draw_timeline_panel(area, sim, bounds, style)?,
+        }
+    }
+    Ok(())
+}
+
+// One line of a time-series panel; values[k] belongs to step first_step + k
+struct PanelSeries {
+    label: String,
+    color: RGBColor,
+    first_step: usize,
+    values: Vec<f64>,
+}
+
+// Common layout of all per-step diagnostic panels
+struct TimeSeriesPanel {
+    caption: &'static str,
+    y_desc: &'static str,
+    y_range: (f64, f64),
+    series: Vec<PanelSeries>,
+    reference: Option<(String, f64)>, // Dashed horizontal line, e.g. the collision threshold
+}
+
+fn draw_time_series_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    panel: TimeSeriesPanel,
+    bounds: PlotBounds,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let mut chart = ChartBuilder::on(area)
+        .caption(panel.caption, style.text(20))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(35))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(bounds.first_step as f64..bounds.max_step as f64, panel.y_range.0..panel.y_range.1)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc(panel.y_desc)
+        .x_desc("Step")
+        .draw()?;
+
+    for series in panel.series {
+        let (color, line) = (series.color, style.line(series.color));
+        chart
+            .draw_series(LineSeries::new(
+                series.values.into_iter().enumerate().map(|(k, v)| ((series.first_step + k) as f64, v)),
+                style.line(color),
+            ))?
+            .label(series.label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+    }
+
+    if let Some((label, value)) = panel.reference {
+        let reference_color = style.foreground;
+        chart
+            .draw_series(DashedLineSeries::new(
+                [(bounds.first_step as f64, value), (bounds.max_step as f64, value)],
+                8,
+                6,
+                ShapeStyle::from(&style.foreground).stroke_width(style.px(1)),
+            ))?
+            .label(label)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], ShapeStyle::from(&reference_color)));
+    }
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(style.background.mix(0.8))
+        .border_style(style.foreground)
+        .label_font(style.text(15))
+        .draw()?;
+
+    Ok(())
+}
+
+// Interceptor–target separation per step with the collision threshold as reference line
+fn distance_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    let (name, threshold) = end_radius(sim);
+    TimeSeriesPanel {
+        caption: "Interceptor–target separation",
+        y_desc: "Distance (m)",
+        y_range: (0.0, bounds.max_distance),
+        series: vec![PanelSeries { label: "Separation".into(), color: style.separation, first_step: sim.first_step, values: sim.separations() }],
+        reference: Some((format!("{} ({} m)", name, threshold), threshold)),
+    }
+}
+
+fn speed_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    let speeds = |velocities: &[(f64, f64)]| velocities.iter().map(|(vx, vy)| vx.hypot(*vy)).collect();
+    TimeSeriesPanel {
+        caption: "Speed",
+        y_desc: "Speed (m/s)",
+        y_range: (0.0, bounds.max_speed),
+        series: vec![
+            PanelSeries { label: "Target".into(), color: style.target, first_step: sim.first_step, values: speeds(&sim.target_velocities) },
+            PanelSeries { label: "Interceptor".into(), color: style.interceptor, first_step: sim.first_step, values: speeds(&sim.interceptor_velocities) },
+        ],
+        reference: None,
+    }
+}
+
+fn heading_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    TimeSeriesPanel {
+        caption: "Heading (0° = +x, counter-clockwise)",
+        y_desc: "Heading (°)",
+        y_range: bounds.heading_range,
+        series: vec![
+            PanelSeries { label: "Target".into(), color: style.target, first_step: sim.first_step, values: headings_deg(&sim.target_velocities) },
+            PanelSeries { label: "Interceptor".into(), color: style.interceptor, first_step: sim.first_step, values: headings_deg(&sim.interceptor_velocities) },
+        ],
+        reference: None,
+    }
+}
+
+fn los_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    TimeSeriesPanel {
+        caption: "Line-of-sight angle (interceptor → target)",
+        y_desc: "LOS angle (°)",
+        y_range: bounds.los_range,
+        series: vec![PanelSeries { label: "LOS angle".into(), color: style.highlight, first_step: sim.first_step, values: sim.los_angles_deg() }],
+        reference: None,
+    }
+}
+
+fn los_rate_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    TimeSeriesPanel {
+        caption: "Line-of-sight rate",
+        y_desc: "LOS rate (°/step)",
+        y_range: bounds.los_rate_range,
+        // Rates are differences between samples, so the first value belongs to step 1
+        series: vec![PanelSeries { label: "LOS rate".into(), color: style.highlight, first_step: sim.first_step + 1, values: sim.los_rates_deg() }],
+        reference: Some(("Zero rate (collision course)".into(), 0.0)),
+    }
+}
+
+// Specific kinetic energy ½|v|²; the vehicles have no mass in this model. A drop over a turn is
+// energy the interceptor bled maneuvering.
+fn energy_panel(sim: &SimulationResult, bounds: PlotBounds, style: &PlotStyle) -> TimeSeriesPanel {
+    let energies = |velocities: &[(f64, f64)]| velocities.iter().map(|(vx, vy)| 0.5 * (vx * vx + vy * vy)).collect();
+    TimeSeriesPanel {
+        caption: "Kinetic energy per unit mass",
+        y_desc: "Energy (J/kg)",
+        y_range: (0.0, 0.5 * bounds.max_speed * bounds.max_speed),
+        series: vec![
+            PanelSeries { label: "Target".into(), color: style.target, first_step: sim.first_step, values: energies(&sim.target_velocities) },
+            PanelSeries { label: "Interceptor".into(), color: style.interceptor, first_step: sim.first_step, values: energies(&sim.interceptor_velocities) },
+        ],
+        reference: None,
+    }
+}
+
+// Range rate against range, one point per step: a converging engagement runs towards range 0
+// with the range rate staying negative, one that opens again crosses the zero line
+fn draw_phase_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    bounds: PlotBounds,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let points: Vec<(f64, f64)> = engagement::history(sim).iter().map(|e| (e.range, -e.closing_velocity)).collect();
+    let (rate_min, rate_max) = bounds.range_rate_range;
+    let mut chart = ChartBuilder::on(area)
+        .caption("Phase plane (range rate vs range)", style.text(20))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(35))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(0.0..bounds.max_distance, rate_min..rate_max)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc("Range rate (m/s)")
+        .x_desc("Range (m)")
+        .draw()?;
+
+    let (highlight, line) = (style.highlight, style.line(style.highlight));
+    chart
+        .draw_series(LineSeries::new(points.iter().copied(), line))?
+        .label("Engagement")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], line));
+    let marker = style.px(style.marker_size + 1);
+    chart
+        .draw_series(points.first().map(|&p| Circle::new(p, marker, ShapeStyle::from(&highlight).filled())))?
+        .label("Start")
+        .legend(move |(x, y)| Circle::new((x + 10, y), marker, ShapeStyle::from(&highlight).filled()));
+    let foreground = style.foreground;
+    chart.draw_series(DashedLineSeries::new(
+        [(0.0, 0.0), (bounds.max_distance, 0.0)],
+        8,
+        6,
+        ShapeStyle::from(&foreground).stroke_width(style.px(1)),
+    ))?;
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(style.background.mix(0.8))
+        .border_style(style.foreground)
+        .label_font(style.text(15))
+        .draw()?;
+
+    Ok(())
+}
+
+// One row of the timeline panel: the spans a state lasted (lock, faults) and single events, labeled
+// or not, by step
+#[derive(Default)]
+struct TimelineLane {
+    name: String,
+    spans: Vec<(usize, usize)>,
+    open: Option<usize>, // Start of a span still going on
+    events: Vec<(usize, Option<String>)>,
+}
+
+// The event log sorted into lanes in the order they first appear; states that last are spans from
+// the event that starts them to the one that ends them (or to the end of the run)
+fn timeline_lanes(sim: &SimulationResult) -> Vec<TimelineLane> {
+    let mut lanes: Vec<TimelineLane> = Vec::new();
+    for event in &sim.events {
+        let step = event.step();
+        let (name, change) = match *event {
+            SimEvent::LockAcquired { .. } => (String::from("lock"), Some(true)),
+            SimEvent::LockLost { .. } => (String::from("lock"), Some(false)),
+            SimEvent::ActuatorFault { .. } => (String::from("actuator fault"), Some(true)),
+            SimEvent::ActuatorRecovered { .. } => (String::from("actuator fault"), Some(false)),
+            SimEvent::FaultStarted { fault, .. } => (fault.as_str().replace('_', " "), Some(true)),
+            SimEvent::FaultCleared { fault, .. } => (fault.as_str().replace('_', " "), Some(false)),
+            SimEvent::TargetMasked { .. } => (String::from("terrain mask"), Some(true)),
+            SimEvent::TargetVisible { .. } => (String::from("terrain mask"), Some(false)),
+            SimEvent::KeepOutEntered { zone, .. } => (format!("keep-out {}", zone), Some(true)),
+            SimEvent::KeepOutLeft { zone, .. } => (format!("keep-out {}", zone), Some(false)),
+            SimEvent::PhaseChange { .. } => (String::from("phase"), None),
+            SimEvent::ParametersChanged { .. } => (String::from("parameters"), None),
+            SimEvent::MeasurementMissed { .. } => (String::from("missed"), None),
+            SimEvent::AssociationError { .. } => (String::from("false plot"), None),
+            SimEvent::Launch { .. }
+            | SimEvent::Intercept { .. }
+            | SimEvent::GroundImpact { .. }
+            | SimEvent::Pass { .. }
+            | SimEvent::Escape { .. }
+            | SimEvent::Stall { .. }
+            | SimEvent::Timeout { .. } => (String::from("engagement"), None),
+        };
+        let index = match lanes.iter().position(|lane| lane.name == name) {
+            Some(index) => index,
+            None => {
+                lanes.push(TimelineLane { name, ..TimelineLane::default() });
+                lanes.len() - 1
+            }
+        };
+        let lane = &mut lanes[index];
+        match (change, *event) {
+            (Some(true), _) => lane.open = lane.open.or(Some(step)),
+            (Some(false), _) => lane.spans.extend(lane.open.take().map(|start| (start, step))),
+            (None, SimEvent::PhaseChange { phase, .. }) => lane.events.push((step, Some(phase.as_str().replace('_', " ")))),
+            (None, SimEvent::Launch { .. } | SimEvent::Intercept { .. } | SimEvent::GroundImpact { .. } | SimEvent::Pass { .. } | SimEvent::Escape { .. } | SimEvent::Stall { .. } | SimEvent::Timeout { .. }) => {
+                lane.events.push((step, Some(event.label())))
+            }
+            (None, _) => lane.events.push((step, None)),
+        }
+    }
+    let end = sim.first_step + sim.interceptor_positions.len().saturating_sub(1);
+    for lane in &mut lanes {
+        lane.spans.extend(lane.open.take().map(|start| (start, end.max(start))));
+    }
+    lanes
+}
+
+// The event log against the step, lined up with the other panels, so that a miss can be traced
+// back to what happened before it: lock held, faults active and single events, one lane each
+fn draw_timeline_panel<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    bounds: PlotBounds,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let lanes = timeline_lanes(sim);
+    let rows = lanes.len().max(1) as f64;
+    let mut chart = ChartBuilder::on(area)
+        .caption("Event timeline", style.text(20))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(35))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(bounds.first_step as f64..bounds.max_step as f64, -0.5..rows - 0.5)?;
+
+    chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .y_labels(0)
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .x_desc("Step")
+        .draw()?;
+
+    // Labels go below their marker, to its left in the last fifth of the run so they stay inside
+    let offset = style.px(6) as i32;
+    let late = bounds.first_step as f64 + 0.8 * (bounds.max_step - bounds.first_step) as f64;
+    let marker_style = ShapeStyle::from(&style.foreground).filled();
+    let span_style = ShapeStyle::from(&style.highlight.mix(0.5)).filled();
+    for (i, lane) in lanes.iter().enumerate() {
+        // The first lane at the top
+        let y = rows - 1.0 - i as f64;
+        let x = |step: usize| step.max(bounds.first_step) as f64;
+        chart.draw_series(lane.spans.iter().map(|&(start, end)| Rectangle::new([(x(start), y - 0.25), (x(end), y + 0.25)], span_style)))?;
+        chart.draw_series(lane.events.iter().map(|(step, label)| {
+            let (anchor, offset) = match x(*step) > late {
+                true => (HPos::Right, (-offset, offset)),
+                false => (HPos::Left, (offset, offset)),
+            };
+            EmptyElement::at((x(*step), y))
+                + TriangleMarker::new((0, 0), style.px(5), marker_style)
+                + Text::new(label.clone().unwrap_or_default(), offset, style.text(13).pos(Pos::new(anchor, VPos::Top)))
+        }))?;
+        chart.draw_series(std::iter::once(Text::new(lane.name.clone(), (bounds.first_step as f64, y + 0.45), style.text(13))))?;
+    }
+    Ok(())
+}
+
+// Magnified view of the end game: everything within `radius` meters of the final interceptor position,
+// with the collision threshold drawn to scale around the final target position
+fn draw_terminal_zoom<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    radius: f64,
+    options: &PlotOptions,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let Some(&(cx, cy)) = sim.interceptor_positions.last() else {
+        return Ok(());
+    };
+
+    let caption = format!("Terminal phase (±{} m)", radius);
+    let mut window = ((cx - radius, cx + radius), (cy - radius, cy + radius));
+    if options.equal_aspect {
+        let pixels = plot_area_pixels(area, &caption, &style.text(22), style.px(15), style.px(40), style.px(50))?;
+        window = equalize_aspect(window, pixels, false);
+    }
+    let ((x_min, x_max), (y_min, y_max)) = window;
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(caption, style.text(22))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(40))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(13))
+        .y_label_style(style.text(13))
+        .y_desc("Height (m)")
+        .x_desc("Distance (m)")
+        .draw()?;
+
+    for (positions, color) in [(&sim.target_positions, style.target), (&sim.interceptor_positions, style.interceptor)] {
+        // plotters clamps out-of-range points to the border instead of clipping, so clip here
+        for piece in clip_polyline(positions, window) {
+            chart.draw_series(LineSeries::new(piece, style.line(color)))?;
+        }
+        // Only markers inside the window are visible, so skip the rest
+        chart.draw_series(
+            positions
+                .iter()
+                .filter(|(x, y)| (x_min..=x_max).contains(x) && (y_min..=y_max).contains(y))
+                .map(|&p| Circle::new(p, style.px(style.marker_size + 1), ShapeStyle::from(&color).filled())),
+        )?;
+    }
+
+    if let Some(&(tx, ty)) = sim.target_positions.last() {
+        let threshold = end_radius(sim).1;
+        let ring = (0..=64).map(|k| {
+            let a = k as f64 / 64.0 * std::f64::consts::TAU;
+            (tx + threshold * a.cos(), ty + threshold * a.sin())
+        });
+        chart.draw_series(LineSeries::new(ring, style.line(style.highlight)))?;
+    }
+
+    Ok(())
+}
+
+// Size in pixels of the plotting area ChartBuilder leaves inside `area` after the margin,
+// the caption (same padding rule as DrawingArea::titled) and the x/y label areas
+fn plot_area_pixels<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    caption: &str,
+    caption_style: &TextStyle,
+    margin: u32,
+    x_label_area: u32,
+    y_label_area: u32,
+) -> Result<(f64, f64), SimError> {
+    let (width, height) = area.dim_in_pixel();
+    let (_, text_height) = area.estimate_text_size(caption, caption_style)?;
+    let caption_height = text_height + 2 * (text_height / 2).min(5);
+    let plot_width = width.saturating_sub(2 * margin + y_label_area).max(1);
+    let plot_height = height.saturating_sub(2 * margin + caption_height + x_label_area).max(1);
+    Ok((plot_width as f64, plot_height as f64))
+}
+
+// Widen the shorter axis of ((x_min, x_max), (y_min, y_max)) so a meter is as long on screen
+// horizontally as vertically. Anchored ranges keep their minimum (the ground / launch line) and
+// grow upwards, otherwise both ends move to keep the center.
+fn equalize_aspect(
+    ranges: ((f64, f64), (f64, f64)),
+    (plot_width, plot_height): (f64, f64),
+    anchored: bool,
+) -> ((f64, f64), (f64, f64)) {
+    let ((x_min, x_max), (y_min, y_max)) = ranges;
+    let meters_per_pixel = ((x_max - x_min) / plot_width).max((y_max - y_min) / plot_height);
+    let widen = |(min, max): (f64, f64), span: f64| {
+        if anchored {
+            (min, min + span)
+        } else {
+            let center = (min + max) / 2.0;
+            (center - span / 2.0, center + span / 2.0)
+        }
+    };
+    (widen((x_min, x_max), meters_per_pixel * plot_width), widen((y_min, y_max), meters_per_pixel * plot_height))
+}
+
+// Split a polyline into the pieces that lie inside the window ((x_min, x_max), (y_min, y_max)),
+// cutting segments at the border (Liang–Barsky)
+fn clip_polyline(points: &[(f64, f64)], window: ((f64, f64), (f64, f64))) -> Vec<Vec<(f64, f64)>> {
+    let ((x_min, x_max), (y_min, y_max)) = window;
+    let mut pieces: Vec<Vec<(f64, f64)>> = Vec::new();
+    let mut current: Vec<(f64, f64)> = Vec::new();
+    for segment in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (segment[0], segment[1]);
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+        let inside = [(-dx, x0 - x_min), (dx, x_max - x0), (-dy, y0 - y_min), (dy, y_max - y0)]
+            .iter()
+            .all(|&(p, q)| {
+                if p == 0.0 {
+                    return q >= 0.0;
+                }
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+                t0 <= t1
+            });
+        if !inside {
+            if !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let start = (x0 + t0 * dx, y0 + t0 * dy);
+        let end = (x0 + t1 * dx, y0 + t1 * dy);
+        if current.last() != Some(&start) {
+            if !current.is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            current.push(start);
+        }
+        current.push(end);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+// Trajectories of both vehicles in the vertical plane
+fn draw_trajectories<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    bounds: PlotBounds,
+    options: &PlotOptions,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    let target_positions = &sim.target_positions;
+    let interceptor_positions = &sim.interceptor_positions;
+
+    let caption = match sim.config.fuze {
+        Some(fuze) => format!("Target vs Interceptor Simulation (fuze detonates at <{}m)", fuze.detonation_radius),
+        None => format!("Target vs Interceptor Simulation (Stop at <{}m distance)", sim.config.collision_threshold),
+    };
+    let mut ranges = ((0.0, bounds.max_x), (0.0, bounds.max_y));
+    if options.equal_aspect {
+        let pixels = plot_area_pixels(root, &caption, &style.text(30), style.px(15), style.px(40), style.px(50))?;
+        ranges = equalize_aspect(ranges, pixels, true);
+    }
+    let ((x_min, x_max), (y_min, y_max)) = ranges;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, style.text(30))
+        .margin(style.px(15))
+        .x_label_area_size(style.px(40))
+        .y_label_area_size(style.px(50))
+        .build_cartesian_2d(
+            x_min..x_max,
+            y_min..y_max,
+        )?;
+
+    let (target_line, interceptor_line) = (style.line(style.target), style.line(style.interceptor));
+
+    // CLOS: the ground station and the beam to the target, faint at about ten earlier steps and
+    // solid at the last one
+    if sim.config.guidance == GuidanceLaw::Clos && !target_positions.is_empty() {
+        let station = (sim.config.ground_station_x, sim.config.ground_station_y);
+        let faint = ShapeStyle::from(&style.highlight.mix(0.25)).stroke_width(style.px(1));
+        let every = (target_positions.len() / 10).max(1);
+        chart.draw_series(target_positions.iter().step_by(every).map(|&t| PathElement::new(vec![station, t], faint)))?;
+        let beam = ShapeStyle::from(&style.highlight).stroke_width(style.px(1));
+        chart
+            .draw_series(std::iter::once(PathElement::new(vec![station, target_positions[target_positions.len() - 1]], beam)))?
+            .label("Beam (CLOS)")
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], beam));
+        let half = style.px(6) as i32;
+        chart.draw_series(std::iter::once(
+            EmptyElement::at(station) + Rectangle::new([(-half, -half), (half, half)], ShapeStyle::from(&style.highlight).filled()),
+        ))?;
+    }
+
+    // The terrain, filled down to the bottom of the chart
+    if let Some(terrain) = &sim.config.terrain {
+        let fill = style.foreground.mix(0.15).filled();
+        let edge = ShapeStyle::from(&style.foreground.mix(0.5)).stroke_width(style.px(1));
+        chart
+            .draw_series(AreaSeries::new(terrain.outline(x_min, x_max), y_min, fill).border_style(edge))?
+            .label("Terrain")
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], fill));
+    }
+
+    // Keep-out zones, shaded under the trajectories
+    if let Some(keep_out) = &sim.config.keep_out {
+        let shade = style.highlight.mix(0.2).filled();
+        let edge = ShapeStyle::from(&style.highlight.mix(0.6)).stroke_width(style.px(1));
+        chart
+            .draw_series(keep_out.zones.iter().map(|zone| Polygon::new(zone.outline(72), shade)))?
+            .label("Keep-out zone")
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], shade));
+        chart.draw_series(keep_out.zones.iter().map(|zone| {
+            let mut outline = zone.outline(72);
+            outline.extend(outline.first().copied());
+            PathElement::new(outline, edge)
+        }))?;
+    }
+
+    // Draw target line
+    chart
+        .draw_series(LineSeries::new(
+            target_positions.iter().copied(),
+            style.line(style.target),
+        ))?
+        .label(match (sim.config.optimal_evasion, &sim.config.evasion_model, &sim.config.target_script) {
+            (Some(_), _, _) => "Target (optimal evasion)",
+            (None, Some(_), _) => "Target (learned evasion)",
+            (None, None, Some(_)) => "Target (scripted evasion)",
+            (None, None, None) => "Target (random evasion)",
+        })
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], target_line));
+
+    // What guidance saw of the target, when it flew on a filtered track
+    if let Some(tracking) = sim.config.tracking.filter(|_| !sim.track_positions.is_empty()) {
+        let track_line = ShapeStyle::from(&style.target.mix(0.45)).stroke_width(style.px(1));
+        chart
+            .draw_series(DashedLineSeries::new(sim.track_positions.iter().copied(), style.px(6), style.px(4), track_line))?
+            .label(format!("Target track ({})", tracking.filter.as_str()))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], track_line));
+        // Ground sensor sites
+        let sites: Vec<(f64, f64)> = tracking.sensors().filter_map(|sensor| sensor.position).collect();
+        if !sites.is_empty() {
+            let site_style = ShapeStyle::from(&style.target.mix(0.45)).filled();
+            chart
+                .draw_series(sites.into_iter().map(|site| TriangleMarker::new(site, style.px(6), site_style)))?
+                .label("Ground sensor")
+                .legend(move |(x, y)| TriangleMarker::new((x + 10, y), style.px(6), site_style));
+        }
+    }
+
+    // Draw interceptor line
+    chart
+        .draw_series(LineSeries::new(
+            interceptor_positions.iter().copied(),
+            style.line(style.interceptor),
+        ))?
+        .label("Interceptor (pursuing)")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], interceptor_line));
+
+    // Draw points for both vehicles, one series each; every Nth step only for long runs
+    for (positions, color) in [(target_positions, style.target), (interceptor_positions, style.interceptor)] {
+        chart.draw_series(
+            positions
+                .iter()
+                .step_by(options.marker_every)
+                .map(|pos| Circle::new(*pos, style.px(style.marker_size), ShapeStyle::from(&color).filled())),
+        )?;
+    }
+
+    // Arrowheads every N samples pointing along the velocity at that step. The direction is taken in
+    // pixel space so the glyphs stay correct when the axes are scaled differently.
+    if let Some(every) = options.arrows_every {
+        let (length, half_width) = (style.px(9) as f64, style.px(5) as f64);
+        for (positions, velocities, color) in [
+            (target_positions, &sim.target_velocities, style.target),
+            (interceptor_positions, &sim.interceptor_velocities, style.interceptor),
+        ] {
+            let arrows = positions.iter().zip(velocities).step_by(every).filter_map(|(&(x, y), &(vx, vy))| {
+                let speed = vx.hypot(vy);
+                if speed == 0.0 {
+                    return None;
+                }
+                let reach = bounds.max_x.max(bounds.max_y) / speed;
+                let (x0, y0) = chart.backend_coord(&(x, y));
+                let (x1, y1) = chart.backend_coord(&(x + vx * reach, y + vy * reach));
+                let angle = ((y1 - y0) as f64).atan2((x1 - x0) as f64);
+                let (c, s) = (angle.cos(), angle.sin());
+                let corner = |along: f64, across: f64| {
+                    ((along * c - across * s).round() as i32, (along * s + across * c).round() as i32)
+                };
+                let head = vec![corner(length, 0.0), corner(-length / 2.0, half_width), corner(-length / 2.0, -half_width)];
+                Some(EmptyElement::at((x, y)) + Polygon::new(head, ShapeStyle::from(&color).filled()))
+            });
+            let arrows: Vec<_> = arrows.collect();
+            chart.draw_series(arrows)?;
+        }
+    }
+
+    // Step numbers every N samples; a faint line joins the positions both vehicles had at that step
+    if let Some(every) = options.annotate_every {
+        let offset = (style.px(6) as i32, -(style.px(18) as i32));
+        for (step, (&t, &i)) in target_positions.iter().zip(interceptor_positions).enumerate().step_by(every) {
+            chart.draw_series(std::iter::once(PathElement::new(
+                vec![t, i],
+                ShapeStyle::from(&style.foreground.mix(0.3)).stroke_width(style.px(1)),
+            )))?;
+            chart.draw_series([t, i].map(|pos| {
+                EmptyElement::at(pos) + Text::new((sim.first_step + step).to_string(), offset, style.text(13))
+            }))?;
+        }
+    }
+
+    // Event log entries with their time, placed where the interceptor was then (or where the event happened);
+    // events at the same place share one label
+    if options.annotate_events && !interceptor_positions.is_empty() {
+        let offset = (style.px(8) as i32, style.px(4) as i32);
+        let marker_style = ShapeStyle::from(&style.foreground).filled();
+        let mut labels: Vec<((f64, f64), String)> = Vec::new();
+        for event in &sim.events {
+            let Some(position) = sim.event_position(event) else { continue };
+            match labels.last_mut() {
+                Some((last, text)) if *last == position => *text += &format!(", {}", event.label()),
+                _ => labels.push((position, format!("{:.1} s: {}", event.step() as f64 * sim.config.dt, event.label()))),
+            }
+        }
+        chart.draw_series(labels.into_iter().map(|(position, text)| {
+            EmptyElement::at(position)
+                + TriangleMarker::new((0, 0), style.px(6), marker_style)
+                + Text::new(text, offset, style.text(13))
+        }))?;
+    }
+
+    // Draw blue circle at the last position of interceptor
+    if let Some(&last_interceptor_pos) = interceptor_positions.last() {
+        let (collision_x, collision_y) = last_interceptor_pos;
+        let highlight_line = style.line(style.highlight);
+
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                (collision_x, collision_y),
+                style.px(style.end_marker_size),
+                ShapeStyle::from(&style.highlight).stroke_width(style.px(style.line_width + 1)),
+            )))?
+            .label("Collision / end point")
+            .legend(move |(x, y)| Circle::new((x + 10, y), 6, highlight_line));
+
+        // Outline the region shown magnified in the terminal-phase chart
+        if let Some(r) = options.zoom {
+            chart.draw_series(std::iter::once(Rectangle::new(
+                [(collision_x - r, collision_y - r), (collision_x + r, collision_y + r)],
+                ShapeStyle::from(&style.foreground.mix(0.6)).stroke_width(style.px(1)),
+            )))?;
+        }
+    }
+
+    // Configure axes
+    chart
+        .configure_mesh()
+        .bold_line_style(style.foreground.mix(0.2))
+        .light_line_style(style.foreground.mix(0.1))
+        .axis_style(style.foreground)
+        .x_label_style(style.text(15))
+        .y_label_style(style.text(15))
+        .y_desc("Height (m)")
+        .x_desc("Distance (m)")
+        .draw()?;
+
+    if let Some(position) = &options.legend {
+        chart
+            .configure_series_labels()
+            .position(position.clone())
+            .background_style(style.background.mix(0.8))
+            .border_style(style.foreground)
+            .label_font(style.text(18))
+            .draw()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+// Perspective view of the engagement with plotters' 3D charts.
+//
+// The simulation itself is planar (downrange distance and height), so the third axis is time:
+// each trajectory becomes a curve through (distance, height, step), which shows where both
+// vehicles were at the same moment. Once a 3D simulation mode exists its cross-range axis can
+// take the place of the step axis here.
+
+use crate::log;
+use crate::bounds::PlotBounds;
+use crate::error::SimError;
+use crate::plot::{PlotOptions, PlotStyle};
+use crate::SimulationResult;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub azimuth_deg: f64,   // Rotation around the vertical axis
+    pub elevation_deg: f64, // Tilt of the view from horizontal
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera { azimuth_deg: 30.0, elevation_deg: 15.0 }
+    }
+}
+
+// What to produce: a still image, a rotating GIF or both
+#[derive(Debug, Clone, Copy, Default)]
+pub struct View3dOptions {
+    pub still: bool,
+    pub rotating_gif: bool,
+    pub camera: Camera,
+}
+
+impl View3dOptions {
+    // --view3d renders a still image, --view3d-gif a GIF rotating once around the vertical axis;
+    // --azimuth DEG and --elevation DEG place the camera. None when neither output is requested.
+    pub fn from_args(args: &[String]) -> Result<Option<View3dOptions>, String> {
+        let still = args.iter().any(|a| a == "--view3d");
+        let rotating_gif = args.iter().any(|a| a == "--view3d-gif");
+        if !still && !rotating_gif {
+            return Ok(None);
+        }
+        let mut camera = Camera::default();
+        for pair in args.windows(2) {
+            match pair[0].as_str() {
+                "--azimuth" => camera.azimuth_deg = pair[1].parse().map_err(|_| "--azimuth must be an angle in degrees")?,
+                "--elevation" => {
+                    let elevation = pair[1].parse::<f64>().ok().filter(|e| (-90.0..=90.0).contains(e));
+                    camera.elevation_deg = elevation.ok_or("--elevation must be an angle between -90 and 90 degrees")?;
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(View3dOptions { still, rotating_gif, camera }))
+    }
+}
+
+// Writes `<base>_3d.png` and/or `<base>_3d.gif` next to the regular plot
+pub fn export_3d(
+    sim: &SimulationResult,
+    base_path: &std::path::Path,
+    view: &View3dOptions,
+    plot: &PlotOptions,
+) -> Result<(), SimError> {
+    let style = &plot.scaled_style();
+    let size = (style.px(plot.size.0), style.px(plot.size.1));
+    let stem = base_path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+
+    if view.still {
+        let out_png = base_path.with_file_name(format!("{}_3d.png", stem));
+        let root = BitMapBackend::new(&out_png, size).into_drawing_area();
+        draw_3d(&root, sim, view.camera, style)?;
+        root.present()?;
+        log::info!("✅ 3D view saved as '{}'", out_png.display());
+    }
+
+    if view.rotating_gif {
+        const FRAMES: usize = 36;
+        let out_gif = base_path.with_file_name(format!("{}_3d.gif", stem));
+        let root = BitMapBackend::gif(&out_gif, size, 100)
+            .map_err(|e| SimError::PlottingError(format!("cannot create '{}': {}", out_gif.display(), e)))?
+            .into_drawing_area();
+        for frame in 0..FRAMES {
+            let camera = Camera {
+                azimuth_deg: view.camera.azimuth_deg + 360.0 * frame as f64 / FRAMES as f64,
+                ..view.camera
+            };
+            draw_3d(&root, sim, camera, style)?;
+            root.present()?;
+        }
+        log::info!("✅ Rotating 3D view saved as '{}' ({} frames)", out_gif.display(), FRAMES);
+    }
+    Ok(())
+}
+
+fn draw_3d<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    sim: &SimulationResult,
+    camera: Camera,
+    style: &PlotStyle,
+) -> Result<(), SimError> {
+    root.fill(&style.background)?;
+    let bounds = PlotBounds::from_result(sim);
+
+    // plotters' 3D charts are y-up: x = downrange distance, y = height, z = step
+    let mut chart = ChartBuilder::on(root)
+        .caption("Target vs Interceptor over time", style.text(30))
+        .margin(style.px(20))
+        .build_cartesian_3d(0.0..bounds.max_x, 0.0..bounds.max_y, bounds.first_step as f64..bounds.max_step as f64)?;
+
+    chart.with_projection(|mut projection| {
+        projection.yaw = camera.azimuth_deg.to_radians();
+        projection.pitch = camera.elevation_deg.to_radians();
+        projection.scale = 0.8;
+        projection.into_matrix()
+    });
+
+    chart
+        .configure_axes()
+        .light_grid_style(style.foreground.mix(0.1))
+        .bold_grid_style(style.foreground.mix(0.2))
+        .axis_panel_style(style.foreground.mix(0.05))
+        .label_style(style.text(13))
+        .max_light_lines(4)
+        .draw()?;
+
+    let at_step = |positions: &[(f64, f64)]| -> Vec<(f64, f64, f64)> {
+        positions.iter().enumerate().map(|(k, &(x, y))| (x, y, (sim.first_step + k) as f64)).collect()
+    };
+    let target_line = style.line(style.target);
+    let interceptor_line = style.line(style.interceptor);
+    chart
+        .draw_series(LineSeries::new(at_step(&sim.target_positions), target_line))?
+        .label("Target")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], target_line));
+    chart
+        .draw_series(LineSeries::new(at_step(&sim.interceptor_positions), interceptor_line))?
+        .label("Interceptor")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], interceptor_line));
+
+    // Ground track of both curves on the height = 0 plane, to read off downrange progress over time
+    for positions in [&sim.target_positions, &sim.interceptor_positions] {
+        let shadow = positions.iter().enumerate().map(|(k, &(x, _))| (x, 0.0, (sim.first_step + k) as f64));
+        chart.draw_series(LineSeries::new(shadow, ShapeStyle::from(&style.foreground.mix(0.3))))?;
+    }
+
+    if let (Some(&(x, y)), Some(_)) = (sim.interceptor_positions.last(), sim.collision_point) {
+        let end = (x, y, sim.steps() as f64);
+        chart.draw_series(std::iter::once(Circle::new(end, style.px(8), style.line(style.highlight))))?;
+    }
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(style.background.mix(0.8))
+        .border_style(style.foreground)
+        .label_font(style.text(18))
+        .draw()?;
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// Guidance laws distributed as WebAssembly plugins, so that guidance can be shared without its
+// source. With "guidance": "plugin" and "guidance_plugin": "law.wasm" the interceptor flies on the
+// command of the module's exported function
+//
+//   guidance(t, dt, tx, ty, tvx, tvy, ix, iy, ivx, ivy: f64) -> f64
+//
+// which gets the time (s) at the start of the step, the seconds per step, the target's position and
+// velocity as guidance sees it (the track, with tracking) and the interceptor's, and returns the
+// lateral acceleration (m/s²) normal to the interceptor's velocity, positive turning it
+// counter-clockwise. The interceptor keeps its speed, so that is all a command can change.
+//
+// Modules run in the interpreter of wasm.rs: they can't import anything, every call gets a fresh
+// instance with a budget of CALL_FUEL instructions, and nothing carries over between steps. A call
+// that traps or returns NaN or infinity commands no turn, is reported once and counts as no lock.
+//
+// A loaded module is shared through an Arc by the configs that use it, and dropped with the last.
+
+use crate::log;
+use crate::vec2::Vec2;
+use crate::wasm::{Module, ValType, Value};
+use crate::{GuidancePhase, Interceptor, Steering, Target};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const ENTRY: &str = "guidance";
+const CALL_FUEL: u64 = 1_000_000;
+
+#[derive(Debug)]
+pub struct GuidancePlugin {
+    file: PathBuf,
+    module: Module,
+    failed: AtomicBool, // Whether a failing call was reported already
+}
+
+// Plugins are told apart by the file they were loaded from
+impl PartialEq for GuidancePlugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.file == other.file
+    }
+}
+
+impl GuidancePlugin {
+    // The module at `path`
+    pub fn load(path: &Path) -> Result<Arc<GuidancePlugin>, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        let module = Module::decode(&bytes).map_err(|e| format!("'{}': {}", path.display(), e))?;
+        let expected = [ValType::F64; 10];
+        match module.export_type(ENTRY) {
+            Some(ty) if ty.params == expected && ty.results == [ValType::F64] => {}
+            Some(_) => return Err(format!("'{}': '{}' must take ten f64 and return one f64", path.display(), ENTRY)),
+            None => return Err(format!("'{}' exports no function '{}'", path.display(), ENTRY)),
+        }
+        log::debug!("guidance plugin '{}' loaded", path.display());
+        Ok(Arc::new(GuidancePlugin { file: path.to_path_buf(), module, failed: AtomicBool::new(false) }))
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    fn command(&self, args: [f64; 10]) -> Result<f64, String> {
+        let mut instance = self.module.instantiate(CALL_FUEL)?;
+        match instance.call(ENTRY, &args.map(Value::F64))?[..] {
+            [Value::F64(accel)] if accel.is_finite() => Ok(accel),
+            _ => Err("the command is not a finite number".into()),
+        }
+    }
+
+    pub fn steering(&self, from: &Interceptor, to: &Target, t: f64, dt: f64) -> Steering {
+        let args = [t, dt, to.position.x, to.position.y, to.velocity.x, to.velocity.y, from.position.x, from.position.y, from.velocity.x, from.velocity.y];
+        let heading = from.velocity.normalized().unwrap_or(Vec2::new(1.0, 0.0));
+        match self.command(args) {
+            Ok(accel) => {
+                log::trace!("plugin: commanded acceleration {:.2} m/s²", accel);
+                let direction = (from.velocity + heading.perp() * (accel * dt)).normalized().unwrap_or(heading);
+                Steering { direction, lead_solution: true, phase: GuidancePhase::Direct }
+            }
+            Err(e) => {
+                if !self.failed.swap(true, Ordering::Relaxed) {
+                    log::info!("⚠️ guidance plugin '{}' failed at t = {:.2} s: {} (flying straight; later failures aren't reported)", self.file.display(), t, e);
+                }
+                Steering { direction: heading, lead_solution: false, phase: GuidancePhase::Direct }
+            }
+        }
+    }
+}
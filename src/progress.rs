@@ -0,0 +1,123 @@
+// Single-line progress bar on stderr for batches and sweeps: completed runs, hit rate so far and
+// an ETA. Only drawn when stderr is a terminal and --quiet is not given, so piped or scripted
+// output stays clean. Other console output goes through `suspend`, which clears the bar, prints
+// and draws it again underneath.
+
+use crate::log;
+use std::io::{IsTerminal, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const BAR_WIDTH: usize = 30;
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+// The line currently shown, if any
+static ACTIVE_LINE: Mutex<Option<String>> = Mutex::new(None);
+
+pub struct Progress {
+    label: String,
+    total: usize,
+    done: usize,
+    hits: usize,
+    start: Instant,
+    last_draw: Option<Instant>,
+    visible: bool,
+}
+
+impl Progress {
+    // `enabled` lets callers turn the bar off when something else owns the terminal (e.g. --tui)
+    pub fn new(label: impl Into<String>, total: usize, enabled: bool) -> Progress {
+        let visible = enabled && total > 1 && !log::quiet() && std::io::stderr().is_terminal();
+        let mut progress =
+            Progress { label: label.into(), total, done: 0, hits: 0, start: Instant::now(), last_draw: None, visible };
+        progress.draw();
+        progress
+    }
+
+    pub fn inc(&mut self, hit: bool) {
+        self.done += 1;
+        self.hits += hit as usize;
+        let due = self.last_draw.is_none_or(|t| t.elapsed() >= REDRAW_INTERVAL);
+        if due || self.done == self.total {
+            self.draw();
+        }
+    }
+
+    pub fn finish(&mut self) {
+        if self.visible {
+            self.visible = false;
+            set_line(None);
+        }
+    }
+
+    fn draw(&mut self) {
+        if !self.visible {
+            return;
+        }
+        self.last_draw = Some(Instant::now());
+        let filled = BAR_WIDTH * self.done / self.total.max(1);
+        let hit_rate = match self.done {
+            0 => String::from("-"),
+            done => format!("{:.1}%", 100.0 * self.hits as f64 / done as f64),
+        };
+        let eta = match self.done {
+            0 => String::from("-"),
+            done => format_duration(self.start.elapsed().mul_f64((self.total - done) as f64 / done as f64)),
+        };
+        set_line(Some(format!(
+            "{} [{}{}] {}/{}  hit rate {}  ETA {}",
+            self.label,
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total,
+            hit_rate,
+            eta
+        )));
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+// Runs `print` with the bar cleared from the terminal, then redraws the bar
+pub fn suspend(print: impl FnOnce()) {
+    let active = ACTIVE_LINE.lock().unwrap_or_else(|e| e.into_inner());
+    if active.is_some() {
+        clear_line();
+    }
+    print();
+    if let Some(line) = active.as_ref() {
+        draw_line(line);
+    }
+}
+
+fn set_line(line: Option<String>) {
+    let mut active = ACTIVE_LINE.lock().unwrap_or_else(|e| e.into_inner());
+    clear_line();
+    if let Some(line) = &line {
+        draw_line(line);
+    }
+    *active = line;
+}
+
+fn clear_line() {
+    eprint!("\r\x1b[2K");
+}
+
+fn draw_line(line: &str) {
+    eprint!("{}", line);
+    let _ = std::io::stderr().flush();
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
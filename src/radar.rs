@@ -0,0 +1,102 @@
+// Radar seeker on the interceptor, a sensor for tracking (see tracking.rs). It measures the range
+// and bearing to the target, both with Gaussian noise:
+//
+//   range error σ = range_noise + range_noise_growth · range
+//   bearing error σ = bearing_noise_deg, i.e. a cross-range error that grows with range too
+//
+// Whether a look detects the target at all is a draw against the detection probability
+// 1 / (1 + (range / detection_range)⁴), which follows the r⁴ fall-off of the echo and is 50% at
+// `detection_range`; beyond `max_range` nothing is ever detected. A look without a detection is a
+// missed measurement the tracking filter has to coast through.
+//
+// e.g. "tracking": { "filter": "kalman", "radar": { "bearing_noise_deg": 1, "max_range": 50 } }
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::noise::standard_normal;
+use crate::tracking::Measurement;
+use crate::validation::Violations;
+use rand::{Rng, RngCore};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radar {
+    pub range_noise: f64,        // Range error σ (m) at zero range
+    pub range_noise_growth: f64, // Added range error σ per meter of range
+    pub bearing_noise_deg: f64,  // Bearing error σ
+    pub detection_range: f64,    // Range (m) with a 50% detection probability
+    pub max_range: f64,          // Instrumented range (m); no detections beyond it
+}
+
+impl Default for Radar {
+    fn default() -> Self {
+        Radar { range_noise: 0.1, range_noise_growth: 0.005, bearing_noise_deg: 0.5, detection_range: 40.0, max_range: 60.0 }
+    }
+}
+
+impl Radar {
+    pub fn detection_probability(&self, range: f64) -> f64 {
+        if range > self.max_range {
+            0.0
+        } else {
+            1.0 / (1.0 + (range / self.detection_range).powi(4))
+        }
+    }
+
+    // The error of a measurement at `range` as the filters take it, one standard deviation for both
+    // axes: the mean of the range and cross-range variances
+    pub fn noise_at(&self, range: f64) -> f64 {
+        let range_sigma = self.range_noise + self.range_noise_growth * range;
+        let cross_range_sigma = range * self.bearing_noise_deg.to_radians();
+        ((range_sigma * range_sigma + cross_range_sigma * cross_range_sigma) / 2.0).sqrt()
+    }
+
+    // One look from `from` at the target at `to`; None if it wasn't detected
+    pub fn measure(&self, from: (f64, f64), to: (f64, f64), rng: &mut dyn RngCore) -> Option<Measurement> {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let range = dx.hypot(dy);
+        if rng.gen::<f64>() >= self.detection_probability(range) {
+            return None;
+        }
+        let range_sigma = self.range_noise + self.range_noise_growth * range;
+        let bearing_sigma = self.bearing_noise_deg.to_radians();
+        let measured_range = (range + range_sigma * standard_normal(rng)).max(0.0);
+        let bearing = dy.atan2(dx) + bearing_sigma * standard_normal(rng);
+        let (sin, cos) = bearing.sin_cos();
+        Some(Measurement { position: (from.0 + measured_range * cos, from.1 + measured_range * sin), noise: self.noise_at(range) })
+    }
+
+    // A message naming the first parameter out of range
+    pub fn validate(&self, v: &mut Violations) {
+        let positive = |x: f64| x > 0.0 && x.is_finite();
+        v.check(positive(self.range_noise), "range_noise", "must be positive");
+        v.check(self.range_noise_growth >= 0.0 && self.range_noise_growth.is_finite(), "range_noise_growth", "must be zero or positive");
+        v.check((0.0..90.0).contains(&self.bearing_noise_deg), "bearing_noise_deg", "must be between 0 and 90 degrees");
+        v.check(positive(self.detection_range), "detection_range", "must be positive");
+        v.check(positive(self.max_range), "max_range", "must be positive");
+    }
+}
+
+impl ToJson for Radar {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("range_noise", self.range_noise.into()),
+            ("range_noise_growth", self.range_noise_growth.into()),
+            ("bearing_noise_deg", self.bearing_noise_deg.into()),
+            ("detection_range", self.detection_range.into()),
+            ("max_range", self.max_range.into()),
+        ])
+    }
+}
+
+// Every parameter is optional
+impl FromJson for Radar {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let defaults = Radar::default();
+        Ok(Radar {
+            range_noise: json.f64_field_or("range_noise", defaults.range_noise)?,
+            range_noise_growth: json.f64_field_or("range_noise_growth", defaults.range_noise_growth)?,
+            bearing_noise_deg: json.f64_field_or("bearing_noise_deg", defaults.bearing_noise_deg)?,
+            detection_range: json.f64_field_or("detection_range", defaults.detection_range)?,
+            max_range: json.f64_field_or("max_range", defaults.max_range)?,
+        })
+    }
+}
@@ -0,0 +1,306 @@
+// Raids: targets that keep appearing, for sustained-raid simulations. The `raid` section of a
+// scenario spawns them in scheduled waves and/or at random intervals, each in one of the spawn
+// regions, and the raid command engages them from the interceptor's start with a limited number
+// of fire channels (interceptors in flight at once):
+//
+//   "raid": {
+//     "waves": [{ "time": 0, "count": 3 }, { "time": 40, "count": 6 }],
+//     "interval": 8, "until": 120,
+//     "regions": [{ "x": [-60, -20], "y": [20, 50], "weight": 2 }, { "x": [80, 120], "y": [30, 60] }],
+//     "channels": 2, "max_wait": 20, "assignment": "nearest"
+//   }
+//
+//   waves       `count` targets appearing at `time` seconds
+//   interval    mean seconds between targets appearing at random (exponentially distributed)
+//               from 0 to `until`, in addition to the waves
+//   regions     rectangles the targets appear in, picked in proportion to `weight` (default 1)
+//   channels    engagements that can run at once (default 1)
+//   max_wait    seconds a target may wait for a free channel before it leaks through unengaged
+//   assignment  which waiting target a channel that comes free takes: the first to appear
+//               (`fifo`, the default) or the one nearest the interceptor's start (`nearest`)
+//
+// A waiting target flies straight on at its speed and heading (the scenario's, or drawn from its
+// envelope); once assigned it is engaged like any run of the scenario from where it has got to,
+// and its channel is busy for the engagement's flight time. Targets that aren't intercepted leak.
+
+use crate::error::SimError;
+use crate::json::{FromJson, Json};
+use crate::log;
+use crate::scenario::Scenario;
+use crate::{simulate_once, Outcome, SimConfig};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Assignment {
+    Fifo,
+    Nearest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Region {
+    x: (f64, f64),
+    y: (f64, f64),
+    weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raid {
+    waves: Vec<(f64, usize)>, // Time (s) and number of targets
+    interval: Option<f64>,    // Mean seconds between random arrivals
+    until: f64,
+    regions: Vec<Region>,
+    channels: usize,
+    max_wait: Option<f64>,
+    assignment: Assignment,
+}
+
+impl FromJson for Raid {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let waves = match json.get("waves") {
+            None => Vec::new(),
+            Some(waves) => waves
+                .as_array()
+                .ok_or("field 'waves' must be an array of {\"time\": s, \"count\": n}")?
+                .iter()
+                .enumerate()
+                .map(|(i, wave)| {
+                    let time = wave.f64_field("time").ok().filter(|t| *t >= 0.0 && t.is_finite());
+                    let count = wave.get("count").and_then(Json::as_usize);
+                    Ok((time.ok_or(format!("waves[{}]: 'time' must be a non-negative number of seconds", i))?, count.ok_or(format!("waves[{}]: 'count' must be a non-negative integer", i))?))
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+        };
+        let interval = match json.get("interval") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("interval").ok().filter(|s| *s > 0.0 && s.is_finite()).ok_or("field 'interval' must be a positive number of seconds")?),
+        };
+        let until = json.f64_field_or("until", 0.0)?;
+        if interval.is_some() && !(until > 0.0 && until.is_finite()) {
+            return Err(String::from("field 'until' must be a positive number of seconds with 'interval'"));
+        }
+        if waves.is_empty() && interval.is_none() {
+            return Err(String::from("needs 'waves' and/or an 'interval' for targets to appear"));
+        }
+        let regions = json
+            .field("regions")?
+            .as_array()
+            .filter(|regions| !regions.is_empty())
+            .ok_or("field 'regions' must be a non-empty array of {\"x\": [min, max], \"y\": [min, max]}")?
+            .iter()
+            .enumerate()
+            .map(|(i, region)| {
+                let range = |key: &str| region.get(key).and_then(Json::as_point).filter(|(min, max)| min.is_finite() && max.is_finite() && min <= max);
+                let weight = region.f64_field_or("weight", 1.0)?;
+                Ok(Region {
+                    x: range("x").ok_or(format!("regions[{}]: 'x' must be [min, max]", i))?,
+                    y: range("y").ok_or(format!("regions[{}]: 'y' must be [min, max]", i))?,
+                    weight: Some(weight).filter(|w| *w > 0.0 && w.is_finite()).ok_or(format!("regions[{}]: 'weight' must be positive", i))?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let channels = match json.get("channels") {
+            None => 1,
+            Some(channels) => channels.as_usize().filter(|n| *n > 0).ok_or("field 'channels' must be a positive integer")?,
+        };
+        let max_wait = match json.get("max_wait") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("max_wait").ok().filter(|s| *s >= 0.0 && s.is_finite()).ok_or("field 'max_wait' must be a non-negative number of seconds")?),
+        };
+        let assignment = match json.get("assignment").map(|a| a.as_str()) {
+            None | Some(Some("fifo")) => Assignment::Fifo,
+            Some(Some("nearest")) => Assignment::Nearest,
+            _ => return Err(String::from("field 'assignment' must be \"fifo\" or \"nearest\"")),
+        };
+        Ok(Raid { waves, interval, until, regions, channels, max_wait, assignment })
+    }
+}
+
+// A target of the raid
+struct Arrival {
+    time: f64,
+    position: (f64, f64),
+    config: SimConfig, // Its speed and heading, and the rest of its engagement
+}
+
+impl Arrival {
+    // Where it has flown to by `time`
+    fn position_at(&self, time: f64) -> (f64, f64) {
+        let (sin, cos) = self.config.target_heading_deg.to_radians().sin_cos();
+        let flown = self.config.target_speed * (time - self.time);
+        (self.position.0 + flown * cos, self.position.1 + flown * sin)
+    }
+}
+
+// What became of a target
+struct Fate {
+    engaged_at: Option<f64>,
+    outcome: Option<Outcome>,
+    flight_time: f64,
+    miss_distance: f64,
+}
+
+impl Raid {
+    // The targets of one raid in order of appearance
+    fn arrivals(&self, scenario: &Scenario, rng: &mut StdRng) -> Vec<Arrival> {
+        let mut times: Vec<f64> = self.waves.iter().flat_map(|&(time, count)| std::iter::repeat_n(time, count)).collect();
+        if let Some(interval) = self.interval {
+            let mut t = 0.0;
+            loop {
+                t += -interval * (1.0 - rng.gen::<f64>()).ln();
+                if t > self.until {
+                    break;
+                }
+                times.push(t);
+            }
+        }
+        times.sort_by(f64::total_cmp);
+        let total_weight: f64 = self.regions.iter().map(|region| region.weight).sum();
+        times
+            .into_iter()
+            .map(|time| {
+                let mut pick = rng.gen::<f64>() * total_weight;
+                let region = self.regions.iter().find(|region| {
+                    pick -= region.weight;
+                    pick < 0.0
+                });
+                let region = region.unwrap_or(&self.regions[self.regions.len() - 1]);
+                let draw = |(min, max): (f64, f64), rng: &mut StdRng| if min < max { rng.gen_range(min..max) } else { min };
+                let position = (draw(region.x, rng), draw(region.y, rng).max(0.0));
+                Arrival { time, position, config: scenario.envelope.sample(&scenario.config, rng) }
+            })
+            .collect()
+    }
+
+    // Engages `arrivals` with the channels, in time order; every engagement draws from `rng`
+    fn engage(&self, arrivals: &[Arrival], launcher: (f64, f64), rng: &mut StdRng) -> (Vec<Fate>, usize) {
+        let mut fates: Vec<Fate> = arrivals.iter().map(|_| Fate { engaged_at: None, outcome: None, flight_time: 0.0, miss_distance: f64::INFINITY }).collect();
+        let mut free_at = vec![0.0f64; self.channels];
+        let mut waiting: Vec<usize> = Vec::new();
+        let mut next = 0;
+        let mut most_waiting = 0;
+        loop {
+            // The next decision: when a channel comes free, or later when a target appears
+            let channel = (0..free_at.len()).min_by(|&a, &b| free_at[a].total_cmp(&free_at[b])).unwrap_or(0);
+            let mut now = free_at[channel];
+            if waiting.is_empty() {
+                let Some(arrival) = arrivals.get(next) else { break };
+                now = now.max(arrival.time);
+            }
+            while next < arrivals.len() && arrivals[next].time <= now {
+                waiting.push(next);
+                next += 1;
+            }
+            most_waiting = most_waiting.max(waiting.len());
+            // Targets that waited too long have leaked
+            if let Some(max_wait) = self.max_wait {
+                waiting.retain(|&k| now - arrivals[k].time <= max_wait);
+            }
+            let distance = |k: usize| {
+                let (x, y) = arrivals[k].position_at(now);
+                (x - launcher.0).hypot(y - launcher.1)
+            };
+            let chosen = match self.assignment {
+                Assignment::Fifo => (!waiting.is_empty()).then_some(0),
+                Assignment::Nearest => (0..waiting.len()).min_by(|&a, &b| distance(waiting[a]).total_cmp(&distance(waiting[b]))),
+            };
+            let Some(chosen) = chosen else { continue };
+            let k = waiting.remove(chosen);
+            let (x, y) = arrivals[k].position_at(now);
+            let config = SimConfig { target_start_x: x, target_start_y: y, ..arrivals[k].config.clone() };
+            let sim = simulate_once(rng, &config);
+            let flight_time = sim.steps() as f64 * sim.config.dt;
+            log::debug!("target {} engaged at {:.1} s from ({:.1}, {:.1}): {}", k + 1, now, x, y, sim.outcome.as_str());
+            fates[k] = Fate { engaged_at: Some(now), outcome: Some(sim.outcome), flight_time, miss_distance: sim.miss_distance };
+            free_at[channel] = now + flight_time;
+        }
+        (fates, most_waiting)
+    }
+}
+
+// Usage: raid [--runs N / -n N] [--seed N] [--scenario FILE] [--preset NAME] [--out-dir DIR / -o DIR]
+//             [--quiet / -q]
+pub fn run_raid(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let raid = scenario.raid.clone().ok_or("the scenario has no 'raid' section, see the README for its fields")?;
+    let mut runs: usize = 1;
+    let mut seed: u64 = 1;
+    let mut out_dir = String::from(".");
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--runs must be a positive integer")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    let csv_path = std::path::Path::new(&out_dir).join("raid.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "raid,target,appeared,x,y,engaged,wait,outcome,flight_time,miss_distance")?;
+    let launcher = (scenario.config.interceptor_start_x, scenario.config.interceptor_start_y);
+    let (mut targets, mut kills, mut unengaged, mut wait_sum, mut busy, mut duration) = (0, 0, 0, 0.0, 0.0, 0.0);
+    for run in 1..=runs {
+        // Raid k uses seed N + k - 1, so raids can be reproduced on their own
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(run as u64 - 1));
+        let arrivals = raid.arrivals(&scenario, &mut rng);
+        let (fates, most_waiting) = raid.engage(&arrivals, launcher, &mut rng);
+        let killed = fates.iter().filter(|fate| fate.outcome == Some(Outcome::Intercept)).count();
+        let leaked_unengaged = fates.iter().filter(|fate| fate.engaged_at.is_none()).count();
+        for (k, (arrival, fate)) in arrivals.iter().zip(&fates).enumerate() {
+            let (engaged, wait, outcome, flight_time, miss) = match fate.engaged_at {
+                Some(at) => (
+                    format!("{:.2}", at),
+                    format!("{:.2}", at - arrival.time),
+                    fate.outcome.map_or("", |outcome| outcome.as_str()),
+                    format!("{:.2}", fate.flight_time),
+                    format!("{:.4}", fate.miss_distance),
+                ),
+                None => (String::new(), String::new(), "unengaged", String::new(), String::new()),
+            };
+            writeln!(csv, "{},{},{:.2},{:.3},{:.3},{},{},{},{},{}", run, k + 1, arrival.time, arrival.position.0, arrival.position.1, engaged, wait, outcome, flight_time, miss)?;
+        }
+        let end = fates.iter().zip(&arrivals).map(|(fate, arrival)| fate.engaged_at.map_or(arrival.time, |at| at + fate.flight_time)).fold(0.0, f64::max);
+        log::info!(
+            "🌊 Raid {}: {} targets, {} killed, {} leaked ({} unengaged), at most {} waiting, over {:.1} s",
+            run,
+            arrivals.len(),
+            killed,
+            arrivals.len() - killed,
+            leaked_unengaged,
+            most_waiting,
+            end
+        );
+        targets += arrivals.len();
+        kills += killed;
+        unengaged += leaked_unengaged;
+        wait_sum += fates.iter().zip(&arrivals).filter_map(|(fate, arrival)| fate.engaged_at.map(|at| at - arrival.time)).sum::<f64>();
+        busy += fates.iter().map(|fate| fate.flight_time).sum::<f64>();
+        duration += end;
+    }
+    let engaged = targets - unengaged;
+    log::info!(
+        "🌊 Over {} raid(s): {}/{} targets killed ({:.1}% leaked, {} of them unengaged), mean wait {:.1} s, channels busy {:.1}% of the time",
+        runs,
+        kills,
+        targets,
+        100.0 * (targets - kills) as f64 / targets.max(1) as f64,
+        unengaged,
+        if engaged > 0 { wait_sum / engaged as f64 } else { 0.0 },
+        if duration > 0.0 { 100.0 * busy / (duration * raid.channels as f64) } else { 0.0 }
+    );
+    log::info!("✅ Raid log saved as '{}'", csv_path.display());
+    Ok(())
+}
@@ -0,0 +1,190 @@
+// Reachability: where the interceptor can be T seconds after launch, against where the target is
+// predicted to be then, to show why a geometry can or can't be intercepted. The interceptor flies
+// at constant speed, so with a lateral acceleration limit a (the actuator's max_accel, or
+// --reach-accel) it turns on circles of radius v²/a at the tightest, and the points it reaches
+// within T lie in a fan ahead of it, bounded by paths that turn at the limit and then fly straight
+// or turn back. Without a limit it can turn at once and reaches a disc of radius vT. The target is
+// predicted to fly on as it started, straight at its initial velocity, which is what an
+// interceptor can count on at launch; where its prediction lies outside the interceptor's reach at
+// every T, a non-manoeuvring target couldn't be met either.
+//
+// With --reachability [T1,T2,...] a run writes `<plot>_reach.png` next to its plot: the reachable
+// sets at the given times (by default four, evenly spaced over the engagement) over both paths,
+// with the target's predicted positions, and logs the earliest time at which the prediction is
+// within reach.
+
+use crate::error::SimError;
+use crate::log;
+use crate::plot::{visualize_reachability, PlotOptions};
+use crate::vec2::Vec2;
+use crate::SimulationResult;
+use std::path::Path;
+
+// Bearings of the boundary of a reachable set
+const BEARINGS: usize = 180;
+// Switch times tried per turning manoeuvre
+const SWITCHES: usize = 1000;
+
+pub struct ReachOptions {
+    times: Vec<f64>, // Seconds after launch in increasing order, empty for four over the engagement
+    max_accel: Option<f64>,
+}
+
+impl ReachOptions {
+    // --reachability [T1,T2,...] and --reach-accel A, None without --reachability
+    pub fn from_args(args: &[String]) -> Result<Option<ReachOptions>, String> {
+        let Some(i) = args.iter().position(|a| a == "--reachability") else {
+            return Ok(None);
+        };
+        let mut times = match args.get(i + 1).filter(|a| !a.starts_with('-')) {
+            Some(list) => list
+                .split(',')
+                .map(|t| t.trim().parse().ok().filter(|t: &f64| *t > 0.0 && t.is_finite()))
+                .collect::<Option<Vec<f64>>>()
+                .ok_or("--reachability takes a comma-separated list of positive times in seconds, e.g. 5,10,20")?,
+            None => Vec::new(),
+        };
+        times.sort_by(f64::total_cmp);
+        let max_accel = match args.windows(2).find(|pair| pair[0] == "--reach-accel") {
+            Some(pair) => Some(pair[1].parse().ok().filter(|a: &f64| *a > 0.0 && a.is_finite()).ok_or("--reach-accel must be a positive acceleration in m/s²")?),
+            None => None,
+        };
+        Ok(Some(ReachOptions { times, max_accel }))
+    }
+}
+
+// The points the interceptor reaches within `time` and where the target is predicted then
+pub struct ReachableSet {
+    pub time: f64,
+    pub boundary: Vec<(f64, f64)>, // Closed polygon around the launch point
+    pub predicted_target: (f64, f64),
+}
+
+impl ReachableSet {
+    // Whether `p` lies within the boundary, by ray casting
+    pub fn contains(&self, p: (f64, f64)) -> bool {
+        let mut inside = false;
+        for (a, b) in self.boundary.iter().zip(self.boundary.iter().cycle().skip(1)) {
+            if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+// Launch state and limits of a run
+struct Launch {
+    position: Vec2,
+    heading: f64, // Radians
+    speed: f64,
+    max_turn_rate: Option<f64>, // Radians per second
+    target: Vec2,
+    target_velocity: Vec2,
+}
+
+impl Launch {
+    // Where the interceptor is after turning at `rate` for `t` seconds from `position` and `heading`
+    fn fly(&self, position: Vec2, heading: f64, rate: f64, t: f64) -> (Vec2, f64) {
+        if rate.abs() < 1e-12 {
+            return (position + Vec2::new(heading.cos(), heading.sin()) * (self.speed * t), heading);
+        }
+        let end = heading + rate * t;
+        let r = self.speed / rate;
+        (position + Vec2::new(end.sin() - heading.sin(), heading.cos() - end.cos()) * r, end)
+    }
+
+    fn reachable_set(&self, time: f64) -> ReachableSet {
+        let predicted_target = self.target + self.target_velocity * time;
+        let origin = (self.position.x, self.position.y);
+        let Some(max_rate) = self.max_turn_rate else {
+            let radius = self.speed * time;
+            let boundary = (0..BEARINGS).map(|k| (k as f64 / BEARINGS as f64) * std::f64::consts::TAU).map(|b| (origin.0 + radius * b.cos(), origin.1 + radius * b.sin()));
+            return ReachableSet { time, boundary: boundary.collect(), predicted_target: (predicted_target.x, predicted_target.y) };
+        };
+        // End points of a turn at the limit for a while, then straight on or a turn at the limit
+        // either way for the rest of the time, and the farthest of them in every bearing
+        let mut farthest = [0.0f64; BEARINGS];
+        for first in [-max_rate, max_rate] {
+            for k in 0..=SWITCHES {
+                let t = time * k as f64 / SWITCHES as f64;
+                let (position, heading) = self.fly(self.position, self.heading, first, t);
+                for second in [-max_rate, 0.0, max_rate] {
+                    let (end, _) = self.fly(position, heading, second, time - t);
+                    let offset = end - self.position;
+                    let bearing = offset.y.atan2(offset.x).rem_euclid(std::f64::consts::TAU);
+                    let bin = ((bearing / std::f64::consts::TAU * BEARINGS as f64) as usize).min(BEARINGS - 1);
+                    farthest[bin] = farthest[bin].max(offset.length());
+                }
+            }
+        }
+        // Bearings nothing reaches go through the launch point
+        let boundary = farthest.iter().enumerate().map(|(bin, &r)| {
+            let bearing = (bin as f64 + 0.5) / BEARINGS as f64 * std::f64::consts::TAU;
+            (origin.0 + r * bearing.cos(), origin.1 + r * bearing.sin())
+        });
+        ReachableSet { time, boundary: boundary.collect(), predicted_target: (predicted_target.x, predicted_target.y) }
+    }
+}
+
+// The reachable sets of `sim` at the times of `options`, and the earliest time the target's
+// prediction is within reach, if any before the run's max_steps; None when the run's launch isn't
+// recorded (a --window run)
+fn reachable_sets(sim: &SimulationResult, options: &ReachOptions) -> Option<(Vec<ReachableSet>, Option<f64>)> {
+    if sim.first_step > 0 || sim.interceptor_positions.is_empty() {
+        return None;
+    }
+    let config = &sim.config;
+    let velocity = Vec2::from(sim.interceptor_velocities[0]);
+    let max_accel = options.max_accel.or(config.actuator.and_then(|actuator| actuator.max_accel));
+    let launch = Launch {
+        position: Vec2::from(sim.interceptor_positions[0]),
+        heading: velocity.y.atan2(velocity.x),
+        speed: config.interceptor_speed,
+        max_turn_rate: max_accel.map(|a| a / config.interceptor_speed),
+        target: Vec2::from(sim.target_positions[0]),
+        target_velocity: Vec2::from(sim.target_velocities[0]),
+    };
+    let duration = sim.steps().max(1) as f64 * config.dt;
+    let times = match options.times.is_empty() {
+        true => (1..=4).map(|k| duration * k as f64 / 4.0).collect(),
+        false => options.times.clone(),
+    };
+    let horizon = config.max_steps as f64 * config.dt;
+    let earliest = (1..=200).map(|k| horizon * k as f64 / 200.0).find(|&t| {
+        let set = launch.reachable_set(t);
+        set.contains(set.predicted_target)
+    });
+    Some((times.into_iter().map(|t| launch.reachable_set(t)).collect(), earliest))
+}
+
+// Logs what the sets of run `run` show
+fn report(run: usize, sets: &[ReachableSet], earliest: Option<f64>) {
+    for set in sets {
+        let within = if set.contains(set.predicted_target) { "within" } else { "out of" };
+        log::info!(
+            "🧭 Run {}: after {:.1} s the target is predicted at ({:.1}, {:.1}), {} the interceptor's reach",
+            run,
+            set.time,
+            set.predicted_target.0,
+            set.predicted_target.1,
+            within
+        );
+    }
+    match earliest {
+        Some(t) => log::info!("🧭 Run {}: the earliest intercept of a non-manoeuvring target is about {:.1} s after launch", run, t),
+        None => log::info!("🧭 Run {}: a non-manoeuvring target stays out of the interceptor's reach until max_steps", run),
+    }
+}
+
+// Reports the reachability of run `run` and writes `<base>_reach.<ext>` next to its plot at `base_path`
+pub fn export(sim: &SimulationResult, options: &ReachOptions, run: usize, base_path: &Path, plot: &PlotOptions) -> Result<(), SimError> {
+    let Some((sets, earliest)) = reachable_sets(sim, options) else {
+        log::info!("🧭 Run {}: the launch is no longer recorded (--window), so there is no reachability plot", run);
+        return Ok(());
+    };
+    report(run, &sets, earliest);
+    let stem = base_path.file_stem().unwrap_or_default().to_string_lossy();
+    let out_path = base_path.with_file_name(format!("{}_reach.{}", stem, plot.format.extension()));
+    visualize_reachability(sim, &sets, out_path.to_str().unwrap(), plot)
+}
@@ -0,0 +1,56 @@
+// Wall-clock pacing: with --realtime [SPEEDUP] every step of dt seconds takes dt / SPEEDUP seconds
+// of real time instead of running as fast as possible, so live views and anything consuming the
+// run as it happens see it unfold at a known rate.
+
+use crate::simulation::SimObserver;
+use crate::SimulationResult;
+use std::time::{Duration, Instant};
+
+// Falling further behind than this (slow rendering, a paused live view) restarts the schedule
+// instead of rushing through the missed steps
+const MAX_LAG: Duration = Duration::from_millis(250);
+
+pub struct RealtimePacer {
+    speedup: f64,
+    start: Option<(Instant, usize)>, // Wall clock and step the schedule counts from
+}
+
+impl RealtimePacer {
+    pub fn new(speedup: f64) -> RealtimePacer {
+        RealtimePacer { speedup, start: None }
+    }
+
+    // --realtime with an optional speed-up factor (default 1); None when the flag is absent
+    pub fn from_args(args: &[String]) -> Result<Option<RealtimePacer>, String> {
+        let Some(i) = args.iter().position(|a| a == "--realtime") else {
+            return Ok(None);
+        };
+        let speedup = match args.get(i + 1).filter(|a| !a.starts_with('-')) {
+            Some(value) => value.parse().ok().filter(|s: &f64| *s > 0.0 && s.is_finite()).ok_or("--realtime speed-up must be a positive number")?,
+            None => 1.0,
+        };
+        Ok(Some(RealtimePacer::new(speedup)))
+    }
+
+    pub fn speedup(&self) -> f64 {
+        self.speedup
+    }
+}
+
+impl SimObserver for RealtimePacer {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        let step = sim.steps();
+        let (start, start_step) = *self.start.get_or_insert_with(|| (Instant::now(), step.saturating_sub(1)));
+        let due = start + Duration::from_secs_f64((step - start_step) as f64 * sim.config.dt / self.speedup);
+        let now = Instant::now();
+        if now < due {
+            std::thread::sleep(due - now);
+        } else if now - due > MAX_LAG {
+            self.start = Some((now, step));
+        }
+    }
+
+    fn on_finish(&mut self, _sim: &SimulationResult) {
+        self.start = None;
+    }
+}
@@ -0,0 +1,496 @@
+// Scenario files: a JSON document describing the engagement and how to present it, e.g.
+//
+//   {
+//     "config": { "interceptor_speed": 3.0, "p_gain": 0.1, "interceptor_start": [10, 0] },
+//     "style": { "theme": "dark", "line_width": 3 },
+//     "envelope": { "target_altitude": { "uniform": [20, 40] } },
+//     "costs": { "interceptor": 50000, "fuel": 20 }
+//   }
+//
+// Every section and field is optional; whatever is left out keeps its default, or the value of
+// the built-in preset selected with --preset.
+
+use crate::actuator::Actuator;
+use crate::atmosphere::Atmosphere;
+use crate::cost::UnitCosts;
+use crate::evasion::OptimalEvasion;
+use crate::faults::FaultSchedule;
+use crate::termination::TerminationCriteria;
+use crate::terrain::Terrain;
+use crate::zones::KeepOut;
+use crate::envelope::{envelope_with_defaults, Envelope};
+use crate::error::SimError;
+use crate::frames::Frame;
+use crate::fuze::Fuze;
+use crate::tracking::Tracking;
+use crate::trajectory::TargetPath;
+use crate::script::TargetScript;
+use crate::plugin::GuidancePlugin;
+use crate::raid::Raid;
+use crate::onnx::NeuralGuidance;
+use crate::guidance::LqrWeights;
+use crate::json::{FromJson, Json};
+use crate::log;
+use crate::noise::NoiseKind;
+use crate::plot::PlotStyle;
+use crate::schedule::GainSchedule;
+use crate::simulation::{Simulation, SimulationBuilder};
+use crate::{mpc_settings_from_json, GuidanceLaw, SimConfig, Vehicle};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+// How often a watched scenario file is checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(200);
+
+// Target scripts (script.rs) of the presets with a canned manoeuvre. Their source is stored in the
+// result like any script's, so it can be copied into a scenario file and changed.
+//
+// Sea-skimming ingress on the altitude hold towards the ship at x = 90, pulling up 60 m out and
+// diving onto it from 35 m out, both at up to 25° per step
+const POP_UP: &str = "togo = 90 - x
+climb = clamp(40 - heading, -25, 25)
+dive = clamp(deg(atan2(-y, max(togo, 1))) - heading, -25, 25)
+turn = if(togo > 60, 0.9 * hold + 0.1 * noise, if(togo > 35, climb, dive))";
+// Level cruise on the altitude hold until the interceptor is 60 m away, then a corkscrew: seen in
+// the vertical plane, a weave of ±60° with a 6 s period, at up to 30° per step
+const SPIRAL: &str = "weave = 60 * sin(2 * pi * t / 6)
+turn = if(range > 60, 0.8 * hold + 0.2 * noise, clamp(weave - heading, -30, 30))";
+
+#[derive(Debug, Clone, Default)]
+pub struct Scenario {
+    pub config: SimConfig,
+    pub style: PlotStyle,
+    pub envelope: Envelope, // Per-run random initial conditions, see envelope.rs
+    pub costs: UnitCosts,   // Prices of what engagements use up, see cost.rs
+    pub raid: Option<Raid>, // Targets that keep appearing, for the raid command, see raid.rs
+}
+
+impl Scenario {
+    // Starts from --preset NAME (or the defaults) and applies the file given with --scenario FILE on top
+    pub fn from_args(args: &[String]) -> Result<Scenario, SimError> {
+        let base = Scenario::preset_from_args(args)?;
+        match args.windows(2).find(|pair| pair[0] == "--scenario") {
+            Some(pair) => Scenario::load(Path::new(&pair[1]), base),
+            None => Ok(base),
+        }
+    }
+
+    fn preset_from_args(args: &[String]) -> Result<Scenario, SimError> {
+        match args.windows(2).find(|pair| pair[0] == "--preset") {
+            Some(pair) => Scenario::preset(&pair[1])
+                .ok_or_else(|| SimError::ConfigError(format!("unknown preset '{}' (available: {})", pair[1], PRESETS.join(", ")))),
+            None => Ok(Scenario::default()),
+        }
+    }
+
+    // Fields missing from the file keep their value in `base`
+    pub fn load(path: &Path, base: Scenario) -> Result<Scenario, SimError> {
+        let text = std::fs::read_to_string(path).map_err(SimError::io(format!("cannot read scenario '{}'", path.display())))?;
+        let json = Json::parse(&text).map_err(|e| SimError::invalid_scenario(path, e))?;
+        Scenario::from_json_with_defaults(&json, base).map_err(|e| SimError::invalid_scenario(path, e))
+    }
+
+    // Built-in engagements with fixed geometry, speeds and target behavior, see PRESETS
+    pub fn preset(name: &str) -> Option<Scenario> {
+        let builder = Simulation::builder();
+        let builder = match name {
+            // Target flies straight at the interceptor, which waits at the same height
+            "head-on" => builder.target((0.0, 30.0), 2.0).interceptor((120.0, 30.0), 2.5).evasion_noise(2.0),
+            // Interceptor starts behind and slightly below the target and has to run it down
+            "tail-chase" => builder.target((20.0, 30.0), 2.0).interceptor((0.0, 26.0), 3.0).evasion_noise(2.0),
+            // Ground launch from under the target's path, meeting it at right angles
+            "crossing" => builder.target((0.0, 30.0), 2.0).interceptor((50.0, 0.0), 2.5),
+            // Target enters high and dives towards 20 m under a stiff altitude hold
+            "high-altitude-dive" => builder
+                .target((0.0, 120.0), 3.0)
+                .interceptor((80.0, 0.0), 3.5)
+                .altitude_hold(20.0, 0.03, 0.8)
+                .evasion_noise(3.0),
+            // Fast, low target holding 5 m with little jitter
+            "sea-skimmer" => builder
+                .target((0.0, 5.0), 2.5)
+                .interceptor((90.0, 0.0), 3.0)
+                .altitude_hold(5.0, 0.4, 0.9)
+                .evasion_noise(3.0),
+            // The sea-skimmer pulling up for a terminal dive onto the ship it attacks
+            "sea-skimmer-pop-up" => builder
+                .target((0.0, 5.0), 2.5)
+                .interceptor((90.0, 0.0), 3.0)
+                .altitude_hold(5.0, 0.4, 0.9)
+                .evasion_noise(3.0)
+                .target_script(Some(Arc::new(TargetScript::parse(POP_UP).expect("built-in scripts parse")))),
+            // Target closing level at 40 m that corkscrews once the interceptor is near
+            "terminal-spiral" => builder
+                .target((0.0, 40.0), 2.5)
+                .interceptor((110.0, 10.0), 3.0)
+                .altitude_hold(40.0, 0.2, 0.8)
+                .evasion_noise(2.0)
+                .target_script(Some(Arc::new(TargetScript::parse(SPIRAL).expect("built-in scripts parse")))),
+            _ => return None,
+        };
+        let config = builder.validated_config().expect("built-in presets are valid");
+        Some(Scenario { config, ..Scenario::default() })
+    }
+
+    pub fn from_json_with_defaults(json: &Json, base: Scenario) -> Result<Scenario, String> {
+        let config = match json.get("config") {
+            Some(config) => config_with_defaults(config, base.config)
+                .map_err(|e| format!("config: {}", e))?
+                .validated_config()
+                .map_err(|violations| violations.within("config").to_string())?,
+            None => base.config,
+        };
+        let style = match json.get("style") {
+            Some(style) => PlotStyle::from_json(style).map_err(|e| format!("style: {}", e))?,
+            None => base.style,
+        };
+        let envelope = match json.get("envelope") {
+            Some(envelope) => envelope_with_defaults(envelope, base.envelope).map_err(|e| format!("envelope: {}", e))?,
+            None => base.envelope,
+        };
+        let costs = match json.get("costs") {
+            Some(costs) => UnitCosts::from_json(costs).map_err(|e| format!("costs: {}", e))?,
+            None => base.costs,
+        };
+        let raid = match json.get("raid") {
+            Some(raid) => Some(Raid::from_json(raid).map_err(|e| format!("raid: {}", e))?),
+            None => base.raid,
+        };
+        if config.target_path.is_some() && !envelope.is_empty() {
+            return Err("envelope: the target's initial conditions come from config.target_path".into());
+        }
+        Ok(Scenario { config, style, envelope, costs, raid })
+    }
+}
+
+impl FromJson for Scenario {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Scenario::from_json_with_defaults(json, Scenario::default())
+    }
+}
+
+// Re-reads the --scenario file whenever it changes on disk, so that gains, noise and the other
+// parameters that only affect the steps still to come can be edited while a run is watched live.
+// Start positions, max_steps and dt are fixed for a run and not taken over.
+pub struct ScenarioWatcher {
+    path: PathBuf,
+    base: Scenario, // What the file is applied on, i.e. the --preset
+    config: SimConfig,
+    modified: (Option<SystemTime>, Option<SystemTime>), // Of the file and of the target script it names
+    last_check: Instant,
+}
+
+impl ScenarioWatcher {
+    // None without --scenario
+    pub fn from_args(args: &[String]) -> Result<Option<ScenarioWatcher>, SimError> {
+        let Some(pair) = args.windows(2).find(|pair| pair[0] == "--scenario") else {
+            return Ok(None);
+        };
+        let path = PathBuf::from(&pair[1]);
+        let base = Scenario::preset_from_args(args)?;
+        let config = Scenario::load(&path, base.clone())?.config;
+        let modified = modification_times(&path, &config);
+        Ok(Some(ScenarioWatcher { path, base, config, modified, last_check: Instant::now() }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Loads the file again if it or its target script was modified since the last call (checked
+    // every WATCH_INTERVAL at most) and returns whether it did. On an error the previous parameters
+    // stay in effect.
+    pub fn reload(&mut self) -> Result<bool, SimError> {
+        if self.last_check.elapsed() < WATCH_INTERVAL {
+            return Ok(false);
+        }
+        self.last_check = Instant::now();
+        let modified = modification_times(&self.path, &self.config);
+        if modified == self.modified {
+            return Ok(false);
+        }
+        self.modified = modified;
+        self.config = Scenario::load(&self.path, self.base.clone())?.config;
+        // The file may name another script now
+        self.modified = modification_times(&self.path, &self.config);
+        Ok(true)
+    }
+
+    // `config` with the live-editable parameters as last loaded from the file
+    pub fn apply(&self, config: &SimConfig) -> SimConfig {
+        SimConfig {
+            interceptor_speed: self.config.interceptor_speed,
+            guidance: self.config.guidance,
+            guidance_plugin: self.config.guidance_plugin.clone(),
+            guidance_model: self.config.guidance_model.clone(),
+            navigation_constant: self.config.navigation_constant,
+            lqr_weights: self.config.lqr_weights,
+            mpc: self.config.mpc,
+            clos_lookahead: self.config.clos_lookahead,
+            terminal_time_to_go: self.config.terminal_time_to_go,
+            autopilot_tau: self.config.autopilot_tau,
+            actuator: self.config.actuator,
+            faults: self.config.faults,
+            atmosphere: self.config.atmosphere,
+            keep_out: self.config.keep_out.clone(),
+            collision_threshold: self.config.collision_threshold,
+            fuze: self.config.fuze,
+            target_initial_height: self.config.target_initial_height,
+            correction_weight: self.config.correction_weight,
+            p_gain: self.config.p_gain,
+            p_gain_schedule: self.config.p_gain_schedule,
+            target_script: self.config.target_script.clone(),
+            optimal_evasion: self.config.optimal_evasion,
+            evasion_model: self.config.evasion_model.clone(),
+            noise_range_deg: self.config.noise_range_deg,
+            noise_model: self.config.noise_model,
+            interceptor_noise_deg: self.config.interceptor_noise_deg,
+            interceptor_noise_model: self.config.interceptor_noise_model,
+            ..config.clone()
+        }
+    }
+}
+
+fn modification_times(path: &Path, config: &SimConfig) -> (Option<SystemTime>, Option<SystemTime>) {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    (modified(path), config.target_script.as_deref().and_then(|script| script.file()).and_then(modified))
+}
+
+pub const PRESETS: [&str; 7] = ["head-on", "tail-chase", "crossing", "high-altitude-dive", "sea-skimmer", "sea-skimmer-pop-up", "terminal-spiral"];
+
+// Like SimConfig::from_json, but fields missing from the file are taken from `base`. The builder is
+// validated by the caller, which reports the violations under their paths in the file.
+fn config_with_defaults(json: &Json, base: SimConfig) -> Result<SimulationBuilder, String> {
+    let frame = match json.get("frame") {
+        Some(Json::Null) => None,
+        Some(frame) => Some(Frame::from_json(frame).map_err(|e| format!("frame: {}", e))?),
+        None => base.frame,
+    };
+    // [x, y] in the simulation plane, or with a frame a geographic position or an offset from its origin
+    let point_or = |key: &str, default: (f64, f64)| -> Result<(f64, f64), String> {
+        let Some(point) = json.get(key) else { return Ok(default) };
+        if let Some(point) = point.as_point() {
+            return Ok(point);
+        }
+        let frame = frame.ok_or_else(|| format!("field '{}' must be [x, y], or a geographic position with a 'frame'", key))?;
+        let (point, off_plane) = frame.position_from_json(point).map_err(|e| format!("{}: {}", key, e))?;
+        if off_plane.abs() > 1.0 {
+            log::info!("📍 {} lies {:.1} m off the engagement plane, which the 2D simulation drops", key, off_plane);
+        }
+        Ok(point)
+    };
+    let max_steps = match json.get("max_steps") {
+        Some(steps) => steps.as_usize().ok_or("field 'max_steps' must be a non-negative integer")?,
+        None => base.max_steps,
+    };
+    let guidance = match json.get("guidance") {
+        Some(law) => law.as_str().and_then(GuidanceLaw::parse).ok_or_else(|| format!("field 'guidance' must be one of: {}", GuidanceLaw::NAMES))?,
+        None => base.guidance,
+    };
+    // null switches a schedule inherited from the preset off
+    let p_gain_schedule = match json.get("p_gain_schedule") {
+        Some(Json::Null) => None,
+        Some(schedule) => Some(GainSchedule::from_json(schedule).map_err(|e| format!("p_gain_schedule: {}", e))?),
+        None => base.p_gain_schedule,
+    };
+    // A CSV file, or the samples inline as a result file stores them
+    let target_path = match json.get("target_path") {
+        Some(Json::Null) => None,
+        Some(Json::String(file)) => Some(TargetPath::load(Path::new(file)).map_err(|e| format!("target_path: {}", e))?),
+        Some(samples) => Some(Arc::new(TargetPath::from_json(samples).map_err(|e| format!("target_path: {}", e))?)),
+        None => base.target_path.clone(),
+    };
+    let guidance_plugin = match json.get("guidance_plugin") {
+        Some(Json::Null) => None,
+        Some(Json::String(file)) => Some(GuidancePlugin::load(Path::new(file)).map_err(|e| format!("guidance_plugin: {}", e))?),
+        Some(_) => return Err("field 'guidance_plugin' must be a file name".into()),
+        None => base.guidance_plugin.clone(),
+    };
+    let guidance_model = match json.get("guidance_model") {
+        Some(Json::Null) => None,
+        Some(Json::String(file)) => Some(NeuralGuidance::load(Path::new(file)).map_err(|e| format!("guidance_model: {}", e))?),
+        Some(_) => return Err("field 'guidance_model' must be a file name".into()),
+        None => base.guidance_model.clone(),
+    };
+    // A script file, or its source inline as a result file stores it
+    let target_script = match json.get("target_script") {
+        Some(Json::Null) => None,
+        Some(Json::String(file)) => Some(TargetScript::load(Path::new(file)).map_err(|e| format!("target_script: {}", e))?),
+        Some(script) => Some(Arc::new(TargetScript::from_json(script).map_err(|e| format!("target_script: {}", e))?)),
+        None => base.target_script.clone(),
+    };
+    let evasion_model = match json.get("evasion_model") {
+        Some(Json::Null) => None,
+        Some(Json::String(file)) => Some(NeuralGuidance::load(Path::new(file)).map_err(|e| format!("evasion_model: {}", e))?),
+        Some(_) => return Err("field 'evasion_model' must be a file name".into()),
+        None => base.evasion_model.clone(),
+    };
+    let optimal_evasion = match json.get("optimal_evasion") {
+        Some(Json::Null) => None,
+        Some(evasion) => Some(OptimalEvasion::from_json(evasion).map_err(|e| format!("optimal_evasion: {}", e))?),
+        None => base.optimal_evasion,
+    };
+    let actuator = match json.get("actuator") {
+        Some(Json::Null) => None,
+        Some(actuator) => Some(Actuator::from_json(actuator).map_err(|e| format!("actuator: {}", e))?),
+        None => base.actuator,
+    };
+    let faults = match json.get("faults") {
+        Some(Json::Null) => None,
+        Some(faults) => Some(FaultSchedule::from_json(faults).map_err(|e| format!("faults: {}", e))?),
+        None => base.faults,
+    };
+    let atmosphere = match json.get("atmosphere") {
+        Some(Json::Null) => None,
+        Some(atmosphere) => Some(Atmosphere::from_json(atmosphere).map_err(|e| format!("atmosphere: {}", e))?),
+        None => base.atmosphere,
+    };
+    let termination = match json.get("termination") {
+        Some(Json::Null) => TerminationCriteria::default(),
+        Some(termination) => TerminationCriteria::from_json(termination).map_err(|e| format!("termination: {}", e))?,
+        None => base.termination,
+    };
+    let keep_out = match json.get("keep_out") {
+        Some(Json::Null) => None,
+        Some(keep_out) => Some(Arc::new(KeepOut::from_json(keep_out).map_err(|e| format!("keep_out: {}", e))?)),
+        None => base.keep_out.clone(),
+    };
+    let terrain = match json.get("terrain") {
+        Some(Json::Null) => None,
+        Some(terrain) => Some(Arc::new(Terrain::from_json(terrain).map_err(|e| format!("terrain: {}", e))?)),
+        None => base.terrain.clone(),
+    };
+    let fuze = match json.get("fuze") {
+        Some(Json::Null) => None,
+        Some(fuze) => Some(Fuze::from_json(fuze).map_err(|e| format!("fuze: {}", e))?),
+        None => base.fuze,
+    };
+    let tracking = match json.get("tracking") {
+        Some(Json::Null) => None,
+        Some(tracking) => Some(Tracking::from_json(tracking).map_err(|e| format!("tracking: {}", e))?),
+        None => base.tracking,
+    };
+    Ok(Simulation::builder()
+        .config(base.clone())
+        .target(
+            point_or("target_start", (base.target_start_x, base.target_start_y))?,
+            json.f64_field_or("target_speed", base.target_speed)?,
+        )
+        .target_heading(json.f64_field_or("target_heading_deg", base.target_heading_deg)?)
+        .target_path(target_path)
+        .target_script(target_script)
+        .optimal_evasion(optimal_evasion)
+        .evasion_model(evasion_model)
+        .frame(frame)
+        .interceptor(
+            point_or("interceptor_start", (base.interceptor_start_x, base.interceptor_start_y))?,
+            json.f64_field_or("interceptor_speed", base.interceptor_speed)?,
+        )
+        .guidance(guidance)
+        .guidance_plugin(guidance_plugin)
+        .guidance_model(guidance_model)
+        .navigation_constant(json.f64_field_or("navigation_constant", base.navigation_constant)?)
+        .lqr_weights(LqrWeights {
+            miss: json.f64_field_or("lqr_miss_weight", base.lqr_weights.miss)?,
+            rate: json.f64_field_or("lqr_rate_weight", base.lqr_weights.rate)?,
+            effort: json.f64_field_or("lqr_effort_weight", base.lqr_weights.effort)?,
+        })
+        .mpc(mpc_settings_from_json(json, base.mpc)?)
+        .ground_station(
+            point_or("ground_station", (base.ground_station_x, base.ground_station_y))?,
+            json.f64_field_or("clos_lookahead", base.clos_lookahead)?,
+        )
+        .terminal_time_to_go(json.f64_field_or("terminal_time_to_go", base.terminal_time_to_go)?)
+        .guidance_rate(match json.get("guidance_rate_hz") {
+            Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("guidance_rate_hz")?),
+            None => base.guidance_rate_hz,
+        })
+        .autopilot_tau(json.f64_field_or("autopilot_tau", base.autopilot_tau)?)
+        .actuator(actuator)
+        .faults(faults)
+        .atmosphere(atmosphere)
+        .termination(termination)
+        .keep_out(keep_out)
+        .terrain(terrain)
+        .altitude_hold(
+            json.f64_field_or("target_initial_height", base.target_initial_height)?,
+            json.f64_field_or("p_gain", base.p_gain)?,
+            json.f64_field_or("correction_weight", base.correction_weight)?,
+        )
+        .p_gain_schedule(p_gain_schedule)
+        .noise(
+            Vehicle::Target,
+            NoiseKind::from_json_fields(json, "noise_model", "noise_tau", base.noise_model)?,
+            json.f64_field_or("noise_range_deg", base.noise_range_deg)?,
+        )
+        .noise(
+            Vehicle::Interceptor,
+            NoiseKind::from_json_fields(json, "interceptor_noise_model", "interceptor_noise_tau", base.interceptor_noise_model)?,
+            json.f64_field_or("interceptor_noise_deg", base.interceptor_noise_deg)?,
+        )
+        .fuze(fuze)
+        .tracking(tracking)
+        .collision_threshold(json.f64_field_or("collision_threshold", base.collision_threshold)?)
+        .max_steps(max_steps)
+        .dt(json.f64_field_or("dt", base.dt)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::ToJson;
+
+    fn apply(text: &str, base: Scenario) -> Result<Scenario, String> {
+        Scenario::from_json_with_defaults(&Json::parse(text).unwrap(), base)
+    }
+
+    #[test]
+    fn every_preset_is_valid_and_unknown_ones_are_reported() {
+        for name in PRESETS {
+            assert!(Scenario::preset(name).is_some(), "{}", name);
+        }
+        assert!(Scenario::preset("dogfight").is_none());
+        let args: Vec<String> = ["--preset", "dogfight"].iter().map(|a| a.to_string()).collect();
+        let error = Scenario::from_args(&args).unwrap_err().to_string();
+        assert!(error.contains("unknown preset 'dogfight'") && error.contains("head-on, tail-chase"), "{}", error);
+    }
+
+    #[test]
+    fn fields_missing_from_the_document_keep_the_base() {
+        let base = Scenario::preset("head-on").unwrap();
+        let scenario = apply(r#"{"config": {"guidance": "zem", "max_steps": 40}}"#, base.clone()).unwrap();
+        assert_eq!((scenario.config.guidance, scenario.config.max_steps), (GuidanceLaw::Zem, 40));
+        let unchanged = SimConfig { guidance: base.config.guidance, max_steps: base.config.max_steps, ..scenario.config.clone() };
+        assert_eq!(unchanged, base.config);
+        // An empty document is the base itself
+        assert_eq!(apply("{}", base.clone()).unwrap().config, base.config);
+    }
+
+    #[test]
+    fn null_switches_an_inherited_setting_off() {
+        let base = Scenario::preset("sea-skimmer-pop-up").unwrap();
+        assert!(base.config.target_script.is_some());
+        assert!(apply(r#"{"config": {"target_script": null}}"#, base).unwrap().config.target_script.is_none());
+    }
+
+    #[test]
+    fn configs_round_trip_through_a_scenario_document() {
+        for name in PRESETS {
+            let config = Scenario::preset(name).unwrap().config;
+            let document = Json::object(vec![("config", config.to_json())]);
+            assert_eq!(Scenario::from_json(&document).unwrap().config, config, "{}", name);
+        }
+    }
+
+    #[test]
+    fn mistakes_are_reported_with_their_path() {
+        let error = apply(r#"{"config": {"guidance": "magic"}}"#, Scenario::default()).unwrap_err();
+        assert_eq!(error, format!("config: field 'guidance' must be one of: {}", GuidanceLaw::NAMES));
+        let error = apply(r#"{"config": {"interceptor_speed": -1}}"#, Scenario::default()).unwrap_err();
+        assert_eq!(error, "config.interceptor_speed: must be positive");
+        let error = apply(r#"{"config": {"guidance_plugin": 3}}"#, Scenario::default()).unwrap_err();
+        assert_eq!(error, "config: field 'guidance_plugin' must be a file name");
+    }
+}
@@ -0,0 +1,102 @@
+// Gain scheduling: instead of one fixed P gain, the gain is interpolated every step from a small
+// table keyed by the current range to the other vehicle or by the closing speed, e.g. in a
+// scenario file
+//
+//   "p_gain_schedule": { "by": "range", "points": [[0, 0.05], [50, 0.2], [200, 0.4]] }
+//
+// Between two points the gain is linear in the key; outside the table it stays at the first or
+// last value.
+
+use crate::json::{FromJson, Json, ToJson};
+
+// Tables are stored inline, so that a GainSchedule is Copy
+pub const MAX_POINTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleKey {
+    Range,        // Distance between the vehicles (m)
+    ClosingSpeed, // Rate at which that distance shrinks (m/s), negative while opening
+}
+
+impl ScheduleKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleKey::Range => "range",
+            ScheduleKey::ClosingSpeed => "closing_speed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<ScheduleKey> {
+        match s {
+            "range" => Some(ScheduleKey::Range),
+            "closing_speed" => Some(ScheduleKey::ClosingSpeed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GainSchedule {
+    pub key: ScheduleKey,
+    points: [(f64, f64); MAX_POINTS], // (key value, gain), ascending by key
+    len: usize,
+}
+
+impl GainSchedule {
+    // Points are (key value, gain) pairs with strictly increasing key values
+    pub fn new(key: ScheduleKey, points: &[(f64, f64)]) -> Result<GainSchedule, String> {
+        if points.is_empty() || points.len() > MAX_POINTS {
+            return Err(format!("gain schedule needs between 1 and {} points", MAX_POINTS));
+        }
+        if points.iter().any(|(k, g)| !k.is_finite() || !g.is_finite()) {
+            return Err(String::from("gain schedule points must be finite numbers"));
+        }
+        if points.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(format!("gain schedule points must be in increasing order of {}", key.as_str()));
+        }
+        let mut table = [(0.0, 0.0); MAX_POINTS];
+        table[..points.len()].copy_from_slice(points);
+        Ok(GainSchedule { key, points: table, len: points.len() })
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points[..self.len]
+    }
+
+    pub fn gain(&self, key_value: f64) -> f64 {
+        let points = self.points();
+        let (first, last) = (points[0], points[points.len() - 1]);
+        if key_value <= first.0 {
+            return first.1;
+        }
+        if key_value >= last.0 {
+            return last.1;
+        }
+        let i = points.partition_point(|(k, _)| *k <= key_value);
+        let ((k0, g0), (k1, g1)) = (points[i - 1], points[i]);
+        g0 + (g1 - g0) * (key_value - k0) / (k1 - k0)
+    }
+}
+
+impl ToJson for GainSchedule {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("by", self.key.as_str().into()),
+            ("points", Json::Array(self.points().iter().map(|&p| p.into()).collect())),
+        ])
+    }
+}
+
+impl FromJson for GainSchedule {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let key = json.field("by")?.as_str().and_then(ScheduleKey::parse).ok_or("schedule 'by' must be \"range\" or \"closing_speed\"")?;
+        let points = json
+            .field("points")?
+            .as_array()
+            .ok_or("schedule 'points' must be an array")?
+            .iter()
+            .map(|p| p.as_point().ok_or("schedule 'points' must contain [key, gain] pairs"))
+            .collect::<Result<Vec<_>, _>>()?;
+        GainSchedule::new(key, &points)
+    }
+}
@@ -0,0 +1,452 @@
+// Scripted target evasion: a small expression language for custom evasion logic that is loaded at
+// run time, so trying a new maneuver doesn't need a rebuild. A script is a list of assignments,
+// one per line or separated by `;`, that must assign `turn`, the degrees the target turns its
+// velocity by this step (positive counter-clockwise, i.e. climbing when flying along +x):
+//
+//   # Weave with a 10 s period, breaking hard once the interceptor is close
+//   weave = 8 * sin(2 * pi * t / 10)
+//   turn = if(range < 15, 25 * sign(los - heading), weave) + 0.5 * hold
+//
+// Each step the script sees the names below and the values it assigned earlier in the same step:
+//
+//   t, dt, step          simulated time (s) at the start of the step, seconds per step, step number
+//   x, y, vx, vy         the target's position and velocity (y the height)
+//   speed, heading       the target's speed and flight-path angle (degrees, 0 = level along +x)
+//   ix, iy, ivx, ivy     the interceptor's position and velocity
+//   range, closing       separation and closing speed
+//   los                  direction from the target to the interceptor (degrees, as heading)
+//   noise, hold          the configured noise and altitude hold turns for this step (degrees)
+//
+// Expressions have numbers, + - * / % ^ (power), comparisons (1 or 0), parentheses, `pi` and the
+// functions sin cos tan asin acos atan atan2 sqrt abs sign floor ceil exp ln min max clamp and
+// if(condition, then, else); trigonometry is in radians, with deg(x) and rad(x) to convert.
+//
+// Scripts are sandboxed by construction: there are no loops, calls out or I/O, names are resolved
+// and arities checked when the script is loaded, and every step evaluates each assignment once. A
+// turn that comes out NaN or infinite is taken as no turn. Nothing carries over between steps, so
+// a script's turn is a function of the current state alone and resumed runs behave the same.
+//
+// Configs share a parsed script through an Arc; a hot reload replaces it with a new one.
+
+use crate::json::{FromJson, Json, ToJson};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// What a script can read, in the order of ScriptInputs::values
+const INPUTS: [&str; 18] =
+    ["t", "dt", "step", "x", "y", "vx", "vy", "speed", "heading", "ix", "iy", "ivx", "ivy", "range", "closing", "los", "noise", "hold"];
+
+// The state a script sees in a step; angles in degrees
+pub struct ScriptInputs {
+    pub t: f64,
+    pub dt: f64,
+    pub step: usize,
+    pub position: (f64, f64),
+    pub velocity: (f64, f64),
+    pub threat_position: (f64, f64),
+    pub threat_velocity: (f64, f64),
+    pub closing: f64,
+    pub noise_deg: f64,
+    pub hold_deg: f64,
+}
+
+impl ScriptInputs {
+    fn values(&self) -> [f64; INPUTS.len()] {
+        let ((x, y), (vx, vy)) = (self.position, self.velocity);
+        let ((ix, iy), (ivx, ivy)) = (self.threat_position, self.threat_velocity);
+        let (speed, heading) = (vx.hypot(vy), vy.atan2(vx).to_degrees());
+        let (range, los) = ((ix - x).hypot(iy - y), (iy - y).atan2(ix - x).to_degrees());
+        [self.t, self.dt, self.step as f64, x, y, vx, vy, speed, heading, ix, iy, ivx, ivy, range, self.closing, los, self.noise_deg, self.hold_deg]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sqrt,
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Exp,
+    Ln,
+    Deg,
+    Rad,
+    Min,
+    Max,
+    Clamp,
+    If,
+}
+
+impl Func {
+    fn parse(name: &str) -> Option<(Func, usize)> {
+        Some(match name {
+            "sin" => (Func::Sin, 1),
+            "cos" => (Func::Cos, 1),
+            "tan" => (Func::Tan, 1),
+            "asin" => (Func::Asin, 1),
+            "acos" => (Func::Acos, 1),
+            "atan" => (Func::Atan, 1),
+            "atan2" => (Func::Atan2, 2),
+            "sqrt" => (Func::Sqrt, 1),
+            "abs" => (Func::Abs, 1),
+            "sign" => (Func::Sign, 1),
+            "floor" => (Func::Floor, 1),
+            "ceil" => (Func::Ceil, 1),
+            "exp" => (Func::Exp, 1),
+            "ln" => (Func::Ln, 1),
+            "deg" => (Func::Deg, 1),
+            "rad" => (Func::Rad, 1),
+            "min" => (Func::Min, 2),
+            "max" => (Func::Max, 2),
+            "clamp" => (Func::Clamp, 3),
+            "if" => (Func::If, 3),
+            _ => return None,
+        })
+    }
+
+    fn apply(self, a: &[f64]) -> f64 {
+        match self {
+            Func::Sin => a[0].sin(),
+            Func::Cos => a[0].cos(),
+            Func::Tan => a[0].tan(),
+            Func::Asin => a[0].asin(),
+            Func::Acos => a[0].acos(),
+            Func::Atan => a[0].atan(),
+            Func::Atan2 => a[0].atan2(a[1]),
+            Func::Sqrt => a[0].sqrt(),
+            Func::Abs => a[0].abs(),
+            Func::Sign => if a[0] == 0.0 { 0.0 } else { a[0].signum() },
+            Func::Floor => a[0].floor(),
+            Func::Ceil => a[0].ceil(),
+            Func::Exp => a[0].exp(),
+            Func::Ln => a[0].ln(),
+            Func::Deg => a[0].to_degrees(),
+            Func::Rad => a[0].to_radians(),
+            Func::Min => a[0].min(a[1]),
+            Func::Max => a[0].max(a[1]),
+            Func::Clamp => a[0].max(a[1]).min(a[2]),
+            Func::If => if a[0] != 0.0 { a[1] } else { a[2] },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl BinOp {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        let truth = |c: bool| if c { 1.0 } else { 0.0 };
+        match self {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+            BinOp::Rem => a % b,
+            BinOp::Pow => a.powf(b),
+            BinOp::Lt => truth(a < b),
+            BinOp::Le => truth(a <= b),
+            BinOp::Gt => truth(a > b),
+            BinOp::Ge => truth(a >= b),
+            BinOp::Eq => truth(a == b),
+            BinOp::Ne => truth(a != b),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Expr {
+    Number(f64),
+    Slot(usize), // An input, or a value assigned before
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, slots: &[f64]) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Slot(i) => slots[*i],
+            Expr::Neg(e) => -e.eval(slots),
+            Expr::Binary(op, a, b) => op.apply(a.eval(slots), b.eval(slots)),
+            // Both branches of if() are evaluated, which is harmless without side effects
+            Expr::Call(func, args) => func.apply(&args.iter().map(|arg| arg.eval(slots)).collect::<Vec<_>>()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(&'static str),
+    End, // Of a statement: a line break or ;
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        i += 1;
+        match c {
+            '#' => break,
+            ';' => tokens.push(Token::End),
+            c if c.is_whitespace() => {}
+            c if c.is_ascii_digit() || c == '.' => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.' || matches!(chars[i], 'e' | 'E') || (matches!(chars[i], '+' | '-') && matches!(chars[i - 1], 'e' | 'E'))) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().collect();
+                tokens.push(Token::Number(text.parse().map_err(|_| format!("'{}' is not a number", text))?));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            _ => {
+                let two: String = chars[start..(start + 2).min(chars.len())].iter().collect();
+                let op = ["<=", ">=", "==", "!="].into_iter().find(|op| *op == two).or_else(|| ["+", "-", "*", "/", "%", "^", "<", ">", "=", "(", ")", ","].into_iter().find(|op| op.starts_with(c)));
+                let op = op.ok_or_else(|| format!("unexpected '{}'", c))?;
+                i = start + op.len();
+                tokens.push(Token::Op(op));
+            }
+        }
+    }
+    tokens.push(Token::End);
+    Ok(tokens)
+}
+
+// Recursive descent over one statement's tokens, resolving names against `names`
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    names: &'a [String],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        self.pos = (self.pos + 1).min(self.tokens.len() - 1);
+        token
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        match self.next() {
+            Token::Op(found) if found == op => Ok(()),
+            _ => Err(format!("expected '{}'", op)),
+        }
+    }
+
+    // Comparisons bind loosest and don't chain
+    fn expression(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+        let op = match self.peek() {
+            Token::Op("<") => BinOp::Lt,
+            Token::Op("<=") => BinOp::Le,
+            Token::Op(">") => BinOp::Gt,
+            Token::Op(">=") => BinOp::Ge,
+            Token::Op("==") => BinOp::Eq,
+            Token::Op("!=") => BinOp::Ne,
+            _ => return Ok(left),
+        };
+        self.next();
+        Ok(Expr::Binary(op, Box::new(left), Box::new(self.sum()?)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        loop {
+            let op = match self.peek() {
+                Token::Op("+") => BinOp::Add,
+                Token::Op("-") => BinOp::Sub,
+                _ => return Ok(left),
+            };
+            self.next();
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Op("*") => BinOp::Mul,
+                Token::Op("/") => BinOp::Div,
+                Token::Op("%") => BinOp::Rem,
+                _ => return Ok(left),
+            };
+            self.next();
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    // -x^2 is -(x^2), as in mathematics
+    fn unary(&mut self) -> Result<Expr, String> {
+        if *self.peek() == Token::Op("-") {
+            self.next();
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    // Right-associative: 2^3^2 is 2^9
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.primary()?;
+        if *self.peek() != Token::Op("^") {
+            return Ok(base);
+        }
+        self.next();
+        Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(self.unary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Op("(") => {
+                let e = self.expression()?;
+                self.expect(")")?;
+                Ok(e)
+            }
+            Token::Name(name) if *self.peek() == Token::Op("(") => {
+                let (func, arity) = Func::parse(&name).ok_or_else(|| format!("unknown function '{}'", name))?;
+                self.next();
+                let mut args = Vec::new();
+                if *self.peek() != Token::Op(")") {
+                    args.push(self.expression()?);
+                    while *self.peek() == Token::Op(",") {
+                        self.next();
+                        args.push(self.expression()?);
+                    }
+                }
+                self.expect(")")?;
+                if args.len() != arity {
+                    return Err(format!("{}() takes {} argument{}, found {}", name, arity, if arity == 1 { "" } else { "s" }, args.len()));
+                }
+                Ok(Expr::Call(func, args))
+            }
+            Token::Name(name) if name == "pi" => Ok(Expr::Number(std::f64::consts::PI)),
+            Token::Name(name) => match self.names.iter().position(|known| *known == name) {
+                Some(slot) => Ok(Expr::Slot(slot)),
+                None => Err(format!("unknown name '{}'", name)),
+            },
+            Token::Op(op) => Err(format!("unexpected '{}'", op)),
+            Token::End => Err("expression ends early".into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TargetScript {
+    source: String,
+    file: Option<PathBuf>,         // Where it was loaded from, which the scenario watcher also watches
+    program: Vec<(usize, Expr)>,   // Assignments in order: slot and value
+    slots: usize,                  // Inputs plus every name assigned
+    turn: usize,                   // Slot of `turn`
+}
+
+impl TargetScript {
+    pub fn parse(source: &str) -> Result<TargetScript, String> {
+        let mut names: Vec<String> = INPUTS.iter().map(|name| name.to_string()).collect();
+        let mut program = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let tokens = tokenize(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            for statement in tokens.split(|token| *token == Token::End).filter(|statement| !statement.is_empty()) {
+                let (slot, value) = assignment(statement, &mut names).map_err(|e| format!("line {}: {}", i + 1, e))?;
+                program.push((slot, value));
+            }
+        }
+        let turn = names.iter().position(|name| name == "turn").filter(|slot| program.iter().any(|(assigned, _)| assigned == slot));
+        let turn = turn.ok_or("the script never assigns 'turn'")?;
+        Ok(TargetScript { source: source.to_string(), file: None, program, slots: names.len(), turn })
+    }
+
+    // The script file at `path`
+    pub fn load(path: &Path) -> Result<Arc<TargetScript>, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        let script = TargetScript::parse(&text).map_err(|e| format!("'{}' {}", path.display(), e))?;
+        Ok(Arc::new(TargetScript { file: Some(path.to_path_buf()), ..script }))
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    // The turn for this step (degrees), 0 if the script's arithmetic broke down
+    pub fn turn_deg(&self, inputs: &ScriptInputs) -> f64 {
+        let mut slots = vec![0.0; self.slots];
+        slots[..INPUTS.len()].copy_from_slice(&inputs.values());
+        for (slot, value) in &self.program {
+            slots[*slot] = value.eval(&slots);
+        }
+        Some(slots[self.turn]).filter(|turn| turn.is_finite()).unwrap_or(0.0)
+    }
+}
+
+// `name = expression`, adding a new name to `names` after its value is parsed, so that a statement
+// can't read the name it introduces
+fn assignment(tokens: &[Token], names: &mut Vec<String>) -> Result<(usize, Expr), String> {
+    let name = match (tokens.first(), tokens.get(1)) {
+        (Some(Token::Name(name)), Some(Token::Op("="))) => name.clone(),
+        _ => return Err("expected an assignment 'name = expression'".into()),
+    };
+    if INPUTS.contains(&name.as_str()) || name == "pi" || Func::parse(&name).is_some() {
+        return Err(format!("'{}' is built in and can't be assigned", name));
+    }
+    let tokens: Vec<Token> = tokens[2..].iter().cloned().chain([Token::End]).collect();
+    let mut parser = Parser { tokens: &tokens, pos: 0, names };
+    let value = parser.expression()?;
+    if parser.pos != tokens.len() - 1 {
+        return Err("unexpected text after the expression".into());
+    }
+    let slot = match names.iter().position(|known| *known == name) {
+        Some(slot) => slot,
+        None => {
+            names.push(name);
+            names.len() - 1
+        }
+    };
+    Ok((slot, value))
+}
+
+// As the source, so that result files replay without the script file
+impl ToJson for TargetScript {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("source", self.source.as_str().into())])
+    }
+}
+
+impl FromJson for TargetScript {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let source = json.get("source").and_then(Json::as_str).ok_or("a target script must be a file name or {\"source\": \"...\"}")?;
+        TargetScript::parse(source)
+    }
+}
@@ -0,0 +1,95 @@
+// Seed lists: with --seeds a batch flies one run per seed of a fixed list instead of --runs runs
+// from one random sequence, every run drawing from a sequence of its own seeded with its seed. A
+// run is then reproduced on its own by `batch --seed SEED` (one run) with the same scenario and options, e.g.
+// with --stream and --events for the full telemetry of a run that failed in a batch. The list is
+//
+//   A..B        the seeds A to B - 1, A..=B to B
+//   S1,S2,...   the seeds given
+//   FILE        one seed per line (blank lines and # comments skipped), or the seeds.csv of an
+//               earlier batch, whose `seed` column is used
+//
+// The batch writes seeds.csv with the outcome of every seed and lists the seeds that missed.
+
+use crate::error::SimError;
+use crate::log;
+use crate::{Outcome, SimulationResult};
+use std::io::Write;
+use std::path::Path;
+
+// --seeds LIST, None without it
+pub fn from_args(args: &[String]) -> Result<Option<Vec<u64>>, SimError> {
+    let Some(pair) = args.windows(2).find(|pair| pair[0] == "--seeds") else {
+        return Ok(None);
+    };
+    let list = pair[1].as_str();
+    let seeds = if let Some((start, end)) = list.split_once("..") {
+        let (end, inclusive) = match end.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (end, false),
+        };
+        let bound = |s: &str| s.trim().parse::<u64>().map_err(|_| format!("--seeds range '{}' must be A..B or A..=B with non-negative integers", list));
+        let (start, end) = (bound(start)?, bound(end)?);
+        match inclusive {
+            true => (start..=end).collect(),
+            false => (start..end).collect(),
+        }
+    } else if let Ok(seeds) = list.split(',').map(|s| s.trim().parse::<u64>()).collect::<Result<Vec<_>, _>>() {
+        seeds
+    } else {
+        let text = std::fs::read_to_string(list).map_err(SimError::io(format!("cannot read seed list '{}'", list)))?;
+        parse(&text).map_err(|e| format!("seed list '{}': {}", list, e))?
+    };
+    if seeds.is_empty() {
+        return Err(format!("--seeds '{}' gives no seeds", list).into());
+    }
+    Ok(Some(seeds))
+}
+
+fn parse(text: &str) -> Result<Vec<u64>, String> {
+    let mut lines = text.lines().enumerate().map(|(i, line)| (i, line.trim())).filter(|(_, line)| !line.is_empty() && !line.starts_with('#')).peekable();
+    // A header names the columns, as in seeds.csv
+    let column = match lines.peek() {
+        Some((_, header)) if header.parse::<u64>().is_err() => {
+            let column = header.split(',').position(|name| name.trim() == "seed").ok_or("the header has no 'seed' column")?;
+            lines.next();
+            column
+        }
+        _ => 0,
+    };
+    lines
+        .map(|(i, line)| {
+            line.split(',').nth(column).and_then(|s| s.trim().parse().ok()).ok_or_else(|| format!("line {}: expected a non-negative integer seed", i + 1))
+        })
+        .collect()
+}
+
+// seeds.csv in `out_dir`: one line per run with its seed and outcome
+pub struct SeedLog {
+    file: std::fs::File,
+    missed: Vec<u64>,
+}
+
+impl SeedLog {
+    pub fn create(out_dir: &str) -> Result<SeedLog, SimError> {
+        let path = Path::new(out_dir).join("seeds.csv");
+        let mut file = std::fs::File::create(&path).map_err(SimError::io(format!("cannot create '{}'", path.display())))?;
+        writeln!(file, "run,seed,outcome,steps,miss_distance,pk")?;
+        Ok(SeedLog { file, missed: Vec::new() })
+    }
+
+    pub fn add(&mut self, run: usize, seed: u64, sim: &SimulationResult) -> Result<(), SimError> {
+        writeln!(self.file, "{},{},{},{},{:.3},{:.3}", run, seed, sim.outcome.as_str(), sim.steps(), sim.miss_distance, sim.pk())?;
+        if sim.outcome != Outcome::Intercept {
+            self.missed.push(seed);
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) {
+        if let Some(seed) = self.missed.first() {
+            let seeds: Vec<String> = self.missed.iter().map(u64::to_string).collect();
+            log::info!("🌱 Seeds without an intercept: {}", seeds.join(", "));
+            log::info!("   `batch --seed {} --stream --events` with the same scenario and options flies one again with its full telemetry", seed);
+        }
+    }
+}
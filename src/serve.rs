@@ -0,0 +1,307 @@
+// Live telemetry over WebSocket: `serve` flies engagements like `batch` and streams them as they
+// happen to every client connected to ws://ADDR (--bind ADDR, default 127.0.0.1:8765), for browser
+// dashboards and other tools that render the engagement live. Runs are paced at wall-clock speed
+// unless --realtime gives another speed-up, and the first one waits for a client to connect.
+//
+// Every message is one JSON text frame with a "type":
+//
+//   {"type": "run", "run": 1, "config": {...}}                     when a run starts (and to a client joining it)
+//   {"type": "step", "run": 1, "step": 12, "time": 12.0, "target": {"position": [x, y], "velocity": [vx, vy]},
+//    "interceptor": {...}, "separation": 3.2}                      after every step
+//   {"type": "event", "run": 1, "event": {"type": "lock_acquired", "step": 3, ...}}  as the event log grows
+//   {"type": "end", "run": 1, "outcome": "intercept", "steps": 42, "miss_distance": 0.4, "pk": 0.98}
+//
+// Clients only listen; whatever they send is ignored, and one that can't keep up or goes away is
+// dropped. The handshake and framing are the minimal server side of RFC 6455, on std::net alone.
+// A plain HTTP request to the same address gets web/viewer.html, a page that connects back and
+// animates the runs on a canvas.
+
+use crate::error::SimError;
+use crate::json::{Json, ToJson};
+use crate::log;
+use crate::simulation::SimObserver;
+use crate::{SimConfig, SimEvent, SimulationResult};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8765";
+
+// Appended to the client's key for the handshake's accept value (RFC 6455, section 1.3)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const VIEWER: &str = include_str!("../web/viewer.html");
+
+// A client that blocks a frame this long is dropped rather than holding up the run
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct Clients {
+    streams: Vec<TcpStream>,
+    greeting: Option<Vec<u8>>, // The current run's start frame, for clients joining it
+}
+
+impl Clients {
+    // Sends `frame` to every client, dropping those it fails for
+    fn broadcast(&mut self, frame: &[u8]) {
+        self.streams.retain_mut(|stream| stream.write_all(frame).is_ok());
+    }
+}
+
+pub struct TelemetryServer {
+    addr: String,
+    clients: Arc<Mutex<Clients>>,
+    run: usize,
+    sent: usize, // Steps sent of the current run
+}
+
+impl TelemetryServer {
+    // Listens on `addr` and accepts clients in the background from then on
+    pub fn bind(addr: &str) -> Result<TelemetryServer, SimError> {
+        let listener = TcpListener::bind(addr).map_err(SimError::io(format!("cannot listen on '{}'", addr)))?;
+        let addr = listener.local_addr().map_or_else(|_| addr.to_string(), |a| a.to_string());
+        let clients = Arc::new(Mutex::new(Clients::default()));
+        let accepted = Arc::clone(&clients);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                match handshake(&stream) {
+                    Ok(()) => {
+                        let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                        let _ = stream.set_nodelay(true);
+                        let peer = stream.peer_addr().map_or_else(|_| String::from("a client"), |a| a.to_string());
+                        log::debug!("📡 {} connected", peer);
+                        let mut clients = accepted.lock().unwrap();
+                        let mut stream = stream;
+                        if clients.greeting.as_ref().is_none_or(|greeting| stream.write_all(greeting).is_ok()) {
+                            clients.streams.push(stream);
+                        }
+                    }
+                    Err(e) => log::debug!("📡 rejected a connection: {}", e),
+                }
+            }
+        });
+        log::info!("📡 Serving live telemetry on ws://{}", addr);
+        Ok(TelemetryServer { addr, clients, run: 0, sent: 0 })
+    }
+
+    // serve's --bind ADDR
+    pub fn from_args(args: &[String]) -> Result<TelemetryServer, SimError> {
+        let addr = args.windows(2).find(|pair| pair[0] == "--bind").map_or(DEFAULT_ADDR, |pair| pair[1].as_str());
+        TelemetryServer::bind(addr)
+    }
+
+    // Blocks until at least one client is connected
+    pub fn wait_for_client(&self) {
+        if self.clients.lock().unwrap().streams.is_empty() {
+            log::info!("⏳ Waiting for a client on ws://{}", self.addr);
+            while self.clients.lock().unwrap().streams.is_empty() {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        }
+    }
+
+    // Announces run `run` flown with `config`; call before it is observed
+    pub fn start_run(&mut self, run: usize, config: &SimConfig) {
+        self.run = run;
+        self.sent = 0;
+        let frame = text_frame(&Json::object(vec![("type", "run".into()), ("run", run.into()), ("config", config.to_json())]));
+        let mut clients = self.clients.lock().unwrap();
+        clients.broadcast(&frame);
+        clients.greeting = Some(frame);
+    }
+
+    // Closes every connection, once the last run has been sent
+    pub fn close(self) {
+        let mut clients = self.clients.lock().unwrap();
+        clients.greeting = None;
+        clients.broadcast(&[0x88, 0x00]);
+        clients.streams.clear();
+    }
+
+    // A message of type `kind` about the current run
+    fn send(&self, kind: &str, message: Vec<(&str, Json)>) {
+        let fields = [("type", kind.into()), ("run", self.run.into())].into_iter().chain(message).collect::<Vec<_>>();
+        self.clients.lock().unwrap().broadcast(&text_frame(&Json::object(fields)));
+    }
+
+    // The steps recorded since the last call that are still in memory
+    fn send_new(&mut self, sim: &SimulationResult) {
+        for step in self.sent.max(sim.first_step)..=sim.steps() {
+            self.send("step", step_fields(sim, step));
+        }
+        self.sent = sim.steps() + 1;
+    }
+}
+
+// The state after `step`, which must still be in memory, as in a "step" message. Also what the
+// MQTT publisher sends (see mqtt.rs).
+pub fn step_fields(sim: &SimulationResult, step: usize) -> Vec<(&'static str, Json)> {
+    let vehicle = |position: (f64, f64), velocity: (f64, f64)| Json::object(vec![("position", position.into()), ("velocity", velocity.into())]);
+    let k = step - sim.first_step;
+    let (target, interceptor) = (sim.target_positions[k], sim.interceptor_positions[k]);
+    vec![
+        ("step", step.into()),
+        ("time", (step as f64 * sim.config.dt).into()),
+        ("target", vehicle(target, sim.target_velocities[k])),
+        ("interceptor", vehicle(interceptor, sim.interceptor_velocities[k])),
+        ("separation", (target.0 - interceptor.0).hypot(target.1 - interceptor.1).into()),
+    ]
+}
+
+// How the run ended, as in an "end" message
+pub fn end_fields(sim: &SimulationResult) -> Vec<(&'static str, Json)> {
+    vec![
+        ("outcome", sim.outcome.as_str().into()),
+        ("steps", sim.steps().into()),
+        ("miss_distance", sim.miss_distance.into()),
+        ("pk", sim.pk().into()),
+    ]
+}
+
+impl SimObserver for TelemetryServer {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        self.send_new(sim);
+    }
+
+    fn on_event(&mut self, event: &SimEvent, _sim: &SimulationResult) {
+        self.send("event", vec![("event", event.to_json())]);
+    }
+
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        self.send_new(sim);
+        self.send("end", end_fields(sim));
+    }
+}
+
+// Reads the client's upgrade request and accepts it; a page request is answered with the viewer
+fn handshake(stream: &TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut key = None;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+    let mut stream = stream;
+    let Some(key) = key else {
+        write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", VIEWER.len(), VIEWER)?;
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "not a WebSocket upgrade request, sent the viewer"));
+    };
+    write!(stream, "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n", accept_key(&key))
+}
+
+// Sec-WebSocket-Accept for the client's Sec-WebSocket-Key
+fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, WEBSOCKET_GUID).as_bytes()))
+}
+
+// An unmasked, unfragmented text frame, as a server sends them
+fn text_frame(message: &Json) -> Vec<u8> {
+    let payload = message.to_string().into_bytes();
+    let mut frame = vec![0x81];
+    match payload.len() {
+        n if n < 126 => frame.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            frame.push(127);
+            frame.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    frame.extend(payload);
+    frame
+}
+
+// SHA-1, which the handshake needs and nothing else (so no security rests on it)
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(wi);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t);
+        }
+        for (h, x) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(x);
+        }
+    }
+    let mut digest = [0u8; 20];
+    for (bytes, word) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            text.push(match i <= chunk.len() {
+                true => ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char,
+                false => '=',
+            });
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha1_matches_the_fips_180_examples() {
+        assert_eq!(hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        // Two blocks, as the padding doesn't fit after 56 bytes
+        assert_eq!(hex(&sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")), "84983e441c3bd26ebaae4aa1f95129e5e54670f1");
+    }
+
+    #[test]
+    fn base64_matches_the_rfc_4648_examples() {
+        for (data, text) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")] {
+            assert_eq!(base64(data.as_bytes()), text);
+        }
+    }
+
+    #[test]
+    fn the_accept_key_is_the_one_of_rfc_6455() {
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+}
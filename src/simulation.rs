@@ -0,0 +1,673 @@
+// The engagement loop. A Simulation owns the result recorded so far and is advanced by its owner
+// in chunks of steps, which is what checkpointing and the live views build on. Observers
+// registered with `observe` are told about every step, every event as it is logged and the
+// finished run, so viewers and loggers can follow along without touching the loop. A step is the
+// systems of systems.rs run over the engagement's entities (world.rs).
+//
+// Engagements are assembled with the builder, which checks the parameters before anything runs:
+//
+//   let sim = Simulation::builder()
+//       .target((0.0, 30.0), 2.0)
+//       .interceptor((10.0, 0.0), 2.5)
+//       .guidance(GuidanceLaw::LeadPursuit)
+//       .max_steps(500)
+//       .dt(0.5)
+//       .build()?;
+
+use crate::actuator::Actuator;
+use crate::atmosphere::Atmosphere;
+use crate::error::SimError;
+use crate::evasion::OptimalEvasion;
+use crate::faults::FaultSchedule;
+use crate::termination::TerminationCriteria;
+use crate::terrain::Terrain;
+use crate::feed::TargetFeed;
+use crate::frames::Frame;
+use crate::fusion::Tracker;
+use crate::fuze::Fuze;
+use crate::guidance::{LqrWeights, MpcSettings, MPC_MAX_EVALUATIONS};
+use crate::log;
+use crate::noise::NoiseKind;
+use crate::schedule::GainSchedule;
+use crate::systems::{StepContext, SYSTEMS};
+use crate::tracking::Tracking;
+use crate::trajectory::TargetPath;
+use crate::script::TargetScript;
+use crate::plugin::GuidancePlugin;
+use crate::onnx::NeuralGuidance;
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+use crate::zones::KeepOut;
+use crate::world::World;
+use crate::{GuidanceLaw, Interceptor, Outcome, SimConfig, SimEvent, SimulationResult, Target, Vehicle};
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+pub trait SimObserver {
+    // After every step, with everything recorded so far; the outcome is only final in on_finish
+    fn on_step(&mut self, _sim: &SimulationResult) {}
+
+    // For every entry added to the event log, in order
+    fn on_event(&mut self, _event: &SimEvent, _sim: &SimulationResult) {}
+
+    // Once, when the run has ended
+    fn on_finish(&mut self, _sim: &SimulationResult) {}
+}
+
+// Registered observers, and how much of the event log they have already seen
+#[derive(Default)]
+struct Observers<'a> {
+    list: Vec<&'a mut dyn SimObserver>,
+    notified_events: usize,
+}
+
+impl Observers<'_> {
+    fn events(&mut self, sim: &SimulationResult) {
+        for event in &sim.events[self.notified_events..] {
+            for observer in self.list.iter_mut() {
+                observer.on_event(event, sim);
+            }
+        }
+        self.notified_events = sim.events.len();
+    }
+
+    fn step(&mut self, sim: &SimulationResult) {
+        for observer in self.list.iter_mut() {
+            observer.on_step(sim);
+        }
+    }
+
+    fn finish(&mut self, sim: &SimulationResult) {
+        for observer in self.list.iter_mut() {
+            observer.on_finish(sim);
+        }
+    }
+}
+
+// Where a run stands after a call to step or advance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    Running,           // More steps to go
+    Finished(Outcome), // Ended (see SimulationResult::termination for why); further calls do nothing
+}
+
+impl StepOutcome {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, StepOutcome::Finished(_))
+    }
+}
+
+// Longest run a configuration may ask for; every step is kept in memory unless --window is given
+pub const MAX_STEPS: usize = 1_000_000;
+
+pub struct Simulation<'a> {
+    result: SimulationResult,
+    observers: Observers<'a>,
+    window: Option<usize>, // Samples kept in memory, None for the whole run
+    feed: Option<&'a mut TargetFeed>,
+    command: Option<(Vehicle, f64)>, // Lateral acceleration commanded from outside for the next step
+    tracker: Option<Tracker>,        // The track as the last advance left it, None to pick it up from the result
+}
+
+// Parameters not set on the builder keep their SimConfig::default() value
+#[derive(Debug, Clone, Default)]
+pub struct SimulationBuilder {
+    config: SimConfig,
+}
+
+impl SimulationBuilder {
+    // Starts over from a complete configuration, e.g. one loaded from a scenario file
+    pub fn config(mut self, config: SimConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Start position and speed; the target starts out flying level in +x
+    pub fn target(mut self, start: (f64, f64), speed: f64) -> Self {
+        (self.config.target_start_x, self.config.target_start_y) = start;
+        self.config.target_speed = speed;
+        self
+    }
+
+    // Direction the target starts flying in, 0° = level along +x
+    pub fn target_heading(mut self, degrees: f64) -> Self {
+        self.config.target_heading_deg = degrees;
+        self
+    }
+
+    pub fn interceptor(mut self, start: (f64, f64), speed: f64) -> Self {
+        (self.config.interceptor_start_x, self.config.interceptor_start_y) = start;
+        self.config.interceptor_speed = speed;
+        self
+    }
+
+    pub fn guidance(mut self, law: GuidanceLaw) -> Self {
+        self.config.guidance = law;
+        self
+    }
+
+    // The module GuidanceLaw::Plugin runs, see plugin.rs
+    pub fn guidance_plugin(mut self, plugin: Option<Arc<GuidancePlugin>>) -> Self {
+        self.config.guidance_plugin = plugin;
+        self
+    }
+
+    // The network GuidanceLaw::Neural runs, see onnx.rs
+    pub fn guidance_model(mut self, model: Option<Arc<NeuralGuidance>>) -> Self {
+        self.config.guidance_model = model;
+        self
+    }
+
+    // Gain of the laws that command acceleration proportional to a miss (ZEM)
+    pub fn navigation_constant(mut self, n: f64) -> Self {
+        self.config.navigation_constant = n;
+        self
+    }
+
+    // The target's P controller towards `height`, blended with the evasion noise by `weight`
+    // (0 = pure noise, 1 = pure altitude hold)
+    pub fn altitude_hold(mut self, height: f64, p_gain: f64, weight: f64) -> Self {
+        self.config.target_initial_height = height;
+        self.config.p_gain = p_gain;
+        self.config.correction_weight = weight;
+        self
+    }
+
+    // Interpolates the altitude hold's P gain from a table instead of using the fixed gain; None
+    // goes back to the fixed gain
+    pub fn p_gain_schedule(mut self, schedule: Option<GainSchedule>) -> Self {
+        self.config.p_gain_schedule = schedule;
+        self
+    }
+
+    // The target turns by a uniform random angle in ±range_deg every step
+    pub fn evasion_noise(mut self, range_deg: f64) -> Self {
+        self.config.noise_range_deg = range_deg;
+        self
+    }
+
+    // Random heading deviation of one vehicle: the target's evasion jitter or noise on the
+    // interceptor's commanded heading; `amplitude_deg` is the range for uniform noise and the
+    // standard deviation for the other models
+    pub fn noise(mut self, vehicle: Vehicle, model: NoiseKind, amplitude_deg: f64) -> Self {
+        match vehicle {
+            Vehicle::Target => (self.config.noise_model, self.config.noise_range_deg) = (model, amplitude_deg),
+            Vehicle::Interceptor => (self.config.interceptor_noise_model, self.config.interceptor_noise_deg) = (model, amplitude_deg),
+        }
+        self
+    }
+
+    // Costs of the LQR law: lateral miss at the end, lateral rate and acceleration along the way
+    pub fn lqr_weights(mut self, weights: LqrWeights) -> Self {
+        self.config.lqr_weights = weights;
+        self
+    }
+
+    // Search space and cost of the MPC law
+    pub fn mpc(mut self, settings: MpcSettings) -> Self {
+        self.config.mpc = settings;
+        self
+    }
+
+    // Where the CLOS beam starts, and how far ahead along it (in seconds of flight) the law aims
+    pub fn ground_station(mut self, position: (f64, f64), lookahead: f64) -> Self {
+        (self.config.ground_station_x, self.config.ground_station_y) = position;
+        self.config.clos_lookahead = lookahead;
+        self
+    }
+
+    // Time-to-go in seconds below which guidance switches to its terminal phase; 0 never does
+    pub fn terminal_time_to_go(mut self, seconds: f64) -> Self {
+        self.config.terminal_time_to_go = seconds;
+        self
+    }
+
+    // Guidance updates per second, like a flight computer's frame rate: the law runs every
+    // 1 / (rate · dt) physics steps (rounded) and its command is held in between. None updates
+    // every step.
+    pub fn guidance_rate(mut self, rate_hz: Option<f64>) -> Self {
+        self.config.guidance_rate_hz = rate_hz;
+        self
+    }
+
+    // Time constant in seconds of the interceptor's autopilot: the acceleration it achieves follows
+    // the guidance command with a first-order lag. 0 follows the command at once.
+    pub fn autopilot_tau(mut self, seconds: f64) -> Self {
+        self.config.autopilot_tau = seconds;
+        self
+    }
+
+    // Saturation, noise and intermittent faults of the interceptor's actuators; None makes them ideal
+    pub fn actuator(mut self, actuator: Option<Actuator>) -> Self {
+        self.config.actuator = actuator;
+        self
+    }
+
+    // Failures at scheduled times of the flight, see faults.rs
+    pub fn faults(mut self, faults: Option<FaultSchedule>) -> Self {
+        self.config.faults = faults;
+        self
+    }
+
+    // Air density by altitude limiting the interceptor's turns and slowing it by drag; None flies
+    // it at constant speed, see atmosphere.rs
+    pub fn atmosphere(mut self, atmosphere: Option<Atmosphere>) -> Self {
+        self.config.atmosphere = atmosphere;
+        self
+    }
+
+    // Criteria ending the run besides intercepts, ground impacts and max_steps, see termination.rs
+    pub fn termination(mut self, criteria: TerminationCriteria) -> Self {
+        self.config.termination = criteria;
+        self
+    }
+
+    // Zones guidance steers the interceptor around, flagging it when it enters one; see zones.rs
+    pub fn keep_out(mut self, keep_out: Option<Arc<KeepOut>>) -> Self {
+        self.config.keep_out = keep_out;
+        self
+    }
+
+    // The ground's height profile instead of flat ground at 0, which also masks the sensors and can
+    // be followed by the target; see terrain.rs
+    pub fn terrain(mut self, terrain: Option<Arc<Terrain>>) -> Self {
+        self.config.terrain = terrain;
+        self
+    }
+
+    // Fly the target along a recorded path instead of the evasion; None goes back to the evasion.
+    // Its start, speed and heading are those of the path's first segment.
+    pub fn target_path(mut self, path: Option<Arc<TargetPath>>) -> Self {
+        if let Some(path) = &path {
+            let (start, velocity) = (path.position(0.0), path.velocity(0.0));
+            (self.config.target_start_x, self.config.target_start_y) = start.into();
+            self.config.target_speed = velocity.length();
+            self.config.target_heading_deg = velocity.y.atan2(velocity.x).to_degrees();
+        }
+        self.config.target_path = path;
+        self
+    }
+
+    // Turn the target by a script's command each step instead of the noise and altitude hold; None
+    // goes back to them
+    pub fn target_script(mut self, script: Option<Arc<TargetScript>>) -> Self {
+        self.config.target_script = script;
+        self
+    }
+
+    // Turn the target by the worst-case manoeuvre against the interceptor instead of the noise and
+    // altitude hold; None goes back to them
+    pub fn optimal_evasion(mut self, evasion: Option<OptimalEvasion>) -> Self {
+        self.config.optimal_evasion = evasion;
+        self
+    }
+
+    // Turn the target by a learned policy's command each step, with the roles of guidance_model
+    // swapped (see onnx.rs), instead of the noise and altitude hold; None goes back to them
+    pub fn evasion_model(mut self, model: Option<Arc<NeuralGuidance>>) -> Self {
+        self.config.evasion_model = model;
+        self
+    }
+
+    // Where the simulation plane lies on the earth, for geographic positions and reports
+    pub fn frame(mut self, frame: Option<Frame>) -> Self {
+        self.config.frame = frame;
+        self
+    }
+
+    // Proximity fuze that ends the run instead of the collision threshold; None goes back to the threshold
+    pub fn fuze(mut self, fuze: Option<Fuze>) -> Self {
+        self.config.fuze = fuze;
+        self
+    }
+
+    // Measure the target with noise and guide on a filtered track of it; None guides on the true state
+    pub fn tracking(mut self, tracking: Option<Tracking>) -> Self {
+        self.config.tracking = tracking;
+        self
+    }
+
+    pub fn collision_threshold(mut self, meters: f64) -> Self {
+        self.config.collision_threshold = meters;
+        self
+    }
+
+    pub fn max_steps(mut self, steps: usize) -> Self {
+        self.config.max_steps = steps;
+        self
+    }
+
+    pub fn dt(mut self, seconds: f64) -> Self {
+        self.config.dt = seconds;
+        self
+    }
+
+    // The assembled configuration, or every parameter out of range under its scenario field name
+    pub fn validated_config(&self) -> Result<SimConfig, Violations> {
+        let c = &self.config;
+        let mut v = Violations::default();
+        let points = [
+            ("target_start", (c.target_start_x, c.target_start_y)),
+            ("interceptor_start", (c.interceptor_start_x, c.interceptor_start_y)),
+            ("ground_station", (c.ground_station_x, c.ground_station_y)),
+        ];
+        for (field, (x, y)) in points {
+            v.check(x.is_finite() && y.is_finite(), field, "must be finite numbers");
+        }
+        for (field, value) in [("target_heading_deg", c.target_heading_deg), ("target_initial_height", c.target_initial_height), ("p_gain", c.p_gain)] {
+            v.check(value.is_finite(), field, "must be a finite number");
+        }
+        let (nonneg, positive) = (|x: f64| x >= 0.0 && x.is_finite(), |x: f64| x > 0.0 && x.is_finite());
+        v.check(nonneg(c.target_speed), "target_speed", "must be zero or positive");
+        v.check(positive(c.interceptor_speed), "interceptor_speed", "must be positive");
+        v.check(c.guidance != GuidanceLaw::Plugin || c.guidance_plugin.is_some(), "guidance_plugin", "is needed for guidance 'plugin'");
+        v.check(c.guidance != GuidanceLaw::Neural || c.guidance_model.is_some(), "guidance_model", "is needed for guidance 'neural'");
+        v.check(positive(c.navigation_constant), "navigation_constant", "must be positive");
+        v.check(nonneg(c.lqr_weights.miss), "lqr_miss_weight", "must be zero or positive");
+        v.check(nonneg(c.lqr_weights.rate), "lqr_rate_weight", "must be zero or positive");
+        v.check(c.lqr_weights.miss + c.lqr_weights.rate > 0.0, "lqr_miss_weight", "this or lqr_rate_weight must be positive");
+        v.check(positive(c.lqr_weights.effort), "lqr_effort_weight", "must be positive");
+        v.check(c.mpc.horizon > 0, "mpc_horizon", "must be positive");
+        v.check(c.mpc.levels >= 2, "mpc_levels", "must be at least 2");
+        v.check(
+            c.mpc.levels.saturating_mul(c.mpc.levels).saturating_mul(c.mpc.horizon) <= MPC_MAX_EVALUATIONS,
+            "mpc_levels",
+            "search too large: mpc_levels² × mpc_horizon must stay within 20000 simulated steps per step",
+        );
+        v.check(positive(c.mpc.max_accel), "mpc_max_accel", "must be positive");
+        v.check(nonneg(c.mpc.effort), "mpc_effort_weight", "must be zero or positive");
+        v.check(positive(c.clos_lookahead), "clos_lookahead", "must be a positive number of seconds");
+        v.check(nonneg(c.terminal_time_to_go), "terminal_time_to_go", "must be zero or a positive number of seconds");
+        if let Some(rate) = c.guidance_rate_hz {
+            v.check(positive(rate) && rate * c.dt <= 1.0 + 1e-9, "guidance_rate_hz", "must be positive and at most the physics rate 1 / dt");
+        }
+        v.check(nonneg(c.autopilot_tau), "autopilot_tau", "must be zero or a positive number of seconds");
+        v.check(positive(c.collision_threshold), "collision_threshold", "must be positive");
+        v.check((0.0..=1.0).contains(&c.correction_weight), "correction_weight", "must be between 0 and 1");
+        v.check((0.0..180.0).contains(&c.noise_range_deg), "noise_range_deg", "must be between 0 and 180 degrees");
+        v.check((0.0..180.0).contains(&c.interceptor_noise_deg), "interceptor_noise_deg", "must be between 0 and 180 degrees");
+        for (field, model) in [("noise_tau", c.noise_model), ("interceptor_noise_tau", c.interceptor_noise_model)] {
+            v.check(model.tau().is_none_or(positive), field, "must be a positive number of seconds");
+        }
+        v.check((1..=MAX_STEPS).contains(&c.max_steps), "max_steps", "must be between 1 and 1000000");
+        v.check(positive(c.dt), "dt", "must be a positive number of seconds");
+        v.check(c.target_script.is_none() || c.target_path.is_none(), "target_script", "has no effect on a target flying target_path");
+        v.check(c.optimal_evasion.is_none() || c.target_path.is_none(), "optimal_evasion", "has no effect on a target flying target_path");
+        v.check(c.optimal_evasion.is_none() || c.target_script.is_none(), "optimal_evasion", "can't be combined with target_script, which turns the target too");
+        v.check(c.evasion_model.is_none() || c.target_path.is_none(), "evasion_model", "has no effect on a target flying target_path");
+        v.check(
+            c.evasion_model.is_none() || (c.target_script.is_none() && c.optimal_evasion.is_none()),
+            "evasion_model",
+            "can't be combined with target_script or optimal_evasion, which turn the target too",
+        );
+        if let Some(evasion) = &c.optimal_evasion {
+            v.section("optimal_evasion", |v| evasion.validate(v));
+        }
+        if let Some(frame) = &c.frame {
+            v.section("frame", |v| frame.validate(v));
+        }
+        if let Some(actuator) = &c.actuator {
+            v.section("actuator", |v| actuator.validate(v));
+        }
+        if let Some(faults) = &c.faults {
+            faults.validate(&mut v);
+        }
+        if let Some(atmosphere) = &c.atmosphere {
+            v.section("atmosphere", |v| atmosphere.validate(v));
+        }
+        v.section("termination", |v| c.termination.validate(v));
+        if let Some(keep_out) = &c.keep_out {
+            v.section("keep_out", |v| keep_out.validate(v));
+        }
+        if let Some(terrain) = &c.terrain {
+            v.section("terrain", |v| terrain.validate(v));
+            v.check(!terrain.below(Vec2::new(c.target_start_x, c.target_start_y)), "target_start", "is below the terrain");
+            v.check(!terrain.below(Vec2::new(c.interceptor_start_x, c.interceptor_start_y)), "interceptor_start", "is below the terrain");
+            v.check(terrain.follow.is_none() || c.correction_weight > 0.0, "terrain.follow", "needs a correction_weight above 0 for the altitude hold to follow the terrain");
+        }
+        if let Some(fuze) = &c.fuze {
+            v.section("fuze", |v| fuze.validate(v));
+        }
+        if let Some(tracking) = &c.tracking {
+            v.section("tracking", |v| tracking.validate(v));
+        }
+        v.into_result().map(|()| c.clone())
+    }
+
+    pub fn build<'a>(self) -> Result<Simulation<'a>, SimError> {
+        Ok(Simulation::new(&self.validated_config()?))
+    }
+}
+
+impl<'a> Simulation<'a> {
+    pub fn builder() -> SimulationBuilder {
+        SimulationBuilder::default()
+    }
+
+    // Both vehicles at their start positions, nothing simulated yet
+    pub fn new(config: &SimConfig) -> Simulation<'a> {
+        let (sin, cos) = config.target_heading_deg.to_radians().sin_cos();
+        let target = Target::new((config.target_start_x, config.target_start_y), (config.target_speed * cos, config.target_speed * sin));
+        let interceptor = Interceptor::new(
+            (config.interceptor_start_x, config.interceptor_start_y),
+            (config.interceptor_speed * 0.707, config.interceptor_speed * 0.707),
+        );
+        log::debug!(
+            "launch: interceptor at ({:.2}, {:.2}), speed {}, target at ({:.2}, {:.2})",
+            interceptor.position.x, interceptor.position.y, config.interceptor_speed, target.position.x, target.position.y
+        );
+        let result = SimulationResult {
+            config: config.clone(),
+            events: vec![SimEvent::Launch { step: 0 }],
+            first_step: 0,
+            target_positions: vec![target.position.into()],
+            interceptor_positions: vec![interceptor.position.into()],
+            target_velocities: vec![target.velocity.into()],
+            interceptor_velocities: vec![interceptor.velocity.into()],
+            heading_noise_deg: vec![(0.0, 0.0)],
+            track_measurements: Vec::new(),
+            track_positions: Vec::new(),
+            track_velocities: Vec::new(),
+            track_filter: None,
+            guidance_commands: Vec::new(),
+            outcome: Outcome::Timeout,
+            collision_point: None,
+            collision_angle: None,
+            miss_distance: f64::INFINITY,
+        };
+        Simulation { result, observers: Observers::default(), window: None, feed: None, command: None, tracker: None }
+    }
+
+    // Continues a run from a partial result, e.g. a checkpoint. Observers only hear about events
+    // logged from here on.
+    pub fn resume(result: SimulationResult) -> Simulation<'a> {
+        let notified_events = result.events.len();
+        let observers = Observers { list: Vec::new(), notified_events };
+        Simulation { result, observers, window: None, feed: None, command: None, tracker: None }
+    }
+
+    pub fn observe(&mut self, observer: &'a mut dyn SimObserver) -> &mut Self {
+        self.observers.list.push(observer);
+        self
+    }
+
+    // The target goes where `feed` says each step instead of evading, see feed.rs; the run ends
+    // when the feed does
+    pub fn feed_target(&mut self, feed: &'a mut TargetFeed) -> &mut Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    // Flies `vehicle` on lateral acceleration `accel` (m/s², positive counter-clockwise) in the next
+    // step instead of on its guidance or evasion, for a controller outside the simulation (see gym.rs)
+    pub fn command(&mut self, vehicle: Vehicle, accel: f64) -> &mut Self {
+        self.command = Some((vehicle, accel));
+        self
+    }
+
+    // Keeps only the last `samples` positions (and the rest of the per-step record) in memory,
+    // e.g. while an observer streams them to disk. Older samples are dropped in batches, after
+    // observers have seen them; the track has to keep the measurements of its slowest sensor, so
+    // `samples` must be at least telemetry::MIN_WINDOW.
+    pub fn window(&mut self, samples: usize) -> &mut Self {
+        self.window = Some(samples);
+        self
+    }
+
+    // Everything recorded so far
+    pub fn result(&self) -> &SimulationResult {
+        &self.result
+    }
+
+    pub fn into_result(self) -> SimulationResult {
+        self.result
+    }
+
+    // Parameters for the steps still to come; the recorded part of the run is kept as it is. A
+    // change after the first step is logged as a ParametersChanged event.
+    pub fn set_config(&mut self, config: SimConfig) {
+        if config == self.result.config {
+            return;
+        }
+        // A different track starts over on the measurements still recorded
+        if config.tracking != self.result.config.tracking {
+            (self.tracker, self.result.track_filter) = (None, None);
+        }
+        self.result.config = config;
+        if self.result.steps() > 0 {
+            self.result.events.push(SimEvent::ParametersChanged { step: self.result.steps() });
+            self.observers.events(&self.result);
+        }
+    }
+
+    // Back to the state after `step` steps (clamped to the steps recorded, and to those still in
+    // memory): later positions and events are dropped and the outcome is open again, so the run can
+    // continue from there, e.g. with different parameters. Guidance state is derived from the event
+    // log and rewinds with it, and the track is picked up again from the measurements kept.
+    pub fn rewind(&mut self, step: usize) {
+        self.tracker = None;
+        let result = &mut self.result;
+        let step = step.clamp(result.first_step, result.steps());
+        let kept = step - result.first_step;
+        for history in [
+            &mut result.target_positions,
+            &mut result.interceptor_positions,
+            &mut result.target_velocities,
+            &mut result.interceptor_velocities,
+            &mut result.heading_noise_deg,
+        ] {
+            history.truncate(kept + 1);
+        }
+        result.track_measurements.truncate(kept);
+        result.track_positions.truncate(kept);
+        result.track_velocities.truncate(kept);
+        result.guidance_commands.truncate(kept);
+        // Events of step `step` itself belong to the step that is simulated again
+        result.events.retain(|e| matches!(e, SimEvent::Launch { .. }) || e.step() < step);
+        result.outcome = Outcome::Timeout;
+        result.collision_point = None;
+        result.collision_angle = None;
+        result.miss_distance = result.separations()[..kept].iter().copied().fold(f64::INFINITY, f64::min);
+        self.observers.notified_events = self.observers.notified_events.min(result.events.len());
+    }
+
+    pub fn finished(&self) -> bool {
+        self.result.outcome != Outcome::Timeout || matches!(self.result.events.last(), Some(SimEvent::Timeout { .. }))
+    }
+
+    // Runs to the end and returns the result
+    pub fn run(mut self, rng: &mut impl Rng) -> SimulationResult {
+        self.run_to_completion(rng);
+        self.result
+    }
+
+    // Runs the steps still to go, e.g. after a caller drove the first ones itself, and returns the
+    // outcome; the simulation and its result stay with the caller
+    pub fn run_to_completion(&mut self, rng: &mut impl Rng) -> Outcome {
+        self.advance(rng, usize::MAX);
+        self.result.outcome
+    }
+
+    // One physics step. Lets a caller drive the run at its own pace and do its own work between
+    // steps, e.g. pause or single-step it, or command a vehicle (see command).
+    pub fn step(&mut self, rng: &mut impl Rng) -> StepOutcome {
+        self.advance(rng, 1)
+    }
+
+    // Continues the run for at most `steps` more steps, starting from the vehicles' last recorded
+    // state and the track as the last call left it; advancing in several chunks gives the same
+    // result as one call.
+    pub fn advance(&mut self, rng: &mut impl Rng, steps: usize) -> StepOutcome {
+        if self.finished() {
+            return StepOutcome::Finished(self.result.outcome);
+        }
+        let Simulation { result, observers, window, feed, command, tracker } = self;
+        let config = result.config.clone();
+        // The engagement as recorded so far, so that a resumed run continues from it
+        let mut world = World::engagement(result, tracker.take());
+        let rng: &mut dyn RngCore = rng;
+
+        let start = result.steps();
+        let limit = config.termination.step_limit(config.max_steps, config.dt);
+        for step in start..limit.min(start.saturating_add(steps)) {
+            if let Some(feed) = feed.as_mut() {
+                let Some(position) = feed.next_position() else {
+                    log::debug!("target feed ended after {} steps, closest approach {:.3}", step, result.miss_distance);
+                    result.events.push(SimEvent::Timeout { step });
+                    break;
+                };
+                world.feed_target(position);
+            }
+            if let Some((vehicle, accel)) = command.take() {
+                world.command(vehicle, accel, &config);
+            }
+            let mut context = StepContext { step, config: &config, result: &mut *result, rng: &mut *rng };
+            for system in SYSTEMS {
+                system(&mut world, &mut context);
+                if context.result.outcome != Outcome::Timeout {
+                    break;
+                }
+            }
+            // Outcomes decided before anything moved end the run on the state recorded last
+            if !matches!(result.outcome, Outcome::Timeout | Outcome::GroundImpact) {
+                break;
+            }
+            world.record(result);
+            observers.events(result);
+            observers.step(result);
+            if result.outcome == Outcome::GroundImpact {
+                break;
+            }
+            if let Some(window) = *window {
+                if result.target_positions.len() >= 2 * window {
+                    keep_last(result, world.seekers.iter_mut().flatten().find_map(|seeker| seeker.tracker.as_mut()), window);
+                }
+            }
+        }
+        *tracker = world.seekers.iter_mut().flatten().find_map(|seeker| seeker.tracker.take());
+
+        if result.outcome == Outcome::Timeout && result.steps() >= limit {
+            log::debug!("timeout after {} steps, closest approach {:.3}", limit, result.miss_distance);
+            result.events.push(SimEvent::Timeout { step: limit });
+        }
+        observers.events(result);
+        if !self.finished() {
+            return StepOutcome::Running;
+        }
+        self.observers.finish(&self.result);
+        if let Some(window) = self.window {
+            keep_last(&mut self.result, self.tracker.as_mut(), window);
+        }
+        StepOutcome::Finished(self.result.outcome)
+    }
+}
+
+// Drops the oldest per-step records so that at most `samples` positions remain. The measurements
+// dropped go into the filter the track continues from, which the result keeps.
+fn keep_last(result: &mut SimulationResult, tracker: Option<&mut Tracker>, samples: usize) {
+    let dropped = result.target_positions.len().saturating_sub(samples).min(result.track_measurements.len());
+    if let Some(tracker) = tracker {
+        result.track_filter = Some(tracker.forget(&result.track_measurements[..dropped]));
+    }
+    result.keep_last(samples);
+}
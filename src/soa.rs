@@ -0,0 +1,68 @@
+// Struct-of-arrays state for many vehicles stepped together, e.g. the candidate trajectories MPC
+// guidance rolls out every step. Each state variable is its own contiguous buffer, so a step of
+// all vehicles is a few tight loops over plain f64 slices instead of a walk over vehicle structs,
+// which keeps them in cache and lets the compiler vectorize them. With the `simd` feature the
+// loops are explicitly vectorized kernels from simd.rs; the scalar ones below are the fallback.
+// Only MPC's rollouts are stored this way so far; Monte Carlo batches and the engagement itself
+// still step one vehicle struct at a time.
+
+#[cfg(feature = "simd")]
+use crate::simd::{advance, closest, turn};
+#[cfg(not(feature = "simd"))]
+use scalar::{advance, closest, turn};
+
+#[derive(Debug, Clone, Default)]
+pub struct Kinematics {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub vx: Vec<f64>,
+    pub vy: Vec<f64>,
+}
+
+impl Kinematics {
+    // `count` vehicles, all in the same state
+    pub fn filled(count: usize, (x, y): (f64, f64), (vx, vy): (f64, f64)) -> Kinematics {
+        Kinematics { x: vec![x; count], y: vec![y; count], vx: vec![vx; count], vy: vec![vy; count] }
+    }
+
+    // Constant-velocity step of every vehicle
+    pub fn advance(&mut self, dt: f64) {
+        advance(&mut self.x, &self.vx, dt);
+        advance(&mut self.y, &self.vy, dt);
+    }
+
+    // Turns every vehicle's velocity by its lateral acceleration `accels[i]` (m/s², positive to
+    // the left) over `dt`, keeping the speed at `speed`
+    pub fn turn(&mut self, accels: &[f64], speed: f64, dt: f64) {
+        turn(&mut self.vx, &mut self.vy, accels, speed, dt);
+    }
+
+    // Lowers each vehicle's closest approach so far, `misses[i]`, by its distance to `to`
+    pub fn closest_approach(&self, misses: &mut [f64], to: (f64, f64)) {
+        closest(misses, &self.x, &self.y, to);
+    }
+}
+
+// The reference kernels, one element at a time
+pub mod scalar {
+    pub fn advance(positions: &mut [f64], velocities: &[f64], dt: f64) {
+        for (p, v) in positions.iter_mut().zip(velocities) {
+            *p += v * dt;
+        }
+    }
+
+    pub fn turn(vx: &mut [f64], vy: &mut [f64], accels: &[f64], speed: f64, dt: f64) {
+        for ((vx, vy), u) in vx.iter_mut().zip(vy.iter_mut()).zip(accels) {
+            let (nvx, nvy) = (*vx - u * *vy / speed * dt, *vy + u * *vx / speed * dt);
+            let norm = (nvx * nvx + nvy * nvy).sqrt();
+            (*vx, *vy) = (nvx / norm * speed, nvy / norm * speed);
+        }
+    }
+
+    pub fn closest(misses: &mut [f64], x: &[f64], y: &[f64], (tx, ty): (f64, f64)) {
+        for ((miss, x), y) in misses.iter_mut().zip(x).zip(y) {
+            let (dx, dy) = (tx - x, ty - y);
+            *miss = miss.min((dx * dx + dy * dy).sqrt());
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Descriptive statistics and binning for batch results, and the intervals and tests that tell how far
+// conclusions drawn from a batch hold up.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64, // Sample standard deviation, 0 for fewer than two values
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+}
+
+// None when there are no finite values
+pub fn summarize(values: &[f64]) -> Option<Summary> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let count = sorted.len();
+    let mean = sorted.iter().sum::<f64>() / count as f64;
+    let variance = if count > 1 {
+        sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+    } else {
+        0.0
+    };
+    let median = if count % 2 == 1 {
+        sorted[count / 2]
+    } else {
+        (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0
+    };
+    Some(Summary { count, mean, std_dev: variance.sqrt(), min: sorted[0], median, max: sorted[count - 1] })
+}
+
+// Equal-width bins; bin k covers [min + k * width, min + (k + 1) * width), the last one includes its upper edge
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub min: f64,
+    pub width: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    // `bins` equal-width bins spanning the finite values; a single value gets a bin of width 1 around it
+    pub fn new(values: &[f64], bins: usize) -> Histogram {
+        let bins = bins.max(1);
+        let finite = || values.iter().copied().filter(|v| v.is_finite());
+        let (lo, hi) = finite().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let (min, width) = match (lo.is_finite(), hi > lo) {
+            (false, _) => (0.0, 1.0),
+            (true, false) => (lo - 0.5, 1.0 / bins as f64),
+            (true, true) => (lo, (hi - lo) / bins as f64),
+        };
+        let mut counts = vec![0; bins];
+        for v in finite() {
+            let k = (((v - min) / width) as usize).min(bins - 1);
+            counts[k] += 1;
+        }
+        Histogram { min, width, counts }
+    }
+
+    // Square-root rule, a reasonable default for the batch sizes used here
+    pub fn auto_bins(count: usize) -> usize {
+        ((count as f64).sqrt().ceil() as usize).clamp(1, 50)
+    }
+
+    pub fn bin_range(&self, k: usize) -> (f64, f64) {
+        (self.min + k as f64 * self.width, self.min + (k + 1) as f64 * self.width)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.min + self.counts.len() as f64 * self.width
+    }
+}
+
+// Resamples drawn for bootstrap intervals and permutation tests. They come from a fixed seed, so
+// the same results give the same report.
+const RESAMPLES: usize = 10_000;
+const RESAMPLE_SEED: u64 = 0x5eed;
+
+// Quantile of the standard normal distribution at probability `p` in (0, 1), by Acklam's rational
+// approximation (relative error below 1.2e-9)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2, 1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2, 6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838, -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    let tail = |q: f64| {
+        let (num, den) = (C.iter().fold(0.0, |acc, c| acc * q + c), D.iter().fold(0.0, |acc, d| acc * q + d) * q + 1.0);
+        num / den
+    };
+    if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let (q, r) = (p - 0.5, (p - 0.5) * (p - 0.5));
+        q * A.iter().fold(0.0, |acc, a| acc * r + a) / (B.iter().fold(0.0, |acc, b| acc * r + b) * r + 1.0)
+    }
+}
+
+// Wilson score interval of a proportion `hits` / `n` at `confidence` (e.g. 0.95); unlike the normal
+// approximation it stays within [0, 1] and works for rates near 0 or 1 and small batches
+pub fn wilson_interval(hits: usize, n: usize, confidence: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    let (n, p) = (n as f64, hits as f64 / n as f64);
+    let z = normal_quantile(0.5 + confidence / 2.0);
+    let center = (p + z * z / (2.0 * n)) / (1.0 + z * z / n);
+    let half = z / (1.0 + z * z / n) * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+// Percentile bootstrap interval of the mean of `values` at `confidence`; None without values
+pub fn bootstrap_mean_interval(values: &[f64], confidence: f64) -> Option<(f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut rng = StdRng::seed_from_u64(RESAMPLE_SEED);
+    let mut means: Vec<f64> = (0..RESAMPLES)
+        .map(|_| (0..values.len()).map(|_| values[rng.gen_range(0..values.len())]).sum::<f64>() / values.len() as f64)
+        .collect();
+    means.sort_by(f64::total_cmp);
+    let at = |q: f64| means[((q * RESAMPLES as f64) as usize).min(RESAMPLES - 1)];
+    Some((at((1.0 - confidence) / 2.0), at((1.0 + confidence) / 2.0)))
+}
+
+// Two-sided p-value of McNemar's exact test for paired outcomes: whether `b` pairs where only the
+// first configuration succeeded and `c` where only the second did are more lopsided than chance
+pub fn mcnemar_p_value(b: usize, c: usize) -> f64 {
+    let n = b + c;
+    // P(X <= min(b, c)) for X ~ Binomial(n, 1/2), summed in log space for large n
+    let ln_choose = |k: usize| (1..=k).map(|i| ((n - k + i) as f64 / i as f64).ln()).sum::<f64>();
+    let tail: f64 = (0..=b.min(c)).map(|k| (ln_choose(k) - n as f64 * std::f64::consts::LN_2).exp()).sum();
+    (2.0 * tail).min(1.0)
+}
+
+// Two-sided p-value of a sign-flip permutation test on paired `differences`: how often flipping
+// their signs at random gives a mean at least as far from 0. None without differences.
+pub fn paired_permutation_p_value(differences: &[f64]) -> Option<f64> {
+    if differences.is_empty() {
+        return None;
+    }
+    let observed = differences.iter().sum::<f64>().abs();
+    let mut rng = StdRng::seed_from_u64(RESAMPLE_SEED);
+    let extreme = (0..RESAMPLES)
+        .filter(|_| differences.iter().map(|d| if rng.gen_bool(0.5) { *d } else { -d }).sum::<f64>().abs() >= observed - 1e-12)
+        .count();
+    Some((extreme + 1) as f64 / (RESAMPLES + 1) as f64)
+}
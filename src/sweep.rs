@@ -0,0 +1,213 @@
+// Parameter sweep: runs a batch of seeded simulations for every cell of a 2D parameter grid and
+// renders the hit rate as a heatmap, showing which tuning region reliably intercepts.
+
+use crate::error::SimError;
+use crate::log;
+use crate::plot::{visualize_heatmap, PlotOptions};
+use crate::progress::Progress;
+use crate::scenario::Scenario;
+use crate::{simulate_once, Outcome, SimConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+
+// Grid of values in [0, 1] over two parameters; values[row][col] belongs to (xs[col], ys[row])
+pub struct Heatmap {
+    pub caption: String,
+    pub x_desc: &'static str,
+    pub y_desc: &'static str,
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    pub values: Vec<Vec<f64>>,
+}
+
+// A SimConfig field that can be put on a sweep axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepParam {
+    Speed,
+    Gain,
+    Weight,
+    Noise,
+    Threshold,
+    Rate,
+}
+
+impl SweepParam {
+    pub const NAMES: &'static str = "speed, gain, weight, noise, threshold, rate";
+
+    pub fn parse(s: &str) -> Option<SweepParam> {
+        match s {
+            "speed" => Some(SweepParam::Speed),
+            "gain" => Some(SweepParam::Gain),
+            "weight" => Some(SweepParam::Weight),
+            "noise" => Some(SweepParam::Noise),
+            "threshold" => Some(SweepParam::Threshold),
+            "rate" => Some(SweepParam::Rate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SweepParam::Speed => "speed",
+            SweepParam::Gain => "gain",
+            SweepParam::Weight => "weight",
+            SweepParam::Noise => "noise",
+            SweepParam::Threshold => "threshold",
+            SweepParam::Rate => "rate",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SweepParam::Speed => "Interceptor speed (m/s)",
+            SweepParam::Gain => "Target altitude P gain",
+            SweepParam::Weight => "Correction weight",
+            SweepParam::Noise => "Evasion noise range (±°)",
+            SweepParam::Threshold => "Collision threshold (m)",
+            SweepParam::Rate => "Guidance update rate (Hz)",
+        }
+    }
+
+    pub fn apply(&self, config: &mut SimConfig, value: f64) {
+        match self {
+            SweepParam::Speed => config.interceptor_speed = value,
+            SweepParam::Gain => config.p_gain = value,
+            SweepParam::Weight => config.correction_weight = value,
+            SweepParam::Noise => config.noise_range_deg = value,
+            SweepParam::Threshold => config.collision_threshold = value,
+            SweepParam::Rate => config.guidance_rate_hz = Some(value),
+        }
+    }
+}
+
+// One sweep axis, "NAME:MIN:MAX:COUNT" on the command line
+#[derive(Debug, Clone, Copy)]
+pub struct SweepAxis {
+    pub param: SweepParam,
+    pub min: f64,
+    pub max: f64,
+    pub count: usize,
+}
+
+impl SweepAxis {
+    pub fn parse(s: &str) -> Result<SweepAxis, String> {
+        let usage = || format!("sweep axis must be NAME:MIN:MAX:COUNT, e.g. speed:1:4:13 (names: {})", SweepParam::NAMES);
+        let parts: Vec<&str> = s.split(':').collect();
+        let [name, min, max, count] = parts[..] else {
+            return Err(usage());
+        };
+        let param = SweepParam::parse(name).ok_or_else(usage)?;
+        let (min, max) = (min.parse().map_err(|_| usage())?, max.parse().map_err(|_| usage())?);
+        let count = count.parse().ok().filter(|c| *c > 0).ok_or_else(usage)?;
+        Ok(SweepAxis { param, min, max, count })
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        if self.count == 1 {
+            return vec![self.min];
+        }
+        (0..self.count).map(|k| self.min + (self.max - self.min) * k as f64 / (self.count - 1) as f64).collect()
+    }
+}
+
+// Usage: sweep [--x NAME:MIN:MAX:COUNT] [--y NAME:MIN:MAX:COUNT] [--runs N / -n N] [--seed N]
+//              [--scenario FILE] [--out-dir DIR / -o DIR] [--quiet / -q] [plot options]
+pub fn run_sweep(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut x_axis = SweepAxis { param: SweepParam::Speed, min: 1.0, max: 4.0, count: 13 };
+    let mut y_axis = SweepAxis { param: SweepParam::Noise, min: 0.0, max: 45.0, count: 10 };
+    let mut runs: usize = 20;
+    let mut seed: u64 = 1;
+    let mut out_dir = String::from(".");
+    let plot_options = PlotOptions { style: scenario.style, ..PlotOptions::from_args(args)? };
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--x" if i + 1 < args.len() => {
+                x_axis = SweepAxis::parse(&args[i + 1])?;
+                i += 1;
+            }
+            "--y" if i + 1 < args.len() => {
+                y_axis = SweepAxis::parse(&args[i + 1])?;
+                i += 1;
+            }
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--runs must be a positive integer")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    let (xs, ys) = (x_axis.values(), y_axis.values());
+    log::info!("🧮 Sweeping {} × {} grid, {} runs per cell", xs.len(), ys.len(), runs);
+
+    let csv_path = std::path::Path::new(&out_dir).join("sweep.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "x_param,x,y_param,y,runs,hits,hit_rate,mean_miss_distance,mean_pk")?;
+
+    let mut hit_rates = vec![vec![0.0; xs.len()]; ys.len()];
+    let mut progress = Progress::new("Sweep", xs.len() * ys.len() * runs, true);
+    for (row, &y) in ys.iter().enumerate() {
+        let _span = log::span(format!("sweep row {}", row + 1));
+        for (col, &x) in xs.iter().enumerate() {
+            let mut config: SimConfig = scenario.config.clone();
+            x_axis.param.apply(&mut config, x);
+            y_axis.param.apply(&mut config, y);
+            // Every cell sees the same random sequence, so differences come from the parameters alone
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (mut hits, mut miss_sum, mut pk_sum) = (0, 0.0, 0.0);
+            for _ in 0..runs {
+                let run_config = scenario.envelope.sample(&config, &mut rng);
+                let sim = simulate_once(&mut rng, &run_config);
+                hits += (sim.outcome == Outcome::Intercept) as usize;
+                progress.inc(sim.outcome == Outcome::Intercept);
+                miss_sum += sim.miss_distance;
+                pk_sum += sim.pk();
+            }
+            let hit_rate = hits as f64 / runs as f64;
+            log::debug!("{} = {}, {} = {}: {}/{} hits", x_axis.param.as_str(), x, y_axis.param.as_str(), y, hits, runs);
+            hit_rates[row][col] = hit_rate;
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.4},{:.4},{:.4}",
+                x_axis.param.as_str(),
+                x,
+                y_axis.param.as_str(),
+                y,
+                runs,
+                hits,
+                hit_rate,
+                miss_sum / runs as f64,
+                pk_sum / runs as f64
+            )?;
+        }
+        log::info!("   {} = {:.3}: row {}/{} done", y_axis.param.label(), y, row + 1, ys.len());
+    }
+    progress.finish();
+    log::info!("✅ Sweep results saved as '{}'", csv_path.display());
+
+    if plot_options.enabled {
+        let heatmap = Heatmap {
+            caption: format!("Hit rate over {} runs per cell", runs),
+            x_desc: x_axis.param.label(),
+            y_desc: y_axis.param.label(),
+            xs,
+            ys,
+            values: hit_rates,
+        };
+        let out_plot = std::path::Path::new(&out_dir).join(format!("sweep_heatmap.{}", plot_options.format.extension()));
+        visualize_heatmap(&heatmap, out_plot.to_str().unwrap(), &plot_options)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,476 @@
+// The systems that advance a World (world.rs) by one step, in the order they run: collision ends
+// the run on a hit, termination when an optional termination criterion is met, keep_out logs the
+// seekers entering or leaving keep-out zones, evasion turns the evading entities, follow_path keeps
+// the entities on a recorded path on it, follow_feed moves those on an external feed where it
+// says, faults logs the scheduled faults that start or clear, sensing measures the seekers'
+// targets, guidance steers the seekers and physics moves every body and ends the run when one that
+// is recorded drops below the ground. Each acts on the entities that have its components, so a new
+// kind of entity only needs a new system if it brings new behavior. Events and the track go
+// straight into the result; the recorded positions are appended by the loop after the step.
+
+use rand::RngCore;
+
+use crate::engagement::Engagement;
+use crate::faults::{FaultKind, FaultSchedule};
+use crate::guidance;
+use crate::log;
+use crate::schedule::ScheduleKey;
+use crate::script::ScriptInputs;
+use crate::tracking::TrackEstimate;
+use crate::vec2::Vec2;
+use crate::world::{Seeker, World};
+use crate::calculate_steering_direction;
+use crate::{GuidanceLaw, GuidancePhase, Outcome, SimConfig, SimEvent, SimulationResult, Steering, Target};
+
+// What a system gets besides the world: the step being simulated and the run it belongs to
+pub struct StepContext<'a> {
+    pub step: usize,
+    pub config: &'a SimConfig,
+    pub result: &'a mut SimulationResult,
+    pub rng: &'a mut dyn RngCore,
+}
+
+pub type System = fn(&mut World, &mut StepContext);
+
+// Run in this order every step, until one of them ends the run
+pub const SYSTEMS: [System; 10] = [collision, termination, keep_out, evasion, follow_path, follow_feed, faults, sensing, guidance, physics];
+
+// A seeker within reach of its target hits it: within an armed fuze's radius, or without a fuze
+// closer than the collision threshold. Also keeps the run's closest approach and logs the passes
+// it misses.
+pub fn collision(world: &mut World, ctx: &mut StepContext) {
+    let (config, step) = (ctx.config, ctx.step);
+    for entity in world.entities() {
+        let Some(seeker) = &world.seekers[entity.0] else { continue };
+        let (target, interceptor) = (world.bodies[seeker.target.0], world.bodies[entity.0]);
+        let distance = interceptor.distance_to(&target);
+        let result = &mut *ctx.result;
+        result.miss_distance = result.miss_distance.min(distance);
+        let hit = match config.fuze {
+            Some(fuze) => fuze.armed(step as f64 * config.dt) && distance < fuze.detonation_radius,
+            None => distance < config.collision_threshold,
+        };
+        if hit {
+            let Vec2 { x, y } = target.position;
+            result.collision_point = Some((x, y));
+            let angle = target.velocity.angle_deg(interceptor.velocity);
+            result.collision_angle = Some(angle);
+            log::debug!("intercept at step {}: ({:.2}, {:.2}), distance {:.3}, angle {:.2}°", step, x, y, distance, angle);
+            result.events.push(SimEvent::Intercept { step, x, y });
+            result.outcome = Outcome::Intercept;
+            return;
+        }
+        if let Some(miss) = missed_pass(result, interceptor, target, distance) {
+            log::debug!("pass missed at step {}, closest approach {:.3}", step, miss);
+            result.events.push(SimEvent::Pass { step, miss });
+        }
+    }
+}
+
+// The closest approach of the pass just missed, when the range opens again after closing with the
+// target abeam or behind the interceptor (see termination.rs); it is the closest since the last pass
+fn missed_pass(result: &SimulationResult, interceptor: Target, target: Target, distance: f64) -> Option<f64> {
+    let separation = |i: usize| Vec2::from(result.target_positions[i]).distance(Vec2::from(result.interceptor_positions[i]));
+    let n = result.target_positions.len();
+    if n < 3 || distance <= separation(n - 2) || separation(n - 2) > separation(n - 3) || (target.position - interceptor.position).dot(interceptor.velocity) > 0.0 {
+        return None;
+    }
+    let last_pass = result.events.iter().rev().find_map(|e| if let SimEvent::Pass { step, .. } = *e { Some(step) } else { None });
+    let from = last_pass.map_or(0, |step| step.saturating_sub(result.first_step));
+    Some((from.min(n - 2)..n - 1).map(separation).fold(f64::INFINITY, f64::min))
+}
+
+// The optional termination criteria (termination.rs) on the state the last step left: a target
+// beyond the escape range of its seeker, a seeker slower than the minimum speed, or one that has
+// just missed its last allowed pass ends the run
+pub fn termination(world: &mut World, ctx: &mut StepContext) {
+    let (criteria, step) = (ctx.config.termination, ctx.step);
+    for entity in world.entities() {
+        let Some(seeker) = &world.seekers[entity.0] else { continue };
+        let (target, interceptor) = (world.bodies[seeker.target.0], world.bodies[entity.0]);
+        let (range, speed) = (interceptor.distance_to(&target), interceptor.velocity.length());
+        let result = &mut *ctx.result;
+        if criteria.escape_range.is_some_and(|escape| range > escape) {
+            log::debug!("target escaped at step {}, range {:.2}", step, range);
+            result.events.push(SimEvent::Escape { step, range });
+            result.outcome = Outcome::Escape;
+            return;
+        }
+        if criteria.min_speed.is_some_and(|min| speed < min) {
+            log::debug!("interceptor stalled at step {}, speed {:.2}", step, speed);
+            result.events.push(SimEvent::Stall { step, speed });
+            result.outcome = Outcome::Stall;
+            return;
+        }
+        if matches!(result.events.last(), Some(SimEvent::Pass { step: passed, .. }) if *passed == step) {
+            let passes = result.events.iter().filter(|e| matches!(e, SimEvent::Pass { .. })).count();
+            if criteria.max_passes.is_some_and(|max| passes >= max) {
+                log::debug!("interceptor gave up after {} missed passes at step {}", passes, step);
+                result.outcome = Outcome::Miss;
+                return;
+            }
+        }
+    }
+}
+
+// A seeker that crossed the edge of a keep-out zone (zones.rs) during the last step flags it; one
+// starting inside a zone enters it at step 0
+pub fn keep_out(world: &mut World, ctx: &mut StepContext) {
+    let (Some(keep_out), step) = (ctx.config.keep_out.as_deref(), ctx.step) else { return };
+    for entity in world.entities() {
+        if world.seekers[entity.0].is_none() {
+            continue;
+        }
+        let body = world.bodies[entity.0];
+        let before = body.position - body.velocity * ctx.config.dt;
+        for (i, zone) in keep_out.zones.iter().enumerate() {
+            let (was, is) = (step > 0 && zone.contains(before), zone.contains(body.position));
+            if was != is {
+                log::debug!("step {}: interceptor {} keep-out zone {}", step, if is { "entered" } else { "left" }, i + 1);
+                ctx.result.events.push(if is { SimEvent::KeepOutEntered { step, zone: i + 1 } } else { SimEvent::KeepOutLeft { step, zone: i + 1 } });
+            }
+        }
+    }
+}
+
+// Each evading entity turns by its heading noise blended with the altitude hold's correction, by
+// the optimal evasion's turn away from the predicted intercept while that is in range, or by the
+// evasion model's command
+pub fn evasion(world: &mut World, ctx: &mut StepContext) {
+    let config = ctx.config;
+    let noise = config.noise_model.model(config.noise_range_deg);
+    for entity in world.entities() {
+        let Some(evasion) = world.evasions[entity.0].as_mut() else { continue };
+        let (target, threat) = (world.bodies[entity.0], world.bodies[evasion.threat.0]);
+        let distance = threat.distance_to(&target);
+        evasion.noise_deg = noise.sample(ctx.rng, evasion.noise_deg, config.dt);
+        let random_angle_deg = evasion.noise_deg;
+        let p_gain = match config.p_gain_schedule {
+            Some(schedule) => schedule.gain(match schedule.key {
+                ScheduleKey::Range => distance,
+                // Zero rather than NaN when the vehicles coincide, e.g. before a fuze has armed
+                ScheduleKey::ClosingSpeed => Engagement::between(&threat, &target).closing_velocity,
+            }),
+            None => config.p_gain,
+        };
+        // Following the terrain, the hold climbs for the highest ground the next seconds cross
+        let hold_height = match config.terrain.as_deref().and_then(|terrain| Some((terrain, terrain.follow?))) {
+            Some((terrain, clearance)) => terrain.highest(target.position.x, target.position.x + target.velocity.x * terrain.lookahead) + clearance,
+            None => config.target_initial_height,
+        };
+        let height_error = target.position.y - hold_height;
+        let correction_angle_deg = -height_error * p_gain;
+        let optimal_turn = config.optimal_evasion.and_then(|optimal| optimal.turn(target.position, target.velocity, threat.position, threat.velocity, config.dt));
+        let blended_angle_deg = match (optimal_turn, config.evasion_model.as_deref(), config.target_script.as_deref()) {
+            (Some(turn), _, _) => turn.to_degrees(),
+            // The model steers the target as guidance_model steers an interceptor, by the threat's
+            // state relative to the target
+            (None, Some(model), _) => {
+                let direction = model.steering(&target, &threat, config.dt).direction;
+                target.velocity.cross(direction).atan2(target.velocity.dot(direction)).to_degrees()
+            }
+            // The script decides, with both terms to draw on
+            (None, None, Some(script)) => script.turn_deg(&ScriptInputs {
+                t: ctx.step as f64 * config.dt,
+                dt: config.dt,
+                step: ctx.step,
+                position: target.position.into(),
+                velocity: target.velocity.into(),
+                threat_position: threat.position.into(),
+                threat_velocity: threat.velocity.into(),
+                closing: Engagement::between(&threat, &target).closing_velocity,
+                noise_deg: random_angle_deg,
+                hold_deg: correction_angle_deg,
+            }),
+            (None, None, None) => (random_angle_deg * (1.0 - config.correction_weight)) + (correction_angle_deg * config.correction_weight),
+        };
+        world.bodies[entity.0].velocity = target.velocity.rotated(blended_angle_deg.to_radians());
+        log::trace!(
+            "step {}: height error {:.3}, noise {:+.2}°, P term {:+.2}°, commanded turn {:+.2}°",
+            ctx.step,
+            height_error,
+            random_angle_deg,
+            correction_angle_deg,
+            blended_angle_deg
+        );
+    }
+}
+
+// Each entity on a recorded path gets the velocity that takes it to where the path has it at the
+// end of the step
+pub fn follow_path(world: &mut World, ctx: &mut StepContext) {
+    let (dt, t) = (ctx.config.dt, (ctx.step + 1) as f64 * ctx.config.dt);
+    for entity in world.entities() {
+        let Some(path) = world.paths[entity.0].as_deref() else { continue };
+        let body = &mut world.bodies[entity.0];
+        body.velocity = (path.position(t) - body.position) / dt;
+    }
+}
+
+// Entities on an external feed fly to the position it gave for the end of this step
+pub fn follow_feed(world: &mut World, ctx: &mut StepContext) {
+    for entity in world.entities() {
+        let Some(position) = world.fed[entity.0].take() else { continue };
+        let body = &mut world.bodies[entity.0];
+        body.velocity = (position - body.position) / ctx.config.dt;
+    }
+}
+
+// Logs the scheduled faults that become active or clear this step; sensing and guidance look up
+// which are active in the schedule themselves
+pub fn faults(_world: &mut World, ctx: &mut StepContext) {
+    let (config, step) = (ctx.config, ctx.step);
+    let Some(schedule) = config.faults else { return };
+    for fault in schedule.faults() {
+        let (now, before) = (fault.active(step, config.dt), step > 0 && fault.active(step - 1, config.dt));
+        if now != before {
+            log::debug!("step {}: {} {}", step, fault.kind.as_str(), if now { "started" } else { "cleared" });
+            ctx.result.events.push(match now {
+                true => SimEvent::FaultStarted { step, fault: fault.kind },
+                false => SimEvent::FaultCleared { step, fault: fault.kind },
+            });
+        }
+    }
+}
+
+// With tracking, each seeker's sensors look at its target and guidance gets the tracker's estimate
+// instead of the truth
+pub fn sensing(world: &mut World, ctx: &mut StepContext) {
+    let (config, step) = (ctx.config, ctx.step);
+    let Some(tracking) = config.tracking else { return };
+    for entity in world.entities() {
+        let Some(seeker) = world.seekers[entity.0].as_mut() else { continue };
+        let Some(tracker) = seeker.tracker.as_mut() else { continue };
+        let (interceptor, target) = (world.bodies[entity.0], world.bodies[seeker.target.0]);
+        let result = &mut *ctx.result;
+        // Where the track expects the target, for associating the sensor's plots
+        let predicted = match (result.track_positions.last(), result.track_velocities.last()) {
+            (Some(&(x, y)), Some(&(vx, vy))) => (x + vx * config.dt, y + vy * config.dt),
+            _ => TrackEstimate::handover(config, step as f64 * config.dt).position,
+        };
+        // During a sensor dropout nothing is measured and the track coasts
+        let dropout = config.faults.is_some_and(|f| f.active(FaultKind::SensorDropout, step, config.dt));
+        let mut looks = Vec::new();
+        for sensor in tracking.sensors().filter(|_| !dropout) {
+            // A ground site sees from where it stands, an onboard sensor from the interceptor
+            let site = sensor.position.map_or(interceptor.position, Vec2::from);
+            if config.terrain.as_ref().is_some_and(|terrain| terrain.masks(site, target.position)) {
+                looks.push(None);
+                continue;
+            }
+            let plot = sensor.look(tracking.clutter, interceptor.position.into(), target.position.into(), predicted, ctx.rng);
+            if plot.is_some_and(|plot| !plot.target) {
+                log::trace!("step {}: false plot associated", step);
+                result.events.push(SimEvent::AssociationError { step });
+            }
+            looks.push(plot.map(|plot| plot.measurement));
+        }
+        looks.resize(tracking.sensors().count(), None);
+        result.track_measurements.push(looks);
+        let (estimate, arrived) = tracker.estimate(&result.track_measurements, result.track_measurements.len() - 1);
+        if !arrived && !dropout {
+            log::trace!("step {}: no new measurement", step);
+            result.events.push(SimEvent::MeasurementMissed { step });
+        }
+        let estimate = estimate.unwrap_or_else(|| TrackEstimate::handover(config, step as f64 * config.dt));
+        result.track_positions.push(estimate.position);
+        result.track_velocities.push(estimate.velocity);
+        seeker.perceived = Some(Target::new(estimate.position, estimate.velocity));
+    }
+}
+
+// Each seeker steers by the configured law onto its target as sensing perceived it, turned away
+// from the keep-out zones, then flies the commanded heading, with its heading noise, at the
+// interceptor speed, or as far as the atmosphere lets it turn and at the speed drag leaves it.
+// With guidance_rate_hz the law only runs every few steps and the command is held in between.
+pub fn guidance(world: &mut World, ctx: &mut StepContext) {
+    let (config, step) = (ctx.config, ctx.step);
+    let interval = guidance_interval(config);
+    for entity in world.entities() {
+        let Some(seeker) = world.seekers[entity.0].as_mut() else { continue };
+        let interceptor = world.bodies[entity.0];
+        let perceived = seeker.perceived.take().unwrap_or(world.bodies[seeker.target.0]);
+        let held = seeker.command.filter(|_| interval.is_some_and(|k| step % k != 0));
+        // Without a track to coast on, a sensor dropout or the terrain masking the target leaves
+        // guidance nothing to steer by
+        let dropout = config.faults.is_some_and(|f| f.active(FaultKind::SensorDropout, step, config.dt));
+        let masked = config.terrain.as_ref().is_some_and(|terrain| terrain.masks(interceptor.position, world.bodies[seeker.target.0].position));
+        let blind = config.tracking.is_none() && (dropout || masked);
+        if config.terrain.is_some() && masked != terrain_masked(ctx.result) {
+            log::debug!("step {}: target {} the terrain", step, if masked { "masked by" } else { "clear of" });
+            ctx.result.events.push(if masked { SimEvent::TargetMasked { step } } else { SimEvent::TargetVisible { step } });
+        }
+        let commanded = seeker.external.take().or(held).unwrap_or_else(|| match blind {
+            true => interceptor.velocity,
+            false => steer(seeker, &interceptor, &perceived, ctx),
+        });
+        if interval.is_some() {
+            seeker.command = Some(commanded);
+            ctx.result.guidance_commands.push(commanded.into());
+        }
+        let mut velocity = match config.autopilot_tau > 0.0 {
+            true => autopilot(interceptor.velocity, commanded, &mut seeker.accel, config),
+            false => commanded,
+        };
+        if let Some(actuator) = config.actuator {
+            let faulted = actuator.faulted(ctx.rng, seeker.faulted, config.dt);
+            if faulted != seeker.faulted {
+                log::debug!("step {}: actuator {}", step, if faulted { "fault" } else { "recovered" });
+                ctx.result.events.push(if faulted { SimEvent::ActuatorFault { step } } else { SimEvent::ActuatorRecovered { step } });
+            }
+            seeker.faulted = faulted;
+            velocity = match faulted {
+                true => interceptor.velocity,
+                false => actuator.respond(ctx.rng, interceptor.velocity, velocity, config.interceptor_speed, config.dt),
+            };
+            seeker.accel = (velocity - interceptor.velocity) / config.dt;
+        }
+        if let Some(faults) = config.faults {
+            velocity = scheduled_faults(&faults, interceptor.velocity, velocity, ctx);
+            seeker.accel = (velocity - interceptor.velocity) / config.dt;
+        }
+        if let Some(atmosphere) = config.atmosphere {
+            velocity = atmosphere.fly(interceptor.velocity, velocity, interceptor.position.y, config.frame, config.interceptor_speed, config.dt);
+            seeker.accel = (velocity - interceptor.velocity) / config.dt;
+        }
+        world.bodies[entity.0].velocity = velocity;
+        log::trace!(
+            "step {}: distance {:.3}, noise {:+.2}°, interceptor heading {:.2}°",
+            step,
+            interceptor.distance_to(&world.bodies[seeker.target.0]),
+            seeker.noise_deg,
+            velocity.y.atan2(velocity.x).to_degrees()
+        );
+    }
+}
+
+// Whether the terrain hid the target from the interceptor at the last mask event
+fn terrain_masked(result: &SimulationResult) -> bool {
+    result.events.iter().rev().find_map(|e| match e {
+        SimEvent::TargetMasked { .. } => Some(true),
+        SimEvent::TargetVisible { .. } => Some(false),
+        _ => None,
+    }) == Some(true)
+}
+
+// The velocity the interceptor reaches from `from` instead of `velocity` under the faults active
+// this step: a stuck actuator repeats the turn of the last step, as recorded, and degraded
+// acceleration limits the turn
+fn scheduled_faults(faults: &FaultSchedule, from: Vec2, mut velocity: Vec2, ctx: &StepContext) -> Vec2 {
+    let (config, step) = (ctx.config, ctx.step);
+    let turn = |a: Vec2, b: Vec2| a.cross(b).atan2(a.dot(b));
+    if faults.active(FaultKind::StuckActuator, step, config.dt) {
+        velocity = match ctx.result.interceptor_velocities.as_slice() {
+            [.., before, last] => from.rotated(turn((*before).into(), (*last).into())),
+            _ => from,
+        };
+    }
+    if let Some(max_accel) = faults.accel_limit(step, config.dt) {
+        let max_turn = max_accel * config.dt / config.interceptor_speed;
+        velocity = from.rotated(turn(from, velocity).clamp(-max_turn, max_turn));
+    }
+    velocity
+}
+
+// Physics steps per guidance update with guidance_rate_hz, rounded to whole steps
+fn guidance_interval(config: &SimConfig) -> Option<usize> {
+    config.guidance_rate_hz.map(|rate| ((1.0 / (rate * config.dt)).round() as usize).max(1))
+}
+
+// The velocity the law commands this step, with the interceptor's heading noise
+fn steer(seeker: &mut Seeker, interceptor: &Target, perceived: &Target, ctx: &mut StepContext) -> Vec2 {
+    let (config, step) = (ctx.config, ctx.step);
+    let noise = config.interceptor_noise_model.model(config.interceptor_noise_deg);
+    let engagement = Engagement::between(interceptor, perceived);
+    let terminal = engagement.time_to_go.is_some_and(|tgo| tgo < config.terminal_time_to_go);
+    let steering = match config.guidance {
+        GuidanceLaw::LeadPursuit => calculate_steering_direction(interceptor, perceived, !terminal),
+        GuidanceLaw::Zem => guidance::zem_steering(interceptor, perceived, &engagement, config.navigation_constant, config.dt),
+        GuidanceLaw::Lqr => guidance::lqr_steering(interceptor, perceived, &engagement, config.lqr_weights, config.dt),
+        GuidanceLaw::Mpc => guidance::mpc_steering(
+            interceptor,
+            perceived,
+            config.mpc,
+            config.dt,
+            config.termination.step_limit(config.max_steps, config.dt) - step,
+            config.collision_threshold,
+        ),
+        GuidanceLaw::Clos => guidance::clos_steering(
+            interceptor,
+            perceived,
+            Vec2::new(config.ground_station_x, config.ground_station_y),
+            config.clos_lookahead,
+            config.collision_threshold,
+        ),
+        // Validation makes sure there is a plugin or model
+        GuidanceLaw::Plugin => match &config.guidance_plugin {
+            Some(plugin) => plugin.steering(interceptor, perceived, step as f64 * config.dt, config.dt),
+            None => calculate_steering_direction(interceptor, perceived, !terminal),
+        },
+        GuidanceLaw::Neural => match &config.guidance_model {
+            Some(model) => model.steering(interceptor, perceived, config.dt),
+            None => calculate_steering_direction(interceptor, perceived, !terminal),
+        },
+    };
+    let steering = if terminal { Steering { phase: GuidancePhase::Terminal, ..steering } } else { steering };
+    log::trace!("step {}: closing at {:.2} m/s, time-to-go {:?}", step, engagement.closing_velocity, engagement.time_to_go);
+    let events = &mut ctx.result.events;
+    if seeker.locked != Some(steering.lead_solution) {
+        seeker.locked = Some(steering.lead_solution);
+        events.push(if steering.lead_solution { SimEvent::LockAcquired { step } } else { SimEvent::LockLost { step } });
+    }
+    // The first step starts in direct guidance without an event
+    if seeker.phase.unwrap_or(GuidancePhase::Direct) != steering.phase {
+        log::debug!("step {}: guidance phase {}", step, steering.phase.as_str());
+        events.push(SimEvent::PhaseChange { step, phase: steering.phase });
+    }
+    seeker.phase = Some(steering.phase);
+
+    let mut direction = steering.direction;
+    let dir_magnitude = direction.length();
+    if dir_magnitude > 0.0 {
+        direction = direction / dir_magnitude;
+    }
+    if let Some(keep_out) = &config.keep_out {
+        direction = keep_out.avoid(interceptor.position, direction);
+    }
+
+    seeker.noise_deg = noise.sample(ctx.rng, seeker.noise_deg, config.dt);
+    direction.rotated(seeker.noise_deg.to_radians()) * config.interceptor_speed
+}
+
+// The velocity the interceptor reaches from `velocity` when guidance commands `commanded`. The
+// command is the acceleration that would turn onto it within the step; the achieved acceleration
+// `accel` follows it with a first-order lag of time constant autopilot_tau, and the speed stays
+// the interceptor's.
+fn autopilot(velocity: Vec2, commanded: Vec2, accel: &mut Vec2, config: &SimConfig) -> Vec2 {
+    let command = (commanded - velocity) / config.dt;
+    let alpha = 1.0 - (-config.dt / config.autopilot_tau).exp();
+    let lagged = velocity + (*accel + (command - *accel) * alpha) * config.dt;
+    let achieved = lagged.normalized().map_or(commanded, |direction| direction * config.interceptor_speed);
+    *accel = (achieved - velocity) / config.dt;
+    achieved
+}
+
+// Every body flies on at its velocity. A recorded vehicle below the ground, height 0 or the
+// terrain, ends the run; the impact point is where its last step crossed it.
+pub fn physics(world: &mut World, ctx: &mut StepContext) {
+    let before = world.bodies.clone();
+    for body in &mut world.bodies {
+        body.update(ctx.config.dt);
+    }
+    for entity in world.entities() {
+        let (Some(vehicle), before, now) = (world.roles[entity.0], before[entity.0].position, world.bodies[entity.0].position) else {
+            continue;
+        };
+        let ground = |p: Vec2| ctx.config.terrain.as_ref().map_or(0.0, |terrain| terrain.height(p.x));
+        let (above, below) = (before.y - ground(before), now.y - ground(now));
+        if below < 0.0 {
+            let x = before.x + (now.x - before.x) * above / (above - below);
+            log::debug!("{} ground impact at step {}, x = {:.2}", vehicle.as_str(), ctx.step + 1, x);
+            ctx.result.events.push(SimEvent::GroundImpact { step: ctx.step + 1, vehicle, x });
+            ctx.result.outcome = Outcome::GroundImpact;
+            return;
+        }
+    }
+}
@@ -0,0 +1,105 @@
+// Streaming telemetry: with --stream every run writes its state at each step to telemetry_NNN.csv
+// while it is simulated, instead of only keeping it for the result at the end. Combined with
+// --window N the run itself keeps just the last N samples in memory, so long runs don't grow
+// without bound; the stream still has every step:
+//
+//   step,time,target_x,target_y,target_vx,target_vy,interceptor_x,interceptor_y,interceptor_vx,interceptor_vy,separation
+//
+// The window is trimmed in batches, once twice as many samples have piled up, and to exactly N
+// when the run ends.
+//
+// With --target-feed (see feed.rs) the same lines go to stdout as they are simulated, flushed
+// every step, so the program feeding the target sees the interceptor's answer to each position.
+
+use crate::error::SimError;
+use crate::simulation::SimObserver;
+use crate::SimulationResult;
+use std::io::Write;
+
+// Smallest --window: the tracker still needs the measurements of its slowest sensor's latency
+pub const MIN_WINDOW: usize = 100;
+
+const HEADER: &str = "step,time,target_x,target_y,target_vx,target_vy,interceptor_x,interceptor_y,interceptor_vx,interceptor_vy,separation";
+
+pub struct TelemetryStream {
+    name: String, // The file, or stdout, for error messages
+    file: std::io::BufWriter<Box<dyn Write>>,
+    flush: bool,                  // Flush after every step, for a reader waiting on each line
+    written: usize,               // Steps written so far
+    error: Option<std::io::Error>, // The first write that failed; reported by finish
+}
+
+impl TelemetryStream {
+    pub fn create(path: &std::path::Path) -> Result<TelemetryStream, SimError> {
+        let file = std::fs::File::create(path).map_err(SimError::io(format!("cannot create '{}'", path.display())))?;
+        TelemetryStream::start(format!("'{}'", path.display()), Box::new(file), false)
+    }
+
+    pub fn stdout() -> Result<TelemetryStream, SimError> {
+        TelemetryStream::start("stdout".into(), Box::new(std::io::stdout()), true)
+    }
+
+    fn start(name: String, out: Box<dyn Write>, flush: bool) -> Result<TelemetryStream, SimError> {
+        let mut file = std::io::BufWriter::new(out);
+        writeln!(file, "{}", HEADER).map_err(SimError::io(format!("cannot write {}", name)))?;
+        Ok(TelemetryStream { name, file, flush, written: 0, error: None })
+    }
+
+    // Flushes the file; fails if any step couldn't be written
+    pub fn finish(mut self) -> Result<(), SimError> {
+        let result = match self.error.take() {
+            Some(e) => Err(e),
+            None => self.file.flush(),
+        };
+        result.map_err(SimError::io(format!("cannot write {}", self.name)))
+    }
+
+    // The steps recorded since the last call that are still in memory
+    fn write_new(&mut self, sim: &SimulationResult) -> std::io::Result<()> {
+        for step in self.written.max(sim.first_step)..=sim.steps() {
+            let k = step - sim.first_step;
+            let ((tx, ty), (ix, iy)) = (sim.target_positions[k], sim.interceptor_positions[k]);
+            let ((tvx, tvy), (ivx, ivy)) = (sim.target_velocities[k], sim.interceptor_velocities[k]);
+            writeln!(
+                self.file,
+                "{},{:.3},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}",
+                step,
+                step as f64 * sim.config.dt,
+                tx, ty, tvx, tvy, ix, iy, ivx, ivy,
+                (tx - ix).hypot(ty - iy)
+            )?;
+        }
+        self.written = sim.steps() + 1;
+        if self.flush {
+            self.file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl SimObserver for TelemetryStream {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        if self.error.is_none() {
+            self.error = self.write_new(sim).err();
+        }
+    }
+
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        self.on_step(sim);
+    }
+}
+
+// --stream: whether runs stream their telemetry to disk
+pub fn stream_from_args(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--stream")
+}
+
+// --window N: samples kept in memory per run, None to keep the whole run
+pub fn window_from_args(args: &[String]) -> Result<Option<usize>, String> {
+    match args.windows(2).find(|pair| pair[0] == "--window") {
+        Some(pair) => Ok(Some(
+            pair[1].parse().ok().filter(|n| *n >= MIN_WINDOW).ok_or_else(|| format!("--window must be at least {} steps", MIN_WINDOW))?,
+        )),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,75 @@
+// When a run ends. Every run ends on an intercept (within collision_threshold, or the fuze's
+// detonation radius), when a vehicle hits the ground, or after max_steps; the `termination` section
+// of a scenario's config adds optional criteria:
+//
+//   "termination": { "max_time": 60, "escape_range": 150, "min_speed": 0.5, "max_passes": 3 }
+//
+//   max_time      seconds of flight; ends the run like max_steps (outcome timeout) if it comes first
+//   escape_range  the target is further than this (m) from the interceptor: outcome escape
+//   min_speed     the interceptor is slower than this (m/s): outcome stall
+//   max_passes    the interceptor missed this many passes: outcome miss
+//
+// A pass is missed when the interceptor overshoots: the range opens again after its closest
+// approach with the target abeam or behind the interceptor. The interceptor re-engages after a
+// missed pass, guidance turning it back for another, so without max_passes it keeps trying until
+// another criterion or max_steps ends the run, and with max_passes 1 the first miss is final. Every
+// missed pass is logged as a `pass` event with its closest approach.
+//
+// Like max_steps they are fixed for a run. Which criterion ended a run is reported in the result
+// as "termination" (SimulationResult::termination).
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TerminationCriteria {
+    pub max_time: Option<f64>,     // Seconds of flight
+    pub escape_range: Option<f64>, // Interceptor–target separation (m) at which the target has escaped
+    pub min_speed: Option<f64>,    // Interceptor speed (m/s) below which it can't go on
+    pub max_passes: Option<usize>, // Missed passes after which the interceptor gives up
+}
+
+impl TerminationCriteria {
+    pub fn is_empty(&self) -> bool {
+        *self == TerminationCriteria::default()
+    }
+
+    // Steps a run may take: max_steps, or fewer if max_time comes first
+    pub fn step_limit(&self, max_steps: usize, dt: f64) -> usize {
+        // Less a rounding margin, so that a max_time of a whole number of steps doesn't take one more
+        self.max_time.map_or(max_steps, |time| max_steps.min(((time / dt) - 1e-9).ceil().max(1.0) as usize))
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        let positive = |value: Option<f64>| value.is_none_or(|value| value > 0.0 && value.is_finite());
+        v.check(positive(self.max_time), "max_time", "must be a positive number of seconds");
+        v.check(positive(self.escape_range), "escape_range", "must be a positive distance");
+        v.check(positive(self.min_speed), "min_speed", "must be a positive speed");
+        v.check(self.max_passes != Some(0), "max_passes", "must be at least 1");
+    }
+}
+
+impl ToJson for TerminationCriteria {
+    fn to_json(&self) -> Json {
+        let fields = [("max_time", self.max_time), ("escape_range", self.escape_range), ("min_speed", self.min_speed)];
+        let max_passes = self.max_passes.map(|passes| ("max_passes", passes.into()));
+        Json::object(fields.into_iter().filter_map(|(name, value)| Some((name, value?.into()))).chain(max_passes).collect())
+    }
+}
+
+impl FromJson for TerminationCriteria {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        if !matches!(json, Json::Object(_)) {
+            return Err(String::from("must be an object with max_time, escape_range, min_speed and/or max_passes"));
+        }
+        let field = |name: &str| match json.get(name) {
+            None | Some(Json::Null) => Ok(None),
+            Some(_) => json.f64_field(name).map(Some),
+        };
+        let max_passes = match json.get("max_passes") {
+            None | Some(Json::Null) => None,
+            Some(passes) => Some(passes.as_usize().ok_or("field 'max_passes' must be a non-negative integer")?),
+        };
+        Ok(TerminationCriteria { max_time: field("max_time")?, escape_range: field("escape_range")?, min_speed: field("min_speed")?, max_passes })
+    }
+}
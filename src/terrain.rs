@@ -0,0 +1,103 @@
+// Terrain: the height of the ground along the plane of the engagement, instead of the flat ground at
+// height 0. The simulation is 2D, x downrange and y the height, so the terrain is a profile of
+// heights over x, e.g. cut from an elevation model along the line of flight:
+//
+//   "terrain": { "profile": [[0, 0], [40, 12], [70, 25], [110, 4]], "follow": 8, "lookahead": 3 }
+//
+//   profile    [x, height] points, x increasing, joined by straight lines; before the first point and
+//              past the last the terrain stays at their heights
+//   follow     clearance (m) the target's altitude hold keeps above the terrain, instead of holding
+//              target_initial_height; without it the target ignores the terrain
+//   lookahead  seconds of flight ahead whose highest terrain the target climbs for (default 3)
+//
+// A vehicle below the terrain has hit the ground, which ends the run as a ground impact does on flat
+// ground. The terrain also blocks lines of sight: a sensor sees nothing of a target behind a ridge
+// (the measurement is missed and the track coasts), and without tracking the interceptor's seeker
+// loses the target and guidance flies straight on until it reappears.
+//
+// The profile is held in an Arc, so that cloning a SimConfig for every run doesn't copy it.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+
+#[derive(Debug, PartialEq)]
+pub struct Terrain {
+    profile: Vec<(f64, f64)>, // x and height, x increasing
+    pub follow: Option<f64>,  // Clearance (m) the target keeps above the terrain
+    pub lookahead: f64,       // Seconds ahead the target looks for rising terrain
+}
+
+impl Terrain {
+    pub fn height(&self, x: f64) -> f64 {
+        let i = self.profile.partition_point(|&(px, _)| px <= x);
+        match (i, self.profile.len()) {
+            (0, _) => self.profile[0].1,
+            (i, n) if i == n => self.profile[n - 1].1,
+            (i, _) => {
+                let ((x0, h0), (x1, h1)) = (self.profile[i - 1], self.profile[i]);
+                h0 + (h1 - h0) * (x - x0) / (x1 - x0)
+            }
+        }
+    }
+
+    // The highest terrain between x0 and x1, in either order; the profile is straight in between
+    // its points, so those and the ends are all there is to check
+    pub fn highest(&self, x0: f64, x1: f64) -> f64 {
+        let (from, to) = (x0.min(x1), x0.max(x1));
+        self.profile.iter().filter(|(x, _)| (from..=to).contains(x)).map(|&(_, h)| h).fold(self.height(from).max(self.height(to)), f64::max)
+    }
+
+    pub fn below(&self, position: Vec2) -> bool {
+        position.y < self.height(position.x)
+    }
+
+    // Whether the terrain blocks the line of sight between two points; like highest, only the
+    // profile's points between them can rise above a straight line that clears both ends
+    pub fn masks(&self, from: Vec2, to: Vec2) -> bool {
+        if self.below(from) || self.below(to) {
+            return true;
+        }
+        let (from, to) = if from.x <= to.x { (from, to) } else { (to, from) };
+        self.profile
+            .iter()
+            .filter(|&&(x, _)| x > from.x && x < to.x)
+            .any(|&(x, h)| from.y + (to.y - from.y) * (x - from.x) / (to.x - from.x) < h)
+    }
+
+    // The profile from x_min to x_max, for drawing
+    pub fn outline(&self, x_min: f64, x_max: f64) -> Vec<(f64, f64)> {
+        let inner = self.profile.iter().copied().filter(|&(x, _)| x > x_min && x < x_max);
+        std::iter::once((x_min, self.height(x_min))).chain(inner).chain(std::iter::once((x_max, self.height(x_max)))).collect()
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.profile.iter().all(|(x, h)| x.is_finite() && h.is_finite()), "profile", "points must be finite");
+        v.check(self.profile.windows(2).all(|w| w[1].0 > w[0].0), "profile", "x must increase from point to point");
+        v.check(self.follow.is_none_or(|clearance| clearance > 0.0 && clearance.is_finite()), "follow", "must be a positive clearance");
+        v.check(self.lookahead >= 0.0 && self.lookahead.is_finite(), "lookahead", "must be zero or a positive number of seconds");
+    }
+}
+
+impl ToJson for Terrain {
+    fn to_json(&self) -> Json {
+        let profile = ("profile", Json::Array(self.profile.iter().map(|&point| point.into()).collect()));
+        let follow = self.follow.map(|clearance| ("follow", clearance.into()));
+        Json::object([profile].into_iter().chain(follow).chain([("lookahead", self.lookahead.into())]).collect())
+    }
+}
+
+impl FromJson for Terrain {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let profile = json.field("profile")?.as_array().ok_or("'profile' must be an array of [x, height] points")?;
+        let profile = profile.iter().map(|point| point.as_point().ok_or("'profile' points must be [x, height]")).collect::<Result<Vec<_>, _>>()?;
+        if profile.len() < 2 {
+            return Err("'profile' needs at least two points".into());
+        }
+        let follow = match json.get("follow") {
+            None | Some(Json::Null) => None,
+            Some(_) => Some(json.f64_field("follow")?),
+        };
+        Ok(Terrain { profile, follow, lookahead: json.f64_field_or("lookahead", 3.0)? })
+    }
+}
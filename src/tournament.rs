@@ -0,0 +1,247 @@
+// Tournaments: every pursuer policy against every evader policy over the same seeds, as a matrix
+// of the pursuers' win rates (the share of runs ending in an intercept). Both sides are pluggable:
+//
+//   pursuers  a guidance law (lead_pursuit, zem, lqr, mpc, clos), plugin:FILE (a WebAssembly
+//             guidance plugin, see plugin.rs) or neural:FILE (a learned policy, see onnx.rs)
+//   evaders   random (the scenario's noise and altitude hold), optimal or optimal:MAX_ACCEL (the
+//             bang-bang evasion of evasion.rs), script:FILE (see script.rs) or neural:FILE (a
+//             learned policy flown as evasion_model)
+//
+// Bare plugin, neural and script take the scenario's guidance_plugin, guidance_model, target_script
+// or evasion_model, and bare optimal its optimal_evasion (2 m/s² without one). Run k of every
+// pairing uses seed N + k, so all pairings meet the same envelope draws and noise as far as they
+// consume randomness alike. The matrix goes to the log and to tournament_matrix.csv, the details of
+// every pairing to tournament.csv.
+
+use crate::error::SimError;
+use crate::evasion::OptimalEvasion;
+use crate::log;
+use crate::onnx::NeuralGuidance;
+use crate::plugin::GuidancePlugin;
+use crate::progress::Progress;
+use crate::scenario::Scenario;
+use crate::script::TargetScript;
+use crate::stats::wilson_interval;
+use crate::{simulate_once, GuidanceLaw, Outcome, SimConfig};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone)]
+enum Pursuer {
+    Law(GuidanceLaw),
+    Plugin(Arc<GuidancePlugin>),
+    Neural(Arc<NeuralGuidance>),
+}
+
+#[derive(Clone)]
+enum Evader {
+    Random,
+    Optimal(OptimalEvasion),
+    Script(Arc<TargetScript>),
+    Neural(Arc<NeuralGuidance>),
+}
+
+impl Pursuer {
+    fn parse(spec: &str, base: &SimConfig) -> Result<Pursuer, String> {
+        let missing = |field: &str| format!("pursuer '{}' needs a file, e.g. {}:policy.onnx, or a scenario with {}", spec, spec, field);
+        Ok(match spec.split_once(':') {
+            Some(("plugin", file)) => Pursuer::Plugin(GuidancePlugin::load(Path::new(file))?),
+            Some(("neural", file)) => Pursuer::Neural(NeuralGuidance::load(Path::new(file))?),
+            _ => match GuidanceLaw::parse(spec) {
+                Some(GuidanceLaw::Plugin) => Pursuer::Plugin(base.guidance_plugin.clone().ok_or_else(|| missing("guidance_plugin"))?),
+                Some(GuidanceLaw::Neural) => Pursuer::Neural(base.guidance_model.clone().ok_or_else(|| missing("guidance_model"))?),
+                Some(law) => Pursuer::Law(law),
+                None => return Err(format!("unknown pursuer '{}', expected a guidance law ({}), plugin:FILE or neural:FILE", spec, GuidanceLaw::NAMES)),
+            },
+        })
+    }
+
+    fn apply(&self, config: SimConfig) -> SimConfig {
+        match self {
+            Pursuer::Law(guidance) => SimConfig { guidance: *guidance, ..config },
+            Pursuer::Plugin(plugin) => SimConfig { guidance: GuidanceLaw::Plugin, guidance_plugin: Some(plugin.clone()), ..config },
+            Pursuer::Neural(model) => SimConfig { guidance: GuidanceLaw::Neural, guidance_model: Some(model.clone()), ..config },
+        }
+    }
+}
+
+impl Evader {
+    fn parse(spec: &str, base: &SimConfig) -> Result<Evader, String> {
+        let missing = |field: &str| format!("evader '{}' needs a file, e.g. {}:evader.onnx, or a scenario with {}", spec, spec, field);
+        Ok(match spec.split_once(':') {
+            Some(("optimal", accel)) => {
+                let max_accel = accel.parse().ok().filter(|a: &f64| *a > 0.0 && a.is_finite()).ok_or_else(|| format!("evader '{}': the acceleration must be a positive number", spec))?;
+                Evader::Optimal(OptimalEvasion { max_accel, range: None })
+            }
+            Some(("script", file)) => Evader::Script(TargetScript::load(Path::new(file))?),
+            Some(("neural", file)) => Evader::Neural(NeuralGuidance::load(Path::new(file))?),
+            _ => match spec {
+                "random" => Evader::Random,
+                "optimal" => Evader::Optimal(base.optimal_evasion.unwrap_or(OptimalEvasion { max_accel: 2.0, range: None })),
+                "script" => Evader::Script(base.target_script.clone().ok_or_else(|| missing("target_script"))?),
+                "neural" => Evader::Neural(base.evasion_model.clone().ok_or_else(|| missing("evasion_model"))?),
+                _ => return Err(format!("unknown evader '{}', expected random, optimal[:MAX_ACCEL], script[:FILE] or neural[:FILE]", spec)),
+            },
+        })
+    }
+
+    // `config` with this policy turning the target instead of any the scenario had
+    fn apply(&self, config: SimConfig) -> SimConfig {
+        let config = SimConfig { optimal_evasion: None, target_script: None, evasion_model: None, ..config };
+        match self {
+            Evader::Random => config,
+            Evader::Optimal(evasion) => SimConfig { optimal_evasion: Some(*evasion), ..config },
+            Evader::Script(script) => SimConfig { target_script: Some(script.clone()), ..config },
+            Evader::Neural(model) => SimConfig { evasion_model: Some(model.clone()), ..config },
+        }
+    }
+}
+
+// The comma-separated policies of `list`, each with the name it is reported by: a file's without
+// its directory and extension, e.g. neural:policy
+fn parse_list<T>(list: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<(String, T)>, String> {
+    let name = |spec: &str| match spec.split_once(':') {
+        Some((kind, file)) if kind != "optimal" => format!("{}:{}", kind, Path::new(file).file_stem().unwrap_or_default().to_string_lossy()),
+        _ => spec.to_string(),
+    };
+    list.split(',').map(str::trim).map(|spec| Ok((name(spec), parse(spec)?))).collect()
+}
+
+// What one pairing came to
+struct Pairing {
+    wins: usize,
+    miss_sum: f64,
+    intercept_time_sum: f64,
+}
+
+// Usage: tournament [--pursuers LIST] [--evaders LIST] [--runs N / -n N] [--seed N] [--confidence C]
+//                   [--scenario FILE] [--preset NAME] [--out-dir DIR / -o DIR] [--quiet / -q]
+pub fn run_tournament(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let base = scenario.config;
+    let mut pursuers: Vec<(String, Pursuer)> = GuidanceLaw::ALL.iter().map(|&law| (law.as_str().to_string(), Pursuer::Law(law))).collect();
+    pursuers.extend(base.guidance_plugin.clone().map(|plugin| (String::from("plugin"), Pursuer::Plugin(plugin))));
+    pursuers.extend(base.guidance_model.clone().map(|model| (String::from("neural"), Pursuer::Neural(model))));
+    let mut evaders = vec![(String::from("random"), Evader::Random), (String::from("optimal"), Evader::parse("optimal", &base)?)];
+    evaders.extend(base.target_script.clone().map(|script| (String::from("script"), Evader::Script(script))));
+    evaders.extend(base.evasion_model.clone().map(|model| (String::from("neural"), Evader::Neural(model))));
+    let mut runs: usize = 50;
+    let mut seed: u64 = 1;
+    let mut confidence = 0.95;
+    let mut out_dir = String::from(".");
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--pursuers" if i + 1 < args.len() => {
+                pursuers = parse_list(&args[i + 1], |spec| Pursuer::parse(spec, &base))?;
+                i += 1;
+            }
+            "--evaders" if i + 1 < args.len() => {
+                evaders = parse_list(&args[i + 1], |spec| Evader::parse(spec, &base))?;
+                i += 1;
+            }
+            "--runs" | "-n" if i + 1 < args.len() => {
+                runs = args[i + 1].parse().ok().filter(|n| *n > 0).ok_or("--runs must be a positive integer")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            "--confidence" if i + 1 < args.len() => {
+                confidence = args[i + 1].parse().ok().filter(|c| *c > 0.0 && *c < 1.0).ok_or("--confidence must be between 0 and 1, e.g. 0.95")?;
+                i += 1;
+            }
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+
+    log::info!("🏆 Tournament of {} pursuers against {} evaders, {} runs per pairing", pursuers.len(), evaders.len(), runs);
+    let mut progress = Progress::new("Runs", pursuers.len() * evaders.len() * runs, true);
+    let mut results = Vec::new();
+    for (_, pursuer) in &pursuers {
+        let row: Vec<Pairing> = evaders
+            .iter()
+            .map(|(_, evader)| {
+                let mut pairing = Pairing { wins: 0, miss_sum: 0.0, intercept_time_sum: 0.0 };
+                for k in 0..runs as u64 {
+                    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(k));
+                    let config = evader.apply(pursuer.apply(scenario.envelope.sample(&base, &mut rng)));
+                    let sim = simulate_once(&mut rng, &config);
+                    let hit = sim.outcome == Outcome::Intercept;
+                    progress.inc(hit);
+                    pairing.miss_sum += sim.miss_distance;
+                    if hit {
+                        pairing.wins += 1;
+                        pairing.intercept_time_sum += sim.steps() as f64 * sim.config.dt;
+                    }
+                }
+                pairing
+            })
+            .collect();
+        results.push(row);
+    }
+    progress.finish();
+
+    let csv_path = Path::new(&out_dir).join("tournament.csv");
+    let mut csv = std::fs::File::create(&csv_path).map_err(SimError::io(format!("cannot create '{}'", csv_path.display())))?;
+    writeln!(csv, "pursuer,evader,runs,wins,win_rate,win_rate_low,win_rate_high,mean_miss_distance,mean_time_to_intercept")?;
+    for ((pursuer, _), row) in pursuers.iter().zip(&results) {
+        for ((evader, _), pairing) in evaders.iter().zip(row) {
+            let (low, high) = wilson_interval(pairing.wins, runs, confidence);
+            let mean_time = match pairing.wins {
+                0 => String::new(),
+                wins => format!("{:.4}", pairing.intercept_time_sum / wins as f64),
+            };
+            writeln!(
+                csv,
+                "{},{},{},{},{:.4},{:.4},{:.4},{:.4},{}",
+                pursuer,
+                evader,
+                runs,
+                pairing.wins,
+                pairing.wins as f64 / runs as f64,
+                low,
+                high,
+                pairing.miss_sum / runs as f64,
+                mean_time
+            )?;
+        }
+    }
+
+    let matrix_path = Path::new(&out_dir).join("tournament_matrix.csv");
+    let mut matrix = std::fs::File::create(&matrix_path).map_err(SimError::io(format!("cannot create '{}'", matrix_path.display())))?;
+    let names = |policies: Vec<&String>| policies.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(",");
+    writeln!(matrix, "pursuer,{}", names(evaders.iter().map(|(name, _)| name).collect()))?;
+    let width = pursuers.iter().map(|(name, _)| name.len()).chain([7]).max().unwrap_or(7);
+    let columns: Vec<usize> = evaders.iter().map(|(name, _)| name.len().max(7)).collect();
+    let header: String = evaders.iter().zip(&columns).map(|((name, _), column)| format!("  {:>column$}", name, column = column)).collect();
+    log::info!("   {:<width$}{}  {:>7}", "pursuer", header, "overall", width = width);
+    for ((pursuer, _), row) in pursuers.iter().zip(&results) {
+        let rates: Vec<f64> = row.iter().map(|pairing| pairing.wins as f64 / runs as f64).collect();
+        writeln!(matrix, "{},{}", pursuer, rates.iter().map(|rate| format!("{:.4}", rate)).collect::<Vec<_>>().join(","))?;
+        let cells: String = rates.iter().zip(&columns).map(|(rate, column)| format!("  {:>column$.1}%", rate * 100.0, column = column - 1)).collect();
+        let overall = rates.iter().sum::<f64>() / rates.len() as f64;
+        log::info!("   {:<width$}{}  {:>6.1}%", pursuer, cells, overall * 100.0, width = width);
+    }
+    let escapes: String = columns
+        .iter()
+        .enumerate()
+        .map(|(col, column)| {
+            let wins: usize = results.iter().map(|row| row[col].wins).sum();
+            format!("  {:>column$.1}%", 100.0 * (1.0 - wins as f64 / (runs * pursuers.len()) as f64), column = column - 1)
+        })
+        .collect();
+    log::info!("   {:<width$}{}", "escapes", escapes, width = width);
+    log::info!("   Cells are the pursuers' win rates, the last row the evaders' escape rates; {}% intervals in '{}'", confidence * 100.0, csv_path.display());
+    log::info!("✅ Win-rate matrix saved as '{}'", matrix_path.display());
+    Ok(())
+}
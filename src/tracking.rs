@@ -0,0 +1,415 @@
+// Target tracking. By default guidance sees the target's true state. With a `tracking` section in
+// the configuration the interceptor instead measures the target every step and guidance works
+// from a filtered estimate of position and velocity. The sensor is either a plain position
+// measurement with Gaussian noise of `measurement_noise` meters per axis, or a `radar` seeker with
+// range-dependent errors and drop-outs (see radar.rs); a `sensors` list instead fuses several of
+// them, on board or on the ground (see fusion.rs). The filters implement the TrackingFilter trait:
+//
+//   kalman      constant-velocity Kalman filter with white-acceleration process noise
+//               `process_noise` (m/s²)²; covariance and gains adapt as the track settles
+//   alpha_beta  fixed gains `alpha` (position) and `beta` (velocity); a handful of operations per
+//               update, but the gains have to be tuned to the noise and the target's maneuvering
+//   imm         interacting multiple models: constant velocity and coordinated turns at
+//               ±`turn_rate_deg`, see imm.rs
+//
+// e.g. "tracking": { "filter": "alpha_beta", "measurement_noise": 0.5, "alpha": 0.5, "beta": 0.1 }
+//
+// A `clutter` section adds false alarms and data association (see clutter.rs). A step without new
+// data is logged as a MeasurementMissed event and the filter coasts on its prediction.
+// Until the first detection guidance flies on the launch handover, the target's start state
+// extrapolated in a straight line, and the first measurement starts the track with its velocity.
+//
+// The measurements and the estimate used at every step are recorded in the result
+// (`track_measurements`, `track_positions`, `track_velocities`). The tracker stays with the
+// Simulation from one advance to the next. When --window drops old measurements, the filter they
+// went into is kept in the result as `track_filter`, so a run that continues from the result, e.g.
+// after a resume or rewind, replays only the measurements still recorded from there and carries on
+// exactly where it was. The track starts at launch, so tracking is not one of the live-editable
+// parameters.
+
+use crate::clutter::Clutter;
+use crate::fusion::{SensorSpec, Tracker, MAX_SENSORS};
+use crate::imm::Imm;
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use crate::{SimConfig, SimEvent, SimulationResult};
+
+// Velocity variance of a new track, whose velocity is only the launch handover's; large enough for any target here
+pub const INITIAL_VELOCITY_VARIANCE: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackEstimate {
+    pub position: (f64, f64),
+    pub velocity: (f64, f64),
+}
+
+impl TrackEstimate {
+    // Before the first detection: the target's start state, flown straight on for `elapsed` seconds
+    pub fn handover(config: &SimConfig, elapsed: f64) -> TrackEstimate {
+        let (sin, cos) = config.target_heading_deg.to_radians().sin_cos();
+        let velocity = (config.target_speed * cos, config.target_speed * sin);
+        TrackEstimate {
+            position: (config.target_start_x + velocity.0 * elapsed, config.target_start_y + velocity.1 * elapsed),
+            velocity,
+        }
+    }
+}
+
+// A measured target position and its error (standard deviation per axis, m)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub position: (f64, f64),
+    pub noise: f64,
+}
+
+impl ToJson for Measurement {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("position", self.position.into()), ("noise", self.noise.into())])
+    }
+}
+
+impl FromJson for Measurement {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let position = json.field("position")?.as_point().ok_or("measurement 'position' must be [x, y]")?;
+        Ok(Measurement { position, noise: json.f64_field("noise")? })
+    }
+}
+
+impl ToJson for TrackEstimate {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("position", self.position.into()), ("velocity", self.velocity.into())])
+    }
+}
+
+impl FromJson for TrackEstimate {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let position = json.field("position")?.as_point().ok_or("estimate 'position' must be [x, y]")?;
+        let velocity = json.field("velocity")?.as_point().ok_or("estimate 'velocity' must be [vx, vy]")?;
+        Ok(TrackEstimate { position, velocity })
+    }
+}
+
+// The state a filter saves with to_json: everything that changes as it runs, but none of the
+// parameters, which come from the configuration. Results holding one go between threads.
+pub trait TrackingFilter: ToJson + Send + Sync {
+    // Folds in a measurement taken `dt` after the previous update or coast (0 for another
+    // measurement of the same moment) and returns the new estimate
+    fn update(&mut self, measurement: Measurement, dt: f64) -> TrackEstimate;
+
+    // A step without a measurement: the prediction `dt` on, or None before the first measurement
+    fn coast(&mut self, dt: f64) -> Option<TrackEstimate>;
+
+    fn boxed_clone(&self) -> Box<dyn TrackingFilter>;
+
+    // Back to a state saved with to_json
+    fn restore(&mut self, state: &Json) -> Result<(), String>;
+}
+
+impl Clone for Box<dyn TrackingFilter> {
+    fn clone(&self) -> Self {
+        self.boxed_clone()
+    }
+}
+
+// `N` numbers from field `key` of a saved filter state
+pub fn numbers<const N: usize>(json: &Json, key: &str) -> Result<[f64; N], String> {
+    let error = || format!("field '{}' must be {} numbers", key, N);
+    let values = json.field(key)?.as_array().filter(|values| values.len() == N).ok_or_else(error)?;
+    let mut numbers = [0.0; N];
+    for (number, value) in numbers.iter_mut().zip(values) {
+        *number = value.as_f64().ok_or_else(error)?;
+    }
+    Ok(numbers)
+}
+
+// The estimate of a saved filter state, null before the first measurement
+fn estimate_from_json(json: &Json) -> Result<Option<TrackEstimate>, String> {
+    match json.field("estimate")? {
+        Json::Null => Ok(None),
+        estimate => TrackEstimate::from_json(estimate).map(Some),
+    }
+}
+
+#[derive(Clone)]
+pub struct AlphaBeta {
+    alpha: f64,
+    beta: f64,
+    initial_velocity: (f64, f64),
+    estimate: Option<TrackEstimate>,
+    coasted: f64, // Seconds since the last measurement
+}
+
+impl TrackingFilter for AlphaBeta {
+    fn update(&mut self, measurement: Measurement, dt: f64) -> TrackEstimate {
+        let (zx, zy) = measurement.position;
+        let estimate = match self.coast(dt) {
+            None => TrackEstimate { position: (zx, zy), velocity: self.initial_velocity },
+            Some(TrackEstimate { position: (px, py), velocity: (vx, vy) }) => {
+                // The velocity correction is spread over the time since the last measurement; a
+                // second measurement of the same moment only corrects the position
+                let elapsed = self.coasted;
+                let (rx, ry) = (zx - px, zy - py);
+                let velocity = if elapsed > 0.0 { (vx + self.beta * rx / elapsed, vy + self.beta * ry / elapsed) } else { (vx, vy) };
+                TrackEstimate { position: (px + self.alpha * rx, py + self.alpha * ry), velocity }
+            }
+        };
+        self.estimate = Some(estimate);
+        self.coasted = 0.0;
+        estimate
+    }
+
+    fn coast(&mut self, dt: f64) -> Option<TrackEstimate> {
+        let TrackEstimate { position: (x, y), velocity: (vx, vy) } = self.estimate?;
+        self.coasted += dt;
+        let predicted = TrackEstimate { position: (x + vx * dt, y + vy * dt), velocity: (vx, vy) };
+        self.estimate = Some(predicted);
+        Some(predicted)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn TrackingFilter> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, state: &Json) -> Result<(), String> {
+        (self.estimate, self.coasted) = (estimate_from_json(state)?, state.f64_field("coasted")?);
+        Ok(())
+    }
+}
+
+impl ToJson for AlphaBeta {
+    fn to_json(&self) -> Json {
+        Json::object(vec![("estimate", self.estimate.map(|e| e.to_json()).into()), ("coasted", self.coasted.into())])
+    }
+}
+
+// Both axes share the same model and noise, so one covariance [[p11, p12], [p12, p22]] serves both
+#[derive(Clone)]
+pub struct Kalman {
+    process_noise: f64,
+    initial_velocity: (f64, f64),
+    covariance: (f64, f64, f64),
+    estimate: Option<TrackEstimate>,
+}
+
+impl TrackingFilter for Kalman {
+    fn update(&mut self, measurement: Measurement, dt: f64) -> TrackEstimate {
+        let (zx, zy) = measurement.position;
+        let measurement_variance = measurement.noise * measurement.noise;
+        let estimate = match self.coast(dt) {
+            None => {
+                self.covariance = (measurement_variance, 0.0, INITIAL_VELOCITY_VARIANCE);
+                TrackEstimate { position: (zx, zy), velocity: self.initial_velocity }
+            }
+            Some(TrackEstimate { position: (px, py), velocity: (vx, vy) }) => {
+                let (p11, p12, p22) = self.covariance;
+                let s = p11 + measurement_variance;
+                let (k1, k2) = (p11 / s, p12 / s);
+                self.covariance = ((1.0 - k1) * p11, (1.0 - k1) * p12, p22 - k2 * p12);
+                let (rx, ry) = (zx - px, zy - py);
+                TrackEstimate { position: (px + k1 * rx, py + k1 * ry), velocity: (vx + k2 * rx, vy + k2 * ry) }
+            }
+        };
+        self.estimate = Some(estimate);
+        estimate
+    }
+
+    fn coast(&mut self, dt: f64) -> Option<TrackEstimate> {
+        let TrackEstimate { position: (x, y), velocity: (vx, vy) } = self.estimate?;
+        let (p11, p12, p22) = self.covariance;
+        let q = self.process_noise;
+        self.covariance = (
+            p11 + 2.0 * dt * p12 + dt * dt * p22 + q * dt.powi(4) / 4.0,
+            p12 + dt * p22 + q * dt.powi(3) / 2.0,
+            p22 + q * dt * dt,
+        );
+        let predicted = TrackEstimate { position: (x + vx * dt, y + vy * dt), velocity: (vx, vy) };
+        self.estimate = Some(predicted);
+        Some(predicted)
+    }
+
+    fn boxed_clone(&self) -> Box<dyn TrackingFilter> {
+        Box::new(self.clone())
+    }
+
+    fn restore(&mut self, state: &Json) -> Result<(), String> {
+        let [p11, p12, p22] = numbers(state, "covariance")?;
+        (self.estimate, self.covariance) = (estimate_from_json(state)?, (p11, p12, p22));
+        Ok(())
+    }
+}
+
+impl ToJson for Kalman {
+    fn to_json(&self) -> Json {
+        let (p11, p12, p22) = self.covariance;
+        Json::object(vec![
+            ("estimate", self.estimate.map(|e| e.to_json()).into()),
+            ("covariance", Json::Array(vec![p11.into(), p12.into(), p22.into()])),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterKind {
+    Kalman { process_noise: f64 },
+    AlphaBeta { alpha: f64, beta: f64 },
+    Imm { process_noise: f64, turn_rate_deg: f64, switch_probability: f64 },
+}
+
+impl FilterKind {
+    pub const NAMES: &'static str = "kalman, alpha_beta, imm";
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterKind::Kalman { .. } => "kalman",
+            FilterKind::AlphaBeta { .. } => "alpha_beta",
+            FilterKind::Imm { .. } => "imm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tracking {
+    pub filter: FilterKind,
+    pub sensors: [Option<SensorSpec>; MAX_SENSORS], // The first `count` are set
+    pub clutter: Option<Clutter>,
+}
+
+impl Tracking {
+    pub fn sensors(&self) -> impl Iterator<Item = &SensorSpec> {
+        self.sensors.iter().flatten()
+    }
+
+    // A filter that has seen nothing yet
+    pub fn new_filter(&self, config: &SimConfig) -> Box<dyn TrackingFilter> {
+        let initial_velocity = TrackEstimate::handover(config, 0.0).velocity;
+        match self.filter {
+            FilterKind::AlphaBeta { alpha, beta } => Box::new(AlphaBeta { alpha, beta, initial_velocity, estimate: None, coasted: 0.0 }),
+            FilterKind::Kalman { process_noise } => {
+                Box::new(Kalman { process_noise, initial_velocity, covariance: (0.0, 0.0, 0.0), estimate: None })
+            }
+            FilterKind::Imm { process_noise, turn_rate_deg, switch_probability } => {
+                Box::new(Imm::new(process_noise, turn_rate_deg, switch_probability, initial_velocity))
+            }
+        }
+    }
+
+    // A tracker that continues the track recorded in `sim` so far: the measurements still recorded
+    // go into the filter the dropped ones left behind, or into a new one
+    pub fn tracker(&self, sim: &SimulationResult) -> Tracker {
+        let filter = sim.track_filter.clone().unwrap_or_else(|| self.new_filter(&sim.config));
+        let sensors: Vec<SensorSpec> = self.sensors().copied().collect();
+        Tracker::new(filter, &sensors, sim.config.dt, &sim.track_measurements, sim.track_measurements.len().saturating_sub(1))
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(self.sensors().next().is_some(), "sensors", "tracking needs at least one sensor");
+        for (i, sensor) in self.sensors().enumerate() {
+            v.section(&format!("sensors[{}]", i), |v| sensor.validate(v, matches!(self.filter, FilterKind::AlphaBeta { .. })));
+        }
+        if let Some(clutter) = &self.clutter {
+            v.section("clutter", |v| clutter.validate(v));
+        }
+        let nonneg = |x: f64| x >= 0.0 && x.is_finite();
+        match self.filter {
+            FilterKind::Kalman { process_noise } => v.check(nonneg(process_noise), "process_noise", "must be zero or positive"),
+            // Stable for 0 < alpha <= 1 and 0 < beta < 4 - 2 alpha
+            FilterKind::AlphaBeta { alpha, beta } => {
+                v.check(alpha > 0.0 && alpha <= 1.0, "alpha", "must satisfy 0 < alpha <= 1");
+                v.check(beta > 0.0 && beta < 4.0 - 2.0 * alpha, "beta", "must satisfy 0 < beta < 4 - 2 alpha");
+            }
+            FilterKind::Imm { process_noise, turn_rate_deg, switch_probability } => {
+                v.check(nonneg(process_noise), "process_noise", "must be zero or positive");
+                v.check(turn_rate_deg > 0.0 && turn_rate_deg < 180.0, "turn_rate_deg", "must be between 0 and 180°/s");
+                v.check(switch_probability > 0.0 && switch_probability < 1.0, "switch_probability", "must be between 0 and 1");
+            }
+        }
+    }
+}
+
+impl ToJson for Tracking {
+    fn to_json(&self) -> Json {
+        let mut fields = vec![
+            ("filter", self.filter.as_str().into()),
+            ("sensors", Json::Array(self.sensors().map(ToJson::to_json).collect())),
+        ];
+        if let Some(clutter) = self.clutter {
+            fields.push(("clutter", clutter.to_json()));
+        }
+        match self.filter {
+            FilterKind::Kalman { process_noise } => fields.push(("process_noise", process_noise.into())),
+            FilterKind::AlphaBeta { alpha, beta } => fields.extend([("alpha", alpha.into()), ("beta", beta.into())]),
+            FilterKind::Imm { process_noise, turn_rate_deg, switch_probability } => fields.extend([
+                ("process_noise", process_noise.into()),
+                ("turn_rate_deg", turn_rate_deg.into()),
+                ("switch_probability", switch_probability.into()),
+            ]),
+        }
+        Json::object(fields)
+    }
+}
+
+// Either a `sensors` list, or the fields of a single onboard sensor without latency
+impl FromJson for Tracking {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let filter = match json.field("filter")?.as_str() {
+            Some("kalman") => FilterKind::Kalman { process_noise: json.f64_field_or("process_noise", 0.1)? },
+            Some("alpha_beta") => FilterKind::AlphaBeta { alpha: json.f64_field_or("alpha", 0.5)?, beta: json.f64_field_or("beta", 0.1)? },
+            Some("imm") => FilterKind::Imm {
+                process_noise: json.f64_field_or("process_noise", 0.01)?,
+                turn_rate_deg: json.f64_field_or("turn_rate_deg", 10.0)?,
+                switch_probability: json.f64_field_or("switch_probability", 0.05)?,
+            },
+            _ => return Err(format!("field 'filter' must be one of: {}", FilterKind::NAMES)),
+        };
+        let mut sensors = [None; MAX_SENSORS];
+        match json.get("sensors") {
+            Some(list) => {
+                let list = list.as_array().ok_or("field 'sensors' must be an array")?;
+                if list.len() > MAX_SENSORS {
+                    return Err(format!("at most {} sensors", MAX_SENSORS));
+                }
+                for (slot, sensor) in sensors.iter_mut().zip(list) {
+                    *slot = Some(SensorSpec::from_json(sensor).map_err(|e| format!("sensors: {}", e))?);
+                }
+            }
+            None => sensors[0] = Some(SensorSpec::from_json(json)?),
+        }
+        let clutter = match json.get("clutter").filter(|v| **v != Json::Null) {
+            Some(clutter) => Some(Clutter::from_json(clutter).map_err(|e| format!("clutter: {}", e))?),
+            None => None,
+        };
+        Ok(Tracking { filter, sensors, clutter })
+    }
+}
+
+// Root-mean-square error of the recorded estimates against the truth, (position in m, velocity in
+// m/s); None without a track. The estimate of step k is compared with the target's position at
+// step k and the velocity it flew step k with.
+fn track_errors(sim: &SimulationResult) -> Option<(f64, f64)> {
+    let (mut position, mut velocity, mut count) = (0.0, 0.0, 0);
+    for (k, (&(ex, ey), &(evx, evy))) in sim.track_positions.iter().zip(&sim.track_velocities).enumerate() {
+        let (Some(&(x, y)), Some(&(vx, vy))) = (sim.target_positions.get(k), sim.target_velocities.get(k + 1)) else {
+            break;
+        };
+        position += (ex - x).powi(2) + (ey - y).powi(2);
+        velocity += (evx - vx).powi(2) + (evy - vy).powi(2);
+        count += 1;
+    }
+    (count > 0).then(|| ((position / count as f64).sqrt(), (velocity / count as f64).sqrt()))
+}
+
+// One line on how well the run was tracked, e.g. "kalman track, RMS error 0.52 m, 0.61 m/s"; None without tracking
+pub fn summary(sim: &SimulationResult) -> Option<String> {
+    let tracking = sim.config.tracking?;
+    let (position, velocity) = track_errors(sim)?;
+    let mut summary = format!("{} track, RMS error {:.2} m, {:.2} m/s", tracking.filter.as_str(), position, velocity);
+    let missed = sim.events.iter().filter(|e| matches!(e, SimEvent::MeasurementMissed { .. })).count();
+    if missed > 0 {
+        summary += &format!(", no new data at {} of {} steps", missed, sim.track_measurements.len());
+    }
+    let misassociated = sim.events.iter().filter(|e| matches!(e, SimEvent::AssociationError { .. })).count();
+    if misassociated > 0 {
+        summary += &format!(", {} false plots associated", misassociated);
+    }
+    Some(summary)
+}
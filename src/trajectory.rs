@@ -0,0 +1,104 @@
+// Target paths recorded outside the simulation, from a flight test or another tool, that the
+// target flies instead of its random evasion. The file is CSV with the columns t,x,y (seconds and
+// meters, y the height as in the simulation) or t,x,y,z, a 3D path with z the height, whose
+// cross-range y the 2D engagement drops. A header line, blank lines and lines starting with # are
+// skipped. Between samples the path is interpolated linearly to the simulation's time steps; past
+// the last sample the target flies on along the last segment.
+//
+// A path is held in an Arc, shared by the config, the world's target and every run of a batch.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::vec2::Vec2;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq)]
+pub struct TargetPath {
+    samples: Vec<(f64, Vec2)>, // Time from the first sample (s) and position, at least two, times increasing
+}
+
+impl TargetPath {
+    pub fn new(samples: Vec<(f64, Vec2)>) -> Result<TargetPath, String> {
+        if samples.len() < 2 {
+            return Err("a target path needs at least two samples".into());
+        }
+        if let Some(i) = samples.iter().position(|(t, p)| !(t.is_finite() && p.x.is_finite() && p.y.is_finite())) {
+            return Err(format!("sample {} is not a finite number", i + 1));
+        }
+        if let Some(i) = samples.windows(2).position(|w| w[1].0 <= w[0].0) {
+            return Err(format!("sample {}: times must increase", i + 2));
+        }
+        let t0 = samples[0].0;
+        Ok(TargetPath { samples: samples.into_iter().map(|(t, p)| (t - t0, p)).collect() })
+    }
+
+    pub fn from_csv(text: &str) -> Result<TargetPath, String> {
+        let mut samples = Vec::new();
+        let mut header = true; // Whether the next line may still be the header
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Ok(values) = line.split(',').map(|v| v.trim().parse::<f64>()).collect::<Result<Vec<_>, _>>() else {
+                if std::mem::take(&mut header) {
+                    continue;
+                }
+                return Err(format!("line {}: expected numbers t,x,y or t,x,y,z", i + 1));
+            };
+            header = false;
+            samples.push(match values[..] {
+                [t, x, y] => (t, Vec2::new(x, y)),
+                [t, x, _, z] => (t, Vec2::new(x, z)),
+                _ => return Err(format!("line {}: expected 3 or 4 columns, found {}", i + 1, values.len())),
+            });
+        }
+        TargetPath::new(samples)
+    }
+
+    // The CSV file at `path`
+    pub fn load(path: &Path) -> Result<Arc<TargetPath>, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        let target_path = TargetPath::from_csv(&text).map_err(|e| format!("'{}' {}", path.display(), e))?;
+        Ok(Arc::new(target_path))
+    }
+
+    // The segment `t` falls in (the first before the path starts, the last after it ends)
+    fn segment(&self, t: f64) -> (&(f64, Vec2), &(f64, Vec2)) {
+        let i = self.samples[1..self.samples.len() - 1].partition_point(|(time, _)| *time <= t);
+        (&self.samples[i], &self.samples[i + 1])
+    }
+
+    pub fn position(&self, t: f64) -> Vec2 {
+        let t = t.max(0.0);
+        let ((t0, p0), (t1, p1)) = self.segment(t);
+        *p0 + (*p1 - *p0) * ((t - t0) / (t1 - t0))
+    }
+
+    pub fn velocity(&self, t: f64) -> Vec2 {
+        let ((t0, p0), (t1, p1)) = self.segment(t);
+        (*p1 - *p0) / (t1 - t0)
+    }
+}
+
+// As [t, x, y] samples, so that result files replay without the CSV
+impl ToJson for TargetPath {
+    fn to_json(&self) -> Json {
+        Json::Array(self.samples.iter().map(|(t, p)| Json::Array(vec![(*t).into(), p.x.into(), p.y.into()])).collect())
+    }
+}
+
+impl FromJson for TargetPath {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let samples = json
+            .as_array()
+            .ok_or("a target path must be a CSV file name or a list of [t, x, y] samples")?
+            .iter()
+            .map(|sample| match sample.as_array().map(|v| v.iter().map(Json::as_f64).collect::<Option<Vec<_>>>()) {
+                Some(Some(v)) if v.len() == 3 => Ok((v[0], Vec2::new(v[1], v[2]))),
+                _ => Err("target path samples must be [t, x, y]"),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        TargetPath::new(samples)
+    }
+}
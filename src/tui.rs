@@ -0,0 +1,224 @@
+// Terminal visualization for headless machines: draws both trajectories with
+// braille characters (2x4 dots per cell) and a live distance readout using
+// plain ANSI escape sequences, so a run can be watched over SSH.
+//
+// The cursor is hidden while the animation runs. It is shown again when a run finishes, when the
+// view is dropped (an error ends the batch) and, on Unix, when Ctrl-C or SIGTERM interrupts the
+// program; elsewhere `reset` brings it back after an interrupt.
+
+use crate::bounds::PlotBounds;
+use crate::simulation::SimObserver;
+use crate::SimulationResult;
+use std::fmt::Write as _;
+use std::io::Write;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+// Shows the cursor before an interrupt ends the program. std has no signal handling, so this
+// declares the few libc functions it needs; the handler only calls async-signal-safe ones.
+#[cfg(unix)]
+mod interrupt {
+    use std::ffi::c_int;
+
+    const SIGINT: c_int = 2;
+    const SIGTERM: c_int = 15;
+    const SIG_DFL: usize = 0;
+
+    extern "C" {
+        fn signal(signum: c_int, handler: usize) -> usize;
+        fn raise(signum: c_int) -> c_int;
+        fn write(fd: c_int, buf: *const u8, count: usize) -> isize;
+    }
+
+    // Restores the cursor, then dies of the signal as the program would have without the handler
+    extern "C" fn restore_cursor(signum: c_int) {
+        let show = super::SHOW_CURSOR.as_bytes();
+        // SAFETY: write, signal and raise are async-signal-safe, and `show` is a static string
+        unsafe {
+            write(1, show.as_ptr(), show.len());
+            signal(signum, SIG_DFL);
+            raise(signum);
+        }
+    }
+
+    pub fn install() {
+        for signum in [SIGINT, SIGTERM] {
+            // SAFETY: the handler has the signature signal() expects and stays valid for good
+            unsafe { signal(signum, restore_cursor as extern "C" fn(c_int) as usize) };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Owner {
+    None,
+    Target,
+    Interceptor,
+}
+
+// Character grid where each cell holds a braille dot pattern and the entity that drew last
+struct Canvas {
+    cols: usize,
+    rows: usize,
+    dots: Vec<u8>,
+    owner: Vec<Owner>,
+}
+
+impl Canvas {
+    fn new(cols: usize, rows: usize) -> Self {
+        Canvas { cols, rows, dots: vec![0; cols * rows], owner: vec![Owner::None; cols * rows] }
+    }
+
+    // Set a single dot; x in [0, 2*cols), y in [0, 4*rows) with y growing downwards
+    fn set(&mut self, x: i64, y: i64, owner: Owner) {
+        if x < 0 || y < 0 || x >= 2 * self.cols as i64 || y >= 4 * self.rows as i64 {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let bit = match (x % 2, y % 4) {
+            (0, 3) => 0x40,
+            (1, 3) => 0x80,
+            (0, row) => 1 << row,
+            (_, row) => 1 << (row + 3),
+        };
+        let cell = (y / 4) * self.cols + x / 2;
+        self.dots[cell] |= bit;
+        self.owner[cell] = owner;
+    }
+
+    // Bresenham line between two dot coordinates
+    fn line(&mut self, (mut x0, mut y0): (i64, i64), (x1, y1): (i64, i64), owner: Owner) {
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x0, y0, owner);
+            if x0 == x1 && y0 == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x0 += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y0 += sy;
+            }
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        for row in 0..self.rows {
+            out.push('│');
+            for col in 0..self.cols {
+                let cell = row * self.cols + col;
+                match self.owner[cell] {
+                    Owner::None => out.push(' '),
+                    owner => {
+                        let color = if owner == Owner::Target { RED } else { GREEN };
+                        let glyph = char::from_u32(0x2800 + self.dots[cell] as u32).unwrap_or(' ');
+                        let _ = write!(out, "{}{}{}", color, glyph, RESET);
+                    }
+                }
+            }
+            out.push_str("\x1b[K\n");
+        }
+    }
+}
+
+pub struct TuiView {
+    cols: usize,
+    rows: usize,
+    closest: f64,
+    step: usize,
+}
+
+impl TuiView {
+    // Terminal size comes from $COLUMNS / $LINES when exported by the shell, otherwise 100x32
+    pub fn new() -> Self {
+        let env_size = |name: &str, default: usize| std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        let cols = env_size("COLUMNS", 100).saturating_sub(2).max(20);
+        let rows = env_size("LINES", 32).saturating_sub(5).max(8);
+        // Clear screen and hide the cursor for the duration of the animation
+        #[cfg(unix)]
+        interrupt::install();
+        print!("\x1b[2J\x1b[?25l");
+        TuiView { cols, rows, closest: f64::INFINITY, step: 0 }
+    }
+
+    fn draw(&mut self, sim: &SimulationResult, done: bool) {
+        let (target_positions, interceptor_positions) = (&sim.target_positions[..], &sim.interceptor_positions[..]);
+        let bounds = PlotBounds::from_result(sim);
+        let (dot_w, dot_h) = ((2 * self.cols) as f64, (4 * self.rows) as f64);
+        let to_dot = |(x, y): (f64, f64)| {
+            let px = (x / bounds.max_x * (dot_w - 1.0)).round() as i64;
+            let py = ((1.0 - y / bounds.max_y) * (dot_h - 1.0)).round() as i64;
+            (px, py)
+        };
+
+        let mut canvas = Canvas::new(self.cols, self.rows);
+        for (positions, owner) in [(target_positions, Owner::Target), (interceptor_positions, Owner::Interceptor)] {
+            if let Some(&first) = positions.first() {
+                canvas.set(to_dot(first).0, to_dot(first).1, owner);
+            }
+            for pair in positions.windows(2) {
+                canvas.line(to_dot(pair[0]), to_dot(pair[1]), owner);
+            }
+        }
+
+        let distance = match (target_positions.last(), interceptor_positions.last()) {
+            (Some(t), Some(i)) => ((t.0 - i.0).powi(2) + (t.1 - i.1).powi(2)).sqrt(),
+            _ => f64::NAN,
+        };
+        self.closest = self.closest.min(distance);
+
+        let mut out = String::from("\x1b[H");
+        let _ = writeln!(out, "{:.1} m\x1b[K", bounds.max_y);
+        canvas.render(&mut out);
+        let _ = writeln!(out, "└{}\x1b[K", "─".repeat(self.cols));
+        let _ = writeln!(out, "0{:>width$.1} m\x1b[K", bounds.max_x, width = self.cols - 1);
+        let _ = writeln!(
+            out,
+            "{}●{} target  {}●{} interceptor   step {:4}   distance {:8.2} m   closest {:8.2} m{}\x1b[K",
+            RED,
+            RESET,
+            GREEN,
+            RESET,
+            self.step,
+            distance,
+            self.closest,
+            if done { "   (done)" } else { "   (p pause, s step, q skip + Enter)" }
+        );
+        print!("{}", out);
+        let _ = std::io::stdout().flush();
+    }
+}
+
+impl SimObserver for TuiView {
+    fn on_step(&mut self, sim: &SimulationResult) {
+        self.step += 1;
+        self.draw(sim, false);
+    }
+
+    // Draw the final frame, restore the cursor and reset the readout for the next run
+    fn on_finish(&mut self, sim: &SimulationResult) {
+        self.draw(sim, true);
+        print!("{}", SHOW_CURSOR);
+        let _ = std::io::stdout().flush();
+        self.closest = f64::INFINITY;
+        self.step = 0;
+    }
+}
+
+impl Drop for TuiView {
+    fn drop(&mut self) {
+        print!("{}", SHOW_CURSOR);
+        let _ = std::io::stdout().flush();
+    }
+}
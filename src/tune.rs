@@ -0,0 +1,175 @@
+// Tuning at a terminal prompt: change parameters with typed commands and re-run the engagement
+// immediately, without the edit-compile-run loop. It is a line-based prompt, not a GUI with
+// sliders; there is no GUI toolkit among the dependencies.
+//
+// Every re-run uses the same RNG seed (until changed with `seed`), so differences between runs
+// come from the parameters alone. The plot is rewritten to `tune.png` in the output directory
+// after each run; keep it open in an auto-reloading image viewer.
+//
+// `rewind <step>` branches from an earlier point of the current run: later re-runs keep its path
+// up to that step, including the random draws made so far, and continue from there with the
+// parameters as they are now — "what if the interceptor had been faster from step 40 on".
+
+use crate::error::SimError;
+use crate::plot::{visualize_simulation, PlotOptions};
+use crate::checkpoint::SimRng;
+use crate::scenario::Scenario;
+use crate::simulation::Simulation;
+use crate::{SimConfig, SimulationResult};
+use rand::SeedableRng;
+use std::io::{BufRead, Write};
+
+const HELP: &str = "\
+Commands (confirm with Enter):
+  speed <m/s>      interceptor speed
+  gain <value>     target altitude P gain
+  weight <0..1>    correction weight (0 = pure random, 1 = pure correction)
+  noise <deg>      target evasion jitter range (±deg)
+  start <x> <y>    interceptor start position
+  seed <n>         RNG seed used by every re-run
+  rewind <step>    re-run from this step of the current run on: the path up to it
+                   is kept and parameter changes only apply from there
+  rewind off       re-run from the start again
+  run              re-run (an empty line does the same)
+  show             print current parameters
+  help             this text
+  quit             leave the session";
+
+// Usage: tune [--scenario FILE] [--out-dir DIR / -o DIR] [--seed N]
+pub fn run_tune(args: &[String], scenario: Scenario) -> Result<(), SimError> {
+    let mut config = scenario.config;
+    let plot = PlotOptions { style: scenario.style, ..PlotOptions::default() };
+    if !plot.enabled {
+        return Err("tune shows every run as a plot, which this build leaves out (see the plot feature)".into());
+    }
+    let mut out_dir = String::from(".");
+    let mut seed: u64 = 1;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--out-dir" | "-o" if i + 1 < args.len() => {
+                out_dir = args[i + 1].clone();
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?;
+                i += 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    std::fs::create_dir_all(&out_dir).map_err(SimError::io(format!("cannot create output directory '{}'", out_dir)))?;
+    let out_png = std::path::Path::new(&out_dir).join("tune.png");
+
+    println!("🎛️ Tuning session, plot: '{}'", out_png.display());
+    println!("{}", HELP);
+    let mut current = rerun(&config, seed, None, out_png.to_str().unwrap(), &plot)?;
+    // Step to branch from and the run that was current when rewinding
+    let mut branch: Option<(usize, Recorded)> = None;
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("🎛️ > ");
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break; // EOF
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let values: Vec<f64> = words.iter().skip(1).filter_map(|w| w.parse().ok()).collect();
+        match (words.first().copied(), values.as_slice()) {
+            (None | Some("run"), _) => {}
+            (Some("speed"), [v]) if *v > 0.0 => config.interceptor_speed = *v,
+            (Some("gain"), [v]) => config.p_gain = *v,
+            (Some("weight"), [v]) if (0.0..=1.0).contains(v) => config.correction_weight = *v,
+            (Some("noise"), [v]) if *v >= 0.0 => config.noise_range_deg = *v,
+            (Some("start"), [x, y]) => {
+                config.interceptor_start_x = *x;
+                config.interceptor_start_y = *y;
+            }
+            (Some("seed"), [v]) if *v >= 0.0 && v.fract() == 0.0 => seed = *v as u64,
+            (Some("rewind"), []) if words.get(1) == Some(&"off") => branch = None,
+            (Some("rewind"), [v]) if *v >= 0.0 && v.fract() == 0.0 => {
+                let last = current.rngs.len() - 1;
+                if *v as usize > last {
+                    println!("❓ The current run can be rewound to step {} at most", last);
+                    continue;
+                }
+                branch = Some((*v as usize, current.clone()));
+            }
+            (Some("show"), _) => {
+                print_config(&config, seed, branch.as_ref().map(|(step, _)| *step));
+                continue;
+            }
+            (Some("help"), _) => {
+                println!("{}", HELP);
+                continue;
+            }
+            (Some("quit" | "exit" | "q"), _) => break,
+            _ => {
+                println!("❓ Unrecognized or out-of-range input, type 'help'");
+                continue;
+            }
+        }
+        // Any accepted change re-simulates straight away
+        current = rerun(&config, seed, branch.as_ref(), out_png.to_str().unwrap(), &plot)?;
+    }
+    Ok(())
+}
+
+// A finished run and the RNG state before each of its steps, which is what rewinding needs
+#[derive(Clone)]
+struct Recorded {
+    sim: SimulationResult,
+    rngs: Vec<SimRng>,
+}
+
+fn rerun(
+    config: &SimConfig,
+    seed: u64,
+    branch: Option<&(usize, Recorded)>,
+    out_png: &str,
+    plot: &PlotOptions,
+) -> Result<Recorded, SimError> {
+    let (mut simulation, mut rng, mut rngs) = match branch {
+        Some((step, base)) => {
+            let mut simulation = Simulation::resume(base.sim.clone());
+            simulation.rewind(*step);
+            simulation.set_config(config.clone());
+            (simulation, base.rngs[*step], base.rngs[..*step].to_vec())
+        }
+        None => (Simulation::new(config), SimRng::seed_from_u64(seed), Vec::new()),
+    };
+    loop {
+        rngs.push(rng);
+        if simulation.step(&mut rng).is_finished() {
+            break;
+        }
+    }
+    let sim = simulation.into_result();
+    visualize_simulation(&sim, out_png, plot)?;
+    let angle = sim.collision_angle.map(|a| format!("{:.2}°", a)).unwrap_or_else(|| "-".into());
+    let branched = branch.map(|(step, _)| format!(" (branched at step {})", step)).unwrap_or_default();
+    println!(
+        "   {} after {} steps{}, miss distance {:.3} m, collision angle {}",
+        sim.outcome.as_str(),
+        sim.steps(),
+        branched,
+        sim.miss_distance,
+        angle
+    );
+    Ok(Recorded { sim, rngs })
+}
+
+fn print_config(config: &SimConfig, seed: u64, branch: Option<usize>) {
+    println!("   speed  {}", config.interceptor_speed);
+    println!("   gain   {}", config.p_gain);
+    println!("   weight {}", config.correction_weight);
+    println!("   noise  {}", config.noise_range_deg);
+    println!("   start  {} {}", config.interceptor_start_x, config.interceptor_start_y);
+    println!("   seed   {}", seed);
+    if let Some(step) = branch {
+        println!("   rewind {}", step);
+    }
+}
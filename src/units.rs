@@ -0,0 +1,139 @@
+// Units of measure for report formatting: newtypes for the quantities the printed reports compute
+// and show (the geometry report, the event log, missed passes, comparisons), so that a distance
+// can't be passed where a speed is expected and a turn rate can't be added to an angle, plus the
+// units they are shown in. They stop at the reports: SimConfig, the vehicles' state and guidance
+// are plain f64 in SI units (meters, seconds, m/s, m/s²) and degrees, and so are all files the
+// simulation writes. --units picks what the printed reports use:
+//
+//   si        meters, m/s and m/s² (the default)
+//   aviation  feet, knots and g (standard gravity, 9.80665 m/s²)
+//
+// Angles stay in degrees either way. Quantities combine as their units do: a distance over a time
+// is a speed, a speed over a time an acceleration, an angle over a time a turn rate.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul};
+
+const FEET_PER_METER: f64 = 1.0 / 0.3048;
+const KNOTS_PER_METER_PER_SECOND: f64 = 3600.0 / 1852.0;
+const STANDARD_GRAVITY: f64 = 9.806_65;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Meters(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Seconds(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct MetersPerSecond(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct MetersPerSecondSquared(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Degrees(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct DegreesPerSecond(pub f64);
+
+impl Add for Meters {
+    type Output = Meters;
+    fn add(self, other: Meters) -> Meters {
+        Meters(self.0 + other.0)
+    }
+}
+
+impl std::iter::Sum for Meters {
+    fn sum<I: Iterator<Item = Meters>>(iter: I) -> Meters {
+        iter.fold(Meters(0.0), Add::add)
+    }
+}
+
+impl Mul<f64> for Seconds {
+    type Output = Seconds;
+    fn mul(self, factor: f64) -> Seconds {
+        Seconds(self.0 * factor)
+    }
+}
+
+impl Div<Seconds> for Meters {
+    type Output = MetersPerSecond;
+    fn div(self, time: Seconds) -> MetersPerSecond {
+        MetersPerSecond(self.0 / time.0)
+    }
+}
+
+impl Div<Seconds> for MetersPerSecond {
+    type Output = MetersPerSecondSquared;
+    fn div(self, time: Seconds) -> MetersPerSecondSquared {
+        MetersPerSecondSquared(self.0 / time.0)
+    }
+}
+
+impl Div<Seconds> for Degrees {
+    type Output = DegreesPerSecond;
+    fn div(self, time: Seconds) -> DegreesPerSecond {
+        DegreesPerSecond(self.0 / time.0)
+    }
+}
+
+// The larger of two quantities of one kind, for peaks over a run
+pub fn max<Q: PartialOrd>(a: Q, b: Q) -> Q {
+    if b > a { b } else { a }
+}
+
+// The units the printed reports use
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Units {
+    #[default]
+    Si,
+    Aviation,
+}
+
+// A value converted for display with its unit; width and precision apply to the number
+pub struct Shown {
+    value: f64,
+    unit: &'static str,
+}
+
+impl fmt::Display for Shown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)?;
+        write!(f, " {}", self.unit)
+    }
+}
+
+impl Units {
+    // --units si|aviation, SI when absent
+    pub fn from_args(args: &[String]) -> Result<Units, String> {
+        match args.windows(2).find(|w| w[0] == "--units") {
+            Some(w) => match w[1].as_str() {
+                "si" => Ok(Units::Si),
+                "aviation" => Ok(Units::Aviation),
+                _ => Err(String::from("--units must be si or aviation")),
+            },
+            None => Ok(Units::default()),
+        }
+    }
+
+    pub fn distance(self, distance: Meters) -> Shown {
+        match self {
+            Units::Si => Shown { value: distance.0, unit: "m" },
+            Units::Aviation => Shown { value: distance.0 * FEET_PER_METER, unit: "ft" },
+        }
+    }
+
+    pub fn speed(self, speed: MetersPerSecond) -> Shown {
+        match self {
+            Units::Si => Shown { value: speed.0, unit: "m/s" },
+            Units::Aviation => Shown { value: speed.0 * KNOTS_PER_METER_PER_SECOND, unit: "kn" },
+        }
+    }
+
+    pub fn accel(self, accel: MetersPerSecondSquared) -> Shown {
+        match self {
+            Units::Si => Shown { value: accel.0, unit: "m/s²" },
+            Units::Aviation => Shown { value: accel.0 / STANDARD_GRAVITY, unit: "g" },
+        }
+    }
+}
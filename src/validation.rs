@@ -0,0 +1,61 @@
+// Every problem found in a configuration, each under the path of the field it concerns (the field
+// names of the scenario file, e.g. `tracking.sensors[0].radar.range_noise`), so that a file with
+// several mistakes is reported in one go rather than one fix at a time.
+
+use std::fmt;
+
+#[derive(Debug, Default)]
+pub struct Violations {
+    found: Vec<(String, String)>, // Field path and what is wrong with it
+}
+
+impl Violations {
+    // Records a violation of `field` unless `ok`
+    pub fn check(&mut self, ok: bool, field: &str, message: &str) {
+        if !ok {
+            self.found.push((field.into(), message.into()));
+        }
+    }
+
+    // Runs the checks of a nested section, whose fields are recorded under `name`
+    pub fn section(&mut self, name: &str, check: impl FnOnce(&mut Violations)) {
+        let mut inner = Violations::default();
+        check(&mut inner);
+        self.found.extend(inner.within(name).found);
+    }
+
+    // The same violations with `name` in front of their paths, for a section checked on its own
+    pub fn within(mut self, name: &str) -> Violations {
+        for (path, _) in &mut self.found {
+            *path = format!("{}.{}", name, path);
+        }
+        self
+    }
+
+    pub fn into_result(self) -> Result<(), Violations> {
+        match self.found.is_empty() {
+            true => Ok(()),
+            false => Err(self),
+        }
+    }
+}
+
+// One violation on a line of its own; several as a list under their count
+impl fmt::Display for Violations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let [(path, message)] = self.found.as_slice() {
+            return write!(f, "{}: {}", path, message);
+        }
+        write!(f, "{} problems", self.found.len())?;
+        for (path, message) in &self.found {
+            write!(f, "\n  {}: {}", path, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Violations> for String {
+    fn from(violations: Violations) -> String {
+        violations.to_string()
+    }
+}
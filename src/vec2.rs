@@ -0,0 +1,119 @@
+// Planar vector for positions, velocities and directions. The simulation is 2D (x downrange,
+// y height); keeping the component math in one type means the guidance laws read as the vector
+// formulas they implement, and normalization and rotation exist once.
+//
+// Recorded histories in SimulationResult stay (x, y) tuples, the form plotters and the JSON files
+// use; Vec2 converts to and from them.
+
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f64, y: f64) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn dot(self, other: Vec2) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    // z component of the 3D cross product: positive when `other` is counter-clockwise of self
+    pub fn cross(self, other: Vec2) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length(self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+
+    pub fn distance(self, other: Vec2) -> f64 {
+        (self - other).length()
+    }
+
+    // Unit vector in the same direction; None for (near) zero vectors
+    pub fn normalized(self) -> Option<Vec2> {
+        let length = self.length();
+        (length > 1e-9).then(|| self / length)
+    }
+
+    // Rotated 90° counter-clockwise
+    pub fn perp(self) -> Vec2 {
+        Vec2::new(-self.y, self.x)
+    }
+
+    // Rotated counter-clockwise by `radians`
+    pub fn rotated(self, radians: f64) -> Vec2 {
+        let (sin, cos) = (radians.sin(), radians.cos());
+        Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    // Angle between the two directions, 0° to 180°; 0 if either is zero
+    pub fn angle_deg(self, other: Vec2) -> f64 {
+        let (magnitude1, magnitude2) = (self.length(), other.length());
+        if magnitude1 > 0.0 && magnitude2 > 0.0 {
+            (self.dot(other) / (magnitude1 * magnitude2)).acos().to_degrees()
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, factor: f64) -> Vec2 {
+        Vec2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Div<f64> for Vec2 {
+    type Output = Vec2;
+    fn div(self, divisor: f64) -> Vec2 {
+        Vec2::new(self.x / divisor, self.y / divisor)
+    }
+}
+
+impl From<(f64, f64)> for Vec2 {
+    fn from((x, y): (f64, f64)) -> Vec2 {
+        Vec2::new(x, y)
+    }
+}
+
+impl From<Vec2> for (f64, f64) {
+    fn from(v: Vec2) -> (f64, f64) {
+        (v.x, v.y)
+    }
+}
@@ -0,0 +1,167 @@
+// Golden-result regression check: re-runs the seeded engagement stored in a golden file and
+// compares the outcome, the event log and both trajectories with the stored result, so a change
+// to the physics or the guidance can be shown to leave the behavior alone (or where it doesn't).
+// A golden file is {"seed": N, "result": {...}}, the result as `run` exports it; `--update`
+// records a new one or accepts the current behavior for an existing one.
+
+use crate::checkpoint::SimRng;
+use crate::error::SimError;
+use crate::json::{FromJson, Json, ToJson};
+use crate::log;
+use crate::scenario::Scenario;
+use crate::{flag_values, parsed_elsewhere, simulate_once, SimulationResult};
+use rand::SeedableRng;
+use std::path::{Path, PathBuf};
+
+// Default allowed deviation of a trajectory or the miss distance, in meters. Runs on the same
+// build reproduce their golden exactly; this leaves room for another compiler or platform.
+const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+struct Golden {
+    seed: u64,
+    result: SimulationResult,
+}
+
+impl Golden {
+    fn load(path: &Path) -> Result<Golden, SimError> {
+        let text = std::fs::read_to_string(path).map_err(SimError::io(format!("cannot read golden result '{}'", path.display())))?;
+        let json = Json::parse(&text).map_err(|e| SimError::invalid_scenario(path, e))?;
+        let golden = (|| -> Result<Golden, String> {
+            let seed = json.field("seed")?.as_usize().ok_or("field 'seed' must be a non-negative integer")?;
+            Ok(Golden { seed: seed as u64, result: SimulationResult::from_json(json.field("result")?)? })
+        })()
+        .map_err(|e| SimError::invalid_scenario(path, e))?;
+        if golden.result.first_step != 0 {
+            return Err(SimError::invalid_scenario(path, "a golden result must hold the whole run (recorded without --window)"));
+        }
+        Ok(golden)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), SimError> {
+        let json = Json::object(vec![("seed", (self.seed as usize).into()), ("result", self.result.to_json())]);
+        std::fs::write(path, json.to_string_pretty() + "\n").map_err(SimError::io(format!("cannot write golden result '{}'", path.display())))
+    }
+
+    // The engagement flown again with the golden's configuration and seed, like run 1 of a batch
+    fn rerun(&self) -> SimulationResult {
+        simulate_once(&mut SimRng::seed_from_u64(self.seed), &self.result.config)
+    }
+}
+
+// Largest distance between two recorded paths over the steps both have, and the step it is at
+fn max_deviation(path: &[(f64, f64)], golden: &[(f64, f64)]) -> (f64, usize) {
+    path.iter()
+        .zip(golden)
+        .enumerate()
+        .map(|(step, (a, b))| ((a.0 - b.0).hypot(a.1 - b.1), step))
+        .fold((0.0, 0), |max, d| if d.0 > max.0 { d } else { max })
+}
+
+// Everything in which `sim` differs from `golden` beyond `tolerance`, as messages, and the largest
+// trajectory deviation found
+fn differences(sim: &SimulationResult, golden: &SimulationResult, tolerance: f64) -> (Vec<String>, f64) {
+    let mut found = Vec::new();
+    if sim.outcome != golden.outcome {
+        found.push(format!("outcome {}, golden {}", sim.outcome.as_str(), golden.outcome.as_str()));
+    }
+    if sim.steps() != golden.steps() {
+        found.push(format!("{} steps, golden {}", sim.steps(), golden.steps()));
+    }
+    let events = |sim: &SimulationResult| sim.events.iter().map(|e| (e.step(), e.label())).collect::<Vec<_>>();
+    let (ours, theirs) = (events(sim), events(golden));
+    if let Some(i) = (0..ours.len().max(theirs.len())).find(|&i| ours.get(i) != theirs.get(i)) {
+        let describe = |event: Option<&(usize, String)>| event.map_or_else(|| String::from("none"), |(step, label)| format!("step {} {}", step, label));
+        found.push(format!("event {}: {}, golden {}", i + 1, describe(ours.get(i)), describe(theirs.get(i))));
+    }
+    let mut largest: f64 = 0.0;
+    for (vehicle, path, golden_path) in [
+        ("target", &sim.target_positions, &golden.target_positions),
+        ("interceptor", &sim.interceptor_positions, &golden.interceptor_positions),
+    ] {
+        let (deviation, step) = max_deviation(path, golden_path);
+        largest = largest.max(deviation);
+        if deviation > tolerance {
+            found.push(format!("{} path off by {:.3e} m at step {}", vehicle, deviation, step));
+        }
+    }
+    let miss = (sim.miss_distance - golden.miss_distance).abs();
+    if miss > tolerance || miss.is_nan() {
+        found.push(format!("miss distance {:.6} m, golden {:.6} m", sim.miss_distance, golden.miss_distance));
+    }
+    (found, largest)
+}
+
+// Usage: verify <golden.json>... [--tolerance M] [--update [--seed N] [--scenario FILE] [--preset NAME]]
+//
+// Without --update every file is checked and the command fails if any no longer matches. With it
+// the files are (re)written from the current build: the engagement comes from --scenario or
+// --preset, or else the existing golden, and so does the seed (default 1) unless --seed is given.
+pub fn run_verify(args: &[String]) -> Result<(), SimError> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut tolerance = DEFAULT_TOLERANCE;
+    let mut seed: Option<u64> = None;
+    let update = args.iter().any(|a| a == "--update");
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--tolerance" if i + 1 < args.len() => {
+                tolerance = args[i + 1].parse().ok().filter(|t: &f64| *t >= 0.0).ok_or("--tolerance must be zero or a positive number of meters")?;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                seed = Some(args[i + 1].parse().map_err(|_| "--seed must be a non-negative integer")?);
+                i += 1;
+            }
+            "--update" => {}
+            flag if parsed_elsewhere(flag) => i += flag_values(flag),
+            other => files.push(other.into()),
+        }
+        i += 1;
+    }
+    if files.is_empty() {
+        return Err("usage: verify <golden.json>... [--tolerance M] [--update [--seed N] [--scenario FILE] [--preset NAME]]".into());
+    }
+
+    if update {
+        let scenario = args.iter().any(|a| a == "--scenario" || a == "--preset").then(|| Scenario::from_args(args)).transpose()?;
+        for path in &files {
+            let existing = match path.exists() {
+                true => Some(Golden::load(path)?),
+                false => None,
+            };
+            let config = match (&scenario, &existing) {
+                (Some(scenario), _) => scenario.config.clone(),
+                (None, Some(golden)) => golden.result.config.clone(),
+                (None, None) => return Err(format!("'{}' doesn't exist yet; give the engagement with --scenario or --preset", path.display()).into()),
+            };
+            let seed = seed.or(existing.as_ref().map(|golden| golden.seed)).unwrap_or(1);
+            // Stored as a JSON number, which holds integers exactly up to 2^53
+            if seed > 1 << 53 {
+                return Err("--seed must be at most 2^53 to be stored in a golden file".into());
+            }
+            let result = simulate_once(&mut SimRng::seed_from_u64(seed), &config);
+            log::info!("📝 '{}': {}, {} steps with seed {}", path.display(), result.outcome.as_str(), result.steps(), seed);
+            Golden { seed, result }.save(path)?;
+        }
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for path in &files {
+        let golden = Golden::load(path)?;
+        let (found, largest) = differences(&golden.rerun(), &golden.result, tolerance);
+        if found.is_empty() {
+            log::info!("✅ '{}' matches ({}, {} steps, largest deviation {:.3e} m)", path.display(), golden.result.outcome.as_str(), golden.result.steps(), largest);
+        } else {
+            failed += 1;
+            log::info!("❌ '{}' differs:", path.display());
+            for difference in found {
+                log::info!("   {}", difference);
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        n => Err(SimError::Regression(format!("{} of {} golden results no longer match (tolerance {} m)", n, files.len(), tolerance))),
+    }
+}
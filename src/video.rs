@@ -0,0 +1,115 @@
+// MP4 export: renders the engagement frame by frame and pipes raw RGB frames
+// into an external `ffmpeg` process, which must be available on PATH.
+
+use crate::log;
+use crate::bounds::PlotBounds;
+use crate::error::SimError;
+use crate::plot::{draw_engagement, parse_size, PlotOptions};
+use crate::SimulationResult;
+use plotters::prelude::*;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Clone, Copy)]
+pub struct VideoOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub steps_per_second: f64, // Playback speed in simulation steps per second of video
+}
+
+impl Default for VideoOptions {
+    fn default() -> Self {
+        VideoOptions { width: 1280, height: 720, fps: 30, steps_per_second: 10.0 }
+    }
+}
+
+impl VideoOptions {
+    // Video export is enabled by --video; --video-size WxH (both even), --video-fps N and
+    // --video-speed STEPS_PER_SEC adjust the defaults.
+    pub fn from_args(args: &[String]) -> Result<Option<VideoOptions>, String> {
+        if !args.iter().any(|a| a == "--video") {
+            return Ok(None);
+        }
+        let mut options = VideoOptions::default();
+        for pair in args.windows(2) {
+            match pair[0].as_str() {
+                "--video-size" => {
+                    // yuv420p stores color at half the resolution, so ffmpeg refuses odd sizes
+                    let size = parse_size(&pair[1]).filter(|(w, h)| w % 2 == 0 && h % 2 == 0);
+                    (options.width, options.height) = size.ok_or("--video-size must be WIDTHxHEIGHT in even numbers of pixels, e.g. 1280x720")?;
+                }
+                "--video-fps" => {
+                    if let Some(v) = pair[1].parse().ok().filter(|v| *v > 0) { options.fps = v; }
+                }
+                "--video-speed" => {
+                    if let Some(v) = pair[1].parse().ok().filter(|v: &f64| *v > 0.0) { options.steps_per_second = v; }
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(options))
+    }
+}
+
+pub fn export_video(
+    sim: &SimulationResult,
+    out_path: &str,
+    options: &VideoOptions,
+    plot: &PlotOptions,
+) -> Result<(), SimError> {
+    let (width, height) = (options.width, options.height);
+    let mut ffmpeg = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgb24"])
+        .args(["-s", &format!("{}x{}", width, height)])
+        .args(["-r", &options.fps.to_string()])
+        .args(["-i", "-", "-pix_fmt", "yuv420p", out_path])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(SimError::io("failed to start ffmpeg; is it installed and on PATH?"))?;
+
+    let bounds = PlotBounds::from_result(sim);
+    let steps = sim.target_positions.len();
+    let steps_per_frame = (options.steps_per_second / options.fps as f64).max(1e-9);
+    let frames = ((steps as f64 / steps_per_frame).ceil() as usize).max(1);
+
+    let mut buffer = vec![0u8; width as usize * height as usize * 3];
+    let mut stdin = ffmpeg.stdin.take().ok_or(SimError::PlottingError("ffmpeg stdin unavailable".into()))?;
+    for frame in 0..frames {
+        // Number of recorded positions visible in this frame (at least the start point)
+        let shown = (((frame + 1) as f64 * steps_per_frame).ceil() as usize).clamp(1, steps);
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+            draw_engagement(&root, &sim.truncated(shown), bounds, plot)?;
+            root.present()?;
+        }
+        stdin.write_all(&buffer).map_err(SimError::io("writing frames to ffmpeg"))?;
+    }
+    drop(stdin);
+
+    let status = ffmpeg.wait()?;
+    if !status.success() {
+        return Err(SimError::PlottingError(format!("ffmpeg exited with {}", status)));
+    }
+    log::info!("✅ Video saved as '{}' ({} frames)", out_path, frames);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn video_sizes_must_be_even() {
+        let options = VideoOptions::from_args(&args(&["--video", "--video-size", "640x360"])).unwrap().unwrap();
+        assert_eq!((options.width, options.height), (640, 360));
+        for size in ["641x360", "640x361", "640", "0x360"] {
+            assert!(VideoOptions::from_args(&args(&["--video", "--video-size", size])).is_err(), "{}", size);
+        }
+        assert!(VideoOptions::from_args(&args(&["--video-size", "641x360"])).unwrap().is_none());
+    }
+}
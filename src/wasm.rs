@@ -0,0 +1,1035 @@
+// A WebAssembly interpreter for guidance plugins (see plugin.rs). It runs modules in the binary
+// format of the core specification 1.0, plus the sign-extension, saturating float-to-int and
+// memory.copy/memory.fill operators compilers emit by default; SIMD, threads, reference types and
+// multiple memories are rejected when the module is loaded.
+//
+// The code is decoded but not type-checked, so a module a validator would reject traps when it
+// runs instead; traps are errors, never panics. A module can't import anything, so all it can do
+// is compute on its own memory, and every call runs with an instruction budget, a bounded call
+// depth and at most MAX_PAGES of memory.
+
+const PAGE: usize = 65536;
+const MAX_PAGES: u32 = 256; // 16 MiB of linear memory
+const MAX_DEPTH: usize = 256; // Nested calls
+const MAX_LOCALS: usize = 50_000; // Per function, as their zeroing is work the budget doesn't count
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValType {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl ValType {
+    fn decode(byte: u8) -> Result<ValType, String> {
+        match byte {
+            0x7F => Ok(ValType::I32),
+            0x7E => Ok(ValType::I64),
+            0x7D => Ok(ValType::F32),
+            0x7C => Ok(ValType::F64),
+            0x7B => Err("SIMD (v128) is not supported".into()),
+            _ => Err(format!("unsupported value type 0x{:02x}", byte)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+}
+
+impl Value {
+    // As the interpreter keeps it: the bits, integers of 32 bits zero-extended
+    fn raw(self) -> u64 {
+        match self {
+            Value::I32(v) => v as u32 as u64,
+            Value::I64(v) => v as u64,
+            Value::F32(v) => v.to_bits() as u64,
+            Value::F64(v) => v.to_bits(),
+        }
+    }
+
+    fn ty(self) -> ValType {
+        match self {
+            Value::I32(_) => ValType::I32,
+            Value::I64(_) => ValType::I64,
+            Value::F32(_) => ValType::F32,
+            Value::F64(_) => ValType::F64,
+        }
+    }
+
+    fn from_raw(ty: ValType, raw: u64) -> Value {
+        match ty {
+            ValType::I32 => Value::I32(raw as u32 as i32),
+            ValType::I64 => Value::I64(raw as i64),
+            ValType::F32 => Value::F32(f32::from_bits(raw as u32)),
+            ValType::F64 => Value::F64(f64::from_bits(raw)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuncType {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+// Decoded instructions; the structured ones know where they end, so branches don't search
+#[derive(Debug, Clone)]
+enum Instr {
+    Unreachable,
+    Nop,
+    Block { params: usize, results: usize, end: usize },
+    Loop { params: usize },
+    If { params: usize, results: usize, else_at: Option<usize>, end: usize },
+    Else { end: usize },
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Vec<u32>, u32),
+    Return,
+    Call(u32),
+    CallIndirect(u32),
+    Drop,
+    Select,
+    LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    Load(u8, u32), // Opcode and offset
+    Store(u8, u32),
+    MemorySize,
+    MemoryGrow,
+    MemoryCopy,
+    MemoryFill,
+    Const(u64),
+    Numeric(u8),   // Opcodes 0x45 to 0xC4, which only work on the stack
+    TruncSat(u8),  // 0xFC 0 to 7
+}
+
+#[derive(Debug)]
+struct Function {
+    ty: usize,
+    locals: usize, // Besides the parameters, all zero at the start
+    code: Vec<Instr>,
+}
+
+#[derive(Debug)]
+pub struct Module {
+    types: Vec<FuncType>,
+    functions: Vec<Function>,
+    table: Option<u32>,            // Initial size
+    elements: Vec<(u32, Vec<u32>)>, // Table offset and function indices
+    memory: Option<(u32, u32)>,     // Initial and largest size in pages
+    globals: Vec<(bool, u64)>,      // Mutable, initial value
+    exports: Vec<(String, usize)>,  // Exported functions
+    start: Option<usize>,
+    data: Vec<(u32, Vec<u8>)>,      // Memory offset and bytes
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.bytes.get(self.pos).ok_or("unexpected end of the module")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let bytes = self.bytes.get(self.pos..self.pos.saturating_add(n)).ok_or("unexpected end of the module")?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    // LEB128 of at most `bits` bits, sign-extended if `signed`
+    fn leb(&mut self, bits: u32, signed: bool) -> Result<u64, String> {
+        let (mut result, mut shift) = (0u64, 0u32);
+        loop {
+            let byte = self.byte()?;
+            result |= ((byte & 0x7F) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if signed && shift < 64 && byte & 0x40 != 0 {
+                    result |= !0 << shift;
+                }
+                break;
+            }
+            if shift >= bits.div_ceil(7) * 7 {
+                return Err("integer too long".into());
+            }
+        }
+        Ok(result)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        self.leb(32, false).map(|v| v as u32)
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec()).map_err(|_| "a name is not UTF-8".to_string())
+    }
+
+    fn limits(&mut self) -> Result<(u32, Option<u32>), String> {
+        match self.byte()? {
+            0 => Ok((self.u32()?, None)),
+            1 => Ok((self.u32()?, Some(self.u32()?))),
+            _ => Err("shared memories and 64-bit limits are not supported".into()),
+        }
+    }
+
+    // A constant expression: one constant, or the value of a global before it, then end
+    fn init(&mut self, globals: &[(bool, u64)]) -> Result<u64, String> {
+        let value = match self.byte()? {
+            0x41 => self.leb(32, true)? as u32 as u64,
+            0x42 => self.leb(64, true)?,
+            0x43 => u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()) as u64,
+            0x44 => u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()),
+            0x23 => globals.get(self.u32()? as usize).ok_or("initializer reads an unknown global")?.1,
+            op => return Err(format!("unsupported initializer opcode 0x{:02x}", op)),
+        };
+        match self.byte()? {
+            0x0B => Ok(value),
+            _ => Err("initializers must be a single constant".into()),
+        }
+    }
+
+    // Parameter and result counts of a block type
+    fn block_type(&mut self, types: &[FuncType]) -> Result<(usize, usize), String> {
+        match self.bytes.get(self.pos) {
+            Some(0x40) => {
+                self.pos += 1;
+                Ok((0, 0))
+            }
+            Some(&byte) if (0x7B..=0x7F).contains(&byte) => {
+                self.pos += 1;
+                ValType::decode(byte).map(|_| (0, 1))
+            }
+            _ => {
+                let ty = types.get(self.leb(33, true)? as usize).ok_or("block of an unknown type")?;
+                Ok((ty.params.len(), ty.results.len()))
+            }
+        }
+    }
+}
+
+impl Module {
+    pub fn decode(bytes: &[u8]) -> Result<Module, String> {
+        if bytes.get(..8) != Some(b"\0asm\x01\0\0\0") {
+            return Err("not a WebAssembly module (version 1)".into());
+        }
+        let mut module = Module { types: Vec::new(), functions: Vec::new(), table: None, elements: Vec::new(), memory: None, globals: Vec::new(), exports: Vec::new(), start: None, data: Vec::new() };
+        let mut function_types = Vec::new();
+        let mut r = Reader { bytes, pos: 8 };
+        while !r.at_end() {
+            let id = r.byte()?;
+            let size = r.u32()? as usize;
+            let mut s = Reader { bytes: r.bytes(size)?, pos: 0 };
+            match id {
+                0 | 12 => {} // Custom sections (names, producers) and the data count
+                1 => {
+                    for _ in 0..s.u32()? {
+                        if s.byte()? != 0x60 {
+                            return Err("malformed function type".into());
+                        }
+                        let params = (0..s.u32()?).map(|_| ValType::decode(s.byte()?)).collect::<Result<_, _>>()?;
+                        let results = (0..s.u32()?).map(|_| ValType::decode(s.byte()?)).collect::<Result<_, _>>()?;
+                        module.types.push(FuncType { params, results });
+                    }
+                }
+                2 => {
+                    let import = match s.u32()? {
+                        0 => None,
+                        _ => Some(format!("{}.{}", s.name()?, s.name()?)),
+                    };
+                    if let Some(import) = import {
+                        return Err(format!("it imports '{}', and plugins can't import anything", import));
+                    }
+                }
+                3 => function_types = (0..s.u32()?).map(|_| s.u32().map(|ty| ty as usize)).collect::<Result<_, _>>()?,
+                4 => {
+                    for _ in 0..s.u32()? {
+                        if s.byte()? != 0x70 || module.table.is_some() {
+                            return Err("only one table of functions is supported".into());
+                        }
+                        module.table = Some(s.limits()?.0);
+                    }
+                }
+                5 => {
+                    for _ in 0..s.u32()? {
+                        if module.memory.is_some() {
+                            return Err("multiple memories are not supported".into());
+                        }
+                        let (min, max) = s.limits()?;
+                        if min > MAX_PAGES {
+                            return Err(format!("it needs {} pages of memory, more than the {} allowed", min, MAX_PAGES));
+                        }
+                        module.memory = Some((min, max.unwrap_or(MAX_PAGES).min(MAX_PAGES)));
+                    }
+                }
+                6 => {
+                    for _ in 0..s.u32()? {
+                        ValType::decode(s.byte()?)?;
+                        let mutable = s.byte()? == 1;
+                        let value = s.init(&module.globals)?;
+                        module.globals.push((mutable, value));
+                    }
+                }
+                7 => {
+                    for _ in 0..s.u32()? {
+                        let name = s.name()?;
+                        let (kind, index) = (s.byte()?, s.u32()? as usize);
+                        if kind == 0 {
+                            module.exports.push((name, index));
+                        }
+                    }
+                }
+                8 => module.start = Some(s.u32()? as usize),
+                9 => {
+                    for _ in 0..s.u32()? {
+                        if s.u32()? != 0 {
+                            return Err("only active element segments of function indices are supported".into());
+                        }
+                        let offset = s.init(&module.globals)? as u32;
+                        let functions = (0..s.u32()?).map(|_| s.u32()).collect::<Result<_, _>>()?;
+                        module.elements.push((offset, functions));
+                    }
+                }
+                10 => {
+                    let count = s.u32()? as usize;
+                    if count != function_types.len() {
+                        return Err("function and code sections differ in length".into());
+                    }
+                    for &ty in &function_types {
+                        let size = s.u32()? as usize;
+                        let mut body = Reader { bytes: s.bytes(size)?, pos: 0 };
+                        let mut locals = 0usize;
+                        for _ in 0..body.u32()? {
+                            locals = locals.saturating_add(body.u32()? as usize);
+                            ValType::decode(body.byte()?)?;
+                        }
+                        if locals > MAX_LOCALS {
+                            return Err(format!("a function has {} locals, more than the {} allowed", locals, MAX_LOCALS));
+                        }
+                        if ty >= module.types.len() {
+                            return Err("function of an unknown type".into());
+                        }
+                        module.functions.push(Function { ty, locals, code: decode_code(&mut body, &module.types)? });
+                    }
+                }
+                11 => {
+                    for _ in 0..s.u32()? {
+                        let memory = match s.u32()? {
+                            0 => 0,
+                            2 => s.u32()?,
+                            _ => return Err("passive data segments are not supported".into()),
+                        };
+                        if memory != 0 {
+                            return Err("multiple memories are not supported".into());
+                        }
+                        let offset = s.init(&module.globals)? as u32;
+                        let len = s.u32()? as usize;
+                        module.data.push((offset, s.bytes(len)?.to_vec()));
+                    }
+                }
+                _ => return Err(format!("unknown section {}", id)),
+            }
+        }
+        if module.functions.len() != function_types.len() {
+            return Err("functions without code".into());
+        }
+        Ok(module)
+    }
+
+    // Parameters and results of the exported function `name`
+    pub fn export_type(&self, name: &str) -> Option<&FuncType> {
+        let (_, index) = self.exports.iter().find(|(export, _)| export == name)?;
+        self.functions.get(*index).map(|function| &self.types[function.ty])
+    }
+
+    // A fresh instance whose calls together may run `fuel` instructions
+    pub fn instantiate(&self, fuel: u64) -> Result<Instance<'_>, String> {
+        let memory = vec![0; self.memory.map_or(0, |(min, _)| min as usize * PAGE)];
+        let mut table = vec![None; self.table.unwrap_or(0) as usize];
+        for (offset, functions) in &self.elements {
+            let slots = table.get_mut(*offset as usize..(*offset as usize).saturating_add(functions.len())).ok_or("element segment out of the table's bounds")?;
+            for (slot, &function) in slots.iter_mut().zip(functions) {
+                *slot = Some(function as usize);
+            }
+        }
+        let mut instance = Instance { module: self, memory, globals: self.globals.iter().map(|(_, value)| *value).collect(), table, fuel, stack: Vec::new(), depth: 0 };
+        for (offset, bytes) in &self.data {
+            let target = instance.memory.get_mut(*offset as usize..(*offset as usize).saturating_add(bytes.len())).ok_or("data segment out of the memory's bounds")?;
+            target.copy_from_slice(bytes);
+        }
+        if let Some(start) = self.start {
+            instance.invoke(start)?;
+        }
+        Ok(instance)
+    }
+}
+
+fn decode_code(r: &mut Reader, types: &[FuncType]) -> Result<Vec<Instr>, String> {
+    let mut code = Vec::new();
+    let mut open: Vec<usize> = Vec::new(); // Blocks, loops and ifs not ended yet
+    loop {
+        let at = code.len();
+        let instr = match r.byte()? {
+            0x00 => Instr::Unreachable,
+            0x01 => Instr::Nop,
+            op @ 0x02..=0x04 => {
+                let (params, results) = r.block_type(types)?;
+                open.push(at);
+                match op {
+                    0x02 => Instr::Block { params, results, end: 0 },
+                    0x03 => Instr::Loop { params },
+                    _ => Instr::If { params, results, else_at: None, end: 0 },
+                }
+            }
+            0x05 => {
+                let Some(Instr::If { else_at, .. }) = open.last().map(|&i| &mut code[i]) else { return Err("else outside of an if".into()) };
+                *else_at = Some(at);
+                Instr::Else { end: 0 }
+            }
+            0x0B => {
+                let Some(start) = open.pop() else {
+                    code.push(Instr::End);
+                    return Ok(code);
+                };
+                match &mut code[start] {
+                    Instr::Block { end, .. } => *end = at,
+                    Instr::If { else_at, end, .. } => {
+                        *end = at;
+                        if let Some(else_at) = *else_at {
+                            code[else_at] = Instr::Else { end: at };
+                        }
+                    }
+                    _ => {}
+                }
+                Instr::End
+            }
+            0x0C => Instr::Br(r.u32()?),
+            0x0D => Instr::BrIf(r.u32()?),
+            0x0E => {
+                let labels = (0..r.u32()?).map(|_| r.u32()).collect::<Result<_, _>>()?;
+                Instr::BrTable(labels, r.u32()?)
+            }
+            0x0F => Instr::Return,
+            0x10 => Instr::Call(r.u32()?),
+            0x11 => {
+                let ty = r.u32()?;
+                if r.u32()? != 0 {
+                    return Err("only one table is supported".into());
+                }
+                Instr::CallIndirect(ty)
+            }
+            0x1A => Instr::Drop,
+            0x1B => Instr::Select,
+            0x1C => {
+                for _ in 0..r.u32()? {
+                    ValType::decode(r.byte()?)?;
+                }
+                Instr::Select
+            }
+            0x20 => Instr::LocalGet(r.u32()?),
+            0x21 => Instr::LocalSet(r.u32()?),
+            0x22 => Instr::LocalTee(r.u32()?),
+            0x23 => Instr::GlobalGet(r.u32()?),
+            0x24 => Instr::GlobalSet(r.u32()?),
+            op @ 0x28..=0x3E => {
+                r.u32()?; // Alignment, a hint
+                let offset = r.u32()?;
+                if op <= 0x35 { Instr::Load(op, offset) } else { Instr::Store(op, offset) }
+            }
+            0x3F => {
+                r.byte()?;
+                Instr::MemorySize
+            }
+            0x40 => {
+                r.byte()?;
+                Instr::MemoryGrow
+            }
+            0x41 => Instr::Const(r.leb(32, true)? as u32 as u64),
+            0x42 => Instr::Const(r.leb(64, true)?),
+            0x43 => Instr::Const(u32::from_le_bytes(r.bytes(4)?.try_into().unwrap()) as u64),
+            0x44 => Instr::Const(u64::from_le_bytes(r.bytes(8)?.try_into().unwrap())),
+            op @ 0x45..=0xC4 => Instr::Numeric(op),
+            0xFC => match r.u32()? {
+                op @ 0..=7 => Instr::TruncSat(op as u8),
+                10 => {
+                    r.bytes(2)?;
+                    Instr::MemoryCopy
+                }
+                11 => {
+                    r.byte()?;
+                    Instr::MemoryFill
+                }
+                op => return Err(format!("unsupported instruction 0xfc {}", op)),
+            },
+            0xFD => return Err("SIMD instructions are not supported".into()),
+            op => return Err(format!("unsupported instruction 0x{:02x}", op)),
+        };
+        code.push(instr);
+    }
+}
+
+// Where a branch to a label goes
+struct Label {
+    target: usize, // Instruction to continue at
+    height: usize, // Stack height below the block's values
+    arity: usize,  // Values a branch carries
+    is_loop: bool, // Branches restart the loop and keep its label
+}
+
+pub struct Instance<'m> {
+    module: &'m Module,
+    memory: Vec<u8>,
+    globals: Vec<u64>,
+    table: Vec<Option<usize>>,
+    fuel: u64,
+    stack: Vec<u64>,
+    depth: usize,
+}
+
+impl Instance<'_> {
+    // Calls the exported function `name`
+    pub fn call(&mut self, name: &str, args: &[Value]) -> Result<Vec<Value>, String> {
+        let ty = self.module.export_type(name).ok_or_else(|| format!("no function '{}' is exported", name))?;
+        if args.len() != ty.params.len() || args.iter().map(|arg| arg.ty()).ne(ty.params.iter().copied()) {
+            return Err(format!("'{}' takes {:?}", name, ty.params));
+        }
+        let index = self.module.exports.iter().find(|(export, _)| export == name).map(|(_, index)| *index).unwrap();
+        self.stack.clear();
+        self.stack.extend(args.iter().map(|arg| arg.raw()));
+        self.invoke(index)?;
+        Ok(ty.results.iter().zip(&self.stack).map(|(ty, raw)| Value::from_raw(*ty, *raw)).collect())
+    }
+
+    fn pop(&mut self) -> Result<u64, String> {
+        self.stack.pop().ok_or_else(|| "value stack underflow".to_string())
+    }
+
+    fn pop_i32(&mut self) -> Result<u32, String> {
+        self.pop().map(|v| v as u32)
+    }
+
+    // Runs function `index` on the arguments on top of the stack, leaving its results there
+    fn invoke(&mut self, index: usize) -> Result<(), String> {
+        let module = self.module;
+        let function = module.functions.get(index).ok_or("call of an unknown function")?;
+        let ty = &module.types[function.ty];
+        if self.depth >= MAX_DEPTH {
+            return Err("call stack exhausted".into());
+        }
+        let base = self.stack.len().checked_sub(ty.params.len()).ok_or("value stack underflow")?;
+        let mut locals: Vec<u64> = self.stack.split_off(base);
+        locals.resize(ty.params.len() + function.locals, 0);
+        self.depth += 1;
+        let result = self.run(&function.code, &mut locals, base, ty.results.len());
+        self.depth -= 1;
+        result
+    }
+
+    // Branches to the label `depth` out, returning where to continue
+    fn branch(&mut self, labels: &mut Vec<Label>, depth: u32) -> Result<usize, String> {
+        let index = labels.len().checked_sub(depth as usize + 1).ok_or("branch to an unknown label")?;
+        let label = &labels[index];
+        let keep = self.stack.len().checked_sub(label.arity).filter(|&keep| keep >= label.height).ok_or("value stack underflow")?;
+        self.stack.drain(label.height..keep);
+        let target = label.target;
+        labels.truncate(if label.is_loop { index + 1 } else { index });
+        Ok(target)
+    }
+
+    fn run(&mut self, code: &[Instr], locals: &mut [u64], base: usize, results: usize) -> Result<(), String> {
+        let mut labels = vec![Label { target: code.len(), height: base, arity: results, is_loop: false }];
+        let mut pc = 0;
+        while pc < code.len() {
+            self.fuel = self.fuel.checked_sub(1).ok_or("instruction budget exhausted")?;
+            let mut next = pc + 1;
+            match &code[pc] {
+                Instr::Unreachable => return Err("unreachable executed".into()),
+                Instr::Nop => {}
+                Instr::Block { params, results, end } => {
+                    let height = self.stack.len().checked_sub(*params).ok_or("value stack underflow")?;
+                    labels.push(Label { target: end + 1, height, arity: *results, is_loop: false });
+                }
+                Instr::Loop { params } => {
+                    let height = self.stack.len().checked_sub(*params).ok_or("value stack underflow")?;
+                    labels.push(Label { target: pc + 1, height, arity: *params, is_loop: true });
+                }
+                Instr::If { params, results, else_at, end } => {
+                    let condition = self.pop_i32()?;
+                    let height = self.stack.len().checked_sub(*params).ok_or("value stack underflow")?;
+                    labels.push(Label { target: end + 1, height, arity: *results, is_loop: false });
+                    if condition == 0 {
+                        // To the else branch, or to the end, which drops the label again
+                        next = else_at.map_or(*end, |at| at + 1);
+                    }
+                }
+                Instr::Else { end } => next = *end,
+                Instr::End => {
+                    labels.pop();
+                }
+                Instr::Br(depth) => next = self.branch(&mut labels, *depth)?,
+                Instr::BrIf(depth) => {
+                    if self.pop_i32()? != 0 {
+                        next = self.branch(&mut labels, *depth)?;
+                    }
+                }
+                Instr::BrTable(depths, default) => {
+                    let i = self.pop_i32()? as usize;
+                    next = self.branch(&mut labels, *depths.get(i).unwrap_or(default))?;
+                }
+                Instr::Return => {
+                    let outermost = labels.len() as u32 - 1;
+                    next = self.branch(&mut labels, outermost)?;
+                }
+                Instr::Call(index) => self.invoke(*index as usize)?,
+                Instr::CallIndirect(ty) => {
+                    let slot = self.pop_i32()? as usize;
+                    let index = self.table.get(slot).copied().flatten().ok_or("call through an empty table slot")?;
+                    let function = self.module.functions.get(index).ok_or("call of an unknown function")?;
+                    if self.module.types.get(*ty as usize) != Some(&self.module.types[function.ty]) {
+                        return Err("indirect call of a function of another type".into());
+                    }
+                    self.invoke(index)?;
+                }
+                Instr::Drop => {
+                    self.pop()?;
+                }
+                Instr::Select => {
+                    let condition = self.pop_i32()?;
+                    let (b, a) = (self.pop()?, self.pop()?);
+                    self.stack.push(if condition != 0 { a } else { b });
+                }
+                Instr::LocalGet(i) => self.stack.push(*locals.get(*i as usize).ok_or("unknown local")?),
+                Instr::LocalSet(i) => {
+                    let value = self.pop()?;
+                    *locals.get_mut(*i as usize).ok_or("unknown local")? = value;
+                }
+                Instr::LocalTee(i) => {
+                    let value = *self.stack.last().ok_or("value stack underflow")?;
+                    *locals.get_mut(*i as usize).ok_or("unknown local")? = value;
+                }
+                Instr::GlobalGet(i) => self.stack.push(*self.globals.get(*i as usize).ok_or("unknown global")?),
+                Instr::GlobalSet(i) => {
+                    let value = self.pop()?;
+                    if !self.module.globals.get(*i as usize).is_some_and(|(mutable, _)| *mutable) {
+                        return Err("write to an immutable global".into());
+                    }
+                    self.globals[*i as usize] = value;
+                }
+                Instr::Load(op, offset) => {
+                    let address = self.pop_i32()? as usize + *offset as usize;
+                    let size = match op {
+                        0x29 | 0x2B => 8,
+                        0x28 | 0x2A | 0x34 | 0x35 => 4,
+                        0x2E | 0x2F | 0x32 | 0x33 => 2,
+                        _ => 1,
+                    };
+                    let bytes = self.memory.get(address..address + size).ok_or("out of bounds memory access")?;
+                    let mut value = bytes.iter().rev().fold(0u64, |value, &byte| value << 8 | byte as u64);
+                    if matches!(op, 0x2C | 0x2E | 0x30 | 0x32 | 0x34) {
+                        let shift = 64 - 8 * size as u32;
+                        value = ((value << shift) as i64 >> shift) as u64;
+                    }
+                    // Results of the i32 loads are 32 bits
+                    if *op <= 0x2F && *op != 0x29 && *op != 0x2B {
+                        value &= 0xFFFF_FFFF;
+                    }
+                    self.stack.push(value);
+                }
+                Instr::Store(op, offset) => {
+                    let value = self.pop()?;
+                    let address = self.pop_i32()? as usize + *offset as usize;
+                    let size = match op {
+                        0x37 | 0x39 => 8,
+                        0x36 | 0x38 | 0x3E => 4,
+                        0x3B | 0x3D => 2,
+                        _ => 1,
+                    };
+                    let bytes = self.memory.get_mut(address..address + size).ok_or("out of bounds memory access")?;
+                    bytes.copy_from_slice(&value.to_le_bytes()[..size]);
+                }
+                Instr::MemorySize => self.stack.push((self.memory.len() / PAGE) as u64),
+                Instr::MemoryGrow => {
+                    let delta = self.pop_i32()? as usize;
+                    let pages = self.memory.len() / PAGE;
+                    let max = self.module.memory.map_or(0, |(_, max)| max as usize);
+                    if pages + delta <= max {
+                        self.memory.resize((pages + delta) * PAGE, 0);
+                        self.stack.push(pages as u64);
+                    } else {
+                        self.stack.push(u32::MAX as u64);
+                    }
+                }
+                Instr::MemoryCopy => {
+                    let (n, source, dest) = (self.pop_i32()? as usize, self.pop_i32()? as usize, self.pop_i32()? as usize);
+                    if source.max(dest) + n > self.memory.len() {
+                        return Err("out of bounds memory access".into());
+                    }
+                    self.fuel = self.fuel.saturating_sub(n as u64 / 8);
+                    self.memory.copy_within(source..source + n, dest);
+                }
+                Instr::MemoryFill => {
+                    let (n, value, dest) = (self.pop_i32()? as usize, self.pop_i32()? as u8, self.pop_i32()? as usize);
+                    let bytes = self.memory.get_mut(dest..dest + n).ok_or("out of bounds memory access")?;
+                    bytes.fill(value);
+                    self.fuel = self.fuel.saturating_sub(n as u64 / 8);
+                }
+                Instr::Const(value) => self.stack.push(*value),
+                Instr::Numeric(op) => numeric(&mut self.stack, *op)?,
+                Instr::TruncSat(op) => {
+                    let x = self.pop()?;
+                    let x = if op % 4 < 2 { f32::from_bits(x as u32) as f64 } else { f64::from_bits(x) };
+                    // `as` saturates and takes NaN to 0, as these instructions do
+                    self.stack.push(match op {
+                        0 | 2 => x as i32 as u32 as u64,
+                        1 | 3 => x as u32 as u64,
+                        4 | 6 => x as i64 as u64,
+                        _ => x as u64,
+                    });
+                }
+            }
+            pc = next;
+        }
+        // Past the function's end: its results are the top values over its base
+        let keep = self.stack.len().checked_sub(results).filter(|&keep| keep >= base).ok_or("value stack underflow")?;
+        self.stack.drain(base..keep);
+        Ok(())
+    }
+}
+
+fn f32v(raw: u64) -> f32 {
+    f32::from_bits(raw as u32)
+}
+
+fn f64v(raw: u64) -> f64 {
+    f64::from_bits(raw)
+}
+
+fn of_f32(value: f32) -> u64 {
+    value.to_bits() as u64
+}
+
+fn truth(condition: bool) -> u64 {
+    condition as u64
+}
+
+// NaN if either is NaN, and -0 below +0
+fn wasm_min(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() { f64::NAN } else if a == b { f64::from_bits(a.to_bits() | b.to_bits()) } else { a.min(b) }
+}
+
+fn wasm_max(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() { f64::NAN } else if a == b { f64::from_bits(a.to_bits() & b.to_bits()) } else { a.max(b) }
+}
+
+// A float to integer conversion that traps on NaN and values out of the range
+fn trunc(x: f64, min: f64, max: f64) -> Result<f64, String> {
+    match x > min && x < max {
+        true => Ok(x.trunc()),
+        false => Err(if x.is_nan() { "invalid conversion of NaN to an integer" } else { "integer overflow" }.into()),
+    }
+}
+
+fn numeric(stack: &mut Vec<u64>, op: u8) -> Result<(), String> {
+    let arity = match op {
+        0x45 | 0x50 | 0x67..=0x69 | 0x79..=0x7B | 0x8B..=0x91 | 0x99..=0x9F | 0xA7..=0xC4 => 1,
+        _ => 2,
+    };
+    let b = stack.pop().ok_or("value stack underflow")?;
+    let a = if arity == 2 { stack.pop().ok_or("value stack underflow")? } else { b };
+    let (a32, b32, ai32, bi32, ai64, bi64) = (a as u32, b as u32, a as u32 as i32, b as u32 as i32, a as i64, b as i64);
+    let (af32, bf32, af64, bf64) = (f32v(a), f32v(b), f64v(a), f64v(b));
+    let div_zero = || "integer divide by zero".to_string();
+    let result = match op {
+        0x45 => truth(a32 == 0),
+        0x46 => truth(a32 == b32),
+        0x47 => truth(a32 != b32),
+        0x48 => truth(ai32 < bi32),
+        0x49 => truth(a32 < b32),
+        0x4A => truth(ai32 > bi32),
+        0x4B => truth(a32 > b32),
+        0x4C => truth(ai32 <= bi32),
+        0x4D => truth(a32 <= b32),
+        0x4E => truth(ai32 >= bi32),
+        0x4F => truth(a32 >= b32),
+        0x50 => truth(a == 0),
+        0x51 => truth(a == b),
+        0x52 => truth(a != b),
+        0x53 => truth(ai64 < bi64),
+        0x54 => truth(a < b),
+        0x55 => truth(ai64 > bi64),
+        0x56 => truth(a > b),
+        0x57 => truth(ai64 <= bi64),
+        0x58 => truth(a <= b),
+        0x59 => truth(ai64 >= bi64),
+        0x5A => truth(a >= b),
+        0x5B => truth(af32 == bf32),
+        0x5C => truth(af32 != bf32),
+        0x5D => truth(af32 < bf32),
+        0x5E => truth(af32 > bf32),
+        0x5F => truth(af32 <= bf32),
+        0x60 => truth(af32 >= bf32),
+        0x61 => truth(af64 == bf64),
+        0x62 => truth(af64 != bf64),
+        0x63 => truth(af64 < bf64),
+        0x64 => truth(af64 > bf64),
+        0x65 => truth(af64 <= bf64),
+        0x66 => truth(af64 >= bf64),
+        0x67 => a32.leading_zeros() as u64,
+        0x68 => a32.trailing_zeros() as u64,
+        0x69 => a32.count_ones() as u64,
+        0x6A..=0x78 => (match op {
+            0x6A => a32.wrapping_add(b32),
+            0x6B => a32.wrapping_sub(b32),
+            0x6C => a32.wrapping_mul(b32),
+            0x6D if b32 == 0 => return Err(div_zero()),
+            0x6D => ai32.checked_div(bi32).ok_or("integer overflow")? as u32,
+            0x6E => a32.checked_div(b32).ok_or_else(div_zero)?,
+            0x6F if b32 == 0 => return Err(div_zero()),
+            0x6F => ai32.wrapping_rem(bi32) as u32,
+            0x70 => a32.checked_rem(b32).ok_or_else(div_zero)?,
+            0x71 => a32 & b32,
+            0x72 => a32 | b32,
+            0x73 => a32 ^ b32,
+            0x74 => a32.wrapping_shl(b32),
+            0x75 => ai32.wrapping_shr(b32) as u32,
+            0x76 => a32.wrapping_shr(b32),
+            0x77 => a32.rotate_left(b32 % 32),
+            _ => a32.rotate_right(b32 % 32),
+        }) as u64,
+        0x79 => a.leading_zeros() as u64,
+        0x7A => a.trailing_zeros() as u64,
+        0x7B => a.count_ones() as u64,
+        0x7C => a.wrapping_add(b),
+        0x7D => a.wrapping_sub(b),
+        0x7E => a.wrapping_mul(b),
+        0x7F if b == 0 => return Err(div_zero()),
+        0x7F => ai64.checked_div(bi64).ok_or("integer overflow")? as u64,
+        0x80 => a.checked_div(b).ok_or_else(div_zero)?,
+        0x81 if b == 0 => return Err(div_zero()),
+        0x81 => ai64.wrapping_rem(bi64) as u64,
+        0x82 => a.checked_rem(b).ok_or_else(div_zero)?,
+        0x83 => a & b,
+        0x84 => a | b,
+        0x85 => a ^ b,
+        0x86 => a.wrapping_shl(b as u32),
+        0x87 => ai64.wrapping_shr(b as u32) as u64,
+        0x88 => a.wrapping_shr(b as u32),
+        0x89 => a.rotate_left((b % 64) as u32),
+        0x8A => a.rotate_right((b % 64) as u32),
+        0x8B => of_f32(af32.abs()),
+        0x8C => of_f32(-af32),
+        0x8D => of_f32(af32.ceil()),
+        0x8E => of_f32(af32.floor()),
+        0x8F => of_f32(af32.trunc()),
+        0x90 => of_f32(af32.round_ties_even()),
+        0x91 => of_f32(af32.sqrt()),
+        0x92 => of_f32(af32 + bf32),
+        0x93 => of_f32(af32 - bf32),
+        0x94 => of_f32(af32 * bf32),
+        0x95 => of_f32(af32 / bf32),
+        0x96 => of_f32(wasm_min(af32 as f64, bf32 as f64) as f32),
+        0x97 => of_f32(wasm_max(af32 as f64, bf32 as f64) as f32),
+        0x98 => of_f32(af32.copysign(bf32)),
+        0x99 => af64.abs().to_bits(),
+        0x9A => (-af64).to_bits(),
+        0x9B => af64.ceil().to_bits(),
+        0x9C => af64.floor().to_bits(),
+        0x9D => af64.trunc().to_bits(),
+        0x9E => af64.round_ties_even().to_bits(),
+        0x9F => af64.sqrt().to_bits(),
+        0xA0 => (af64 + bf64).to_bits(),
+        0xA1 => (af64 - bf64).to_bits(),
+        0xA2 => (af64 * bf64).to_bits(),
+        0xA3 => (af64 / bf64).to_bits(),
+        0xA4 => wasm_min(af64, bf64).to_bits(),
+        0xA5 => wasm_max(af64, bf64).to_bits(),
+        0xA6 => af64.copysign(bf64).to_bits(),
+        0xA7 => a32 as u64,
+        0xA8 => trunc(af32 as f64, -2147483649.0, 2147483648.0)? as i32 as u32 as u64,
+        0xA9 => trunc(af32 as f64, -1.0, 4294967296.0)? as u32 as u64,
+        0xAA => trunc(af64, -2147483649.0, 2147483648.0)? as i32 as u32 as u64,
+        0xAB => trunc(af64, -1.0, 4294967296.0)? as u32 as u64,
+        0xAC => ai32 as i64 as u64,
+        0xAD => a32 as u64,
+        0xAE => trunc(af32 as f64, -9223373136366403584.0, 9223372036854775808.0)? as i64 as u64,
+        0xAF => trunc(af32 as f64, -1.0, 18446744073709551616.0)? as u64,
+        0xB0 => trunc(af64, -9223372036854777856.0, 9223372036854775808.0)? as i64 as u64,
+        0xB1 => trunc(af64, -1.0, 18446744073709551616.0)? as u64,
+        0xB2 => of_f32(ai32 as f32),
+        0xB3 => of_f32(a32 as f32),
+        0xB4 => of_f32(ai64 as f32),
+        0xB5 => of_f32(a as f32),
+        0xB6 => of_f32(af64 as f32),
+        0xB7 => (ai32 as f64).to_bits(),
+        0xB8 => (a32 as f64).to_bits(),
+        0xB9 => (ai64 as f64).to_bits(),
+        0xBA => (a as f64).to_bits(),
+        0xBB => (af32 as f64).to_bits(),
+        0xBC..=0xBF => a, // Reinterpretations, which the bits already are
+        0xC0 => a32 as i8 as i32 as u32 as u64,
+        0xC1 => a32 as i16 as i32 as u32 as u64,
+        0xC2 => a as i8 as i64 as u64,
+        0xC3 => a as i16 as i64 as u64,
+        _ => a as i32 as i64 as u64,
+    };
+    stack.push(result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-assembled modules: each function is (params, results, i32 locals, body), exported as
+    // f0, f1, ...; `pages` adds a memory of that many pages
+
+    fn leb(mut n: usize, out: &mut Vec<u8>) {
+        loop {
+            let byte = (n & 0x7F) as u8;
+            n >>= 7;
+            out.push(if n == 0 { byte } else { byte | 0x80 });
+            if n == 0 {
+                break;
+            }
+        }
+    }
+
+    fn section(id: u8, content: Vec<u8>, out: &mut Vec<u8>) {
+        out.push(id);
+        leb(content.len(), out);
+        out.extend(content);
+    }
+
+    fn vector(items: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut out = Vec::new();
+        leb(items.len(), &mut out);
+        items.into_iter().for_each(|item| out.extend(item));
+        out
+    }
+
+    type Func<'a> = (&'a [u8], &'a [u8], usize, &'a [u8]);
+
+    fn module(functions: &[Func], pages: Option<u32>) -> Vec<u8> {
+        let mut out = b"\0asm\x01\0\0\0".to_vec();
+        let types = functions.iter().map(|(params, results, _, _)| [&[0x60, params.len() as u8][..], params, &[results.len() as u8], results].concat());
+        section(1, vector(types.collect()), &mut out);
+        section(3, vector((0..functions.len()).map(|i| vec![i as u8]).collect()), &mut out);
+        if let Some(pages) = pages {
+            section(5, vec![1, 0, pages as u8], &mut out);
+        }
+        let exports = (0..functions.len()).map(|i| [&[2, b'f', b'0' + i as u8][..], &[0, i as u8]].concat());
+        section(7, vector(exports.collect()), &mut out);
+        let bodies = functions.iter().map(|(_, _, locals, code)| {
+            let locals: &[u8] = if *locals == 0 { &[0] } else { &[1, *locals as u8, 0x7F] };
+            let body = [locals, code].concat();
+            let mut out = Vec::new();
+            leb(body.len(), &mut out);
+            out.extend(body);
+            out
+        });
+        section(10, vector(bodies.collect()), &mut out);
+        out
+    }
+
+    fn call(bytes: &[u8], name: &str, args: &[Value]) -> Result<Vec<Value>, String> {
+        Module::decode(bytes)?.instantiate(10_000)?.call(name, args)
+    }
+
+    const I32: u8 = 0x7F;
+    const F64: u8 = 0x7C;
+
+    #[test]
+    fn arithmetic() {
+        // (a + b) * 3 - 1 on i32, and a * b + 0.5 on f64
+        let bytes = module(
+            &[
+                (&[I32, I32], &[I32], 0, &[0x20, 0, 0x20, 1, 0x6A, 0x41, 3, 0x6C, 0x41, 1, 0x6B, 0x0B]),
+                (&[F64, F64], &[F64], 0, &[0x20, 0, 0x20, 1, 0xA2, 0x44, 0, 0, 0, 0, 0, 0, 0xE0, 0x3F, 0xA0, 0x0B]),
+            ],
+            None,
+        );
+        assert_eq!(call(&bytes, "f0", &[Value::I32(4), Value::I32(-9)]), Ok(vec![Value::I32(-16)]));
+        assert_eq!(call(&bytes, "f1", &[Value::F64(1.5), Value::F64(-2.0)]), Ok(vec![Value::F64(-2.5)]));
+        // Wrapping overflow is not a trap, division by zero is
+        assert_eq!(call(&bytes, "f0", &[Value::I32(i32::MAX), Value::I32(1)]), Ok(vec![Value::I32(i32::MIN.wrapping_mul(3).wrapping_sub(1))]));
+        let divide = module(&[(&[I32, I32], &[I32], 0, &[0x20, 0, 0x20, 1, 0x6D, 0x0B])], None);
+        assert_eq!(call(&divide, "f0", &[Value::I32(-7), Value::I32(2)]), Ok(vec![Value::I32(-3)]));
+        assert!(call(&divide, "f0", &[Value::I32(1), Value::I32(0)]).is_err());
+        assert!(call(&bytes, "f0", &[Value::F64(1.0), Value::I32(1)]).unwrap_err().contains("takes"));
+    }
+
+    #[test]
+    fn loops() {
+        // Sums n + (n - 1) + ... + 1 into local 1, counting local 0 down to zero
+        #[rustfmt::skip]
+        let sum: &[u8] = &[
+            0x02, 0x40, 0x03, 0x40,
+            0x20, 0, 0x45, 0x0D, 1,
+            0x20, 1, 0x20, 0, 0x6A, 0x21, 1,
+            0x20, 0, 0x41, 1, 0x6B, 0x21, 0,
+            0x0C, 0,
+            0x0B, 0x0B, 0x20, 1, 0x0B,
+        ];
+        let forever: &[u8] = &[0x03, 0x40, 0x0C, 0, 0x0B, 0x41, 0, 0x0B];
+        let bytes = module(&[(&[I32], &[I32], 1, sum), (&[], &[I32], 0, forever)], None);
+        assert_eq!(call(&bytes, "f0", &[Value::I32(100)]), Ok(vec![Value::I32(5050)]));
+        assert_eq!(call(&bytes, "f0", &[Value::I32(0)]), Ok(vec![Value::I32(0)]));
+        // A loop that never ends runs out of its budget instead of hanging
+        assert_eq!(call(&bytes, "f1", &[]), Err("instruction budget exhausted".into()));
+        assert_eq!(call(&bytes, "f0", &[Value::I32(100_000)]), Err("instruction budget exhausted".into()));
+    }
+
+    #[test]
+    fn memory_accesses_out_of_bounds_trap() {
+        // Stores `value` at `address` and loads it back
+        let store_load: &[u8] = &[0x20, 0, 0x20, 1, 0x36, 2, 0, 0x20, 0, 0x28, 2, 0, 0x0B];
+        let bytes = module(&[(&[I32, I32], &[I32], 0, store_load)], Some(1));
+        assert_eq!(call(&bytes, "f0", &[Value::I32(65532), Value::I32(42)]), Ok(vec![Value::I32(42)]));
+        for address in [65533, 65536, -1] {
+            assert_eq!(call(&bytes, "f0", &[Value::I32(address), Value::I32(42)]), Err("out of bounds memory access".into()), "{}", address);
+        }
+        // Without a memory every access is out of bounds
+        let bytes = module(&[(&[I32, I32], &[I32], 0, store_load)], None);
+        assert_eq!(call(&bytes, "f0", &[Value::I32(0), Value::I32(42)]), Err("out of bounds memory access".into()));
+    }
+
+    #[test]
+    fn malformed_modules_are_rejected() {
+        let good = module(&[(&[], &[I32], 0, &[0x41, 7, 0x0B])], None);
+        assert_eq!(call(&good, "f0", &[]), Ok(vec![Value::I32(7)]));
+        let mut bad_magic = good.clone();
+        bad_magic[1] = b'x';
+        assert_eq!(Module::decode(&bad_magic).err(), Some("not a WebAssembly module (version 1)".into()));
+        let mut version_2 = good.clone();
+        version_2[4] = 2;
+        assert!(Module::decode(&version_2).is_err());
+        assert!(Module::decode(b"\0as").is_err());
+        // Cut off anywhere, the module is an error and not a panic, unless the cut falls between
+        // sections before the function, which leaves a valid module without it
+        for len in 8..good.len() {
+            if let Ok(module) = Module::decode(&good[..len]) {
+                assert!(module.export_type("f0").is_none(), "{} bytes", len);
+            }
+        }
+        assert!(Module::decode(&good[..good.len() - 1]).is_err());
+        assert_eq!(call(&good, "missing", &[]), Err("no function 'missing' is exported".into()));
+    }
+}
@@ -0,0 +1,170 @@
+// The engagement as entities and components, stepped by the systems in systems.rs. An entity is an
+// index into the component lists: every entity has a body (position and velocity), and the
+// optional components decide what the systems do with it. An Evasion maneuvers it, a recorded path
+// or an external feed moves it along that path, a Seeker guides it onto another entity, and a role
+// records its state in the SimulationResult as one of the two vehicles. A new kind of entity, e.g.
+// a decoy, is a new combination of components (or a new component plus the system that acts on
+// it); the loop in simulation.rs only runs the systems.
+//
+// Scenarios still describe one target and one interceptor, entities 0 and 1 of the world the loop
+// rebuilds from the recorded result.
+
+use crate::fusion::Tracker;
+use crate::trajectory::TargetPath;
+use crate::vec2::Vec2;
+use crate::{GuidancePhase, SimConfig, SimEvent, SimulationResult, Target, Vehicle};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entity(pub usize);
+
+// Random heading noise blended with the altitude hold, flown against the entity chasing it
+pub struct Evasion {
+    pub threat: Entity,
+    pub noise_deg: f64, // Deviation applied in the last step, which correlated noise continues from
+}
+
+// Guidance onto `target`
+pub struct Seeker {
+    pub target: Entity,
+    pub noise_deg: f64,                // Heading noise applied in the last step
+    pub locked: Option<bool>,          // Whether guidance has a lock, None before its first decision
+    pub phase: Option<GuidancePhase>,
+    pub tracker: Option<Tracker>,      // The target's track, when guidance flies on measurements
+    pub perceived: Option<Target>,     // What sensing reported of the target this step
+    pub accel: Vec2,                   // Acceleration achieved in the last step, which the autopilot lag continues from
+    pub faulted: bool,                 // Whether the actuators are out, see actuator.rs
+    pub command: Option<Vec2>,         // Velocity last commanded, held between guidance updates
+    pub external: Option<Vec2>,        // Velocity commanded from outside for this step, see World::command
+}
+
+#[derive(Default)]
+pub struct World {
+    pub bodies: Vec<Target>,
+    pub evasions: Vec<Option<Evasion>>,
+    pub paths: Vec<Option<Arc<TargetPath>>>, // Flown as recorded, see trajectory.rs
+    pub fed: Vec<Option<Vec2>>, // Where an external feed puts the entity this step, see feed.rs
+    pub seekers: Vec<Option<Seeker>>,
+    pub roles: Vec<Option<Vehicle>>, // Which vehicle of the result an entity is recorded as
+}
+
+impl World {
+    // A new entity with only a body
+    pub fn spawn(&mut self, body: Target) -> Entity {
+        self.bodies.push(body);
+        self.evasions.push(None);
+        self.paths.push(None);
+        self.fed.push(None);
+        self.seekers.push(None);
+        self.roles.push(None);
+        Entity(self.bodies.len() - 1)
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = Entity> {
+        (0..self.bodies.len()).map(Entity)
+    }
+
+    // The engagement recorded in `result`, at its last recorded step. Guidance state comes from
+    // the event log (including actuator faults), the noise from the recorded deviations, a held
+    // command from the recorded commands and the autopilot's acceleration from the last two
+    // velocities, so a resumed run continues with them. The seeker keeps `tracker` when the caller
+    // still has it from the steps before, otherwise it picks up the track recorded so far.
+    pub fn engagement(result: &SimulationResult, tracker: Option<Tracker>) -> World {
+        let last = |positions: &[(f64, f64)], velocities: &[(f64, f64)]| Target::new(positions[positions.len() - 1], velocities[velocities.len() - 1]);
+        let (target_noise, interceptor_noise) = result.heading_noise_deg.last().copied().unwrap_or_default();
+        let locked = result.events.iter().rev().find_map(|e| match e {
+            SimEvent::LockAcquired { .. } => Some(true),
+            SimEvent::LockLost { .. } => Some(false),
+            _ => None,
+        });
+        let phase = result.events.iter().rev().find_map(|e| match *e {
+            SimEvent::PhaseChange { phase, .. } => Some(phase),
+            _ => None,
+        });
+
+        let faulted = result.events.iter().rev().find_map(|e| match e {
+            SimEvent::ActuatorFault { .. } => Some(true),
+            SimEvent::ActuatorRecovered { .. } => Some(false),
+            _ => None,
+        });
+
+        let mut world = World::default();
+        let target = world.spawn(last(&result.target_positions, &result.target_velocities));
+        let interceptor = world.spawn(last(&result.interceptor_positions, &result.interceptor_velocities));
+        world.roles[target.0] = Some(Vehicle::Target);
+        world.roles[interceptor.0] = Some(Vehicle::Interceptor);
+        match &result.config.target_path {
+            Some(path) => world.paths[target.0] = Some(path.clone()),
+            None => world.evasions[target.0] = Some(Evasion { threat: interceptor, noise_deg: target_noise }),
+        }
+        world.seekers[interceptor.0] = Some(Seeker {
+            target,
+            noise_deg: interceptor_noise,
+            locked,
+            phase,
+            tracker: result.config.tracking.map(|tracking| tracker.unwrap_or_else(|| tracking.tracker(result))),
+            perceived: None,
+            accel: match result.interceptor_velocities.as_slice() {
+                [.., before, last] => (Vec2::from(*last) - Vec2::from(*before)) / result.config.dt,
+                _ => Vec2::ZERO,
+            },
+            faulted: faulted.unwrap_or(false),
+            command: result.guidance_commands.last().map(|&command| command.into()),
+            external: None,
+        });
+        world
+    }
+
+    // Hands the target over to an external feed: this step it goes to `position` instead of
+    // evading or following its recorded path
+    pub fn feed_target(&mut self, position: Vec2) {
+        for entity in 0..self.bodies.len() {
+            if self.roles[entity] == Some(Vehicle::Target) {
+                (self.evasions[entity], self.paths[entity], self.fed[entity]) = (None, None, Some(position));
+            }
+        }
+    }
+
+    // Flies `vehicle` this step on a lateral acceleration `accel` (m/s², positive turning it
+    // counter-clockwise) given from outside, instead of on its guidance or evasion. It keeps its
+    // speed; the interceptor's command still goes through its autopilot and actuators.
+    pub fn command(&mut self, vehicle: Vehicle, accel: f64, config: &SimConfig) {
+        for entity in 0..self.bodies.len() {
+            if self.roles[entity] != Some(vehicle) {
+                continue;
+            }
+            let body = self.bodies[entity];
+            let heading = body.velocity.normalized().unwrap_or(Vec2::new(1.0, 0.0));
+            let turned = (body.velocity + heading.perp() * (accel * config.dt)).normalized().unwrap_or(heading);
+            match self.seekers[entity].as_mut() {
+                Some(seeker) => seeker.external = Some(turned * config.interceptor_speed),
+                None => {
+                    let position = body.position + turned * (body.velocity.length() * config.dt);
+                    (self.evasions[entity], self.paths[entity], self.fed[entity]) = (None, None, Some(position));
+                }
+            }
+        }
+    }
+
+    // Appends the state of the entities with a role to the result's per-step record
+    pub fn record(&self, result: &mut SimulationResult) {
+        let mut noise = (0.0, 0.0);
+        for entity in self.entities() {
+            let body = &self.bodies[entity.0];
+            match self.roles[entity.0] {
+                Some(Vehicle::Target) => {
+                    result.target_positions.push(body.position.into());
+                    result.target_velocities.push(body.velocity.into());
+                    noise.0 = self.evasions[entity.0].as_ref().map_or(0.0, |evasion| evasion.noise_deg);
+                }
+                Some(Vehicle::Interceptor) => {
+                    result.interceptor_positions.push(body.position.into());
+                    result.interceptor_velocities.push(body.velocity.into());
+                    noise.1 = self.seekers[entity.0].as_ref().map_or(0.0, |seeker| seeker.noise_deg);
+                }
+                None => {}
+            }
+        }
+        result.heading_noise_deg.push(noise);
+    }
+}
@@ -0,0 +1,181 @@
+// Keep-out zones: areas the interceptor must not fly through, e.g. a no-fly zone over a town or the
+// airspace of a friendly battery. A scenario lists them under "keep_out":
+//
+//   "keep_out": {
+//     "margin": 15,
+//     "gain": 1,
+//     "zones": [
+//       { "center": [60, 40], "radius": 12 },
+//       { "polygon": [[20, 0], [35, 0], [35, 25], [20, 25]] }
+//     ]
+//   }
+//
+//   zones   circles (center and radius) and polygons (three or more corners, in either order), numbered
+//           from 1 in the order listed
+//   margin  distance (m) from a zone's edge within which guidance starts avoiding it (default 10)
+//   gain    strength of the avoidance (default 1)
+//
+// Guidance avoids the zones with a repulsive term on the commanded direction: within the margin of a
+// zone the part of the command heading into it is cancelled in proportion to the depth into the
+// margin, all of it at the edge at gain 1, so the interceptor slides along the edge rather than
+// across it; beyond that, and inside a zone, the command is pushed outwards. Like any potential
+// field this can be overcome by the turn limits of the autopilot, actuator and faults, so the zones
+// are checked every step and entering or leaving one is logged as an event. A target inside a zone
+// stays out of reach.
+//
+// The zones are held in an Arc, so that cloning a SimConfig for every run doesn't copy them.
+
+use crate::json::{FromJson, Json, ToJson};
+use crate::validation::Violations;
+use crate::vec2::Vec2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Zone {
+    Circle { center: Vec2, radius: f64 },
+    Polygon { corners: Vec<Vec2> },
+}
+
+impl Zone {
+    // The distance from the nearest edge, negative inside, and the outward direction there
+    fn signed_distance(&self, point: Vec2) -> (f64, Vec2) {
+        match self {
+            Zone::Circle { center, radius } => {
+                let outward = (point - *center).normalized().unwrap_or(Vec2::new(0.0, 1.0));
+                (point.distance(*center) - radius, outward)
+            }
+            Zone::Polygon { corners } => {
+                let mut inside = false;
+                let mut nearest = (f64::INFINITY, Vec2::new(0.0, 1.0));
+                for (i, &a) in corners.iter().enumerate() {
+                    let b = corners[(i + 1) % corners.len()];
+                    if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+                        inside = !inside;
+                    }
+                    let edge = b - a;
+                    let along = ((point - a).dot(edge) / edge.dot(edge)).clamp(0.0, 1.0);
+                    let away = point - (a + edge * along);
+                    if away.length() < nearest.0 {
+                        // On the edge itself the outward direction is the edge's normal
+                        let normal = edge.perp().normalized().unwrap_or(Vec2::new(0.0, 1.0));
+                        nearest = (away.length(), away.normalized().unwrap_or(normal));
+                    }
+                }
+                match inside {
+                    true => (-nearest.0, -nearest.1),
+                    false => nearest,
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.signed_distance(point).0 < 0.0
+    }
+
+    // The boundary as a closed outline, circles by `segments` chords
+    pub fn outline(&self, segments: usize) -> Vec<(f64, f64)> {
+        match self {
+            Zone::Circle { center, radius } => (0..segments)
+                .map(|k| (*center + Vec2::new(*radius, 0.0).rotated(std::f64::consts::TAU * k as f64 / segments as f64)).into())
+                .collect(),
+            Zone::Polygon { corners } => corners.iter().map(|&corner| corner.into()).collect(),
+        }
+    }
+
+    fn validate(&self, v: &mut Violations) {
+        match self {
+            Zone::Circle { center, radius } => {
+                v.check(center.x.is_finite() && center.y.is_finite(), "center", "must be a finite point");
+                v.check(*radius > 0.0 && radius.is_finite(), "radius", "must be a positive distance");
+            }
+            Zone::Polygon { corners } => {
+                v.check(corners.len() >= 3, "polygon", "needs at least three corners");
+                v.check(corners.iter().all(|c| c.x.is_finite() && c.y.is_finite()), "polygon", "corners must be finite points");
+            }
+        }
+    }
+}
+
+impl ToJson for Zone {
+    fn to_json(&self) -> Json {
+        match self {
+            Zone::Circle { center, radius } => Json::object(vec![("center", (center.x, center.y).into()), ("radius", (*radius).into())]),
+            Zone::Polygon { corners } => Json::object(vec![("polygon", Json::Array(corners.iter().map(|c| (c.x, c.y).into()).collect()))]),
+        }
+    }
+}
+
+impl FromJson for Zone {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match (json.get("center"), json.get("polygon")) {
+            (Some(center), None) => {
+                let center = center.as_point().ok_or("'center' must be [x, y]")?;
+                Ok(Zone::Circle { center: center.into(), radius: json.f64_field("radius")? })
+            }
+            (None, Some(polygon)) => {
+                let corners = polygon.as_array().ok_or("'polygon' must be an array of [x, y] corners")?;
+                let corners = corners.iter().map(|c| c.as_point().map(Vec2::from).ok_or("'polygon' corners must be [x, y]")).collect::<Result<Vec<_>, _>>()?;
+                Ok(Zone::Polygon { corners })
+            }
+            _ => Err(String::from("a zone is either a circle with 'center' and 'radius' or a 'polygon'")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct KeepOut {
+    pub zones: Vec<Zone>,
+    pub margin: f64, // Distance (m) from the edge within which guidance avoids a zone
+    pub gain: f64,   // Strength of the avoidance, 1 to just slide along the edge
+}
+
+impl KeepOut {
+    // The unit `direction` turned away from the zones near `position`, see the top of the file
+    pub fn avoid(&self, position: Vec2, direction: Vec2) -> Vec2 {
+        let mut avoided = direction;
+        for zone in &self.zones {
+            let (distance, outward) = zone.signed_distance(position);
+            if distance >= self.margin {
+                continue;
+            }
+            let weight = self.gain * (self.margin - distance) / self.margin;
+            let inward = (-avoided.dot(outward)).max(0.0);
+            avoided += outward * (inward * weight.min(1.0) + (weight - 1.0).max(0.0));
+        }
+        // Heading straight at a zone's edge, the cancelled command leaves sliding along it
+        avoided.normalized().unwrap_or(direction.perp())
+    }
+
+    pub fn validate(&self, v: &mut Violations) {
+        v.check(!self.zones.is_empty(), "zones", "must list at least one zone");
+        for (i, zone) in self.zones.iter().enumerate() {
+            v.section(&format!("zones[{}]", i), |v| zone.validate(v));
+        }
+        v.check(self.margin > 0.0 && self.margin.is_finite(), "margin", "must be a positive distance");
+        v.check(self.gain >= 0.0 && self.gain.is_finite(), "gain", "must be zero or positive");
+    }
+}
+
+impl ToJson for KeepOut {
+    fn to_json(&self) -> Json {
+        Json::object(vec![
+            ("margin", self.margin.into()),
+            ("gain", self.gain.into()),
+            ("zones", Json::Array(self.zones.iter().map(Zone::to_json).collect())),
+        ])
+    }
+}
+
+impl FromJson for KeepOut {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let zones = json
+            .field("zones")?
+            .as_array()
+            .ok_or("'zones' must be an array of zones")?
+            .iter()
+            .enumerate()
+            .map(|(i, zone)| Zone::from_json(zone).map_err(|e| format!("zones[{}]: {}", i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(KeepOut { zones, margin: json.f64_field_or("margin", 10.0)?, gain: json.f64_field_or("gain", 1.0)? })
+    }
+}